@@ -0,0 +1,247 @@
+use std::{collections::BTreeSet, fmt::Display, str::FromStr};
+
+use serenity::framework::standard::{Command, CommandGroup, CommandOptions, GroupOptions};
+
+/// Additional Aliases for the Commands of the Bot, which are configured per Deployment in
+/// addition to the built-in Aliases
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommandAliases {
+    /// The Pairs of (Alias, Command-Name)
+    entries: Vec<(String, String)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseAliasesError {
+    /// The Entry is not in the Form `alias=command`
+    InvalidEntry(String),
+}
+
+impl Display for ParseAliasesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidEntry(e) => write!(f, "Invalid Alias '{}', expected 'alias=command'", e),
+        }
+    }
+}
+
+impl FromStr for CommandAliases {
+    type Err = ParseAliasesError;
+
+    /// Parses a comma-separated List of `alias=command` Entries
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut entries = Vec::new();
+        for raw in s.split(',').map(|e| e.trim()).filter(|e| !e.is_empty()) {
+            let (alias, command) = match raw.split_once('=') {
+                Some((a, c)) if !a.trim().is_empty() && !c.trim().is_empty() => {
+                    (a.trim(), c.trim())
+                }
+                _ => return Err(ParseAliasesError::InvalidEntry(raw.to_string())),
+            };
+
+            entries.push((alias.to_string(), command.to_string()));
+        }
+
+        Ok(Self { entries })
+    }
+}
+
+impl CommandAliases {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The additional Aliases for the Command with the given Names, Aliases that are already
+    /// used as the Name of a Command (in `taken`) are skipped
+    fn aliases_for(&self, names: &[&str], taken: &BTreeSet<&str>) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|(_, command)| names.contains(&command.as_str()))
+            .filter(|(alias, _)| {
+                if taken.contains(alias.as_str()) {
+                    tracing::error!("Alias '{}' is already used by a Command", alias);
+                    return false;
+                }
+                true
+            })
+            .map(|(alias, _)| alias.clone())
+            .collect()
+    }
+
+    /// The configured Commands that don't match any of the given Names
+    fn unknown_commands<'s>(&'s self, names: &BTreeSet<&str>) -> Vec<&'s str> {
+        self.entries
+            .iter()
+            .map(|(_, command)| command.as_str())
+            .filter(|command| !names.contains(command))
+            .collect()
+    }
+}
+
+/// Creates a new Group with the same Commands as the given Group, but with the configured
+/// Aliases added to the Names of the Commands.
+///
+/// The Framework only works with static Groups, so the new Group is leaked, which is fine as
+/// this should only be done once while starting the Bot
+pub fn with_aliases(
+    group: &'static CommandGroup,
+    aliases: &CommandAliases,
+) -> &'static CommandGroup {
+    if aliases.is_empty() {
+        return group;
+    }
+
+    let taken: BTreeSet<&str> = group
+        .options
+        .commands
+        .iter()
+        .flat_map(|c| c.options.names.iter().copied())
+        .collect();
+    for unknown in aliases.unknown_commands(&taken) {
+        tracing::error!("Alias configured for unknown Command '{}'", unknown);
+    }
+
+    let commands: Vec<&'static Command> = group
+        .options
+        .commands
+        .iter()
+        .map(|cmd| {
+            let extra = aliases.aliases_for(cmd.options.names, &taken);
+            if extra.is_empty() {
+                return *cmd;
+            }
+
+            let names: Vec<&'static str> = cmd
+                .options
+                .names
+                .iter()
+                .copied()
+                .chain(extra.into_iter().map(|a| &*Box::leak(a.into_boxed_str())))
+                .collect();
+
+            let options = &cmd.options;
+            let options: &'static CommandOptions = Box::leak(Box::new(CommandOptions {
+                checks: options.checks,
+                bucket: options.bucket,
+                names: Box::leak(names.into_boxed_slice()),
+                desc: options.desc,
+                delimiters: options.delimiters,
+                usage: options.usage,
+                examples: options.examples,
+                min_args: options.min_args,
+                max_args: options.max_args,
+                allowed_roles: options.allowed_roles,
+                required_permissions: options.required_permissions,
+                help_available: options.help_available,
+                only_in: options.only_in,
+                owners_only: options.owners_only,
+                owner_privilege: options.owner_privilege,
+                sub_commands: options.sub_commands,
+            }));
+
+            &*Box::leak(Box::new(Command {
+                fun: cmd.fun,
+                options,
+            }))
+        })
+        .collect();
+
+    let options = group.options;
+    let options: &'static GroupOptions = Box::leak(Box::new(GroupOptions {
+        prefixes: options.prefixes,
+        only_in: options.only_in,
+        owners_only: options.owners_only,
+        owner_privilege: options.owner_privilege,
+        help_available: options.help_available,
+        allowed_roles: options.allowed_roles,
+        required_permissions: options.required_permissions,
+        checks: options.checks,
+        default_command: options.default_command,
+        description: options.description,
+        summary: options.summary,
+        commands: Box::leak(commands.into_boxed_slice()),
+        sub_groups: options.sub_groups,
+    }));
+
+    Box::leak(Box::new(CommandGroup {
+        name: group.name,
+        options,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::framework::standard::OnlyIn;
+
+    use super::*;
+
+    #[test]
+    fn parse_aliases() {
+        let aliases: CommandAliases = "rollen=list-roles, spiel = werewolf,".parse().unwrap();
+
+        assert_eq!(
+            vec![
+                ("rollen".to_string(), "list-roles".to_string()),
+                ("spiel".to_string(), "werewolf".to_string())
+            ],
+            aliases.entries
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(
+            Err(ParseAliasesError::InvalidEntry("rollen".to_string())),
+            "rollen".parse::<CommandAliases>()
+        );
+        assert_eq!(
+            Err(ParseAliasesError::InvalidEntry("=werewolf".to_string())),
+            "=werewolf".parse::<CommandAliases>()
+        );
+    }
+
+    #[test]
+    fn aliases_for_any_name() {
+        let aliases: CommandAliases = "rollen=list-roles,spiel=werewolf".parse().unwrap();
+        let taken: BTreeSet<&str> = ["list_roles", "list-roles", "werewolf"]
+            .iter()
+            .copied()
+            .collect();
+
+        assert_eq!(
+            vec!["rollen".to_string()],
+            aliases.aliases_for(&["list_roles", "list-roles"], &taken)
+        );
+        assert!(aliases.unknown_commands(&taken).is_empty());
+    }
+
+    #[test]
+    fn skips_taken_aliases() {
+        let aliases: CommandAliases = "werewolf=list-roles,other=missing".parse().unwrap();
+        let taken: BTreeSet<&str> = ["list-roles", "werewolf"].iter().copied().collect();
+
+        assert!(aliases.aliases_for(&["list-roles"], &taken).is_empty());
+        assert_eq!(vec!["missing"], aliases.unknown_commands(&taken));
+    }
+
+    #[test]
+    fn group_with_aliases() {
+        let aliases: CommandAliases = "rollen=list-roles".parse().unwrap();
+        let group = with_aliases(&crate::GENERAL_GROUP, &aliases);
+
+        let list_roles = group
+            .options
+            .commands
+            .iter()
+            .find(|c| c.options.names.contains(&"list_roles"))
+            .unwrap();
+        assert_eq!(
+            &["list_roles", "list-roles", "rollen"],
+            list_roles.options.names
+        );
+        assert_eq!(
+            crate::GENERAL_GROUP.options.commands.len(),
+            group.options.commands.len()
+        );
+        assert_eq!(OnlyIn::Guild, group.options.only_in);
+    }
+}
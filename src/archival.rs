@@ -0,0 +1,59 @@
+use std::{sync::Arc, time::Duration};
+
+use serenity::{
+    http::{GuildPagination, Http},
+    model::id::GuildId,
+};
+
+use crate::rounds::archive;
+
+/// How often the Inactive-Channels are checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// The Number of Guilds loaded at once
+const GUILD_PAGE_SIZE: u64 = 100;
+
+/// Starts the Background-Task that regularly deletes the Channels, that have not been used in the
+/// Inactive-Category for longer than the Retention, on all the Guilds of the Bot
+pub fn run_archival(http: Arc<Http>, retention: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            archive_guilds(&http, retention).await;
+        }
+    });
+}
+
+async fn archive_guilds(http: &Http, retention: Duration) {
+    let bot_id = match http.get_current_user().await {
+        Ok(u) => u.id,
+        Err(e) => {
+            tracing::error!("Loading the Bot-User: {:?}", e);
+            return;
+        }
+    };
+
+    let mut after = GuildId(0);
+    loop {
+        let guilds = match http
+            .get_guilds(&GuildPagination::After(after), GUILD_PAGE_SIZE)
+            .await
+        {
+            Ok(g) => g,
+            Err(e) => {
+                tracing::error!("Loading Guilds: {:?}", e);
+                return;
+            }
+        };
+
+        for guild in guilds.iter() {
+            archive::archive(http, guild.id, bot_id, retention).await;
+        }
+
+        match guilds.last() {
+            Some(last) if guilds.len() as u64 == GUILD_PAGE_SIZE => after = last.id,
+            _ => return,
+        };
+    }
+}
@@ -4,11 +4,86 @@ pub use add_role::add_role;
 mod remove_role;
 pub use remove_role::remove_role;
 
+mod edit_role;
+pub use edit_role::edit_role;
+
 mod list_roles;
 pub use list_roles::list_roles;
 
+mod find_role;
+pub use find_role::find_role;
+
 mod werewolf;
-pub use werewolf::werewolf;
+pub use werewolf::{repeat, resume_counts, werewolf};
 
 mod help;
 pub use help::help;
+
+mod stats;
+pub use stats::stats;
+
+mod settings;
+pub use settings::settings;
+
+mod backup_roles;
+pub use backup_roles::backup_roles;
+
+mod restore_roles;
+pub use restore_roles::restore_roles;
+
+mod debug_state;
+pub use debug_state::debug_state;
+
+mod state_machines;
+pub use state_machines::state_machines;
+
+mod reservations;
+pub use reservations::reservations;
+
+mod clear_reservation;
+pub use clear_reservation::clear_reservation;
+
+mod self_test;
+pub use self_test::self_test;
+
+mod reassign;
+pub use reassign::reassign;
+
+mod round_history;
+pub use round_history::round_history;
+
+mod validate_set;
+pub use validate_set::validate_set;
+
+mod broadcast;
+pub use broadcast::broadcast;
+
+mod next_phase;
+pub use next_phase::next_phase;
+
+mod start_vote;
+pub use start_vote::start_vote;
+
+mod close_vote;
+pub use close_vote::close_vote;
+
+mod import_roles_url;
+pub use import_roles_url::import_roles_url;
+
+mod proceed;
+pub use proceed::proceed;
+
+mod narration;
+pub use narration::narration;
+
+mod reveal;
+pub use reveal::reveal;
+
+mod game_channels;
+pub use game_channels::game_channels;
+
+mod test_dm;
+pub use test_dm::test_dm;
+
+mod permissions;
+pub use permissions::permissions;
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serenity::{
     client::Context,
     framework::standard::{Args, CommandResult},
@@ -5,7 +7,7 @@ use serenity::{
     model::channel::Message,
 };
 
-use crate::{util, MOD_ROLE_NAME};
+use crate::{roles::validation, util};
 
 mod sm;
 
@@ -13,7 +15,7 @@ fn missing_part(missing_part: &str) -> String {
     format!(
         "```
 Missing '{}'
-Format: 'add-role {{name}}'
+Format: 'add-role {{name}} [team]'
 ```",
         missing_part
     )
@@ -26,30 +28,11 @@ pub async fn add_role(ctx: &Context, msg: &Message, mut args: Args) -> CommandRe
     let channel_id = msg.channel_id;
     let guild_id = msg.guild_id.unwrap();
 
-    let server_mods = match util::mods::load_mods(ctx, guild_id, MOD_ROLE_NAME).await {
-        Ok(m) => m,
-        Err(e) => {
-            tracing::error!("Loading Mods: {:?}", e);
-
-            util::msgs::send_content(channel_id, ctx.http(), "Could not load Mods for the Server")
-                .await;
-
-            return Ok(());
-        }
-    };
-    if !server_mods.contains(&msg.author.id) {
-        tracing::error!("Non Mod User executed the Command");
-
-        util::msgs::send_content(
-            channel_id,
-            ctx.http(),
-            &format!(
-                "Only Users with the '{}'-Role can use this Command",
-                MOD_ROLE_NAME
-            ),
-        )
-        .await;
-
+    let reply = util::msgs::ReplyTarget::Channel(channel_id);
+    if util::mods::require_mod(ctx, guild_id, msg.author.id, reply)
+        .await
+        .is_none()
+    {
         return Ok(());
     }
 
@@ -65,12 +48,34 @@ pub async fn add_role(ctx: &Context, msg: &Message, mut args: Args) -> CommandRe
         }
     };
 
-    let sm = sm::create(name.clone(), msg.author.id, channel_id, ctx)
+    if let Err(e) = validation::validate_name(&name) {
+        util::msgs::send_content(channel_id, ctx.http(), &e.to_string()).await;
+
+        return Ok(());
+    }
+
+    let team = args_iter.next();
+
+    let sm = sm::create(name.clone(), team, msg.author.id, channel_id, ctx)
         .await
         .unwrap();
 
     let sm_msg_id = sm.message_id();
     crate::SMMAP.add(sm_msg_id, sm);
 
+    // Sessions that are abandoned by the Mod would otherwise stay around forever
+    let timeout_minutes = crate::CONFIG.add_role_timeout_minutes;
+    if timeout_minutes > 0 {
+        let http = ctx.http.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(timeout_minutes * 60)).await;
+
+            if crate::SMMAP.discard(sm_msg_id) {
+                tracing::info!("add-role Session timed out");
+                sm::timed_out(&http, channel_id, sm_msg_id).await;
+            }
+        });
+    }
+
     Ok(())
 }
@@ -1,7 +1,4 @@
-use std::{
-    collections::BTreeSet,
-    sync::{Arc, Mutex},
-};
+use std::{collections::BTreeSet, fmt::Display, sync::Arc};
 
 use serenity::{
     http::{CacheHttp, Http},
@@ -10,16 +7,20 @@ use serenity::{
 
 use crate::{
     messages::{
-        AsyncTransition, Event, MessageStateMachine, SingleState, TransitionError, TransitionResult,
+        AsyncTransition, Context, Event, MessageStateMachine, SingleState, TransitionError,
+        TransitionResult, WithLazyState,
     },
-    roles::WereWolfRoleConfig,
+    reactions::emoji_eq,
+    roles::{validation, WereWolfRoleConfig},
     storage::StorageBackend,
+    util::dry_run,
     Reactions,
 };
 
 #[derive(Debug, Clone)]
 struct FirstTransition {
     name: String,
+    team: Option<String>,
     emoji: String,
     author: UserId,
     message: StateMessage,
@@ -28,6 +29,7 @@ struct FirstTransition {
 #[derive(Debug, Clone)]
 struct SecondTransition {
     name: String,
+    team: Option<String>,
     emoji: String,
     multi_player: bool,
     author: UserId,
@@ -37,10 +39,22 @@ struct SecondTransition {
 #[derive(Debug, Clone)]
 struct ThirdTransition {
     name: String,
+    team: Option<String>,
     emoji: String,
     multi_player: bool,
     masks_role: bool,
-    extra_channels: Arc<Mutex<BTreeSet<String>>>,
+    author: UserId,
+    message: StateMessage,
+}
+
+#[derive(Debug, Clone)]
+struct FourthTransition {
+    name: String,
+    team: Option<String>,
+    emoji: String,
+    multi_player: bool,
+    masks_role: bool,
+    links_players: bool,
     author: UserId,
     message: StateMessage,
 }
@@ -63,9 +77,11 @@ impl StateMessage {
     {
         let mut msg = self.channel_id.message(http, self.message_id).await?;
 
-        msg.edit(http, |e| e.content(content.as_ref())).await?;
-
-        msg.delete_reactions(http).await?;
+        dry_run::mutate("Editing Message and removing its Reactions", (), async {
+            msg.edit(http, |e| e.content(content.as_ref())).await?;
+            msg.delete_reactions(http).await
+        })
+        .await?;
 
         for reaction in reactions {
             msg.react(http, reaction).await?;
@@ -75,6 +91,121 @@ impl StateMessage {
     }
 }
 
+/// The maximum Number of extra Channels a single Role can read
+const MAX_EXTRA_CHANNELS: usize = 10;
+
+#[derive(Debug, PartialEq)]
+enum InvalidExtraChannel {
+    Empty,
+    /// The Channel has already been entered
+    Duplicate(String),
+    TooLong {
+        length: usize,
+    },
+    /// The Role already reads the maximum Number of extra Channels
+    LimitReached,
+    /// The Channel to remove has not been added
+    NotAdded(String),
+}
+
+impl Display for InvalidExtraChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "The Name of the Channel must not be empty"),
+            Self::Duplicate(name) => write!(f, "The Channel '{}' has already been added", name),
+            Self::TooLong { length } => write!(
+                f,
+                "The Name of the Channel is {} Characters long, but at most {} are allowed",
+                length,
+                validation::MAX_NAME_LENGTH
+            ),
+            Self::LimitReached => write!(
+                f,
+                "A Role can read at most {} extra Channels",
+                MAX_EXTRA_CHANNELS
+            ),
+            Self::NotAdded(name) => write!(f, "The Channel '{}' has not been added", name),
+        }
+    }
+}
+
+/// The extra Channels entered for the Role, which are owned by the State-Machine of a single
+/// add-role Session so that concurrent Sessions can't see each others Entries
+#[derive(Debug, Default)]
+struct ExtraChannels {
+    channels: BTreeSet<String>,
+}
+
+/// The Prefix of a Reply that removes the Channel again
+const REMOVE_PREFIX: char = '-';
+
+impl ExtraChannels {
+    /// Applies the Reply of the Mod, which either adds a Channel or removes it again if the
+    /// Reply starts with [`REMOVE_PREFIX`]
+    fn apply(&mut self, reply: &str) -> Result<(), InvalidExtraChannel> {
+        match reply.trim().strip_prefix(REMOVE_PREFIX) {
+            Some(channel) => self.remove(channel),
+            None => self.add(reply),
+        }
+    }
+
+    /// Removes the Channel that has been added before
+    fn remove(&mut self, channel: &str) -> Result<(), InvalidExtraChannel> {
+        let channel = channel.trim();
+        if channel.is_empty() {
+            return Err(InvalidExtraChannel::Empty);
+        }
+
+        if !self.channels.remove(channel) {
+            return Err(InvalidExtraChannel::NotAdded(channel.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Adds the Channel from the Reply of the Mod, unless it is not a valid extra Channel
+    fn add(&mut self, reply: &str) -> Result<(), InvalidExtraChannel> {
+        let channel = reply.trim();
+        if channel.is_empty() {
+            return Err(InvalidExtraChannel::Empty);
+        }
+        let length = channel.chars().count();
+        if length > validation::MAX_NAME_LENGTH {
+            return Err(InvalidExtraChannel::TooLong { length });
+        }
+        if self.channels.contains(channel) {
+            return Err(InvalidExtraChannel::Duplicate(channel.to_string()));
+        }
+        if self.channels.len() >= MAX_EXTRA_CHANNELS {
+            return Err(InvalidExtraChannel::LimitReached);
+        }
+
+        self.channels.insert(channel.to_string());
+        Ok(())
+    }
+
+    /// The Content for the Message after the Mod replied with the given Result
+    fn content(&self, result: Result<(), InvalidExtraChannel>) -> String {
+        let content = extra_channel_content(self.channels.iter().map(|s| s.as_str()));
+        match result {
+            Ok(_) => content,
+            Err(e) => format!("{}\n{}", e, content),
+        }
+    }
+}
+
+/// Marks the Message of an add-role Session as timed out, once its State-Machine has been
+/// discarded
+pub async fn timed_out(http: &Http, channel_id: ChannelId, message_id: MessageId) {
+    let message = StateMessage {
+        channel_id,
+        message_id,
+    };
+
+    if let Err(e) = message.update(http, "Role creation timed out", &[]).await {
+        tracing::error!("Updating timed out Message: {:?}", e);
+    }
+}
+
 fn extra_channel_content<'a, I>(channels: I) -> String
 where
     I: Iterator<Item = &'a str>,
@@ -89,11 +220,12 @@ where
     }
 
     format!(
-    "Reply to this Message with all the extra Roles whose Chat this Role should also be able to read ({})", channel_str)
+    "Reply to this Message with all the extra Roles whose Chat this Role should also be able to read, reply with {}Name to remove one again ({})", REMOVE_PREFIX, channel_str)
 }
 
 pub async fn create(
     name: String,
+    team: Option<String>,
     author: UserId,
     channel_id: ChannelId,
     ctx: &serenity::client::Context,
@@ -109,6 +241,7 @@ pub async fn create(
 
     let sm = SingleState::new(move |context, _: ()| {
         let name = name.clone();
+        let team = team.clone();
         let author = author;
 
         async move {
@@ -122,8 +255,6 @@ pub async fn create(
                 return TransitionResult::NoTransition;
             }
 
-            let emoji = reaction.emoji.to_string();
-
             let http = context.http().unwrap();
 
             let msg = StateMessage {
@@ -131,6 +262,16 @@ pub async fn create(
                 message_id: msg_id,
             };
 
+            if let Err(e) = validation::validate_emoji(&reaction.emoji) {
+                let content = format!("{}\nReact with a different Emoji to use for the Role", e);
+                if let Err(e) = msg.update(http, content, &[]).await {
+                    tracing::error!("Updating Message: {:?}", e);
+                }
+
+                return TransitionResult::NoTransition;
+            }
+            let emoji = reaction.emoji.to_string();
+
             if let Err(e) = msg
                 .update(
                     http,
@@ -145,6 +286,7 @@ pub async fn create(
 
             TransitionResult::Done(FirstTransition {
                 name: name.to_string(),
+                team,
                 emoji,
                 author,
                 message: msg,
@@ -180,6 +322,7 @@ pub async fn create(
 
             TransitionResult::Done(SecondTransition {
                 name: state.name,
+                team: state.team,
                 emoji: state.emoji,
                 multi_player,
                 message: state.message,
@@ -209,58 +352,96 @@ pub async fn create(
                 return TransitionResult::NoTransition;
             };
 
-            let content = extra_channel_content(std::iter::empty());
-            if let Err(e) = state.message.update(context.http().unwrap(), content, &[Reactions::Confirm]).await {
+            if let Err(e) = state.message.update(context.http().unwrap(), "Should every Player with this Role link two other Players at the Start of a Round, who then share their own Channel?", &[Reactions::Yes, Reactions::No]).await {
                 tracing::error!("Updating Message: {:?}", e);
                 return TransitionResult::Error(Arc::new(TransitionError::Serenity));
             }
 
             TransitionResult::Done(ThirdTransition {
                 name: state.name,
+                team: state.team,
                 emoji: state.emoji,
                 multi_player: state.multi_player,
                 masks_role: masks,
-                extra_channels: Arc::new(Mutex::new(BTreeSet::new())),
                 message: state.message,
                 author: state.author,
             })
         },
-    )).chain(SingleState::new(|context, state: ThirdTransition| async move {
+    ))
+    .chain(SingleState::new(
+        |context, state: ThirdTransition| async move {
+            let reaction = match context.event() {
+                Some(Event::AddReaction { reaction }) => reaction,
+                _ => return TransitionResult::NoTransition,
+            };
+
+            if reaction.user_id != Some(state.author) {
+                tracing::error!("Different User tried to select an option");
+                return TransitionResult::NoTransition;
+            }
+
+            let reacted_emoji = &reaction.emoji;
+
+            let links = if Reactions::Yes == reacted_emoji {
+                true
+            } else if Reactions::No == reacted_emoji {
+                false
+            } else {
+                return TransitionResult::NoTransition;
+            };
+
+            let content = extra_channel_content(std::iter::empty());
+            if let Err(e) = state.message.update(context.http().unwrap(), content, &[Reactions::Confirm]).await {
+                tracing::error!("Updating Message: {:?}", e);
+                return TransitionResult::Error(Arc::new(TransitionError::Serenity));
+            }
+
+            TransitionResult::Done(FourthTransition {
+                name: state.name,
+                team: state.team,
+                emoji: state.emoji,
+                multi_player: state.multi_player,
+                masks_role: state.masks_role,
+                links_players: links,
+                message: state.message,
+                author: state.author,
+            })
+        },
+    )).chain(WithLazyState::new(|_: &FourthTransition| ExtraChannels::default(), |context: Context, mut extra: ExtraChannels, state: FourthTransition| async move {
         match context.event() {
             Some(Event::Reply { message }) => {
                 if message.author.id != state.author {
                     tracing::error!("Different User tried to select an option");
-                    return TransitionResult::NoTransition;
+                    return (TransitionResult::NoTransition, extra);
                 }
 
-                let content = {
-                    let mut lock = state.extra_channels.lock().unwrap();
-                    lock.insert(message.content.clone());
-
-                    extra_channel_content(lock.iter().map(|s| s.as_str()))
-                };
+                let result = extra.apply(&message.content);
+                let content = extra.content(result);
 
                 let http = context.http().unwrap();
 
-                if let Err(e) = message.delete(http).await {
+                let deletion = dry_run::mutate("Removing User Reply", (), async {
+                    message.delete(http).await
+                });
+                if let Err(e) = deletion.await {
                     tracing::error!("Removing User Reply: {:?}", e);
                 }
 
                 if let Err(e) = state.message.update(http, content, &[Reactions::Confirm]).await {
                     tracing::error!("Updating Message: {:?}", e);
-                    return TransitionResult::Error(Arc::new(TransitionError::Serenity));
+                    return (TransitionResult::Error(Arc::new(TransitionError::Serenity)), extra);
                 }
 
-                TransitionResult::NoTransition
+                (TransitionResult::NoTransition, extra)
             },
             Some(Event::AddReaction { reaction }) => {
                 if reaction.user_id != Some(state.author) {
                     tracing::error!("Different User tried to select an option");
-                    return TransitionResult::NoTransition;
+                    return (TransitionResult::NoTransition, extra);
                 }
 
-                if Reactions::Confirm != &reaction.emoji {
-                    return TransitionResult::NoTransition;
+                if Reactions::Confirm != reaction.emoji {
+                    return (TransitionResult::NoTransition, extra);
                 }
 
                 let http = context.http().unwrap();
@@ -273,46 +454,165 @@ pub async fn create(
                             tracing::error!("Updating Message with Error: {:?}", e);
                         }
 
-                        return TransitionResult::Done(());
+                        return (TransitionResult::Done(()), extra);
                     }
-                    if r.iter().any(|c| c.emoji() == state.emoji.as_str()) {
+                    if r.iter().any(|c| emoji_eq(c.emoji(), &state.emoji)) {
                         let resp = format!("There already exists a Role with the Emoji: {}", state.emoji);
                         if let Err(e) = state.message.update(http, resp, &[]).await {
                             tracing::error!("Updating Message with Error: {:?}", e);
                         }
 
-                        return TransitionResult::Done(());
+                        return (TransitionResult::Done(()), extra);
                     }
                 }
 
-                let extra_channels = {
-                    let tmp = state.extra_channels.lock().unwrap();
-                    tmp.iter().map(|s| s.to_owned()).collect()
+                let extra_channels = extra.channels.iter().cloned().collect();
+                let mut new_config = WereWolfRoleConfig::new(state.name, state.emoji, state.multi_player, state.masks_role, extra_channels);
+                if let Some(team) = state.team {
+                    new_config = new_config.with_team(team);
+                }
+                if state.links_players {
+                    new_config = new_config.with_linked_players();
+                }
+
+                // Extra Channels that don't belong to any Role are allowed, but are most likely
+                // a Typo so the Mod is warned about them
+                let unmatched: Vec<String> = match storage.load_roles(context.guild_id()).await {
+                    Ok(r) => new_config
+                        .unmatched_extra_channels(r.iter().chain(std::iter::once(&new_config)))
+                        .into_iter()
+                        .map(|c| c.to_string())
+                        .collect(),
+                    Err(_) => Vec::new(),
                 };
-                let new_config = WereWolfRoleConfig::new(state.name, state.emoji, state.multi_player, state.masks_role, extra_channels);
 
                 match storage.set_role(context.guild_id(), new_config).await {
                     Ok(_) => {
                         tracing::debug!("Created new Role");
 
-                        if let Err(e) = state.message.update(http, "Successfully added Role", &[]).await {
+                        let resp = if unmatched.is_empty() {
+                            "Successfully added Role".to_string()
+                        } else {
+                            format!("Successfully added Role\nWarning: The extra Channels {} don't belong to any configured Role and will be created as standalone Channels", unmatched.join(", "))
+                        };
+                        if let Err(e) = state.message.update(http, resp, &[]).await {
                             tracing::error!("Updating message with confirmation: {:?}", e);
                         }
                     }
                     Err(e) => {
                         tracing::error!("Setting Role: {:?}", e);
 
-                        if let Err(e) = state.message.update(http, "Could not add the Role", &[]).await {
+                        let resp = format!("Could not add the Role: {}", e);
+                        if let Err(e) = state.message.update(http, resp, &[]).await {
                             tracing::error!("Updating message with confirmation: {:?}", e);
                         }
                     }
                 };
 
-                TransitionResult::Done(())
+                (TransitionResult::Done(()), extra)
             }
-            _ => TransitionResult::NoTransition,
+            _ => (TransitionResult::NoTransition, extra),
         }
     }));
 
     Ok(MessageStateMachine::new(guild_id, msg_id, sm))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interleaved_sessions() {
+        let mut first = ExtraChannels::default();
+        let mut second = ExtraChannels::default();
+
+        first.add("werewolf").unwrap();
+        second.add("seer").unwrap();
+        first.add(" witch ").unwrap();
+
+        assert_eq!(
+            vec!["werewolf", "witch"],
+            first.channels.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["seer"], second.channels.iter().collect::<Vec<_>>());
+        assert_eq!(
+            extra_channel_content(["werewolf", "witch"].iter().copied()),
+            first.content(Ok(()))
+        );
+        assert_eq!(
+            extra_channel_content(["seer"].iter().copied()),
+            second.content(Ok(()))
+        );
+    }
+
+    #[test]
+    fn empty_reply() {
+        let mut extra = ExtraChannels::default();
+
+        assert_eq!(Err(InvalidExtraChannel::Empty), extra.add(""));
+        assert_eq!(Err(InvalidExtraChannel::Empty), extra.add("  \n "));
+        assert!(extra.channels.is_empty());
+
+        let result = extra.add(" ");
+        assert_eq!(
+            format!(
+                "The Name of the Channel must not be empty\n{}",
+                extra_channel_content(std::iter::empty())
+            ),
+            extra.content(result)
+        );
+    }
+
+    #[test]
+    fn duplicate_reply() {
+        let mut extra = ExtraChannels::default();
+
+        extra.add("werewolf").unwrap();
+        assert_eq!(
+            Err(InvalidExtraChannel::Duplicate("werewolf".to_string())),
+            extra.add(" werewolf ")
+        );
+        assert_eq!(vec!["werewolf"], extra.channels.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn add_then_remove() {
+        let mut extra = ExtraChannels::default();
+
+        extra.apply("werewolf").unwrap();
+        extra.apply("wtich").unwrap();
+        extra.apply(" -wtich ").unwrap();
+        extra.apply("witch").unwrap();
+
+        assert_eq!(
+            vec!["werewolf", "witch"],
+            extra.channels.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Err(InvalidExtraChannel::NotAdded("seer".to_string())),
+            extra.apply("-seer")
+        );
+        assert_eq!(Err(InvalidExtraChannel::Empty), extra.apply("-"));
+        assert_eq!(2, extra.channels.len());
+    }
+
+    #[test]
+    fn invalid_replies() {
+        let mut extra = ExtraChannels::default();
+
+        assert_eq!(
+            Err(InvalidExtraChannel::TooLong { length: 101 }),
+            extra.add(&"a".repeat(101))
+        );
+
+        for index in 0..MAX_EXTRA_CHANNELS {
+            extra.add(&format!("channel-{}", index)).unwrap();
+        }
+        assert_eq!(
+            Err(InvalidExtraChannel::LimitReached),
+            extra.add("one-too-many")
+        );
+        assert_eq!(MAX_EXTRA_CHANNELS, extra.channels.len());
+    }
+}
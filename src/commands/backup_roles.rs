@@ -0,0 +1,53 @@
+use serenity::{
+    client::Context, framework::standard::CommandResult, http::CacheHttp, model::channel::Message,
+};
+
+use crate::{get_storage, storage::StorageBackend, util};
+
+#[tracing::instrument(skip(ctx, msg))]
+pub async fn backup_roles(ctx: &Context, msg: &Message) -> CommandResult {
+    tracing::debug!("Received backup-roles Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let reply = util::msgs::ReplyTarget::Channel(channel_id);
+    if util::mods::require_mod(ctx, guild_id, msg.author.id, reply)
+        .await
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    let data = ctx.data.read().await;
+    let storage = get_storage(&data);
+
+    let roles = match storage.load_roles(guild_id).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("Loading Roles: {:?}", e);
+
+            util::msgs::send_content(channel_id, ctx.http(), "Could not load the Roles").await;
+
+            return Ok(());
+        }
+    };
+
+    match storage.store_backup(guild_id, &roles).await {
+        Ok(_) => {
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                &format!("Created a Backup of {} Role(s)", roles.len()),
+            )
+            .await;
+        }
+        Err(e) => {
+            tracing::error!("Storing Backup: {:?}", e);
+
+            util::msgs::send_content(channel_id, ctx.http(), "Could not create the Backup").await;
+        }
+    };
+
+    Ok(())
+}
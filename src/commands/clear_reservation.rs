@@ -0,0 +1,73 @@
+use serenity::{
+    client::Context,
+    framework::standard::{Args, CommandResult},
+    http::CacheHttp,
+    model::{channel::Message, id::MessageId},
+};
+
+use crate::{sms::Reservation, util};
+
+/// Parses the Reservation to clear, which is either `pending` or the ID of the Round-Message
+fn parse_reservation(raw: &str) -> Option<Reservation> {
+    if raw.eq_ignore_ascii_case("pending") {
+        return Some(Reservation::Pending);
+    }
+
+    raw.parse::<u64>()
+        .ok()
+        .map(|id| Reservation::Active(MessageId(id)))
+}
+
+#[tracing::instrument(skip(ctx, msg, args))]
+pub async fn clear_reservation(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    tracing::debug!("Received clear-reservation Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let reply = util::msgs::ReplyTarget::Channel(channel_id);
+    if util::mods::require_mod(ctx, guild_id, msg.author.id, reply)
+        .await
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    let reservation = match args.current().and_then(parse_reservation) {
+        Some(r) => r,
+        None => {
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                "```\nMust supply the Reservation to clear\nFormat: 'clear-reservation {pending|message-id}'\n```",
+            )
+            .await;
+
+            return Ok(());
+        }
+    };
+
+    let content = if crate::SMMAP.clear_reservation(guild_id, reservation).await {
+        format!("Cleared \"{}\"", reservation)
+    } else {
+        format!("There is no \"{}\"", reservation)
+    };
+    util::msgs::send_content(channel_id, ctx.http(), &content).await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Some(Reservation::Pending), parse_reservation("Pending"));
+        assert_eq!(
+            Some(Reservation::Active(MessageId(123))),
+            parse_reservation("123")
+        );
+        assert_eq!(None, parse_reservation("other"));
+    }
+}
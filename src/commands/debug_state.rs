@@ -0,0 +1,48 @@
+use serenity::{
+    client::Context,
+    framework::standard::{Args, CommandResult},
+    http::CacheHttp,
+    model::{channel::Message, id::MessageId},
+};
+
+use crate::util;
+
+#[tracing::instrument(skip(ctx, msg, args))]
+pub async fn debug_state(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    tracing::debug!("Received debug-state Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let reply = util::msgs::ReplyTarget::Channel(channel_id);
+    if util::mods::require_mod(ctx, guild_id, msg.author.id, reply)
+        .await
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    let message_id = match args.single::<u64>() {
+        Ok(id) => MessageId(id),
+        Err(_) => {
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                "Must supply the ID of the Setup-Message",
+            )
+            .await;
+
+            return Ok(());
+        }
+    };
+
+    let info = crate::SMMAP.state_info(message_id);
+    util::msgs::send_content(
+        channel_id,
+        ctx.http(),
+        &format!("State of {}: {}", message_id, info),
+    )
+    .await;
+
+    Ok(())
+}
@@ -0,0 +1,228 @@
+use serenity::{
+    client::Context,
+    framework::standard::{Args, CommandResult},
+    http::CacheHttp,
+    model::channel::Message,
+};
+
+use crate::{
+    get_storage,
+    roles::{
+        templates::{self, TemplateError},
+        validation, WereWolfRoleConfig,
+    },
+    storage::StorageBackend,
+    util,
+};
+
+/// A Change to a single Option of a Role
+#[derive(Debug, PartialEq)]
+enum RoleEdit {
+    /// Bases the Role on the Role with the given Name, or removes its Base with `None`
+    Base(Option<String>),
+    /// Sets the Message posted in the Channel of the Role, or removes it with `None`
+    Intro(Option<String>),
+}
+
+/// Parses the Option to change together with its new Value
+fn parse_edit(option: &str, value: &str) -> Result<RoleEdit, String> {
+    match option {
+        "base" => match value {
+            "" => Err("Must supply the Name of the Role to base the Role on or 'none'".to_string()),
+            "none" => Ok(RoleEdit::Base(None)),
+            name => Ok(RoleEdit::Base(Some(name.to_string()))),
+        },
+        "intro" => match value {
+            "none" => Ok(RoleEdit::Intro(None)),
+            intro => validation::validate_intro(intro)
+                .map(|_| RoleEdit::Intro(Some(intro.to_string())))
+                .map_err(|e| e.to_string()),
+        },
+        other => Err(format!(
+            "Unknown Option '{}', expected 'base' or 'intro'",
+            other
+        )),
+    }
+}
+
+/// Applies the Edit to the stored Role with the given Name, the other Roles of the Guild are
+/// needed to check that the Base exists and that the Role is not based on itself
+fn apply_edit(
+    roles: &[WereWolfRoleConfig],
+    name: &str,
+    edit: RoleEdit,
+) -> Result<WereWolfRoleConfig, String> {
+    let role = roles
+        .iter()
+        .find(|r| r.name() == name)
+        .cloned()
+        .ok_or_else(|| format!("There is no Role \"{}\"", name))?;
+
+    let edited = match edit {
+        RoleEdit::Base(None) => role.without_base(),
+        RoleEdit::Base(Some(base)) => {
+            if !roles.iter().any(|r| r.name() == base) {
+                return Err(format!("There is no Role \"{}\" to base the Role on", base));
+            }
+            role.with_base(base)
+        }
+        RoleEdit::Intro(None) => role.without_channel_intro(),
+        RoleEdit::Intro(Some(intro)) => role.with_channel_intro(intro),
+    };
+
+    // Only a Cycle through the edited Role is rejected, other Roles may already have Bases that
+    // can't be resolved on their own
+    let updated = roles
+        .iter()
+        .map(|r| if r.name() == name { &edited } else { r })
+        .cloned()
+        .collect();
+    let (_, errors) = templates::resolve_templates(updated);
+    if let Some(e) = errors
+        .into_iter()
+        .find(|e| matches!(e, TemplateError::Cycle { role } if role == name))
+    {
+        return Err(e.to_string());
+    }
+
+    Ok(edited)
+}
+
+#[tracing::instrument(skip(ctx, msg, args))]
+pub async fn edit_role(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    tracing::debug!("Received edit-role Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let reply = util::msgs::ReplyTarget::Channel(channel_id);
+    if util::mods::require_mod(ctx, guild_id, msg.author.id, reply)
+        .await
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    let name = match args.single::<String>() {
+        Ok(n) => n,
+        Err(_) => {
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                "Must supply the Name of the Role to edit",
+            )
+            .await;
+
+            return Ok(());
+        }
+    };
+    let option = args.single::<String>().unwrap_or_default();
+    let edit = match parse_edit(&option, args.rest().trim()) {
+        Ok(e) => e,
+        Err(e) => {
+            util::msgs::send_content(channel_id, ctx.http(), &e).await;
+            return Ok(());
+        }
+    };
+
+    let data = ctx.data.read().await;
+    let storage = get_storage(&data);
+
+    let roles = match storage.load_roles(guild_id).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("Loading Roles: {:?}", e);
+
+            util::msgs::send_content(channel_id, ctx.http(), "Could not load the Roles").await;
+
+            return Ok(());
+        }
+    };
+    let edited = match apply_edit(&roles, &name, edit) {
+        Ok(r) => r,
+        Err(e) => {
+            util::msgs::send_content(channel_id, ctx.http(), &e).await;
+            return Ok(());
+        }
+    };
+
+    let content = match storage.set_role(guild_id, edited).await {
+        Ok(_) => format!("Updated the Role \"{}\"", name),
+        Err(e) => {
+            tracing::error!("Setting Role: {:?}", e);
+            format!("Could not update the Role \"{}\"", name)
+        }
+    };
+    util::msgs::send_content(channel_id, ctx.http(), &content).await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(name: &str) -> WereWolfRoleConfig {
+        WereWolfRoleConfig::new(name, "", false, false, Vec::new())
+    }
+
+    #[test]
+    fn parse_base() {
+        assert_eq!(
+            Ok(RoleEdit::Base(Some("Evil".to_string()))),
+            parse_edit("base", "Evil")
+        );
+        assert_eq!(Ok(RoleEdit::Base(None)), parse_edit("base", "none"));
+        assert!(parse_edit("base", "").is_err());
+        assert_eq!(
+            Err("Unknown Option 'colour', expected 'base' or 'intro'".to_string()),
+            parse_edit("colour", "red")
+        );
+    }
+
+    #[test]
+    fn parse_intro() {
+        assert_eq!(
+            Ok(RoleEdit::Intro(Some("You have two Potions".to_string()))),
+            parse_edit("intro", "You have two Potions")
+        );
+        assert_eq!(Ok(RoleEdit::Intro(None)), parse_edit("intro", "none"));
+        assert_eq!(
+            Err("The Channel-Intro must not be empty".to_string()),
+            parse_edit("intro", "")
+        );
+        assert!(parse_edit("intro", &"a".repeat(validation::MAX_INTRO_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn set_and_clear_base() {
+        let roles = vec![role("Evil"), role("Wolf")];
+
+        let based = apply_edit(&roles, "Wolf", RoleEdit::Base(Some("Evil".to_string()))).unwrap();
+        assert_eq!(role("Wolf").with_base("Evil"), based);
+
+        let roles = vec![role("Evil"), based];
+        assert_eq!(
+            Ok(role("Wolf")),
+            apply_edit(&roles, "Wolf", RoleEdit::Base(None))
+        );
+    }
+
+    #[test]
+    fn invalid_base() {
+        let roles = vec![role("Evil").with_base("Wolf"), role("Wolf")];
+
+        assert_eq!(
+            Err("There is no Role \"Seer\"".to_string()),
+            apply_edit(&roles, "Seer", RoleEdit::Base(None))
+        );
+        assert_eq!(
+            Err("There is no Role \"Good\" to base the Role on".to_string()),
+            apply_edit(&roles, "Wolf", RoleEdit::Base(Some("Good".to_string())))
+        );
+        assert_eq!(
+            Err("The Role 'Wolf' is based on itself".to_string()),
+            apply_edit(&roles, "Wolf", RoleEdit::Base(Some("Evil".to_string())))
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use serenity::{
+    client::Context,
+    framework::standard::{Args, CommandResult},
+    http::CacheHttp,
+    model::channel::Message,
+};
+
+use crate::{
+    get_storage,
+    roles::{templates, WereWolfRoleConfig},
+    storage::StorageBackend,
+    util,
+};
+
+/// The Roles whose Name contains the Query, ignoring the Case
+fn matching_roles<'r>(roles: &'r [WereWolfRoleConfig], query: &str) -> Vec<&'r WereWolfRoleConfig> {
+    let query = query.to_lowercase();
+
+    roles
+        .iter()
+        .filter(|r| r.name().to_lowercase().contains(&query))
+        .collect()
+}
+
+fn matches_msg(matches: &[&WereWolfRoleConfig], query: &str) -> String {
+    if matches.is_empty() {
+        return format!("No Roles matching \"{}\"", query);
+    }
+
+    let mut result = format!("Roles matching \"{}\"\n\n", query);
+
+    for role in matches.iter() {
+        result.push_str(&format!("* {}\n", role));
+    }
+
+    result
+}
+
+#[tracing::instrument(skip(ctx, msg, args))]
+pub async fn find_role(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    tracing::debug!("Received find-role Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let query = args.rest().trim();
+    if query.is_empty() {
+        util::msgs::send_content(
+            channel_id,
+            ctx.http(),
+            "Must supply the Name or Part of the Name to search for",
+        )
+        .await;
+
+        return Ok(());
+    }
+
+    let data = ctx.data.read().await;
+    let storage = get_storage(&data);
+
+    let roles = match storage.load_roles(guild_id).await {
+        Ok(r) => templates::resolve_guild_roles(guild_id, r),
+        Err(e) => {
+            tracing::error!("Loading Roles: {:?}", e);
+            util::msgs::send_content(channel_id, ctx.http(), "Could not load Roles").await;
+
+            return Ok(());
+        }
+    };
+
+    let content = matches_msg(&matching_roles(&roles, query), query);
+    util::msgs::send_content(channel_id, ctx.http(), &content).await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(name: &str) -> WereWolfRoleConfig {
+        WereWolfRoleConfig::new(name, "", false, false, Vec::new())
+    }
+
+    #[test]
+    fn case_insensitive_matches() {
+        let roles = vec![role("Werewolf"), role("White-Wolf"), role("Seer")];
+
+        let matches: Vec<&str> = matching_roles(&roles, "WOLF")
+            .into_iter()
+            .map(|r| r.name())
+            .collect();
+        assert_eq!(vec!["Werewolf", "White-Wolf"], matches);
+    }
+
+    #[test]
+    fn no_matches() {
+        let roles = vec![role("Werewolf")];
+
+        let matches = matching_roles(&roles, "Seer");
+        assert!(matches.is_empty());
+        assert_eq!("No Roles matching \"Seer\"", matches_msg(&matches, "Seer"));
+    }
+}
@@ -0,0 +1,142 @@
+use serenity::{
+    client::Context,
+    framework::standard::{Args, CommandResult},
+    http::CacheHttp,
+    model::{channel::Message, id::ChannelId, misc::Mentionable},
+};
+
+use crate::{get_storage, storage::StorageBackend, util};
+
+/// Mentions all the given Channels, separated by Commas
+pub fn channel_list(channels: &[ChannelId]) -> String {
+    channels
+        .iter()
+        .map(|c| c.mention().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn channels_msg(channels: &[ChannelId]) -> String {
+    if channels.is_empty() {
+        return "Rounds can be started in any Channel".to_string();
+    }
+
+    format!("Rounds can only be started in {}", channel_list(channels))
+}
+
+#[tracing::instrument(skip(ctx, msg, args))]
+pub async fn game_channels(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    tracing::debug!("Received game-channels Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let data = ctx.data.read().await;
+    let storage = get_storage(&data);
+
+    let mut settings = match storage.load_settings(guild_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("Loading Settings: {:?}", e);
+            util::msgs::send_content(channel_id, ctx.http(), "Could not load Settings").await;
+
+            return Ok(());
+        }
+    };
+
+    let action = match args.single::<String>() {
+        Ok(a) => a,
+        Err(_) => {
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                &channels_msg(&settings.game_channels),
+            )
+            .await;
+            return Ok(());
+        }
+    };
+
+    let reply = util::msgs::ReplyTarget::Channel(channel_id);
+    if util::mods::require_mod(ctx, guild_id, msg.author.id, reply)
+        .await
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    let target = match action.as_str() {
+        "add" | "remove" => match args
+            .single::<String>()
+            .ok()
+            .and_then(|raw| serenity::utils::parse_channel(&raw))
+        {
+            Some(id) => Some(ChannelId(id)),
+            None => {
+                util::msgs::send_content(
+                    channel_id,
+                    ctx.http(),
+                    &format!("Must mention the Channel to {}", action),
+                )
+                .await;
+
+                return Ok(());
+            }
+        },
+        _ => None,
+    };
+
+    match (action.as_str(), target) {
+        ("add", Some(target)) => {
+            if !settings.game_channels.contains(&target) {
+                settings.game_channels.push(target);
+            }
+        }
+        ("remove", Some(target)) => {
+            settings.game_channels.retain(|c| *c != target);
+        }
+        ("clear", _) => {
+            settings.game_channels.clear();
+        }
+        (other, _) => {
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                &format!(
+                    "Unknown Action '{}', expected 'add', 'remove' or 'clear'",
+                    other
+                ),
+            )
+            .await;
+
+            return Ok(());
+        }
+    };
+
+    let response = channels_msg(&settings.game_channels);
+    match storage.save_settings(guild_id, settings).await {
+        Ok(_) => {
+            util::msgs::send_content(channel_id, ctx.http(), &response).await;
+        }
+        Err(e) => {
+            tracing::error!("Saving Settings: {:?}", e);
+            util::msgs::send_content(channel_id, ctx.http(), "Could not save the Settings").await;
+        }
+    };
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channels_content() {
+        assert_eq!("Rounds can be started in any Channel", channels_msg(&[]));
+        assert_eq!(
+            "Rounds can only be started in <#1>, <#2>",
+            channels_msg(&[ChannelId(1), ChannelId(2)])
+        );
+    }
+}
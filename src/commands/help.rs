@@ -3,17 +3,124 @@ use serenity::{
     model::channel::Message, utils::Color,
 };
 
-const COMMANDS: [(&str, &str); 4] = [
-    ("werewolf", "Starts a new Werewolf Round"),
+const COMMANDS: [(&str, &str); 30] = [
     (
-        "add-role {name} {emoji} {multi-player} {masks role} {extra channels}",
-        "Adds a new Werewolf Role based on the given Options",
+        "werewolf [@Player=Role ...] [spectators=on|off] [channels=public|private] [\"label=Name\"]",
+        "Starts a new Werewolf Round, optionally always assigning the given Roles to the mentioned Players, hiding the Round from dead Players, letting everyone read the Role-Channels and labeling the Round",
+    ),
+    (
+        "repeat [@Player=Role ...] [spectators=on|off] [channels=public|private] [\"label=Name\"]",
+        "Starts a new Werewolf Round like `werewolf`, but selects the Roles of the last Round and starts with the same Counts if they still fit the Players",
+    ),
+    (
+        "add-role {name} [team]",
+        "Adds a new Werewolf Role, the remaining Options are configured interactively",
     ),
     (
         "remove-role {name}",
         "Removes the Werewolf Role with the given Name again",
     ),
+    (
+        "edit-role {name} base {role|none}",
+        "Bases the Werewolf Role on another Role, whose Team and extra Channels it inherits, or removes its Base again",
+    ),
+    (
+        "edit-role {name} intro {text|none}",
+        "Sets the Message posted in the Channel of the Werewolf Role at the Start of a Round, or removes it again",
+    ),
     ("list-roles", "Lists all the configured Werewolf Roles"),
+    (
+        "find-role {query}",
+        "Lists the configured Werewolf Roles whose Name contains the Query",
+    ),
+    (
+        "stats",
+        "Shows some Statistics about the Bot and this Server",
+    ),
+    (
+        "settings [{name} {value}]",
+        "Shows the Settings for this Server or updates the given Setting",
+    ),
+    (
+        "import-roles-url {url}",
+        "Imports the JSON-List of Roles from the URL, replacing existing Roles with the same Name after confirming it",
+    ),
+    (
+        "backup-roles",
+        "Stores a Backup of all the configured Roles in the Settings-Channel",
+    ),
+    (
+        "restore-roles",
+        "Replaces the configured Roles with the most recent Backup, after confirming it",
+    ),
+    (
+        "debug-state {message-id}",
+        "Shows the current State of the Round or Dialog belonging to the given Message",
+    ),
+    (
+        "reservations",
+        "Lists the Rounds on this Server that count towards the Round-Limit",
+    ),
+    (
+        "clear-reservation {pending|message-id}",
+        "Forcefully releases a stuck Round-Reservation",
+    ),
+    (
+        "self-test",
+        "Checks whether the Bot is currently able to set up a Round on this Server",
+    ),
+    (
+        "reassign @Player {role}",
+        "Gives the Player a different Role in their running Round and moves them to the Channels of the new Role",
+    ),
+    (
+        "round-history",
+        "Lists the most recent finished Rounds on this Server",
+    ),
+    (
+        "validate-set {players} {role}[={count}] ...",
+        "Checks whether the given Roles could be distributed among the given Number of Players",
+    ),
+    (
+        "broadcast {text}",
+        "Posts the Message in all the Role-Channels of the running Rounds you moderate",
+    ),
+    (
+        "next-phase",
+        "Moves the running Rounds you moderate on to the next Day/Night and posts the next Narration-Line",
+    ),
+    (
+        "proceed",
+        "Ends the Registration or Ready-Check of the Round in this Channel, as if it had been confirmed",
+    ),
+    (
+        "narration [set {lines}|clear]",
+        "Shows, sets or clears the Narration-Script, one Line per Phase-Change",
+    ),
+    (
+        "reveal [#channel]",
+        "Reveals the Roles and Teams of all Players in the most recently finished Round",
+    ),
+    (
+        "game-channels [add #channel|remove #channel|clear]",
+        "Shows or changes the Channels in which new Rounds can be started, anywhere if there are none",
+    ),
+    (
+        "permissions",
+        "Shows which of the Permissions needed by the Bot it has on this Server",
+    ),
+    (
+        "start-vote",
+        "Starts an anonymous Vote between the alive Players of the running Rounds you moderate",
+    ),
+    (
+        "close-vote",
+        "Closes the open Votes of the running Rounds you moderate, publishes their Results and sends you who voted for whom",
+    ),
+    (
+        "test-dm",
+        "Sends you a Direct Message, to check that you can receive the Direct Messages of the Bot",
+    ),
 ];
 
 fn generate_help_message(m: &mut CreateMessage) {
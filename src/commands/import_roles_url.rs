@@ -0,0 +1,476 @@
+use std::{
+    collections::BTreeSet,
+    convert::TryFrom,
+    fmt::Display,
+    net::{Ipv4Addr, Ipv6Addr},
+    time::Duration,
+};
+
+use serenity::{
+    client::Context,
+    framework::standard::{Args, CommandResult},
+    http::CacheHttp,
+    model::channel::{Message, ReactionType},
+};
+
+use crate::{
+    get_storage,
+    roles::{validation, WereWolfRoleConfig},
+    storage::StorageBackend,
+    util,
+};
+
+/// The maximum Size of a Role-Set that can be imported
+const MAX_IMPORT_BYTES: usize = 256 * 1024;
+/// How long loading the Role-Set may take at most
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// How many Redirects are followed at most while loading the Role-Set
+const MAX_REDIRECTS: usize = 5;
+/// How long the Mod has to confirm the Import
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+/// The Content-Types a Role-Set can be served with, as most Sites serve raw Files as plain Text
+const ACCEPTED_CONTENT_TYPES: [&str; 2] = ["application/json", "text/plain"];
+
+#[derive(Debug, PartialEq)]
+enum ImportError {
+    /// The URL could not be parsed, does not use HTTPS or points to a local Address
+    InvalidUrl,
+    /// The Role-Set could not be loaded
+    Network(String),
+    /// The Server responded with an unsuccessful Status-Code
+    Status(u16),
+    /// The Role-Set is served with a Content-Type that is not accepted
+    ContentType(String),
+    TooLarge,
+    /// The Role-Set is not a valid List of Roles
+    InvalidJson(String),
+    /// One of the Roles can't be used
+    InvalidRole {
+        name: String,
+        reason: String,
+    },
+    /// The same Role is part of the Role-Set more than once
+    DuplicateRole(String),
+    Empty,
+}
+
+impl Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidUrl => write!(
+                f,
+                "Expected a valid URL starting with 'https://' that points to a public Server"
+            ),
+            Self::Network(e) => write!(f, "Could not load the Roles: {}", e),
+            Self::Status(code) => write!(f, "The Server responded with the Status {}", code),
+            Self::ContentType(content_type) => write!(
+                f,
+                "Expected JSON, but the Server responded with '{}'",
+                content_type
+            ),
+            Self::TooLarge => write!(
+                f,
+                "The Roles are larger than the allowed {} KiB",
+                MAX_IMPORT_BYTES / 1024
+            ),
+            Self::InvalidJson(e) => write!(f, "Expected a JSON-List of Roles: {}", e),
+            Self::InvalidRole { name, reason } => {
+                write!(f, "The Role '{}' can't be imported: {}", name, reason)
+            }
+            Self::DuplicateRole(name) => {
+                write!(f, "The Role '{}' is part of the Roles more than once", name)
+            }
+            Self::Empty => write!(f, "There are no Roles to import"),
+        }
+    }
+}
+
+/// Whether the Address is only reachable from the Host of the Bot itself or its local Network
+fn is_local_v4(addr: &Ipv4Addr) -> bool {
+    addr.is_loopback()
+        || addr.is_private()
+        || addr.is_link_local()
+        || addr.is_unspecified()
+        || addr.is_broadcast()
+}
+
+/// Whether the Address is only reachable from the Host of the Bot itself or its local Network
+fn is_local_v6(addr: &Ipv6Addr) -> bool {
+    if let Some(v4) = addr.to_ipv4() {
+        if is_local_v4(&v4) {
+            return true;
+        }
+    }
+
+    let first = addr.segments()[0];
+    addr.is_loopback()
+        || addr.is_unspecified()
+        // Unique-Local Addresses (fc00::/7)
+        || first & 0xfe00 == 0xfc00
+        // Link-Local Addresses (fe80::/10)
+        || first & 0xffc0 == 0xfe80
+}
+
+/// Whether the Role-Set may be loaded from the URL, only HTTPS to public Servers is allowed so
+/// the Command can't be used to reach Services on the Host of the Bot
+fn allowed_url(url: &reqwest::Url) -> bool {
+    if url.scheme() != "https" {
+        return false;
+    }
+
+    let host = match url.host_str() {
+        Some(h) => h,
+        None => return false,
+    };
+
+    if let Some(v6) = host.strip_prefix('[').and_then(|h| h.strip_suffix(']')) {
+        return v6.parse().map(|a| !is_local_v6(&a)).unwrap_or(false);
+    }
+    if let Ok(v4) = host.parse() {
+        return !is_local_v4(&v4);
+    }
+
+    let domain = host.trim_end_matches('.').to_ascii_lowercase();
+    domain != "localhost" && !domain.ends_with(".localhost")
+}
+
+/// Parses the URL of the Role-Set, only HTTPS to public Servers is allowed
+fn parse_url(raw: &str) -> Result<reqwest::Url, ImportError> {
+    let url = reqwest::Url::parse(raw.trim_matches(|c| c == '<' || c == '>'))
+        .map_err(|_| ImportError::InvalidUrl)?;
+    if !allowed_url(&url) {
+        return Err(ImportError::InvalidUrl);
+    }
+
+    Ok(url)
+}
+
+/// Only follows Redirects to URLs the Role-Set could also be loaded from directly
+fn redirect_policy() -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(|attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if !allowed_url(attempt.url()) {
+            attempt.error("redirected to a URL that is not allowed")
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
+/// Checks that the Role-Set is served with one of the accepted Content-Types, ignoring
+/// Parameters like the Charset
+fn check_content_type(content_type: Option<&str>) -> Result<(), ImportError> {
+    let raw = content_type.unwrap_or_default();
+    let mime = raw.split(';').next().unwrap_or_default().trim();
+
+    if ACCEPTED_CONTENT_TYPES
+        .iter()
+        .any(|accepted| mime.eq_ignore_ascii_case(accepted))
+    {
+        Ok(())
+    } else {
+        Err(ImportError::ContentType(raw.to_string()))
+    }
+}
+
+/// Parses and validates the Roles of the Role-Set
+fn parse_roles(body: &[u8]) -> Result<Vec<WereWolfRoleConfig>, ImportError> {
+    let roles: Vec<WereWolfRoleConfig> =
+        serde_json::from_slice(body).map_err(|e| ImportError::InvalidJson(e.to_string()))?;
+    if roles.is_empty() {
+        return Err(ImportError::Empty);
+    }
+
+    let mut names = BTreeSet::new();
+    for role in roles.iter() {
+        let invalid = |reason: String| ImportError::InvalidRole {
+            name: role.name().to_string(),
+            reason,
+        };
+
+        validation::validate_name(role.name()).map_err(|e| invalid(e.to_string()))?;
+        let emoji = ReactionType::try_from(role.emoji())
+            .map_err(|_| invalid("The Emoji is not valid".to_string()))?;
+        validation::validate_emoji(&emoji).map_err(|e| invalid(e.to_string()))?;
+        if let Some(intro) = role.channel_intro() {
+            validation::validate_intro(intro).map_err(|e| invalid(e.to_string()))?;
+        }
+
+        if !names.insert(role.name()) {
+            return Err(ImportError::DuplicateRole(role.name().to_string()));
+        }
+    }
+
+    Ok(roles)
+}
+
+/// Loads the Role-Set from the URL, without ever reading more than the allowed Size
+async fn fetch(url: reqwest::Url) -> Result<Vec<u8>, ImportError> {
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .redirect(redirect_policy())
+        .build()
+        .map_err(|e| ImportError::Network(e.to_string()))?;
+
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| ImportError::Network(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(ImportError::Status(response.status().as_u16()));
+    }
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok());
+    check_content_type(content_type)?;
+
+    if response.content_length().unwrap_or(0) > MAX_IMPORT_BYTES as u64 {
+        return Err(ImportError::TooLarge);
+    }
+
+    // The Content-Length is not always given or correct, so the Size is also checked while
+    // reading the Body
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| ImportError::Network(e.to_string()))?
+    {
+        if body.len() + chunk.len() > MAX_IMPORT_BYTES {
+            return Err(ImportError::TooLarge);
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(body)
+}
+
+#[tracing::instrument(skip(ctx, msg, args))]
+pub async fn import_roles_url(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    tracing::debug!("Received import-roles-url Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let reply = util::msgs::ReplyTarget::Channel(channel_id);
+    if util::mods::require_mod(ctx, guild_id, msg.author.id, reply)
+        .await
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    let roles = match args
+        .single::<String>()
+        .map_err(|_| ImportError::InvalidUrl)
+        .and_then(|raw| parse_url(&raw))
+    {
+        Ok(url) => fetch(url).await.and_then(|body| parse_roles(&body)),
+        Err(e) => Err(e),
+    };
+    let roles = match roles {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("Importing Roles: {:?}", e);
+            util::msgs::send_content(channel_id, ctx.http(), &e.to_string()).await;
+
+            return Ok(());
+        }
+    };
+
+    let data = ctx.data.read().await;
+    let storage = get_storage(&data);
+
+    let current = match storage.load_roles(guild_id).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("Loading Roles: {:?}", e);
+
+            util::msgs::send_content(channel_id, ctx.http(), "Could not load the Roles").await;
+
+            return Ok(());
+        }
+    };
+
+    let replaced = roles
+        .iter()
+        .filter(|r| current.iter().any(|c| c.name() == r.name()))
+        .count();
+    let content = format!(
+        "This will import {} Role(s), {} of them replace existing Roles with the same Name",
+        roles.len(),
+        replaced
+    );
+    let confirmed = match util::confirm::confirm(
+        ctx.http(),
+        channel_id,
+        msg.author.id,
+        &content,
+        CONFIRM_TIMEOUT,
+    )
+    .await
+    {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::error!("Asking for Import-Confirmation: {:?}", e);
+            return Ok(());
+        }
+    };
+    if !confirmed {
+        util::msgs::send_content(channel_id, ctx.http(), "The Roles were not imported").await;
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for role in roles.iter().cloned() {
+        if let Err(e) = storage.set_role(guild_id, role).await {
+            tracing::error!("Importing Role: {:?}", e);
+            failed += 1;
+        }
+    }
+
+    let content = if failed == 0 {
+        format!("Imported {} Role(s)", roles.len())
+    } else {
+        format!(
+            "Imported {} Role(s), {} Role(s) could not be imported",
+            roles.len() - failed,
+            failed
+        )
+    };
+    util::msgs::send_content(channel_id, ctx.http(), &content).await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_https() {
+        assert!(parse_url("https://example.com/roles.json").is_ok());
+        // Discord wraps Links in Angle-Brackets to suppress their Embeds
+        assert!(parse_url("<https://example.com/roles.json>").is_ok());
+
+        assert_eq!(
+            Err(ImportError::InvalidUrl),
+            parse_url("http://example.com/roles.json")
+        );
+        assert_eq!(
+            Err(ImportError::InvalidUrl),
+            parse_url("file:///etc/passwd")
+        );
+        assert_eq!(Err(ImportError::InvalidUrl), parse_url("roles.json"));
+    }
+
+    #[test]
+    fn only_public_hosts() {
+        assert!(parse_url("https://93.184.216.34/roles.json").is_ok());
+        assert!(parse_url("https://[2606:2800:220:1::]/roles.json").is_ok());
+
+        for raw in [
+            "https://localhost/roles.json",
+            "https://api.localhost./roles.json",
+            "https://127.0.0.1:8080/roles.json",
+            "https://10.0.0.1/roles.json",
+            "https://192.168.1.1/roles.json",
+            "https://169.254.169.254/latest/meta-data",
+            "https://0.0.0.0/roles.json",
+            "https://[::1]/roles.json",
+            "https://[::ffff:127.0.0.1]/roles.json",
+            "https://[fd00::1]/roles.json",
+            "https://[fe80::1]/roles.json",
+        ]
+        .iter()
+        {
+            assert_eq!(Err(ImportError::InvalidUrl), parse_url(raw), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn content_types() {
+        assert_eq!(Ok(()), check_content_type(Some("application/json")));
+        assert_eq!(
+            Ok(()),
+            check_content_type(Some("text/plain; charset=utf-8"))
+        );
+
+        assert_eq!(
+            Err(ImportError::ContentType("text/html".to_string())),
+            check_content_type(Some("text/html"))
+        );
+        assert_eq!(
+            Err(ImportError::ContentType(String::new())),
+            check_content_type(None)
+        );
+    }
+
+    #[test]
+    fn valid_roles() {
+        let roles = vec![
+            WereWolfRoleConfig::new("Werewolf", "🐺", true, false, Vec::new()),
+            WereWolfRoleConfig::new("Seer", "🔮", false, false, Vec::new()),
+        ];
+        let body = serde_json::to_vec(&roles).unwrap();
+
+        assert_eq!(Ok(roles), parse_roles(&body));
+    }
+
+    #[test]
+    fn invalid_roles() {
+        assert!(matches!(
+            parse_roles(b"<html></html>"),
+            Err(ImportError::InvalidJson(_))
+        ));
+        assert_eq!(Err(ImportError::Empty), parse_roles(b"[]"));
+
+        let invalid_name = vec![WereWolfRoleConfig::new(
+            "Village Idiot",
+            "🤪",
+            false,
+            false,
+            Vec::new(),
+        )];
+        assert!(matches!(
+            parse_roles(&serde_json::to_vec(&invalid_name).unwrap()),
+            Err(ImportError::InvalidRole { .. })
+        ));
+
+        let invalid_emoji = vec![WereWolfRoleConfig::new(
+            "Seer",
+            "seer",
+            false,
+            false,
+            Vec::new(),
+        )];
+        assert!(matches!(
+            parse_roles(&serde_json::to_vec(&invalid_emoji).unwrap()),
+            Err(ImportError::InvalidRole { .. })
+        ));
+
+        let long_intro = vec![
+            WereWolfRoleConfig::new("Seer", "🔮", false, false, Vec::new())
+                .with_channel_intro("a".repeat(2001)),
+        ];
+        assert_eq!(
+            Err(ImportError::InvalidRole {
+                name: "Seer".to_string(),
+                reason: "The Channel-Intro is 2001 Characters long, but at most 1000 are allowed"
+                    .to_string()
+            }),
+            parse_roles(&serde_json::to_vec(&long_intro).unwrap())
+        );
+
+        let seer = WereWolfRoleConfig::new("Seer", "🔮", false, false, Vec::new());
+        let duplicate = vec![seer.clone(), seer];
+        assert_eq!(
+            Err(ImportError::DuplicateRole("Seer".to_string())),
+            parse_roles(&serde_json::to_vec(&duplicate).unwrap())
+        );
+    }
+}
@@ -2,7 +2,12 @@ use serenity::{
     client::Context, framework::standard::CommandResult, http::CacheHttp, model::channel::Message,
 };
 
-use crate::{get_storage, roles::WereWolfRoleConfig, storage::StorageBackend, util};
+use crate::{
+    get_storage,
+    roles::{templates, WereWolfRoleConfig},
+    storage::StorageBackend,
+    util,
+};
 
 fn role_list_msg(roles: &[WereWolfRoleConfig]) -> String {
     if roles.is_empty() {
@@ -31,7 +36,7 @@ pub async fn list_roles(ctx: &Context, msg: &Message) -> CommandResult {
     let roles_result = storage.load_roles(guild_id).await;
 
     let roles = match roles_result {
-        Ok(r) => r,
+        Ok(r) => templates::resolve_guild_roles(guild_id, r),
         Err(e) => {
             tracing::error!("Loading Roles: {:?}", e);
             util::msgs::send_content(channel_id, ctx.http(), "Could not load Roles").await;
@@ -0,0 +1,138 @@
+use serenity::{
+    client::Context,
+    framework::standard::{Args, CommandResult},
+    http::CacheHttp,
+    model::channel::Message,
+};
+
+use crate::{get_storage, settings::GuildSettings, storage::StorageBackend, util};
+
+/// Parses the Narration-Script, where every non-empty Line is used for one Phase-Change
+fn parse_script(raw: &str) -> Vec<String> {
+    raw.lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .map(|l| l.to_string())
+        .collect()
+}
+
+fn script_msg(settings: &GuildSettings) -> String {
+    if settings.narration.is_empty() {
+        return "There is no Narration-Script configured".to_string();
+    }
+
+    let mut result = "Narration-Script\n\n".to_owned();
+    for (index, line) in settings.narration.iter().enumerate() {
+        result.push_str(&format!("{}. {}\n", index + 1, line));
+    }
+    result
+}
+
+#[tracing::instrument(skip(ctx, msg, args))]
+pub async fn narration(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    tracing::debug!("Received narration Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let data = ctx.data.read().await;
+    let storage = get_storage(&data);
+
+    let mut settings = match storage.load_settings(guild_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("Loading Settings: {:?}", e);
+            util::msgs::send_content(channel_id, ctx.http(), "Could not load Settings").await;
+
+            return Ok(());
+        }
+    };
+
+    // The Script starts on a new Line, so the Action can't be split off using the normal
+    // Argument-Delimiters
+    let raw = args.rest().trim();
+    if raw.is_empty() {
+        util::msgs::send_content(channel_id, ctx.http(), &script_msg(&settings)).await;
+        return Ok(());
+    }
+    let (action, script) = raw.split_once(char::is_whitespace).unwrap_or((raw, ""));
+
+    let reply = util::msgs::ReplyTarget::Channel(channel_id);
+    if util::mods::require_mod(ctx, guild_id, msg.author.id, reply)
+        .await
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    let response = match action {
+        "set" => {
+            let lines = parse_script(script);
+            if lines.is_empty() {
+                util::msgs::send_content(
+                    channel_id,
+                    ctx.http(),
+                    "Must supply the Narration-Lines, one per Line of the Message",
+                )
+                .await;
+
+                return Ok(());
+            }
+
+            let response = format!("Stored a Narration-Script with {} Line(s)", lines.len());
+            settings.narration = lines;
+            response
+        }
+        "clear" => {
+            settings.narration.clear();
+            "Cleared the Narration-Script".to_string()
+        }
+        other => {
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                &format!("Unknown Action '{}', expected 'set' or 'clear'", other),
+            )
+            .await;
+
+            return Ok(());
+        }
+    };
+
+    if settings.exceeds_storage_limit() {
+        util::msgs::send_content(
+            channel_id,
+            ctx.http(),
+            "The Narration-Script is too long to be stored, shorten its Lines or use fewer Lines",
+        )
+        .await;
+
+        return Ok(());
+    }
+
+    match storage.save_settings(guild_id, settings).await {
+        Ok(_) => {
+            util::msgs::send_content(channel_id, ctx.http(), &response).await;
+        }
+        Err(e) => {
+            tracing::error!("Saving Settings: {:?}", e);
+            util::msgs::send_content(channel_id, ctx.http(), "Could not save the Settings").await;
+        }
+    };
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_lines() {
+        assert_eq!(
+            vec!["The Night falls".to_string(), "The Sun rises".to_string()],
+            parse_script("\n  The Night falls \n\nThe Sun rises\n")
+        );
+        assert!(parse_script(" \n ").is_empty());
+    }
+}
@@ -0,0 +1,48 @@
+use serenity::{
+    client::Context, framework::standard::CommandResult, http::CacheHttp, model::channel::Message,
+};
+
+use crate::{get_storage, messages, util};
+
+#[tracing::instrument(skip(ctx, msg))]
+pub async fn next_phase(ctx: &Context, msg: &Message) -> CommandResult {
+    tracing::debug!("Received next-phase Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let data = ctx.data.read().await;
+    let storage = get_storage(&data);
+
+    // Every running Round checks on its own, whether the Author is one of its Moderators, and
+    // reports back once it has moved on to the next Phase
+    let (outcome_tx, mut outcome_rx) = tokio::sync::mpsc::unbounded_channel();
+    for round in crate::SMMAP.running_games(guild_id).await {
+        let context = messages::Context::new(
+            Some(ctx.http.clone()),
+            Some(messages::Event::NextPhase {
+                sender: msg.author.id,
+                outcome: outcome_tx.clone(),
+            }),
+            Some(storage.clone()),
+            guild_id,
+        );
+
+        crate::SMMAP.update(round, context).await;
+    }
+    drop(outcome_tx);
+
+    let mut outcomes = Vec::new();
+    while let Some(outcome) = outcome_rx.recv().await {
+        outcomes.push(outcome);
+    }
+
+    let content = if outcomes.is_empty() {
+        "You are not a Moderator of any running Round".to_string()
+    } else {
+        outcomes.join("\n")
+    };
+    util::msgs::send_content(channel_id, ctx.http(), &content).await;
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use serenity::{
+    client::Context, framework::standard::CommandResult, http::CacheHttp, model::channel::Message,
+    utils::Color,
+};
+
+use crate::util;
+
+/// The Checklist-Entry for a single Permission
+fn checklist_line(name: &str, granted: bool) -> String {
+    if granted {
+        format!("✅ {}", name)
+    } else {
+        format!("❌ {}", name)
+    }
+}
+
+#[tracing::instrument(skip(ctx, msg))]
+pub async fn permissions(ctx: &Context, msg: &Message) -> CommandResult {
+    tracing::debug!("Received permissions Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let bot_id = ctx.cache.current_user_id().await;
+    let permissions = match util::permissions::guild_permissions(guild_id, bot_id, ctx.http()).await
+    {
+        Ok(p) => p,
+        Err(e) => {
+            tracing::error!("Loading Bot-Permissions: {:?}", e);
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                "Could not load the Permissions of the Bot",
+            )
+            .await;
+
+            return Ok(());
+        }
+    };
+
+    let lines: Vec<String> = util::permissions::checklist(permissions)
+        .into_iter()
+        .map(|(name, granted)| checklist_line(name, granted))
+        .collect();
+    let (required, messages) = lines.split_at(util::permissions::REQUIRED_PERMISSIONS.len());
+    let missing = util::permissions::missing_permissions(permissions);
+    let summary = if missing.is_empty() {
+        "All the Permissions needed to start a Round are granted".to_string()
+    } else {
+        format!(
+            "Rounds can't be started until these Permissions are granted: {}",
+            missing.join(", ")
+        )
+    };
+
+    if let Err(e) = channel_id
+        .send_message(ctx.http(), |m| {
+            m.embed(|e| {
+                e.title("Permissions")
+                    .color(Color::from_rgb(130, 10, 10))
+                    .description(summary)
+                    .field("Needed for Rounds", required.join("\n"), true)
+                    .field("Needed for Messages", messages.join("\n"), true)
+            })
+        })
+        .await
+    {
+        tracing::error!("Sending Permissions-Message: {:?}", e);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checklist_lines() {
+        assert_eq!("✅ Manage Roles", checklist_line("Manage Roles", true));
+        assert_eq!("❌ Manage Roles", checklist_line("Manage Roles", false));
+    }
+}
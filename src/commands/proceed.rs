@@ -0,0 +1,49 @@
+use serenity::{
+    client::Context, framework::standard::CommandResult, http::CacheHttp, model::channel::Message,
+};
+
+use crate::{get_storage, messages, util};
+
+#[tracing::instrument(skip(ctx, msg))]
+pub async fn proceed(ctx: &Context, msg: &Message) -> CommandResult {
+    tracing::debug!("Received proceed Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let data = ctx.data.read().await;
+    let storage = get_storage(&data);
+
+    // Only the Round that is being set up in this Channel reacts to the Event and checks on its
+    // own, whether the Author is one of its Moderators
+    let (outcome_tx, mut outcome_rx) = tokio::sync::mpsc::unbounded_channel();
+    for round in crate::SMMAP.running_games(guild_id).await {
+        let context = messages::Context::new(
+            Some(ctx.http.clone()),
+            Some(messages::Event::Proceed {
+                sender: msg.author.id,
+                channel: channel_id,
+                outcome: outcome_tx.clone(),
+            }),
+            Some(storage.clone()),
+            guild_id,
+        );
+
+        crate::SMMAP.update(round, context).await;
+    }
+    drop(outcome_tx);
+
+    let mut outcomes = Vec::new();
+    while let Some(outcome) = outcome_rx.recv().await {
+        outcomes.push(outcome);
+    }
+
+    let content = if outcomes.is_empty() {
+        "There is no Round waiting for its Registration or Ready-Check in this Channel".to_string()
+    } else {
+        outcomes.join("\n")
+    };
+    util::msgs::send_content(channel_id, ctx.http(), &content).await;
+
+    Ok(())
+}
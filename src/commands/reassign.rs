@@ -0,0 +1,113 @@
+use serenity::{
+    client::Context,
+    framework::standard::{Args, CommandResult},
+    http::CacheHttp,
+    model::{channel::Message, id::UserId},
+};
+
+use crate::{get_storage, messages, roles::templates, storage::StorageBackend, util};
+
+#[tracing::instrument(skip(ctx, msg, args))]
+pub async fn reassign(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    tracing::debug!("Received reassign Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let reply = util::msgs::ReplyTarget::Channel(channel_id);
+    if util::mods::require_mod(ctx, guild_id, msg.author.id, reply)
+        .await
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    let player = match args
+        .single::<String>()
+        .ok()
+        .and_then(|raw| serenity::utils::parse_username(&raw))
+    {
+        Some(id) => UserId(id),
+        None => {
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                "Must mention the Player whose Role should be changed",
+            )
+            .await;
+
+            return Ok(());
+        }
+    };
+    let role_name = args.rest().trim();
+    if role_name.is_empty() {
+        util::msgs::send_content(
+            channel_id,
+            ctx.http(),
+            "Must supply the Name of the new Role",
+        )
+        .await;
+
+        return Ok(());
+    }
+
+    let data = ctx.data.read().await;
+    let storage = get_storage(&data);
+
+    let roles = match storage.load_roles(guild_id).await {
+        Ok(r) => templates::resolve_guild_roles(guild_id, r),
+        Err(e) => {
+            tracing::error!("Loading Roles: {:?}", e);
+
+            util::msgs::send_content(channel_id, ctx.http(), "Could not load the Roles").await;
+
+            return Ok(());
+        }
+    };
+    let role = match roles.into_iter().find(|r| r.name() == role_name) {
+        Some(r) => r,
+        None => {
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                &format!("There is no Role \"{}\"", role_name),
+            )
+            .await;
+
+            return Ok(());
+        }
+    };
+
+    // Every running Round checks on its own, whether the Player takes part in it, and reports
+    // back once it has handled the Reassignment
+    let (outcome_tx, mut outcome_rx) = tokio::sync::mpsc::unbounded_channel();
+    for round in crate::SMMAP.running_games(guild_id).await {
+        let context = messages::Context::new(
+            Some(ctx.http.clone()),
+            Some(messages::Event::Reassign {
+                player,
+                role: role.clone(),
+                outcome: outcome_tx.clone(),
+            }),
+            Some(storage.clone()),
+            guild_id,
+        );
+
+        crate::SMMAP.update(round, context).await;
+    }
+    drop(outcome_tx);
+
+    let mut outcomes = Vec::new();
+    while let Some(outcome) = outcome_rx.recv().await {
+        outcomes.push(outcome);
+    }
+
+    let content = if outcomes.is_empty() {
+        format!("<@{}> is not a Player in any running Round", player.0)
+    } else {
+        outcomes.join("\n")
+    };
+    util::msgs::send_content(channel_id, ctx.http(), &content).await;
+
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serenity::{
     client::Context,
     framework::standard::{Args, CommandResult},
@@ -5,7 +7,10 @@ use serenity::{
     model::channel::Message,
 };
 
-use crate::{get_storage, storage::StorageBackend, util, MOD_ROLE_NAME};
+use crate::{get_storage, storage::StorageBackend, util};
+
+/// How long the Mod has to confirm removing the Role
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
 
 #[tracing::instrument(skip(ctx, msg, args))]
 pub async fn remove_role(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
@@ -14,30 +19,11 @@ pub async fn remove_role(ctx: &Context, msg: &Message, args: Args) -> CommandRes
     let channel_id = msg.channel_id;
     let guild_id = msg.guild_id.unwrap();
 
-    let server_mods = match util::mods::load_mods(ctx, guild_id, MOD_ROLE_NAME).await {
-        Ok(m) => m,
-        Err(e) => {
-            tracing::error!("Loading Mods: {:?}", e);
-
-            util::msgs::send_content(channel_id, ctx.http(), "Could not load Mods for the Server")
-                .await;
-
-            return Ok(());
-        }
-    };
-    if !server_mods.contains(&msg.author.id) {
-        tracing::error!("Non Mod User executed the Command");
-
-        util::msgs::send_content(
-            channel_id,
-            ctx.http(),
-            &format!(
-                "Only Users with the '{}'-Role can use this Command",
-                MOD_ROLE_NAME
-            ),
-        )
-        .await;
-
+    let reply = util::msgs::ReplyTarget::Channel(channel_id);
+    if util::mods::require_mod(ctx, guild_id, msg.author.id, reply)
+        .await
+        .is_none()
+    {
         return Ok(());
     }
 
@@ -55,6 +41,33 @@ pub async fn remove_role(ctx: &Context, msg: &Message, args: Args) -> CommandRes
         }
     };
 
+    let content = format!("This will remove the Role \"{}\"", role_name);
+    let confirmed = util::confirm::confirm(
+        ctx.http(),
+        channel_id,
+        msg.author.id,
+        &content,
+        CONFIRM_TIMEOUT,
+    )
+    .await;
+    match confirmed {
+        Ok(true) => {}
+        Ok(false) => {
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                &format!("The Role \"{}\" was not removed", role_name),
+            )
+            .await;
+
+            return Ok(());
+        }
+        Err(e) => {
+            tracing::error!("Asking for Removal-Confirmation: {:?}", e);
+            return Ok(());
+        }
+    };
+
     let data = ctx.data.read().await;
     let storage = get_storage(&data);
 
@@ -0,0 +1,35 @@
+use serenity::{
+    client::Context, framework::standard::CommandResult, http::CacheHttp, model::channel::Message,
+};
+
+use crate::util;
+
+#[tracing::instrument(skip(ctx, msg))]
+pub async fn reservations(ctx: &Context, msg: &Message) -> CommandResult {
+    tracing::debug!("Received reservations Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let reply = util::msgs::ReplyTarget::Channel(channel_id);
+    if util::mods::require_mod(ctx, guild_id, msg.author.id, reply)
+        .await
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    let reservations = crate::SMMAP.reservations(guild_id).await;
+    if reservations.is_empty() {
+        util::msgs::send_content(channel_id, ctx.http(), "There are no running Rounds").await;
+        return Ok(());
+    }
+
+    let mut content = "Rounds\n\n".to_owned();
+    for reservation in reservations {
+        content.push_str(&format!("* {}\n", reservation));
+    }
+    util::msgs::send_content(channel_id, ctx.http(), &content).await;
+
+    Ok(())
+}
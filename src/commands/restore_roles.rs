@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use serenity::{
+    client::Context,
+    framework::standard::CommandResult,
+    http::CacheHttp,
+    model::{channel::Message, id::GuildId},
+};
+
+use crate::{
+    get_storage,
+    roles::WereWolfRoleConfig,
+    storage::{Storage, StorageBackend},
+    util,
+};
+
+/// How long the Mod has to confirm the Restore
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Replaces the current Roles of the Guild with the Roles from the Backup, returns the Number of
+/// Roles that could not be restored
+async fn restore(storage: &Storage, guild_id: GuildId, backup: &[WereWolfRoleConfig]) -> usize {
+    let current = match storage.load_roles(guild_id).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("Loading Roles: {:?}", e);
+            return backup.len();
+        }
+    };
+
+    // Remove the Roles that are not part of the Backup first, so the Role-Limit is not hit
+    // while restoring
+    for role in current
+        .iter()
+        .filter(|r| !backup.iter().any(|b| b.name() == r.name()))
+    {
+        if let Err(e) = storage.remove_role(guild_id, role.name()).await {
+            tracing::error!("Removing Role: {:?}", e);
+        }
+    }
+
+    let mut failed = 0;
+    for role in backup.iter().cloned() {
+        if let Err(e) = storage.set_role(guild_id, role).await {
+            tracing::error!("Restoring Role: {:?}", e);
+            failed += 1;
+        }
+    }
+
+    failed
+}
+
+#[tracing::instrument(skip(ctx, msg))]
+pub async fn restore_roles(ctx: &Context, msg: &Message) -> CommandResult {
+    tracing::debug!("Received restore-roles Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let reply = util::msgs::ReplyTarget::Channel(channel_id);
+    if util::mods::require_mod(ctx, guild_id, msg.author.id, reply)
+        .await
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    let data = ctx.data.read().await;
+    let storage = get_storage(&data);
+
+    let backup = match storage.load_backup(guild_id).await {
+        Ok(Some(b)) => b,
+        Ok(None) => {
+            util::msgs::send_content(channel_id, ctx.http(), "There is no Backup to restore").await;
+
+            return Ok(());
+        }
+        Err(e) => {
+            tracing::error!("Loading Backup: {:?}", e);
+
+            util::msgs::send_content(channel_id, ctx.http(), "Could not load the Backup").await;
+
+            return Ok(());
+        }
+    };
+
+    let current = match storage.load_roles(guild_id).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("Loading Roles: {:?}", e);
+
+            util::msgs::send_content(channel_id, ctx.http(), "Could not load the Roles").await;
+
+            return Ok(());
+        }
+    };
+
+    let content = format!(
+        "This will replace the {} current Role(s) with the {} Role(s) from the Backup",
+        current.len(),
+        backup.len()
+    );
+    let confirmed = match util::confirm::confirm(
+        ctx.http(),
+        channel_id,
+        msg.author.id,
+        &content,
+        CONFIRM_TIMEOUT,
+    )
+    .await
+    {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::error!("Asking for Restore-Confirmation: {:?}", e);
+            return Ok(());
+        }
+    };
+    if !confirmed {
+        util::msgs::send_content(channel_id, ctx.http(), "The Roles were not restored").await;
+        return Ok(());
+    }
+
+    let failed = restore(storage, guild_id, &backup).await;
+    let content = if failed == 0 {
+        format!("Restored {} Role(s) from the Backup", backup.len())
+    } else {
+        format!(
+            "Restored {} Role(s) from the Backup, {} Role(s) could not be restored",
+            backup.len() - failed,
+            failed
+        )
+    };
+    util::msgs::send_content(channel_id, ctx.http(), &content).await;
+
+    Ok(())
+}
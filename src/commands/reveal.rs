@@ -0,0 +1,199 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serenity::{
+    client::Context,
+    framework::standard::{Args, CommandResult},
+    http::CacheHttp,
+    model::{
+        channel::Message,
+        id::{ChannelId, UserId},
+    },
+};
+
+use crate::{
+    get_storage,
+    rounds::{
+        history::{PlayerRecord, RoundRecord},
+        start::load_nicknames,
+    },
+    storage::StorageBackend,
+    util,
+};
+
+/// A single Line of the Reveal, showing the Role and Team of the Player
+fn reveal_line(player: &str, record: &PlayerRecord) -> String {
+    let mut line = format!("{}: {}", player, record.role);
+    if let Some(team) = &record.team {
+        line.push_str(&format!(" ({})", team));
+    }
+    if record.died {
+        line.push_str(" - died");
+    }
+    line.push('\n');
+    line
+}
+
+/// The Reveal of all the Roles in the Round, the Players are shown using the given Names or are
+/// mentioned if they have none
+fn reveal_content(round: &RoundRecord, names: &HashMap<UserId, String>) -> String {
+    let mut content = match &round.label {
+        Some(label) => format!(
+            "Roles of the Round '{}' started <t:{}:f>\n",
+            label, round.started_at
+        ),
+        None => format!("Roles of the Round started <t:{}:f>\n", round.started_at),
+    };
+    for player in round.players.iter() {
+        let display = match names.get(&player.user) {
+            Some(name) => name.clone(),
+            None => format!("<@{}>", player.user.0),
+        };
+        content.push_str(&reveal_line(&display, player));
+    }
+
+    match &round.winner {
+        Some(team) => content.push_str(&format!("\nWon by {}", team)),
+        None => content.push_str("\nNo Winner detected"),
+    }
+
+    content
+}
+
+/// Reveals the Roles of the most recently finished Round, only finished Rounds are part of the
+/// History so this can't spoil a running Round
+#[tracing::instrument(skip(ctx, msg, args))]
+pub async fn reveal(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    tracing::debug!("Received reveal Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let reply = util::msgs::ReplyTarget::Channel(channel_id);
+    if util::mods::require_mod(ctx, guild_id, msg.author.id, reply)
+        .await
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    // The Roles are posted in the mentioned Channel or the current one
+    let target = match args.single::<String>() {
+        Ok(raw) => match serenity::utils::parse_channel(&raw) {
+            Some(id) => ChannelId(id),
+            None => {
+                util::msgs::send_content(
+                    channel_id,
+                    ctx.http(),
+                    "Must mention the Channel in which the Roles should be revealed",
+                )
+                .await;
+
+                return Ok(());
+            }
+        },
+        Err(_) => channel_id,
+    };
+
+    let data = ctx.data.read().await;
+    let storage = get_storage(&data);
+
+    let round = match storage.load_rounds(guild_id).await {
+        Ok(rounds) => rounds.into_iter().next(),
+        Err(e) => {
+            tracing::error!("Loading Round-History: {:?}", e);
+
+            util::msgs::send_content(channel_id, ctx.http(), "Could not load the Round-History")
+                .await;
+
+            return Ok(());
+        }
+    };
+    let round = match round {
+        Some(r) => r,
+        None => {
+            util::msgs::send_content(channel_id, ctx.http(), "No Rounds have been played yet")
+                .await;
+            return Ok(());
+        }
+    };
+
+    let settings = match storage.load_settings(guild_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("Loading Guild-Settings: {:?}", e);
+            Default::default()
+        }
+    };
+
+    let mut names = HashMap::new();
+    if !settings.mention_players {
+        if settings.use_nicknames {
+            let players: BTreeSet<&UserId> = round.players.iter().map(|p| &p.user).collect();
+            names = load_nicknames(&ctx.http, guild_id, &players).await;
+        }
+
+        for player in round.players.iter() {
+            if names.contains_key(&player.user) {
+                continue;
+            }
+
+            match player.user.to_user(ctx).await {
+                Ok(user) => {
+                    names.insert(player.user, user.name);
+                }
+                Err(e) => tracing::error!("Loading User for the Reveal: {:?}", e),
+            };
+        }
+    }
+
+    util::msgs::send_content(target, ctx.http(), &reveal_content(&round, &names)).await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn content() {
+        let round = RoundRecord {
+            started_at: 100,
+            ended_at: 200,
+            players: vec![
+                PlayerRecord {
+                    user: UserId(1),
+                    role: "Villager".to_string(),
+                    team: Some("Village".to_string()),
+                    died: true,
+                },
+                PlayerRecord {
+                    user: UserId(2),
+                    role: "Werewolf".to_string(),
+                    team: None,
+                    died: false,
+                },
+            ],
+            winner: Some("Wolves".to_string()),
+            roles: BTreeMap::new(),
+            label: None,
+        };
+
+        let mut names = HashMap::new();
+        names.insert(UserId(2), "Wolfgang".to_string());
+
+        assert_eq!(
+            "Roles of the Round started <t:100:f>\n<@1>: Villager (Village) - died\nWolfgang: Werewolf\n\nWon by Wolves",
+            reveal_content(&round, &names)
+        );
+
+        let labeled = RoundRecord {
+            label: Some("Final".to_string()),
+            ..round
+        };
+        assert!(reveal_content(&labeled, &names)
+            .starts_with("Roles of the Round 'Final' started <t:100:f>\n"));
+    }
+}
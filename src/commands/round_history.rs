@@ -0,0 +1,51 @@
+use serenity::{
+    client::Context, framework::standard::CommandResult, http::CacheHttp, model::channel::Message,
+};
+
+use crate::{
+    get_storage,
+    storage::StorageBackend,
+    util::{self, time::format_duration},
+};
+
+/// The maximum Number of Rounds that are listed
+const MAX_LISTED_ROUNDS: usize = 10;
+
+#[tracing::instrument(skip(ctx, msg))]
+pub async fn round_history(ctx: &Context, msg: &Message) -> CommandResult {
+    tracing::debug!("Received round-history Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let data = ctx.data.read().await;
+    let storage = get_storage(&data);
+
+    let rounds = match storage.load_rounds(guild_id).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("Loading Round-History: {:?}", e);
+
+            util::msgs::send_content(channel_id, ctx.http(), "Could not load the Round-History")
+                .await;
+
+            return Ok(());
+        }
+    };
+    if rounds.is_empty() {
+        util::msgs::send_content(channel_id, ctx.http(), "No Rounds have been played yet").await;
+        return Ok(());
+    }
+
+    let mut content = format!("Past Rounds ({} in total)\n\n", rounds.len());
+    for round in rounds.iter().take(MAX_LISTED_ROUNDS) {
+        content.push_str(&format!(
+            "* {}, lasted {}\n",
+            round,
+            format_duration(round.duration())
+        ));
+    }
+    util::msgs::send_content(channel_id, ctx.http(), &content).await;
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use serenity::{
+    client::Context,
+    framework::standard::CommandResult,
+    http::{CacheHttp, Http},
+    model::{
+        channel::{ChannelType, Message},
+        id::{GuildId, UserId},
+    },
+};
+
+use crate::{
+    get_storage,
+    storage::StorageBackend,
+    util::{self, dry_run},
+    DEAD_ROLE_NAME,
+};
+
+/// The Name of the temporary Channel created while testing
+const TEST_CHANNEL_NAME: &str = "w-self-test";
+
+/// The Result of a single Check, either a short Description of what worked or what failed
+type CheckResult = Result<String, String>;
+
+/// Formats the Results of all the Checks into a single Report
+fn report(results: &[(&str, CheckResult)]) -> String {
+    let mut content = "Self-Test\n\n".to_owned();
+
+    for (name, result) in results {
+        match result {
+            Ok(details) => content.push_str(&format!("[OK] {}: {}\n", name, details)),
+            Err(details) => content.push_str(&format!("[Failed] {}: {}\n", name, details)),
+        };
+    }
+
+    content
+}
+
+async fn check_permissions(guild_id: GuildId, bot_id: UserId, http: &Http) -> CheckResult {
+    util::permissions::check_permissions(guild_id, bot_id, http)
+        .await
+        .map(|_| "All required Permissions are granted".to_string())
+        .map_err(|e| e.to_string())
+}
+
+async fn check_settings(ctx: &Context, guild_id: GuildId) -> CheckResult {
+    let data = ctx.data.read().await;
+    let storage = get_storage(&data);
+
+    storage
+        .load_roles(guild_id)
+        .await
+        .map(|roles| format!("Loaded {} Role(s)", roles.len()))
+        .map_err(|e| e.to_string())
+}
+
+async fn check_channel_creation(guild_id: GuildId, http: &Http) -> CheckResult {
+    if dry_run::is_enabled() {
+        return Ok("Skipped Channel-Creation in the Dry-Run Mode".to_string());
+    }
+
+    let channel = guild_id
+        .create_channel(http, |c| c.name(TEST_CHANNEL_NAME).kind(ChannelType::Text))
+        .await
+        .map_err(|e| format!("Creating a Channel ({})", e))?;
+
+    channel
+        .delete(http)
+        .await
+        .map(|_| "Created and deleted a Channel".to_string())
+        .map_err(|e| format!("Deleting the test Channel '{}' ({})", TEST_CHANNEL_NAME, e))
+}
+
+async fn check_dead_role(guild_id: GuildId, http: &Http) -> CheckResult {
+    match util::roles::find_role(DEAD_ROLE_NAME, guild_id, http).await {
+        Ok(_) => Ok(format!("Found the '{}'-Role", DEAD_ROLE_NAME)),
+        Err(util::roles::FindRoleError::NotFound) if dry_run::is_enabled() => Ok(format!(
+            "Skipped creating the '{}'-Role in the Dry-Run Mode",
+            DEAD_ROLE_NAME
+        )),
+        Err(util::roles::FindRoleError::NotFound) => {
+            // The Role is only created for the Test, it will be created again once it is
+            // actually needed by a Round
+            let mut role = guild_id
+                .create_role(http, |r| r.name(DEAD_ROLE_NAME).position(0))
+                .await
+                .map_err(|e| format!("Creating the '{}'-Role ({})", DEAD_ROLE_NAME, e))?;
+
+            role.delete(http)
+                .await
+                .map(|_| format!("Created and deleted the '{}'-Role", DEAD_ROLE_NAME))
+                .map_err(|e| format!("Deleting the '{}'-Role ({})", DEAD_ROLE_NAME, e))
+        }
+        Err(e) => Err(format!("Finding the '{}'-Role ({})", DEAD_ROLE_NAME, e)),
+    }
+}
+
+#[tracing::instrument(skip(ctx, msg))]
+pub async fn self_test(ctx: &Context, msg: &Message) -> CommandResult {
+    tracing::debug!("Received self-test Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let reply = util::msgs::ReplyTarget::Channel(channel_id);
+    if util::mods::require_mod(ctx, guild_id, msg.author.id, reply)
+        .await
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    let bot_id = match ctx.http.get_current_user().await {
+        Ok(u) => u.id,
+        Err(e) => {
+            tracing::error!("Loading Bot-User: {:?}", e);
+            return Ok(());
+        }
+    };
+
+    let results = [
+        (
+            "Permissions",
+            check_permissions(guild_id, bot_id, &ctx.http).await,
+        ),
+        ("Settings-Channel", check_settings(ctx, guild_id).await),
+        (
+            "Channel-Creation",
+            check_channel_creation(guild_id, &ctx.http).await,
+        ),
+        ("Dead-Role", check_dead_role(guild_id, &ctx.http).await),
+    ];
+
+    util::msgs::send_content(channel_id, ctx.http(), &report(&results)).await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_results() {
+        let results = [
+            ("First", Ok("Worked".to_string())),
+            ("Second", Err("Missing Permission".to_string())),
+        ];
+
+        assert_eq!(
+            "Self-Test\n\n[OK] First: Worked\n[Failed] Second: Missing Permission\n",
+            report(&results)
+        );
+    }
+}
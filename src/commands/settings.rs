@@ -0,0 +1,110 @@
+use serenity::{
+    client::Context,
+    framework::standard::{Args, CommandResult},
+    http::CacheHttp,
+    model::channel::Message,
+};
+
+use crate::{get_storage, settings::GuildSettings, storage::StorageBackend, util};
+
+fn settings_msg(settings: &GuildSettings) -> String {
+    let mut result = "Settings\n\n".to_owned();
+
+    for (name, value) in settings.entries() {
+        result.push_str(&format!("* {}: {}\n", name, value));
+    }
+
+    result
+}
+
+#[tracing::instrument(skip(ctx, msg, args))]
+pub async fn settings(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    tracing::debug!("Received settings Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let data = ctx.data.read().await;
+    let storage = get_storage(&data);
+
+    let mut settings = match storage.load_settings(guild_id).await {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("Loading Settings: {:?}", e);
+            util::msgs::send_content(channel_id, ctx.http(), "Could not load Settings").await;
+
+            return Ok(());
+        }
+    };
+
+    let name = match args.single::<String>() {
+        Ok(n) => n,
+        Err(_) => {
+            util::msgs::send_content(channel_id, ctx.http(), &settings_msg(&settings)).await;
+            return Ok(());
+        }
+    };
+
+    let server_mods = match util::mods::load_guild_mods(ctx, guild_id).await {
+        Ok(m) => m,
+        Err(e) => {
+            tracing::error!("Loading Mods: {:?}", e);
+
+            util::msgs::send_content(channel_id, ctx.http(), "Could not load Mods for the Server")
+                .await;
+
+            return Ok(());
+        }
+    };
+    if !server_mods.contains(&msg.author.id) {
+        tracing::error!("Non Mod User executed the Command");
+
+        util::msgs::send_content(
+            channel_id,
+            ctx.http(),
+            &format!(
+                "Only Users with the {}-Role can change the Settings",
+                server_mods.role_list()
+            ),
+        )
+        .await;
+
+        return Ok(());
+    }
+
+    let value = match args.rest().trim() {
+        "" => {
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                &format!("Must supply the new Value for \"{}\"", name),
+            )
+            .await;
+
+            return Ok(());
+        }
+        v => v,
+    };
+
+    if let Err(e) = settings.set(&name, value) {
+        util::msgs::send_content(channel_id, ctx.http(), &e.to_string()).await;
+        return Ok(());
+    }
+
+    match storage.save_settings(guild_id, settings).await {
+        Ok(_) => {
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                &format!("Set \"{}\" to \"{}\"", name, value),
+            )
+            .await;
+        }
+        Err(e) => {
+            tracing::error!("Saving Settings: {:?}", e);
+            util::msgs::send_content(channel_id, ctx.http(), "Could not save the Settings").await;
+        }
+    };
+
+    Ok(())
+}
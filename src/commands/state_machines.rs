@@ -0,0 +1,133 @@
+use serenity::{
+    client::Context,
+    framework::standard::{Args, CommandResult},
+    http::CacheHttp,
+    model::channel::Message,
+};
+
+use crate::{sms::MachineSummary, util};
+
+/// The Number of State-Machines listed on a single Page
+const PAGE_SIZE: usize = 20;
+
+/// The Content for the given Page of the State-Machines, the first Page is 1
+fn page_content(machines: &[MachineSummary], page: usize) -> String {
+    if machines.is_empty() {
+        return "There are no State-Machines".to_string();
+    }
+
+    let pages = machines.len().div_ceil(PAGE_SIZE);
+    if page == 0 || page > pages {
+        return format!("There are only {} Page(s) of State-Machines", pages);
+    }
+
+    let mut content = format!(
+        "State-Machines ({} in total), Page {} of {}\n\n",
+        machines.len(),
+        page,
+        pages
+    );
+    for machine in machines.iter().skip((page - 1) * PAGE_SIZE).take(PAGE_SIZE) {
+        content.push_str(&format!(
+            "* Message {} in Guild {}: {}\n",
+            machine.message_id, machine.guild_id, machine.state
+        ));
+    }
+    if page < pages {
+        content.push_str(&format!(
+            "\nUse `{}state-machines {}` for the next Page",
+            crate::PREFIX,
+            page + 1
+        ));
+    }
+
+    content
+}
+
+#[tracing::instrument(skip(ctx, msg, args))]
+pub async fn state_machines(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    tracing::debug!("Received state-machines Command");
+
+    let channel_id = msg.channel_id;
+
+    let page = if args.is_empty() {
+        1
+    } else {
+        match args.single::<usize>() {
+            Ok(p) => p,
+            Err(_) => {
+                util::msgs::send_content(
+                    channel_id,
+                    ctx.http(),
+                    "Expected the Number of the Page to show",
+                )
+                .await;
+
+                return Ok(());
+            }
+        }
+    };
+
+    let machines = crate::SMMAP.summaries();
+    util::msgs::send_content(channel_id, ctx.http(), &page_content(&machines, page)).await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::model::id::{GuildId, MessageId};
+
+    use super::*;
+    use crate::sms::StateInfo;
+
+    fn machines(count: u64) -> Vec<MachineSummary> {
+        (1..=count)
+            .map(|id| MachineSummary {
+                message_id: MessageId(id),
+                guild_id: GuildId(13),
+                state: StateInfo::State(Some("Running")),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn no_machines() {
+        assert_eq!("There are no State-Machines", page_content(&[], 1));
+    }
+
+    #[test]
+    fn single_page() {
+        assert_eq!(
+            "State-Machines (1 in total), Page 1 of 1\n\n* Message 1 in Guild 13: Running\n",
+            page_content(&machines(1), 1)
+        );
+    }
+
+    #[test]
+    fn pages() {
+        let machines = machines(45);
+
+        let first = page_content(&machines, 1);
+        assert!(first.starts_with("State-Machines (45 in total), Page 1 of 3\n\n"));
+        assert!(first.contains("* Message 20 in Guild 13"));
+        assert!(!first.contains("* Message 21 in Guild 13"));
+        assert!(first.ends_with(&format!(
+            "Use `{}state-machines 2` for the next Page",
+            crate::PREFIX
+        )));
+
+        let last = page_content(&machines, 3);
+        assert!(last.contains("* Message 41 in Guild 13"));
+        assert!(last.ends_with("* Message 45 in Guild 13: Running\n"));
+
+        assert_eq!(
+            "There are only 3 Page(s) of State-Machines",
+            page_content(&machines, 4)
+        );
+        assert_eq!(
+            "There are only 3 Page(s) of State-Machines",
+            page_content(&machines, 0)
+        );
+    }
+}
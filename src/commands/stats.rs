@@ -0,0 +1,48 @@
+use serenity::{
+    client::Context, framework::standard::CommandResult, http::CacheHttp, model::channel::Message,
+    utils::Color,
+};
+
+use crate::{get_storage, metrics, storage::StorageBackend, util::time::format_duration};
+
+#[tracing::instrument(skip(ctx, msg))]
+pub async fn stats(ctx: &Context, msg: &Message) -> CommandResult {
+    tracing::debug!("Received stats Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let data = ctx.data.read().await;
+    let storage = get_storage(&data);
+
+    let configured_roles = match storage.load_roles(guild_id).await {
+        Ok(r) => r.len().to_string(),
+        Err(e) => {
+            tracing::error!("Loading Roles: {:?}", e);
+            "Unknown".to_string()
+        }
+    };
+
+    let ready = metrics::gauge_value(&metrics::REGISTRY, "ready").unwrap_or(0.0) > 0.0;
+    let running_rounds =
+        metrics::gauge_value(&metrics::REGISTRY, "running_rounds").unwrap_or(0.0) as i64;
+    let uptime = format_duration(metrics::uptime());
+
+    if let Err(e) = channel_id
+        .send_message(ctx.http(), |m| {
+            m.embed(|e| {
+                e.title("Statistics")
+                    .color(Color::from_rgb(130, 10, 10))
+                    .field("Ready", if ready { "Yes" } else { "No" }, true)
+                    .field("Uptime", uptime, true)
+                    .field("Running Rounds (all Servers)", running_rounds, true)
+                    .field("Configured Roles", configured_roles, true)
+            })
+        })
+        .await
+    {
+        tracing::error!("Sending Stats-Message: {:?}", e);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use serenity::{
+    client::Context,
+    framework::standard::CommandResult,
+    http::{CacheHttp, HttpError},
+    model::channel::Message,
+};
+
+use crate::util;
+
+/// The JSON-Error-Code Discord responds with, when the User does not accept Direct Messages
+/// from the Bot
+const CANNOT_DM_CODE: isize = 50007;
+
+/// The Reply for a Direct Message that could not be sent
+fn failure_content(error: &serenity::Error) -> String {
+    match error {
+        serenity::Error::Http(e) => match e.as_ref() {
+            HttpError::UnsuccessfulRequest(resp) if resp.error.code == CANNOT_DM_CODE => {
+                "Could not send you a Direct Message, as you don't accept Direct Messages from Members of this Server".to_string()
+            }
+            other => format!("Could not send you a Direct Message: {}", other),
+        },
+        other => format!("Could not send you a Direct Message: {}", other),
+    }
+}
+
+#[tracing::instrument(skip(ctx, msg))]
+pub async fn test_dm(ctx: &Context, msg: &Message) -> CommandResult {
+    tracing::debug!("Received test-dm Command");
+
+    let channel_id = msg.channel_id;
+
+    // Only ever messages the Author themselves, so the Command can't be used to spam others
+    let sending = async {
+        let dm_channel = msg.author.create_dm_channel(ctx.http()).await?;
+        dm_channel
+            .say(
+                ctx.http(),
+                "This is a Test-Message, you can receive Direct Messages from the Bot",
+            )
+            .await
+    };
+
+    let content = match sending.await {
+        Ok(_) => "Sent you a Direct Message".to_string(),
+        Err(e) => {
+            tracing::warn!("Sending Test-DM: {:?}", e);
+            failure_content(&e)
+        }
+    };
+    util::msgs::send_content(channel_id, ctx.http(), &content).await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(code: isize) -> serenity::Error {
+        serenity::Error::Http(Box::new(HttpError::UnsuccessfulRequest(
+            serenity::http::error::ErrorResponse {
+                status_code: serenity::http::StatusCode::FORBIDDEN,
+                url: "https://discord.com/api/v8/channels/1/messages"
+                    .parse()
+                    .unwrap(),
+                error: serde_json::from_value(
+                    serde_json::json!({ "code": code, "message": "Missing Access" }),
+                )
+                .unwrap(),
+            },
+        )))
+    }
+
+    #[test]
+    fn closed_dms() {
+        assert_eq!(
+            "Could not send you a Direct Message, as you don't accept Direct Messages from Members of this Server",
+            failure_content(&response(50007))
+        );
+    }
+
+    #[test]
+    fn other_errors() {
+        assert!(
+            failure_content(&response(50001)).starts_with("Could not send you a Direct Message: ")
+        );
+        assert_eq!(
+            "Could not send you a Direct Message: Sending",
+            failure_content(&serenity::Error::Other("Sending"))
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+
+use serenity::{
+    client::Context,
+    framework::standard::{Args, CommandResult},
+    http::CacheHttp,
+    model::channel::Message,
+};
+
+use crate::{get_storage, roles::WereWolfRoleConfig, storage::StorageBackend, util};
+
+/// Parses the Role-Counts given as `Role=Count`, the Count can be omitted for a single Player
+fn parse_role_counts<'a, I>(
+    args: I,
+    configured: &[WereWolfRoleConfig],
+) -> Result<BTreeMap<WereWolfRoleConfig, usize>, String>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let mut result = BTreeMap::new();
+
+    for arg in args {
+        let (name, count) = match arg.split_once('=') {
+            Some((name, raw)) => match raw.parse::<usize>() {
+                Ok(c) => (name, c),
+                Err(_) => return Err(format!("'{}' is not a valid Number of Players", raw)),
+            },
+            None => (arg, 1),
+        };
+
+        let role = configured
+            .iter()
+            .find(|r| r.name() == name)
+            .ok_or_else(|| format!("There is no Role \"{}\"", name))?;
+        if !role.multi_player() && count > 1 {
+            return Err(format!(
+                "The Role \"{}\" can only be given to a single Player",
+                name
+            ));
+        }
+
+        if result.insert(role.clone(), count).is_some() {
+            return Err(format!("The Role \"{}\" was given more than once", name));
+        }
+    }
+
+    Ok(result)
+}
+
+#[tracing::instrument(skip(ctx, msg, args))]
+pub async fn validate_set(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    tracing::debug!("Received validate-set Command");
+
+    let channel_id = msg.channel_id;
+    let guild_id = msg.guild_id.unwrap();
+
+    let reply = util::msgs::ReplyTarget::Channel(channel_id);
+    if util::mods::require_mod(ctx, guild_id, msg.author.id, reply)
+        .await
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    let player_count = match args.single::<usize>() {
+        Ok(c) => c,
+        Err(_) => {
+            reply
+                .send_error(ctx.http(), "Must supply the Number of Players")
+                .await;
+
+            return Ok(());
+        }
+    };
+
+    let data = ctx.data.read().await;
+    let storage = get_storage(&data);
+
+    let configured = match storage.load_roles(guild_id).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("Loading Roles: {:?}", e);
+
+            util::msgs::send_content(channel_id, ctx.http(), "Could not load the Roles").await;
+
+            return Ok(());
+        }
+    };
+
+    let roles = match parse_role_counts(args.rest().split_whitespace(), &configured) {
+        Ok(r) => r,
+        Err(e) => {
+            reply.send_error(ctx.http(), &e).await;
+            return Ok(());
+        }
+    };
+
+    let content = match crate::roles::validate_roles(player_count, roles) {
+        Ok(_) => format!("The Roles can be played with {} Players", player_count),
+        Err(e) => format!("The Roles can't be played, the Set {}", e),
+    };
+    util::msgs::send_content(channel_id, ctx.http(), &content).await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn configured() -> Vec<WereWolfRoleConfig> {
+        vec![
+            WereWolfRoleConfig::new("Villager", "", true, false, Vec::new()),
+            WereWolfRoleConfig::new("Seer", "", false, false, Vec::new()),
+        ]
+    }
+
+    #[test]
+    fn parse_counts() {
+        let counts =
+            parse_role_counts("Villager=3 Seer".split_whitespace(), &configured()).unwrap();
+
+        assert_eq!(
+            vec![("Seer", 1), ("Villager", 3)],
+            counts
+                .iter()
+                .map(|(r, c)| (r.name(), *c))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn parse_invalid_counts() {
+        assert!(parse_role_counts("Seer=2".split_whitespace(), &configured()).is_err());
+        assert!(parse_role_counts("Wolf".split_whitespace(), &configured()).is_err());
+        assert!(parse_role_counts("Villager=x".split_whitespace(), &configured()).is_err());
+        assert!(parse_role_counts("Seer Seer".split_whitespace(), &configured()).is_err());
+    }
+}
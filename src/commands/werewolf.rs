@@ -1,44 +1,90 @@
 use serenity::{
-    client::Context, framework::standard::CommandResult, http::CacheHttp, model::channel::Message,
+    client::Context,
+    framework::standard::{Args, CommandResult},
+    http::CacheHttp,
+    model::channel::Message,
 };
 
-use crate::{util, MOD_ROLE_NAME};
+use crate::{
+    commands::game_channels::channel_list, get_storage, rounds::options::RoundOptions,
+    sms::CreateRoundError, storage::StorageBackend, util,
+};
 
+mod error;
 mod sm;
+pub use sm::resume_counts;
 
-#[tracing::instrument(skip(ctx, msg))]
-pub async fn werewolf(ctx: &Context, msg: &Message) -> CommandResult {
+#[tracing::instrument(skip(ctx, msg, args))]
+pub async fn werewolf(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     tracing::debug!("Received werewolf command");
 
+    start_round(ctx, msg, args, false).await
+}
+
+#[tracing::instrument(skip(ctx, msg, args))]
+pub async fn repeat(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    tracing::debug!("Received repeat command");
+
+    start_round(ctx, msg, args, true).await
+}
+
+/// Starts a new Round, which reuses the Roles and their Counts of the last Round if `repeat` is
+/// set
+async fn start_round(ctx: &Context, msg: &Message, args: Args, repeat: bool) -> CommandResult {
     let guild_id = match msg.guild_id {
         Some(gid) => gid,
         None => return Ok(()),
     };
     let channel_id = msg.channel_id;
 
-    let mod_role = match util::roles::find_role(MOD_ROLE_NAME, guild_id, ctx.http()).await {
-        Ok(r) => r,
-        Err(util::roles::FindRoleError::NotFound) => {
-            tracing::error!("'Game Master'-Role does not exist on the Guild");
-
+    let settings = {
+        let data = ctx.data.read().await;
+        let storage = get_storage(&data);
+        storage.load_settings(guild_id).await
+    };
+    let settings = match settings {
+        Ok(settings) if !settings.allows_rounds_in(channel_id) => {
+            tracing::error!("Attempted to start Round outside of the Game-Channels");
             util::msgs::send_content(
                 channel_id,
                 ctx.http(),
                 &format!(
-                    "Could not start a new Round as it could not find a Role with the Name '{}'",
-                    MOD_ROLE_NAME
+                    "Rounds can only be started in {}",
+                    channel_list(&settings.game_channels)
                 ),
             )
             .await;
 
             return Ok(());
         }
+        Ok(s) => s,
+        Err(e) => {
+            tracing::error!("Loading Settings: {:?}", e);
+            util::msgs::send_content(channel_id, ctx.http(), "Could not load Settings").await;
+
+            return Ok(());
+        }
+    };
+
+    let mods = match util::mods::load_guild_mods(ctx, guild_id).await {
+        Ok(m) => m,
+        Err(util::mods::LoadModsError::FindModRole(util::roles::FindRoleError::NotFound)) => {
+            tracing::error!("None of the Mod-Roles exist on the Guild");
+
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                "Could not start a new Round as it could not find any of the Mod-Roles",
+            )
+            .await;
+
+            return Ok(());
+        }
         Err(e) => {
-            tracing::error!("Error getting 'Game Master'-Role for Guild: {:?}", e);
+            tracing::error!("Error getting Mod-Roles for Guild: {:?}", e);
             return Ok(());
         }
     };
-    let mods = util::roles::role_users(mod_role, guild_id, ctx.http()).await;
 
     if !mods.contains(&msg.author.id) {
         tracing::error!("Non Mod attempted to start Round");
@@ -52,34 +98,71 @@ pub async fn werewolf(ctx: &Context, msg: &Message) -> CommandResult {
         return Ok(());
     }
 
-    if crate::SMMAP.reserve_running_game(guild_id).await.is_err() {
-        tracing::error!("Attempted to start new Round in Guild with running Round");
-        util::msgs::send_content(
-            channel_id,
-            ctx.http(),
-            "There already exists an ongoing Round",
-        )
-        .await;
+    let mut options = match RoundOptions::parse(args.raw_quoted()) {
+        Ok(o) => o,
+        Err(e) => {
+            tracing::error!("Parsing Round-Options: {:?}", e);
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                &format!(
+                    "```\nCould not start a new Round: {}\nFormat: 'werewolf [@Player=Role ...] [spectators=on|off]'\n```",
+                    e
+                ),
+            )
+            .await;
 
-        return Ok(());
-    }
+            return Ok(());
+        }
+    };
 
-    tracing::debug!("Starting new Round");
+    options.repeat = repeat;
 
     let bot_id = ctx.http.get_current_user().await.unwrap().id;
 
-    match sm::create(ctx, guild_id, channel_id, mods, bot_id).await {
-        Ok(round_sm) => {
-            let sm_msg_id = round_sm.message_id();
+    tracing::debug!("Starting new Round");
 
-            crate::SMMAP.add(sm_msg_id, round_sm);
-            crate::SMMAP
-                .mark_running_game(guild_id, sm_msg_id)
-                .await
-                .unwrap();
+    let result = crate::SMMAP
+        .create_round(guild_id, || {
+            sm::create(
+                ctx,
+                guild_id,
+                channel_id,
+                mods.users,
+                bot_id,
+                options,
+                settings.registration_window(),
+            )
+        })
+        .await;
+    match result {
+        Ok(_) => {}
+        Err(e @ CreateRoundError::ReservationCleared) => {
+            tracing::error!("Reservation was cleared while creating the Round");
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                &format!("Could not start a new Round: {}", e),
+            )
+            .await;
         }
-        Err(e) => {
+        Err(CreateRoundError::Reserve(e)) => {
+            tracing::error!("Attempted to start new Round in Guild with running Round");
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                &format!("Could not start a new Round: {}", e),
+            )
+            .await;
+        }
+        Err(CreateRoundError::Create(e)) => {
             tracing::error!("Creating Round Config State-Machine: {:?}", e);
+            util::msgs::send_content(
+                channel_id,
+                ctx.http(),
+                "Could not start a new Round, please try again",
+            )
+            .await;
         }
     };
 
@@ -0,0 +1,165 @@
+use std::fmt::Display;
+
+use crate::{
+    roles::{DistributeError, SlotBalance},
+    rounds::start::StartError,
+    util::permissions::CheckPermissionsError,
+};
+
+/// The Reasons why setting up a Round can fail, which are shown to the Mods on the Message of
+/// the Round
+#[derive(Debug)]
+pub enum WerewolfSetupError {
+    /// The Registration was ended without any registered Players
+    NoPlayers,
+    /// The Guild has no Roles that could be selected for the Round
+    NotEnoughRoles,
+    /// The Roles of the Guild could not be loaded
+    LoadingRoles,
+    /// The Message of the Round could not be updated for the Ready-Check
+    ReadyCheckFailed,
+    /// The Message of the Round could not be updated for selecting the Roles
+    RoleSelectionFailed,
+    /// The Messages for entering the Counts of the Roles could not be sent
+    RoleCountsFailed,
+    /// The Dead-Role of the Guild could not be loaded or created
+    LoadingGuildRoles,
+    /// The Bot is missing Permissions needed for the Round
+    MissingPermissions(CheckPermissionsError),
+    /// The Player-Slots of the entered Role-Counts don't match the Number of Players
+    MismatchedSlots(SlotBalance),
+    /// The Roles could not be distributed to the Players
+    DistributionFailed(DistributeError),
+    /// The Channels of the Round could not be set up
+    ChannelSetupFailed(StartError),
+}
+
+impl Display for WerewolfSetupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoPlayers => write!(f, "The Round has no registered Players yet"),
+            Self::NotEnoughRoles => write!(
+                f,
+                "There are no Roles to select, add some using the add-role Command first"
+            ),
+            Self::LoadingRoles => write!(f, "Could not load the Roles of the Server"),
+            Self::ReadyCheckFailed => write!(
+                f,
+                "Could not start the Ready-Check, make sure I can still edit my Messages and add Reactions"
+            ),
+            Self::RoleSelectionFailed => write!(
+                f,
+                "Could not show the Roles to select, make sure I can still edit my Messages and add Reactions"
+            ),
+            Self::RoleCountsFailed => write!(
+                f,
+                "Could not send the Messages for the Role-Counts, make sure I can send Messages in this Channel"
+            ),
+            Self::LoadingGuildRoles => write!(
+                f,
+                "Could not load or create the Roles for the Players, make sure I can manage Roles"
+            ),
+            Self::MissingPermissions(e) => write!(f, "Could not start the Round: {}", e),
+            Self::MismatchedSlots(balance) => write!(f, "Could not start the Round: {}", balance),
+            Self::DistributionFailed(e) => {
+                write!(f, "Could not distribute the Roles, the Round {}", e)
+            }
+            Self::ChannelSetupFailed(e) => {
+                write!(f, "Could not set up the Channels of the Round: {}", e)
+            }
+        }
+    }
+}
+
+impl From<StartError> for WerewolfSetupError {
+    fn from(e: StartError) -> Self {
+        match e {
+            StartError::Permissions(e) => Self::MissingPermissions(e),
+            StartError::DistributingRoles(e) => Self::DistributionFailed(e),
+            other => Self::ChannelSetupFailed(other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            "The Round has no registered Players yet",
+            WerewolfSetupError::NoPlayers.to_string()
+        );
+        assert_eq!(
+            "There are no Roles to select, add some using the add-role Command first",
+            WerewolfSetupError::NotEnoughRoles.to_string()
+        );
+        assert_eq!(
+            "Could not load the Roles of the Server",
+            WerewolfSetupError::LoadingRoles.to_string()
+        );
+        assert_eq!(
+            "Could not start the Ready-Check, make sure I can still edit my Messages and add Reactions",
+            WerewolfSetupError::ReadyCheckFailed.to_string()
+        );
+        assert_eq!(
+            "Could not show the Roles to select, make sure I can still edit my Messages and add Reactions",
+            WerewolfSetupError::RoleSelectionFailed.to_string()
+        );
+        assert_eq!(
+            "Could not send the Messages for the Role-Counts, make sure I can send Messages in this Channel",
+            WerewolfSetupError::RoleCountsFailed.to_string()
+        );
+        assert_eq!(
+            "Could not load or create the Roles for the Players, make sure I can manage Roles",
+            WerewolfSetupError::LoadingGuildRoles.to_string()
+        );
+        assert_eq!(
+            "Could not start the Round: I'm missing the following Permissions: Manage Roles",
+            WerewolfSetupError::MissingPermissions(CheckPermissionsError::Missing(vec![
+                "Manage Roles"
+            ]))
+            .to_string()
+        );
+        assert_eq!(
+            "Could not distribute the Roles, the Round configured 3 Roles to assign but has 4 Players",
+            WerewolfSetupError::DistributionFailed(DistributeError::MismatchedCount {
+                available_roles: 3,
+                player_count: 4
+            })
+            .to_string()
+        );
+        assert_eq!(
+            "Could not start the Round: The Roles need 1 more Player-Slot(s) so every Player gets a Role, select more Roles or raise their Counts",
+            WerewolfSetupError::MismatchedSlots(SlotBalance::TooFewSlots(1)).to_string()
+        );
+        assert_eq!(
+            "Could not set up the Channels of the Round: Setting up Channel for the Moderators",
+            WerewolfSetupError::ChannelSetupFailed(StartError::SettingUpModeratorChannel)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn from_start_error() {
+        assert!(matches!(
+            WerewolfSetupError::from(StartError::Permissions(CheckPermissionsError::Missing(
+                Vec::new()
+            ))),
+            WerewolfSetupError::MissingPermissions(_)
+        ));
+        assert!(matches!(
+            WerewolfSetupError::from(StartError::DistributingRoles(
+                DistributeError::NotEnoughPlayersToLink {
+                    role: "Cupid".to_string()
+                }
+            )),
+            WerewolfSetupError::DistributionFailed(_)
+        ));
+        assert!(matches!(
+            WerewolfSetupError::from(StartError::LoadingChannels),
+            WerewolfSetupError::ChannelSetupFailed(StartError::LoadingChannels)
+        ));
+    }
+}
@@ -1,38 +1,237 @@
 use std::{
     collections::{BTreeMap, BTreeSet},
     fmt::{Debug, Display},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime},
 };
 
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 use serenity::{
-    http::{CacheHttp, Http},
+    http::{CacheHttp, Http, HttpError},
     model::{
-        channel::{Message, ReactionType},
+        channel::{Reaction, ReactionType},
         id::{ChannelId, GuildId, MessageId, RoleId, UserId},
     },
 };
 use statemachines::{AsyncTransition, TransitionResult};
 
+use super::error::WerewolfSetupError;
 use crate::{
     messages::{Context, Event, MessageStateMachine, TransitionError, WithLazyState, WithState},
-    roles::{self, WereWolfRoleConfig, WereWolfRoleInstance},
-    rounds::{self, start::StartSource},
-    storage::StorageBackend,
-    util, Reactions, DEAD_ROLE_NAME,
+    roles::{self, Distribution, SelectionMode, WereWolfRoleConfig, WereWolfRoleInstance},
+    rounds::{
+        self,
+        counts::{PendingCounts, PendingRole},
+        history::RoundRecord,
+        options::RoundOptions,
+        phase::{self, Narrator, Phase},
+        registry::ChannelRegistry,
+        slots::RoleSlots,
+        start::StartSource,
+        vote::{self, Vote},
+    },
+    settings::GuildSettings,
+    storage::{Storage, StorageBackend},
+    util::{
+        self,
+        confirm::{Answer, Confirmation},
+        dry_run,
+        ratelimit::RateLimiter,
+    },
+    Reactions, DEAD_ROLE_NAME,
 };
 
+lazy_static! {
+    /// Limits the Notices for Non-Moderators to one per Channel in the given Interval
+    static ref NON_MOD_NOTICES: RateLimiter<ChannelId> = RateLimiter::new(Duration::from_secs(10));
+}
+
+/// How long the Notice for Non-Moderators stays in the Channel
+const NON_MOD_NOTICE_LIFETIME: Duration = Duration::from_secs(5);
+
+/// The JSON-Error-Code Discord responds with, when the Channel already has the maximum Number of
+/// pinned Messages
+const MAX_PINS_CODE: isize = 30003;
+
+/// Whether the Error was caused by the Channel already having the maximum Number of pinned
+/// Messages
+fn pin_limit_reached(error: &serenity::Error) -> bool {
+    match error {
+        serenity::Error::Http(e) => matches!(
+            e.as_ref(),
+            HttpError::UnsuccessfulRequest(resp) if resp.error.code == MAX_PINS_CODE
+        ),
+        _ => false,
+    }
+}
+
+/// Lets the User know that only Moderators can perform this Action
+async fn notify_non_mod(context: &Context, channel_id: ChannelId, user: UserId) {
+    if !NON_MOD_NOTICES.try_acquire(channel_id) {
+        return;
+    }
+
+    if let Some(http) = context.shared_http() {
+        let content = format!("<@{}> Only Moderators/Game Masters can do that", user.0);
+        util::msgs::send_transient(channel_id, http, &content, NON_MOD_NOTICE_LIFETIME).await;
+    }
+}
+
+/// Removes the Reaction of a Non-Moderator again and lets them know that they can't do that
+async fn reject_non_mod(context: &Context, reaction: &Reaction, user: UserId) {
+    let removal = dry_run::mutate("Removing Reaction of Non-Moderator", (), async {
+        reaction.delete(context.http().unwrap()).await
+    });
+    if let Err(e) = removal.await {
+        tracing::error!("Removing Reaction of Non-Moderator: {:?}", e);
+    }
+
+    notify_non_mod(context, reaction.channel_id, user).await;
+}
+
 #[derive(Debug, Clone)]
 struct GeneralWerewolfState<C> {
     mods: BTreeSet<UserId>,
     message: StateMessage,
     bot_user: UserId,
+    options: RoundOptions,
 
     inner: C,
 }
 
+/// How often the Countdown of a timed Registration is updated
+const REGISTRATION_TICK: Duration = Duration::from_secs(30);
+
 #[derive(Debug, Clone)]
 struct RegisterPlayers {
     players: Vec<UserId>,
+    /// The Window in which Players can register, if the Registration closes automatically
+    window: Option<RegistrationWindow>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RegistrationWindow {
+    /// The Duration for which the Registration is opened and by which the Mods can extend it
+    length: Duration,
+    /// The Point in Time at which the Registration closes, None once it has closed without any
+    /// registered Players
+    closes_at: Option<SystemTime>,
+}
+
+impl RegistrationWindow {
+    fn open(length: Duration, now: SystemTime) -> Self {
+        Self {
+            length,
+            // A Length that can't be represented closes the Registration right away
+            closes_at: Some(now.checked_add(length).unwrap_or(now)),
+        }
+    }
+
+    /// The Time left until the Registration closes, None if it is already closed
+    fn remaining(&self, now: SystemTime) -> Option<Duration> {
+        self.closes_at
+            .map(|closes_at| closes_at.duration_since(now).unwrap_or_default())
+    }
+
+    /// Extends the Registration by its Length or reopens it if it was already closed, returns
+    /// true if it was reopened
+    fn extend(&mut self, now: SystemTime) -> bool {
+        match self.closes_at {
+            Some(closes_at) => {
+                let start = closes_at.max(now);
+                self.closes_at = Some(start.checked_add(self.length).unwrap_or(start));
+                false
+            }
+            None => {
+                self.closes_at = Some(now.checked_add(self.length).unwrap_or(now));
+                true
+            }
+        }
+    }
+}
+
+/// The Content of the Message for registering the Players, including the Countdown if the
+/// Registration closes automatically
+fn registration_content(window: Option<&RegistrationWindow>, now: SystemTime) -> String {
+    let mut content = format!(
+        "Starting new Round\n{}: Enter as Player\n{}: Start the Round (mods only)",
+        Reactions::Entry,
+        Reactions::Confirm
+    );
+
+    let window = match window {
+        Some(w) => w,
+        None => return content,
+    };
+    let length = util::time::format_duration(window.length);
+    match window.remaining(now) {
+        Some(remaining) => {
+            // Rounds to the nearest Second, as the Ticks never happen exactly on time
+            let remaining = Duration::from_secs((remaining.as_millis() as u64 + 500) / 1000);
+            content.push_str(&format!(
+                "\n{}: Extend the Registration by {} (mods only)\nRegistration closes in {}",
+                Reactions::Extend,
+                length,
+                util::time::format_duration(remaining)
+            ));
+        }
+        None => {
+            content.push_str(&format!(
+                "\n{}: Reopen the Registration for {} (mods only)\nRegistration closed without any Players",
+                Reactions::Extend,
+                length
+            ));
+        }
+    };
+
+    content
+}
+
+#[derive(Debug, Clone)]
+struct ReadyCheck {
+    players: Vec<UserId>,
+    /// The Players that confirmed that they are ready
+    ready: BTreeSet<UserId>,
+}
+
+impl ReadyCheck {
+    /// Marks the User as ready, returns false if the User is not a registered Player
+    fn mark_ready(&mut self, user: UserId) -> bool {
+        if !self.players.contains(&user) {
+            return false;
+        }
+
+        self.ready.insert(user);
+        true
+    }
+
+    /// Marks the User as no longer ready, returns false if the User was not ready before
+    fn unmark_ready(&mut self, user: UserId) -> bool {
+        self.ready.remove(&user)
+    }
+
+    fn all_ready(&self) -> bool {
+        self.players.iter().all(|p| self.ready.contains(p))
+    }
+
+    /// The Confirmation with which the registered Players mark themselves as ready
+    fn confirmation(&self) -> Confirmation {
+        Confirmation::new(Reactions::Ready, self.players.iter().copied())
+    }
+
+    fn content(&self) -> String {
+        format!(
+            "Ready-Check ({}/{} Players are ready)\n{}: I'm ready\n{}: Continue with the Round (mods only)",
+            self.ready.len(),
+            self.players.len(),
+            Reactions::Ready,
+            Reactions::Confirm
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -41,7 +240,10 @@ struct SelectRoles {
 
     all_roles: Vec<WereWolfRoleConfig>,
     role_page: usize,
+    selection: SelectionMode,
     selected_roles: BTreeSet<WereWolfRoleConfig>,
+    /// The Roles and their Counts of the last Round, if the Round repeats it
+    repeated: Option<BTreeMap<WereWolfRoleConfig, usize>>,
 }
 
 #[derive(Debug, Clone)]
@@ -49,31 +251,120 @@ struct RoleCounts {
     players: Vec<UserId>,
 
     roles: BTreeMap<WereWolfRoleConfig, usize>,
-    role_messages: BTreeSet<WereWolfRoleConfig>,
+    /// The Messages asking for the Counts of the Roles, that have not been answered yet
+    role_messages: BTreeMap<WereWolfRoleConfig, (ChannelId, MessageId)>,
+    /// The Number of Roles whose Count had to be entered by the Mods
+    counted_roles: usize,
+    /// Whether or not the Counts are entered using Direct Messages
+    via_dm: bool,
 
     count_queue: Arc<crossbeam::queue::SegQueue<(WereWolfRoleConfig, usize)>>,
+    /// Set once the Mods cancel the Round, which stops the State-Machines for the Role-Counts
+    cancelled: Arc<AtomicBool>,
 }
 
-#[derive(Debug, Clone)]
+impl RoleCounts {
+    /// The Status of the Role-Configuration, including how many of the Role-Counts have
+    /// already been entered
+    fn status(&self) -> String {
+        let configured = self.counted_roles - self.role_messages.len();
+        let location = if self.via_dm {
+            " via Direct Messages"
+        } else {
+            ""
+        };
+
+        format!(
+            "Configuring Roles{}... (Configured {}/{} Roles)",
+            location, configured, self.counted_roles
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Running {
-    players: BTreeMap<UserId, WereWolfRoleInstance>,
+    players: Distribution,
     moderator_channel: ChannelId,
-    channels: BTreeMap<String, ChannelId>,
+    graveyard_channel: ChannelId,
+    channels: ChannelRegistry,
+    /// The Players that have already died during the Round
+    dead: BTreeSet<UserId>,
+    started_at: SystemTime,
+    /// The Channel in which the Phase-Changes are announced, which is the Announcement-Channel
+    /// if there is one and otherwise the Channel in which the Round was started
+    announce_channel: ChannelId,
+    /// The read-only Channel for the Players, if it was created for the Round
+    announcement_channel: Option<ChannelId>,
+    phase: Phase,
+    narrator: Narrator,
+    /// Whether the Mods have already been reminded that the Round has been running for too long
+    overdue_reminded: bool,
+    /// Whether the Control-Message was pinned and needs to be unpinned once the Round is over
+    pinned: bool,
+    /// The Channel in which the Votes take place, once the first Vote of the Round was started
+    vote_channel: Option<ChannelId>,
+    /// The Vote that is currently open for the alive Players
+    vote: Option<OpenVote>,
+}
+
+/// A Vote of a running Round that has not been closed yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OpenVote {
+    channel: ChannelId,
+    /// The Message on which the Players vote
+    ballot: MessageId,
+    vote: Vote,
+}
+
+impl OpenVote {
+    fn ballot(&self, guild_id: GuildId) -> StateMessage {
+        StateMessage {
+            guild_id,
+            channel_id: self.channel,
+            message_id: self.ballot,
+        }
+    }
 }
 
 type RegisterPlayersState = GeneralWerewolfState<RegisterPlayers>;
+type ReadyCheckState = GeneralWerewolfState<ReadyCheck>;
 type SelectRolesState = GeneralWerewolfState<SelectRoles>;
 type RoleCountsState = GeneralWerewolfState<RoleCounts>;
 type RunningState = GeneralWerewolfState<Running>;
 
+/// The Result of registering the Players, the Roles are selected right away unless the Players
+/// first have to confirm that they are ready
+#[derive(Debug, Clone)]
+enum PlayersRegistered {
+    Checking(ReadyCheckState),
+    Selecting(SelectRolesState),
+}
+
+/// The Result of selecting the Roles, Rounds without any Multi-Player Roles don't need any
+/// Role-Counts and are started right away
+#[derive(Debug, Clone)]
+enum RolesSelected {
+    Counting(RoleCountsState),
+    Started(RunningState),
+}
+
+/// The Result of entering the Role-Counts, as the Mods can cancel the Round instead of waiting
+/// for all the Counts
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+enum CountsEntered {
+    Started(RunningState),
+    Cancelled,
+}
+
 impl<C> GeneralWerewolfState<C> {
-    pub async fn get_everyone_role(&self, http: &Http) -> Result<RoleId, serenity::Error> {
-        util::roles::get_everyone_role(self.message.guild_id, http)
-            .await
-            .map_err(|e| match e {
-                util::roles::FindRoleError::NotFound => unreachable!(""),
-                util::roles::FindRoleError::SerenityError(e) => e,
-            })
+    /// The Confirmation of an Action that only the Mods of the Round can confirm
+    fn mod_confirmation(&self, reaction: Reactions) -> Confirmation {
+        Confirmation::new(reaction, self.mods.iter().copied())
+    }
+
+    pub fn get_everyone_role(&self) -> RoleId {
+        util::roles::everyone_role(self.message.guild_id)
     }
 
     pub async fn get_dead_player_role(&self, http: &Http) -> Result<RoleId, serenity::Error> {
@@ -82,16 +373,32 @@ impl<C> GeneralWerewolfState<C> {
         match util::roles::find_role(DEAD_ROLE_NAME, guild_id, http).await {
             Ok(id) => Ok(id),
             Err(util::roles::FindRoleError::NotFound) => {
-                let nrole = guild_id
-                    .create_role(http, |r| r.name(DEAD_ROLE_NAME).position(0))
-                    .await?;
-
-                Ok(nrole.id)
+                dry_run::mutate("Creating Dead-Role", dry_run::placeholder_role(), async {
+                    guild_id
+                        .create_role(http, |r| r.name(DEAD_ROLE_NAME).position(0))
+                        .await
+                        .map(|r| r.id)
+                })
+                .await
             }
             Err(util::roles::FindRoleError::SerenityError(e)) => Err(e),
         }
     }
 
+    /// Loads the Settings of the Guild, falling back to the default Settings if they could not
+    /// be loaded
+    pub async fn load_settings(&self, context: &Context) -> GuildSettings {
+        let storage = context.storage().unwrap();
+
+        match storage.load_settings(self.message.guild_id).await {
+            Ok(s) => s,
+            Err(e) => {
+                tracing::error!("Loading Guild-Settings: {:?}", e);
+                GuildSettings::default()
+            }
+        }
+    }
+
     pub async fn handle_error<E>(&self, http: &Http, error: &E)
     where
         E: Display,
@@ -101,6 +408,72 @@ impl<C> GeneralWerewolfState<C> {
             tracing::error!("Updating Message with Error: {:?}", e);
         }
     }
+
+    /// Shows the Error to the Mods on the Message of the Round and turns it into the Error of
+    /// the Transition
+    async fn setup_failed(&self, http: &Http, error: WerewolfSetupError) -> Arc<TransitionError> {
+        self.handle_error(http, &error).await;
+        TransitionError::Generic(Arc::new(error)).arced()
+    }
+}
+
+impl GeneralWerewolfState<RegisterPlayers> {
+    /// Registers the User as a Player of the Round, the Bot itself is never registered even if
+    /// the Event for its own Entry-Reaction makes it through
+    fn register(&mut self, context: &Context, user: UserId) -> bool {
+        if context.is_bot(user) || user == self.bot_user {
+            tracing::warn!("Ignoring the Entry-Reaction of the Bot itself");
+            return false;
+        }
+
+        self.inner.players.push(user);
+        true
+    }
+}
+
+impl GeneralWerewolfState<ReadyCheck> {
+    async fn from_registered(
+        http: &Http,
+        registered: RegisterPlayersState,
+    ) -> Result<Self, serenity::Error> {
+        let state = Self {
+            mods: registered.mods,
+            message: registered.message,
+            bot_user: registered.bot_user,
+            options: registered.options,
+
+            inner: ReadyCheck {
+                players: registered.inner.players,
+                ready: BTreeSet::new(),
+            },
+        };
+
+        state
+            .message
+            .update(
+                http,
+                state.inner.content(),
+                &[Reactions::Ready, Reactions::Confirm],
+            )
+            .await?;
+
+        Ok(state)
+    }
+
+    /// The State with the registered Players, which the Roles are selected for
+    fn registered(&self) -> RegisterPlayersState {
+        RegisterPlayersState {
+            mods: self.mods.clone(),
+            message: self.message.clone(),
+            bot_user: self.bot_user,
+            options: self.options.clone(),
+
+            inner: RegisterPlayers {
+                players: self.inner.players.clone(),
+                window: None,
+            },
+        }
+    }
 }
 
 impl GeneralWerewolfState<SelectRoles> {
@@ -108,17 +481,26 @@ impl GeneralWerewolfState<SelectRoles> {
         http: &Http,
         first: GeneralWerewolfState<RegisterPlayers>,
         all_roles: Vec<WereWolfRoleConfig>,
+        selection: SelectionMode,
+        repeated: Option<BTreeMap<WereWolfRoleConfig, usize>>,
     ) -> Result<Self, serenity::Error> {
+        let selected_roles = repeated
+            .iter()
+            .flat_map(|roles| roles.keys().cloned())
+            .collect();
         let instant = Self {
             mods: first.mods,
             message: first.message,
             bot_user: first.bot_user,
+            options: first.options,
 
             inner: SelectRoles {
                 players: first.inner.players,
                 all_roles,
                 role_page: 0,
-                selected_roles: BTreeSet::new(),
+                selection,
+                selected_roles,
+                repeated,
             },
         };
 
@@ -128,8 +510,21 @@ impl GeneralWerewolfState<SelectRoles> {
     }
 
     async fn update_msg(&self, http: &Http) -> Result<(), serenity::Error> {
-        let roles_content = Self::roles_content(&self.inner.all_roles);
-        let roles_reactions = roles::reactions(&self.inner.all_roles, 0);
+        let page = self.inner.role_page;
+        let selection = self.inner.selection;
+
+        let mut roles_content = Self::roles_content(
+            roles::page_roles(&self.inner.all_roles, page, selection),
+            selection,
+        );
+        match self.inner.repeated.as_ref() {
+            Some(repeated) => roles_content.push_str(&Self::repeated_content(repeated)),
+            None if self.options.repeat => {
+                roles_content.push_str("\n\nThere is no earlier Round whose Roles can be repeated")
+            }
+            None => {}
+        };
+        let roles_reactions = roles::reactions(&self.inner.all_roles, page, selection);
 
         self.message
             .update(http, roles_content, &roles_reactions)
@@ -138,13 +533,17 @@ impl GeneralWerewolfState<SelectRoles> {
         Ok(())
     }
 
-    fn roles_content(roles: &[WereWolfRoleConfig]) -> String {
+    fn roles_content(roles: &[WereWolfRoleConfig], selection: SelectionMode) -> String {
         let mut result = "Select all the Roles for the Round\n".to_string();
-        for role in roles {
-            result.push_str(role.emoji());
+        for (index, role) in roles.iter().enumerate() {
+            result.push_str(selection.role_reaction(role, index).to_str());
             result.push_str(": ");
+            if selection == SelectionMode::Numbered {
+                result.push_str(role.emoji());
+                result.push(' ');
+            }
             result.push_str(role.name());
-            result.push_str("\n");
+            result.push('\n');
         }
 
         result.push_str(&format!(
@@ -156,26 +555,106 @@ impl GeneralWerewolfState<SelectRoles> {
         result
     }
 
-    fn find_role(&self, emoji: &ReactionType) -> Option<&WereWolfRoleConfig> {
-        self.inner
-            .all_roles
+    fn repeated_content(repeated: &BTreeMap<WereWolfRoleConfig, usize>) -> String {
+        let roles: Vec<String> = repeated
+            .iter()
+            .map(|(role, count)| format!("{}x {}", count, role.name()))
+            .collect();
+
+        format!(
+            "\n\nSelected the Roles of the last Round: {}\n{}: Start the Round with the same Counts, if they still fit the Players",
+            roles.join(", "),
+            Reactions::Confirm
+        )
+    }
+
+    /// The Counts for all the selected Roles, if they are the same as in the repeated Round and
+    /// still fit the Number of Players
+    fn repeated_counts(&self) -> Option<BTreeMap<WereWolfRoleConfig, usize>> {
+        let repeated = self.inner.repeated.as_ref()?;
+
+        let mut counts = BTreeMap::new();
+        for role in self.inner.selected_roles.iter() {
+            let count = if role.multi_player() {
+                *repeated.get(role)?
+            } else {
+                1
+            };
+            counts.insert(role.clone(), count);
+        }
+
+        // Roles that mask another Role don't take up a Player-Slot of their own
+        let slots: usize = counts
             .iter()
-            .find(|r| emoji.unicode_eq(r.emoji()))
+            .filter(|(role, _)| !role.masks_role())
+            .map(|(_, count)| count)
+            .sum();
+        if slots != self.inner.players.len() {
+            return None;
+        }
+
+        Some(counts)
+    }
+
+    fn find_role(&self, emoji: &ReactionType) -> Option<&WereWolfRoleConfig> {
+        roles::find_role(
+            &self.inner.all_roles,
+            self.inner.role_page,
+            self.inner.selection,
+            emoji,
+        )
     }
 }
 
 impl GeneralWerewolfState<RoleCounts> {
-    pub async fn new(http: &Http, previous: SelectRolesState) -> Result<Self, serenity::Error> {
+    /// The Channel in which the Mod, that started the Round, is asked for the Role-Counts, falls
+    /// back to the public Channel if the Mod can't be reached using Direct Messages
+    async fn role_count_channel(
+        context: &Context,
+        previous: &SelectRolesState,
+        starter: UserId,
+    ) -> ChannelId {
+        let public = previous.message.channel_id;
+        if !previous.load_settings(context).await.dm_role_counts {
+            return public;
+        }
+
+        match starter.create_dm_channel(context.http().unwrap()).await {
+            Ok(c) => c.id,
+            Err(e) => {
+                tracing::error!("Opening DM-Channel with the Mod: {:?}", e);
+                public
+            }
+        }
+    }
+
+    pub async fn new(
+        context: &Context,
+        previous: SelectRolesState,
+        starter: UserId,
+    ) -> Result<Self, serenity::Error> {
+        let http = context.http().unwrap();
         let queue = Arc::new(crossbeam::queue::SegQueue::new());
+        let cancelled = Arc::new(AtomicBool::new(false));
 
         let mut roles = BTreeMap::new();
-        let mut role_messages = BTreeSet::new();
+        let mut role_messages = BTreeMap::new();
+
+        // Roles that mask another Role don't take up a Player-Slot of their own
+        let single_roles = previous
+            .inner
+            .selected_roles
+            .iter()
+            .filter(|r| !r.multi_player() && !r.masks_role())
+            .count();
+        let slots = Arc::new(RoleSlots::new(previous.inner.players.len(), single_roles));
 
-        let channel_id = previous.message.channel_id;
+        let public_channel = previous.message.channel_id;
+        let mut channel_id = Self::role_count_channel(context, &previous, starter).await;
 
         for role in previous.inner.selected_roles {
             if role.multi_player() {
-                let tmp_sm = create_role_sm(
+                let tmp_sm = match create_role_sm(
                     http,
                     previous.message.guild_id,
                     channel_id,
@@ -184,53 +663,221 @@ impl GeneralWerewolfState<RoleCounts> {
                     previous.mods.clone(),
                     role.clone(),
                     queue.clone(),
+                    slots.clone(),
+                    cancelled.clone(),
                 )
-                .await?;
+                .await
+                {
+                    Ok(sm) => sm,
+                    // The Mod has closed their DMs, so the remaining Counts are entered in the
+                    // public Channel instead
+                    Err(e) if channel_id != public_channel => {
+                        tracing::error!("Sending Role-Count Message via DM: {:?}", e);
+
+                        channel_id = public_channel;
+                        create_role_sm(
+                            http,
+                            previous.message.guild_id,
+                            channel_id,
+                            previous.message.message_id,
+                            previous.message.guild_id,
+                            previous.mods.clone(),
+                            role.clone(),
+                            queue.clone(),
+                            slots.clone(),
+                            cancelled.clone(),
+                        )
+                        .await?
+                    }
+                    Err(e) => return Err(e),
+                };
 
                 let msg_id = tmp_sm.message_id();
                 crate::SMMAP.add(msg_id, tmp_sm);
-                role_messages.insert(role);
+                role_messages.insert(role, (channel_id, msg_id));
             } else {
                 roles.insert(role, 1);
             }
         }
 
+        let counts = RoleCounts {
+            players: previous.inner.players,
+
+            counted_roles: role_messages.len(),
+            via_dm: channel_id != public_channel,
+            roles,
+            role_messages,
+
+            count_queue: queue,
+            cancelled,
+        };
+
         if let Err(e) = previous
             .message
-            .update(http, "Configuring Roles...", &[])
+            .update(http, counts.status(), &[Reactions::Stop])
             .await
         {
             tracing::error!("Updating Message with current Status: {:?}", e);
         }
 
-        if role_messages.is_empty() {
-            crate::NOTIFY_SM_QUEUE.notify(previous.message.message_id, previous.message.guild_id);
-        }
-
         let instance = Self {
             mods: previous.mods,
             message: previous.message,
             bot_user: previous.bot_user,
+            options: previous.options,
 
-            inner: RoleCounts {
-                players: previous.inner.players,
+            inner: counts,
+        };
+        instance.save_pending(context).await;
 
-                roles,
-                role_messages,
+        Ok(instance)
+    }
 
-                count_queue: queue,
-            },
+    /// Asks the Mods for the Counts of the Multi-Player Roles again, after the entered Counts did
+    /// not add up to the Number of Players
+    async fn recount(
+        &mut self,
+        context: &Context,
+        channel_id: ChannelId,
+    ) -> Result<(), serenity::Error> {
+        let http = context.http().unwrap();
+
+        let counted: Vec<WereWolfRoleConfig> = self
+            .inner
+            .roles
+            .keys()
+            .filter(|r| r.multi_player())
+            .cloned()
+            .collect();
+        for role in counted.iter() {
+            self.inner.roles.remove(role);
+        }
+
+        let slots = Arc::new(RoleSlots::new(
+            self.inner.players.len(),
+            self.pending().allocated_slots(),
+        ));
+
+        for role in counted {
+            let tmp_sm = create_role_sm(
+                http,
+                self.message.guild_id,
+                channel_id,
+                self.message.message_id,
+                self.message.guild_id,
+                self.mods.clone(),
+                role.clone(),
+                self.inner.count_queue.clone(),
+                slots.clone(),
+                self.inner.cancelled.clone(),
+            )
+            .await?;
+
+            let msg_id = tmp_sm.message_id();
+            crate::SMMAP.add(msg_id, tmp_sm);
+            self.inner.role_messages.insert(role, (channel_id, msg_id));
+        }
+
+        Ok(())
+    }
+
+    /// The Snapshot of the State, which allows the Round to continue after a Restart
+    fn pending(&self) -> PendingCounts {
+        PendingCounts {
+            guild: self.message.guild_id,
+            channel: self.message.channel_id,
+            message: self.message.message_id,
+            mods: self.mods.clone(),
+            bot_user: self.bot_user,
+            options: self.options.clone(),
+            players: self.inner.players.clone(),
+            counted: self
+                .inner
+                .roles
+                .iter()
+                .map(|(role, count)| (role.clone(), *count))
+                .collect(),
+            pending: self
+                .inner
+                .role_messages
+                .iter()
+                .map(|(role, (channel, message))| PendingRole {
+                    role: role.clone(),
+                    channel: *channel,
+                    message: *message,
+                })
+                .collect(),
+            counted_roles: self.inner.counted_roles,
+            via_dm: self.inner.via_dm,
+        }
+    }
+
+    /// Restores the State from its Snapshot, the Counts are entered using new State-Machines for
+    /// the Messages that are still waiting for their Count
+    fn from_pending(pending: PendingCounts) -> Self {
+        let counts = RoleCounts {
+            players: pending.players,
+
+            roles: pending.counted.into_iter().collect(),
+            role_messages: pending
+                .pending
+                .into_iter()
+                .map(|p| (p.role, (p.channel, p.message)))
+                .collect(),
+            counted_roles: pending.counted_roles,
+            via_dm: pending.via_dm,
+
+            count_queue: Arc::new(crossbeam::queue::SegQueue::new()),
+            cancelled: Arc::new(AtomicBool::new(false)),
         };
 
-        Ok(instance)
+        Self {
+            mods: pending.mods,
+            message: StateMessage {
+                guild_id: pending.guild,
+                channel_id: pending.channel,
+                message_id: pending.message,
+            },
+            bot_user: pending.bot_user,
+            options: pending.options,
+
+            inner: counts,
+        }
+    }
+
+    /// Stores the current Snapshot of the State, failures are only logged as the Round itself
+    /// can continue without it
+    async fn save_pending(&self, context: &Context) {
+        let storage = context.storage().unwrap();
+        if let Err(e) = storage
+            .save_pending_counts(self.message.guild_id, &self.pending())
+            .await
+        {
+            tracing::error!("Storing the pending Role-Counts: {:?}", e);
+        }
+    }
+
+    /// Removes the stored Snapshot, once the Counts are no longer being entered
+    async fn clear_pending(&self, context: &Context) {
+        let storage = context.storage().unwrap();
+        if let Err(e) = storage
+            .clear_pending_counts(self.message.guild_id, self.message.message_id)
+            .await
+        {
+            tracing::error!("Removing the pending Role-Counts: {:?}", e);
+        }
     }
 }
 
 impl RunningState {
-    pub async fn new(
-        http: &Http,
-        previous: RoleCountsState,
-    ) -> Result<Self, Arc<dyn std::fmt::Display + Send + Sync>> {
+    pub async fn new<C>(
+        context: &Context,
+        previous: GeneralWerewolfState<C>,
+        players: Vec<UserId>,
+        roles: BTreeMap<WereWolfRoleConfig, usize>,
+    ) -> Result<Self, WerewolfSetupError> {
+        let http = context.http().unwrap();
+
         if let Err(e) = previous
             .message
             .update(http, "Setting Round up...", &[])
@@ -239,17 +886,32 @@ impl RunningState {
             tracing::error!("Updating Message with current Status: {:?}", e);
         }
 
-        let everyone_role_id = previous.get_everyone_role(http).await.unwrap();
-        let dead_role_id = previous.get_dead_player_role(http).await.unwrap();
+        let everyone_role_id = previous.get_everyone_role();
+        let dead_role_id = match previous.get_dead_player_role(http).await {
+            Ok(r) => r,
+            Err(e) => {
+                tracing::error!("Loading the Roles for the Players: {:?}", e);
+
+                let error = WerewolfSetupError::LoadingGuildRoles;
+                previous.handle_error(http, &error).await;
+                return Err(error);
+            }
+        };
 
+        let settings = previous.load_settings(context).await;
         let source = StartSource {
-            participants: previous.inner.players.clone(),
-            roles: previous.inner.roles.clone(),
+            participants: players,
+            roles,
             guild: previous.message.guild_id,
             mods: previous.mods.clone(),
+            options: previous.options.clone(),
+            mention_players: settings.mention_players,
+            use_nicknames: settings.use_nicknames,
+            announcement_channel: settings.announcement_channel,
+            dead_see_role_channels: settings.dead_see_role_channels,
         };
 
-        let (players, moderator_channel, channels) = match rounds::start::start(
+        let started = match rounds::start::start(
             previous.bot_user,
             source,
             DEAD_ROLE_NAME,
@@ -261,87 +923,1173 @@ impl RunningState {
         {
             Ok(d) => d,
             Err(e) => {
-                previous.handle_error(http, &e).await;
-                return Err(Arc::new(e));
+                let error = WerewolfSetupError::from(e);
+                previous.handle_error(http, &error).await;
+                return Err(error);
             }
         };
 
-        let running_content = format!(
+        let running_content = previous.options.labeled(format!(
             "Started Werewolf Round, react with {} to End the Round",
             Reactions::Stop
-        );
-        if let Err(e) = previous
-            .message
-            .update(http, &running_content, &[Reactions::Stop])
+        ));
+
+        let setup_message = previous.message.clone();
+        let message = if settings.delete_setup_message {
+            match Self::move_control_message(
+                http,
+                &setup_message,
+                started.moderator_channel,
+                &running_content,
+            )
             .await
-        {
-            tracing::error!("Updating Message with current Status: {:?}", e);
+            {
+                Ok(m) => m,
+                Err(e) => {
+                    tracing::error!(
+                        "Moving the Control-Message into the Moderator-Channel: {:?}",
+                        e
+                    );
+                    setup_message.clone()
+                }
+            }
+        } else {
+            setup_message.clone()
+        };
+
+        // The Setup-Message is only updated if it is still used to control the Round
+        if message.message_id == setup_message.message_id {
+            if let Err(e) = message
+                .update(http, &running_content, &[Reactions::Stop])
+                .await
+            {
+                tracing::error!("Updating Message with current Status: {:?}", e);
+            }
         }
 
+        let pinned = settings.pin_control_message
+            && Self::pin_control_message(http, &message, started.moderator_channel).await;
+
+        crate::SMMAP.publish_participants(setup_message.message_id, started.players.clone().into());
+
         Ok(Self {
             mods: previous.mods,
-            message: previous.message,
+            message,
             bot_user: previous.bot_user,
+            options: previous.options,
 
             inner: Running {
-                players,
-                moderator_channel,
-                channels,
+                players: started.players,
+                moderator_channel: started.moderator_channel,
+                graveyard_channel: started.graveyard_channel,
+                channels: started.channels,
+                dead: BTreeSet::new(),
+                started_at: SystemTime::now(),
+                announce_channel: started
+                    .announcement_channel
+                    .unwrap_or(setup_message.channel_id),
+                announcement_channel: started.announcement_channel,
+                phase: Phase::default(),
+                narrator: Narrator::new(settings.narration),
+                overdue_reminded: false,
+                pinned,
+                vote_channel: None,
+                vote: None,
             },
         })
     }
-}
-
-#[derive(Debug, Clone)]
-struct StateMessage {
-    guild_id: GuildId,
-    channel_id: ChannelId,
-    message_id: MessageId,
-}
 
-impl StateMessage {
-    pub async fn update<C>(
-        &self,
+    /// Pins the Message that controls the Round, returns whether it was pinned. The Mods are
+    /// told in their Channel if the Channel of the Message has no Pins left
+    async fn pin_control_message(
         http: &Http,
-        content: C,
-        reactions: &[Reactions],
-    ) -> Result<(), serenity::Error>
-    where
-        C: AsRef<str>,
-    {
-        let mut msg = self.channel_id.message(http, self.message_id).await?;
+        message: &StateMessage,
+        moderator_channel: ChannelId,
+    ) -> bool {
+        let error = match message.pin(http).await {
+            Ok(_) => return true,
+            Err(e) => e,
+        };
 
-        msg.edit(http, |e| e.content(content.as_ref())).await?;
+        if pin_limit_reached(&error) {
+            tracing::warn!("Channel has no Pins left for the Control-Message");
 
-        msg.delete_reactions(http).await?;
+            let content = "Could not pin the Message to control the Round, as its Channel already has the maximum Number of pinned Messages";
+            util::msgs::send_content(moderator_channel, http, content).await;
+        } else {
+            tracing::error!("Pinning the Control-Message: {:?}", error);
+        }
 
-        for reaction in reactions {
-            msg.react(http, reaction).await?;
+        false
+    }
+
+    /// Marks the Player as dead and removes their Access to the Channels of their Role
+    async fn player_died(
+        &mut self,
+        context: &Context,
+        player: UserId,
+        role: &WereWolfRoleInstance,
+    ) {
+        let http = context.http().unwrap();
+        self.inner.dead.insert(player);
+
+        let player_channels = match self.inner.channels.channels_of(role) {
+            Ok(c) => c,
+            Err(e) => {
+                tracing::error!("Loading Channels of dead Player: {}", e);
+                Vec::new()
+            }
+        };
+        let link_channels = self
+            .inner
+            .channels
+            .link_channels_of(self.inner.players.links(), player);
+        rounds::death::clear_permissions(
+            http,
+            player,
+            player_channels
+                .iter()
+                .chain(link_channels.iter())
+                .chain(self.inner.vote_channel.iter()),
+        )
+        .await;
+
+        if let Some(open) = self.inner.vote.as_mut() {
+            open.vote.remove_player(player);
+
+            let ballot = open.ballot(self.message.guild_id);
+            if let Err(e) = ballot.edit_content(http, open.vote.ballot_content()).await {
+                tracing::error!("Removing dead Player from the Ballot: {:?}", e);
+            }
         }
 
-        Ok(())
+        let settings = self.load_settings(context).await;
+        if settings.reveal_dead_roles {
+            let content = rounds::death::reveal_content(player, role);
+            if let Err(e) = self.inner.graveyard_channel.say(http, content).await {
+                tracing::error!("Revealing Role of dead Player: {:?}", e);
+            }
+        }
     }
-}
 
-pub async fn create(
-    ctx: &serenity::client::Context,
-    guild_id: GuildId,
-    channel_id: ChannelId,
-    mods: BTreeSet<UserId>,
-    bot_user_id: UserId,
+    /// Opens an anonymous Vote between the alive Players in the Vote-Channel of the Round and
+    /// returns a Summary for the Mods
+    async fn start_vote(&mut self, http: &Http) -> String {
+        if self.inner.vote.is_some() {
+            return "There is already an open Vote, close it before starting a new one".to_string();
+        }
+
+        let alive: Vec<UserId> = self
+            .inner
+            .players
+            .players()
+            .filter(|p| !self.inner.dead.contains(p))
+            .copied()
+            .collect();
+        let vote = match Vote::new(alive.iter().copied()) {
+            Ok(v) => v,
+            Err(e) => return format!("Could not start the Vote: {}", e),
+        };
+
+        let everyone_role_id = self.get_everyone_role();
+        let dead_role_id = match self.get_dead_player_role(http).await {
+            Ok(id) => id,
+            Err(e) => {
+                tracing::error!("Loading Dead-Role: {:?}", e);
+                return "Could not start the Vote: Loading the Roles of the Server".to_string();
+            }
+        };
+
+        let channel = match vote::setup_channel(
+            http,
+            self.message.guild_id,
+            self.bot_user,
+            everyone_role_id,
+            dead_role_id,
+            !self.options.hide_from_dead,
+            &self.mods,
+            alive.iter(),
+        )
+        .await
+        {
+            Ok(c) => c,
+            Err(e) => {
+                tracing::error!("Setting up the Vote-Channel: {:?}", e);
+                return format!("Could not start the Vote: {}", e);
+            }
+        };
+        self.inner.vote_channel = Some(channel);
+
+        let ballot = match channel.say(http, vote.ballot_content()).await {
+            Ok(m) => m,
+            Err(e) => {
+                tracing::error!("Sending the Ballot: {:?}", e);
+                return "Could not start the Vote: Sending the Ballot".to_string();
+            }
+        };
+        // The Reactions on the Ballot are handled by the State-Machine of the Round
+        crate::SMMAP.add_alias(ballot.id, self.message.message_id);
+
+        for emoji in vote.emojis() {
+            if let Err(e) = ballot.react(http, ReactionType::Unicode(emoji)).await {
+                tracing::error!("Adding Candidate to the Ballot: {:?}", e);
+            }
+        }
+
+        self.inner.vote = Some(OpenVote {
+            channel,
+            ballot: ballot.id,
+            vote,
+        });
+
+        format!(
+            "Started a Vote between {} Players in <#{}>",
+            alive.len(),
+            channel.0
+        )
+    }
+
+    /// Records the Vote for the Reaction on the Ballot. The Reaction is removed right away, so
+    /// nobody else can see who voted for whom
+    async fn cast_vote(&mut self, http: &Http, reaction: &Reaction) {
+        let removal = dry_run::mutate("Removing Vote-Reaction", (), async {
+            reaction.delete(http).await
+        });
+        if let Err(e) = removal.await {
+            tracing::error!("Removing Vote-Reaction: {:?}", e);
+        }
+
+        let open = match self.inner.vote.as_mut() {
+            Some(o) => o,
+            None => return,
+        };
+        let voter = reaction.user_id.unwrap();
+        let candidate = match open.vote.candidate_for(&reaction.emoji) {
+            Some(c) => c,
+            None => return,
+        };
+        if let Err(e) = open.vote.cast(voter, candidate) {
+            tracing::debug!("Ignoring Vote of {:?}: {:?}", voter, e);
+            return;
+        }
+
+        let ballot = open.ballot(self.message.guild_id);
+        if let Err(e) = ballot.edit_content(http, open.vote.ballot_content()).await {
+            tracing::error!("Updating the Ballot: {:?}", e);
+        }
+    }
+
+    /// Closes the open Vote, publishes its anonymous Result on the Ballot and the Breakdown of
+    /// who voted for whom in the Moderator-Channel
+    async fn close_vote(&mut self, http: &Http) -> String {
+        let open = match self.inner.vote.take() {
+            Some(o) => o,
+            None => return "There is no open Vote to close".to_string(),
+        };
+
+        let result = open.vote.tally().to_string();
+        let ballot = open.ballot(self.message.guild_id);
+        if let Err(e) = ballot.update(http, &result, &[]).await {
+            tracing::error!("Publishing the Result of the Vote: {:?}", e);
+        }
+
+        let content = format!("{}\n{}", open.vote.breakdown(), result);
+        if let Err(e) = self.inner.moderator_channel.say(http, &content).await {
+            tracing::error!("Sending the Votes to the Moderator-Channel: {:?}", e);
+        }
+
+        result
+    }
+
+    /// Gives the Player the new Role and moves their Permissions from the Channels of their
+    /// old Role over to the Channels of the new Role
+    async fn reassign(
+        &mut self,
+        http: &Http,
+        player: UserId,
+        role: &WereWolfRoleConfig,
+    ) -> Result<String, String> {
+        if self.inner.dead.contains(&player) {
+            return Err(format!("<@{}> has already died", player.0));
+        }
+        let new_channels = self.inner.channels.channels_of_config(role).map_err(|e| {
+            format!(
+                "{}, only Roles that are part of the Round can be assigned",
+                e
+            )
+        })?;
+
+        let previous = self
+            .inner
+            .players
+            .reassign(player, role)
+            .map_err(|e| e.to_string())?;
+        crate::SMMAP
+            .publish_participants(self.message.message_id, self.inner.players.clone().into());
+
+        let old_channels = match self.inner.channels.channels_of(&previous) {
+            Ok(c) => c,
+            Err(e) => {
+                tracing::error!("Loading Channels of the previous Role: {}", e);
+                Vec::new()
+            }
+        };
+        rounds::death::clear_permissions(
+            http,
+            player,
+            old_channels.iter().filter(|c| !new_channels.contains(c)),
+        )
+        .await;
+
+        let permissions = rounds::start::channel_access_permissions(player);
+        for channel in new_channels.iter() {
+            let granting = dry_run::mutate("Granting Channel-Access", (), async {
+                channel.create_permission(http, &permissions).await
+            });
+            if let Err(e) = granting.await {
+                tracing::error!("Granting Permissions for the new Role: {:?}", e);
+            }
+        }
+
+        let content = format!(
+            "<@{}> has been reassigned from {} to {}",
+            player.0,
+            previous,
+            role.name()
+        );
+        if let Err(e) = self.inner.moderator_channel.say(http, &content).await {
+            tracing::error!("Announcing Reassignment in the Moderator-Channel: {:?}", e);
+        }
+
+        Ok(content)
+    }
+
+    /// Posts the Content in every Role-Channel of the Round and returns a Summary of the
+    /// Channels that the Message could be sent to
+    async fn broadcast(&self, http: &Http, content: &str) -> String {
+        let mut failed = Vec::new();
+        for (name, channel) in self.inner.channels.iter() {
+            if let Err(e) = channel.say(http, content).await {
+                tracing::error!("Broadcasting to Channel '{}': {:?}", name, e);
+                failed.push(name);
+            }
+        }
+
+        let sent = self.inner.channels.len() - failed.len();
+        let mut result = format!(
+            "Sent the Message to {} of {} Channel(s)",
+            sent,
+            self.inner.channels.len()
+        );
+        if !failed.is_empty() {
+            result.push_str(&format!(", failed for: {}", failed.join(", ")));
+        }
+        result
+    }
+
+    /// Moves the Round on to its next Phase and announces it, together with the next Line of
+    /// the Narration-Script
+    async fn next_phase(&mut self, http: &Http) -> String {
+        self.inner.phase = self.inner.phase.next();
+        let phase = self.inner.phase;
+
+        let line = self.inner.narrator.next_line();
+        let content = phase::announcement(phase, line);
+        if let Err(e) = self.inner.announce_channel.say(http, &content).await {
+            tracing::error!("Announcing the next Phase: {:?}", e);
+            return format!("Could not announce the {}", phase);
+        }
+
+        match self.inner.narrator.remaining() {
+            0 => format!("Started the {}, there are no Narration-Lines left", phase),
+            remaining => format!(
+                "Started the {}, {} Narration-Line(s) left",
+                phase, remaining
+            ),
+        }
+    }
+
+    /// Posts a new Control-Message for the Round in the given Channel and deletes the original
+    /// Setup-Message. The new Message is registered as an Alias for the Round, so that Reactions
+    /// on it still reach the State-Machine of the Round
+    async fn move_control_message(
+        http: &Http,
+        setup: &StateMessage,
+        channel_id: ChannelId,
+        content: &str,
+    ) -> Result<StateMessage, serenity::Error> {
+        let control_msg = channel_id
+            .send_message(http, |m| m.content(content).reactions(&[Reactions::Stop]))
+            .await?;
+
+        crate::SMMAP.add_alias(control_msg.id, setup.message_id);
+
+        let deletion = dry_run::mutate("Deleting Setup-Message", (), async {
+            setup
+                .channel_id
+                .delete_message(http, setup.message_id)
+                .await
+        });
+        if let Err(e) = deletion.await {
+            tracing::error!("Deleting Setup-Message: {:?}", e);
+        }
+
+        Ok(StateMessage {
+            guild_id: setup.guild_id,
+            channel_id,
+            message_id: control_msg.id,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct StateMessage {
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    message_id: MessageId,
+}
+
+impl StateMessage {
+    /// Only changes the Content of the Message, keeping all of its Reactions
+    pub async fn edit_content<C>(&self, http: &Http, content: C) -> Result<(), serenity::Error>
+    where
+        C: AsRef<str>,
+    {
+        dry_run::mutate("Editing Message", (), async {
+            self.channel_id
+                .edit_message(http, self.message_id, |e| e.content(content.as_ref()))
+                .await
+                .map(|_| ())
+        })
+        .await
+    }
+
+    pub async fn pin(&self, http: &Http) -> Result<(), serenity::Error> {
+        dry_run::mutate("Pinning Message", (), async {
+            self.channel_id.pin(http, self.message_id).await
+        })
+        .await
+    }
+
+    pub async fn unpin(&self, http: &Http) -> Result<(), serenity::Error> {
+        dry_run::mutate("Unpinning Message", (), async {
+            self.channel_id.unpin(http, self.message_id).await
+        })
+        .await
+    }
+
+    pub async fn update<C>(
+        &self,
+        http: &Http,
+        content: C,
+        reactions: &[Reactions],
+    ) -> Result<(), serenity::Error>
+    where
+        C: AsRef<str>,
+    {
+        let mut msg = self.channel_id.message(http, self.message_id).await?;
+
+        dry_run::mutate("Editing Message and removing its Reactions", (), async {
+            msg.edit(http, |e| e.content(content.as_ref())).await?;
+            msg.delete_reactions(http).await
+        })
+        .await?;
+
+        for reaction in reactions {
+            msg.react(http, reaction).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The Roles and their Counts of the last Round in the Guild, that still exist
+async fn last_round_roles(
+    context: &Context,
+    guild_id: GuildId,
+    all_roles: &[WereWolfRoleConfig],
+) -> Option<BTreeMap<WereWolfRoleConfig, usize>> {
+    let storage = context.storage().unwrap();
+    let rounds = match storage.load_rounds(guild_id).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("Loading the Round-History: {:?}", e);
+            return None;
+        }
+    };
+
+    // Rounds from before the Counts were recorded can't be repeated
+    let last = rounds.iter().find(|r| !r.roles.is_empty())?;
+    let roles = last.resolve_roles(all_roles);
+    if roles.is_empty() {
+        return None;
+    }
+
+    Some(roles)
+}
+
+/// Moves the Round on to selecting the Roles for the registered Players
+async fn select_roles(
+    context: &Context,
+    state: RegisterPlayersState,
+) -> Result<SelectRolesState, Arc<TransitionError>> {
+    let http = context.http().unwrap();
+    let storage = context.storage().unwrap();
+    let roles = match storage.load_roles(state.message.guild_id).await {
+        Ok(r) => roles::templates::resolve_guild_roles(state.message.guild_id, r),
+        Err(e) => {
+            tracing::error!("Loading Roles: {:?}", e);
+            return Err(state
+                .setup_failed(http, WerewolfSetupError::LoadingRoles)
+                .await);
+        }
+    };
+    if roles.is_empty() {
+        return Err(state
+            .setup_failed(http, WerewolfSetupError::NotEnoughRoles)
+            .await);
+    }
+
+    let settings = state.load_settings(context).await;
+    let selection = if settings.numbered_reactions {
+        SelectionMode::Numbered
+    } else {
+        SelectionMode::Emoji
+    };
+
+    let repeated = if state.options.repeat {
+        last_round_roles(context, state.message.guild_id, &roles).await
+    } else {
+        None
+    };
+
+    match SelectRolesState::from_first(http, state.clone(), roles, selection, repeated).await {
+        Ok(s) => Ok(s),
+        Err(e) => {
+            tracing::error!("Showing the Roles to select: {:?}", e);
+            Err(state
+                .setup_failed(http, WerewolfSetupError::RoleSelectionFailed)
+                .await)
+        }
+    }
+}
+
+async fn ready_check_transition(
+    context: Context,
+    mut state: ReadyCheckState,
+) -> (
+    TransitionResult<SelectRolesState, Arc<TransitionError>>,
+    ReadyCheckState,
+) {
+    let http = context.http().unwrap();
+
+    let continue_round = match context.event() {
+        Some(Event::AddReaction { reaction }) => {
+            let user_id = reaction.user_id.unwrap();
+            let emoji = &reaction.emoji;
+
+            match state.inner.confirmation().answer(user_id, emoji) {
+                Answer::Confirmed => {
+                    state.inner.mark_ready(user_id);
+                    if let Err(e) = state
+                        .message
+                        .edit_content(http, state.inner.content())
+                        .await
+                    {
+                        tracing::error!("Updating Ready-Check Message: {:?}", e);
+                    }
+
+                    state.inner.all_ready()
+                }
+                Answer::NotAllowed => return (TransitionResult::NoTransition, state),
+                Answer::OtherReaction => {
+                    match state
+                        .mod_confirmation(Reactions::Confirm)
+                        .answer(user_id, emoji)
+                    {
+                        // The Mods can always continue, even if not every Player is ready
+                        Answer::Confirmed => true,
+                        Answer::NotAllowed => {
+                            tracing::error!(
+                                "User({:?}) tried to skip the Ready-Check as non Moderator",
+                                user_id
+                            );
+                            reject_non_mod(&context, reaction, user_id).await;
+
+                            return (TransitionResult::NoTransition, state);
+                        }
+                        Answer::OtherReaction => false,
+                    }
+                }
+            }
+        }
+        Some(Event::Proceed {
+            sender,
+            channel,
+            outcome,
+        }) if *channel == state.message.channel_id => {
+            if !state.mods.contains(sender) {
+                let _ = outcome
+                    .send("Only the Moderators of the Round can skip its Ready-Check".to_string());
+                return (TransitionResult::NoTransition, state);
+            }
+
+            let _ = outcome.send("Skipped the Ready-Check".to_string());
+            true
+        }
+        Some(Event::RemoveReaction { reaction }) => {
+            let user_id = reaction.user_id.unwrap();
+            if Reactions::Ready == reaction.emoji && state.inner.unmark_ready(user_id) {
+                if let Err(e) = state
+                    .message
+                    .edit_content(http, state.inner.content())
+                    .await
+                {
+                    tracing::error!("Updating Ready-Check Message: {:?}", e);
+                }
+            }
+
+            false
+        }
+        _ => false,
+    };
+
+    if !continue_round {
+        return (TransitionResult::NoTransition, state);
+    }
+
+    match select_roles(&context, state.registered()).await {
+        Ok(next_state) => (TransitionResult::Done(next_state), state),
+        Err(e) => (TransitionResult::Error(e), state),
+    }
+}
+
+/// Starts the Round right after the Roles have been selected, as the Counts of all the Roles are
+/// already known, either because every selected Role is only given to a single Player or because
+/// the Counts of the repeated Round are used
+async fn start_with_counts(
+    context: &Context,
+    state: SelectRolesState,
+    roles: BTreeMap<WereWolfRoleConfig, usize>,
+) -> Result<RunningState, WerewolfSetupError> {
+    let players = state.inner.players.clone();
+    let (message_id, guild_id) = (state.message.message_id, state.message.guild_id);
+
+    let running = RunningState::new(context, state, players, roles).await?;
+
+    // Passes the Round through the RoleCounts-State without waiting for another Event
+    crate::NOTIFY_SM_QUEUE.notify(message_id, guild_id);
+
+    Ok(running)
+}
+
+/// Cancels the Round while the Role-Counts are still being entered, by removing the Messages
+/// for the missing Counts and stopping their State-Machines
+async fn cancel_counts(context: &Context, state: &RoleCountsState) {
+    let http = context.http().unwrap();
+    state.inner.cancelled.store(true, Ordering::SeqCst);
+
+    for (channel_id, message_id) in state.inner.role_messages.values() {
+        let deletion = dry_run::mutate("Deleting Role-Count Message", (), async {
+            channel_id.delete_message(http, message_id).await
+        });
+        if let Err(e) = deletion.await {
+            tracing::error!("Deleting Role-Count Message: {:?}", e);
+        }
+
+        crate::NOTIFY_SM_QUEUE.notify(*message_id, state.message.guild_id);
+    }
+
+    let content = state.options.labeled("Round was cancelled");
+    if let Err(e) = state.message.update(http, content, &[]).await {
+        tracing::error!("Updating Message with final State: {:?}", e);
+    }
+
+    // Passes the Round through the Running-State without waiting for another Event
+    crate::NOTIFY_SM_QUEUE.notify(state.message.message_id, state.message.guild_id);
+}
+
+/// Collects the Role-Counts entered by the Mods and starts the Round once all of them are known
+async fn role_counts_transition(
+    context: Context,
+    mut state: RoleCountsState,
+) -> (
+    TransitionResult<CountsEntered, Arc<TransitionError>>,
+    RoleCountsState,
+) {
+    match context.event() {
+        Some(Event::Notify) => {
+            let (role, count) = match state.inner.count_queue.pop() {
+                Some(e) => e,
+                None => return (TransitionResult::NoTransition, state),
+            };
+
+            let count_channel = state
+                .inner
+                .role_messages
+                .remove(&role)
+                .map(|(channel, _)| channel)
+                .unwrap_or(state.message.channel_id);
+
+            state.inner.roles.insert(role, count);
+
+            if state.inner.role_messages.is_empty() {
+                let balance = roles::slot_balance(state.inner.players.len(), &state.inner.roles);
+                if balance != roles::SlotBalance::Matching {
+                    let http = context.http().unwrap();
+
+                    // The Round stays in this State, so the Mods can simply enter the Counts again
+                    let content = format!(
+                        "{}, please enter the Counts again",
+                        WerewolfSetupError::MismatchedSlots(balance)
+                    );
+                    util::msgs::send_content(state.message.channel_id, http, &content).await;
+
+                    if let Err(e) = state.recount(&context, count_channel).await {
+                        tracing::error!("Asking for the Role-Counts again: {:?}", e);
+
+                        state.clear_pending(&context).await;
+                        let error = state
+                            .setup_failed(http, WerewolfSetupError::RoleCountsFailed)
+                            .await;
+                        return (TransitionResult::Error(error), state);
+                    }
+
+                    state.save_pending(&context).await;
+                    if let Err(e) = state
+                        .message
+                        .update(http, state.inner.status(), &[Reactions::Stop])
+                        .await
+                    {
+                        tracing::error!("Updating Message with current Status: {:?}", e);
+                    }
+
+                    return (TransitionResult::NoTransition, state);
+                }
+
+                state.clear_pending(&context).await;
+
+                match RunningState::new(
+                    &context,
+                    state.clone(),
+                    state.inner.players.clone(),
+                    state.inner.roles.clone(),
+                )
+                .await
+                {
+                    Ok(n_state) => (
+                        TransitionResult::Done(CountsEntered::Started(n_state)),
+                        state,
+                    ),
+                    Err(e) => (
+                        TransitionResult::Error(TransitionError::Generic(Arc::new(e)).arced()),
+                        state,
+                    ),
+                }
+            } else {
+                state.save_pending(&context).await;
+
+                if let Err(e) = state
+                    .message
+                    .update(
+                        context.http().unwrap(),
+                        state.inner.status(),
+                        &[Reactions::Stop],
+                    )
+                    .await
+                {
+                    tracing::error!("Updating Message with current Status: {:?}", e);
+                }
+
+                (TransitionResult::NoTransition, state)
+            }
+        }
+        Some(Event::AddReaction { reaction }) if Reactions::Stop == reaction.emoji => {
+            let user_id = reaction.user_id.unwrap();
+            if !state.mods.contains(&user_id) {
+                tracing::error!("User({:?}) tried to cancel the Round", user_id);
+                reject_non_mod(&context, reaction, user_id).await;
+
+                return (TransitionResult::NoTransition, state);
+            }
+
+            cancel_counts(&context, &state).await;
+            state.clear_pending(&context).await;
+
+            (TransitionResult::Done(CountsEntered::Cancelled), state)
+        }
+        _ => (TransitionResult::NoTransition, state),
+    }
+}
+
+/// Handles the Events of a running Round until the Mods end it
+async fn running_transition(
+    context: Context,
+    mut state: RunningState,
+) -> (TransitionResult<(), Arc<TransitionError>>, RunningState) {
+    match context.event() {
+        Some(Event::MemberUpdate { member }) => {
+            let user_id = member.user.id;
+            if state.inner.dead.contains(&user_id) {
+                return (TransitionResult::NoTransition, state);
+            }
+            let role = match state.inner.players.role_of(&user_id) {
+                Some(r) => r.clone(),
+                None => return (TransitionResult::NoTransition, state),
+            };
+
+            let http = context.http().unwrap();
+            let dead_role_id = match state.get_dead_player_role(http).await {
+                Ok(id) => id,
+                Err(e) => {
+                    tracing::error!("Loading Dead-Role: {:?}", e);
+                    return (TransitionResult::NoTransition, state);
+                }
+            };
+            if !member.roles.contains(&dead_role_id) {
+                return (TransitionResult::NoTransition, state);
+            }
+
+            tracing::info!("Player({:?}) has died", user_id);
+            state.player_died(&context, user_id, &role).await;
+
+            (TransitionResult::NoTransition, state)
+        }
+        Some(Event::MemberLeft { user }) => {
+            if state.inner.dead.contains(user) {
+                return (TransitionResult::NoTransition, state);
+            }
+            let role = match state.inner.players.role_of(user) {
+                Some(r) => r.clone(),
+                None => return (TransitionResult::NoTransition, state),
+            };
+
+            if !state.load_settings(&context).await.kill_leaving_players {
+                return (TransitionResult::NoTransition, state);
+            }
+
+            tracing::info!("Player({:?}) has left the Guild", user);
+            state.player_died(&context, *user, &role).await;
+
+            (TransitionResult::NoTransition, state)
+        }
+        Some(Event::Reassign {
+            player,
+            role,
+            outcome,
+        }) => {
+            // The Player takes part in a different Round
+            if state.inner.players.role_of(player).is_none() {
+                return (TransitionResult::NoTransition, state);
+            }
+
+            let http = context.http().unwrap();
+            let content = match state.reassign(http, *player, role).await {
+                Ok(c) => c,
+                Err(e) => format!("Could not reassign <@{}>: {}", player.0, e),
+            };
+            if outcome.send(content).is_err() {
+                tracing::warn!("Reassign-Command no longer waits for the Outcome");
+            }
+
+            (TransitionResult::NoTransition, state)
+        }
+        Some(Event::NextPhase { sender, outcome }) => {
+            // Only the Moderators of this Round can change its Phase
+            if !state.mods.contains(sender) {
+                return (TransitionResult::NoTransition, state);
+            }
+
+            let http = context.http().unwrap();
+            let summary = state.next_phase(http).await;
+            if outcome.send(summary).is_err() {
+                tracing::warn!("NextPhase-Command no longer waits for the Outcome");
+            }
+
+            (TransitionResult::NoTransition, state)
+        }
+        Some(Event::StartVote { sender, outcome }) => {
+            // Only the Moderators of this Round can start a Vote
+            if !state.mods.contains(sender) {
+                return (TransitionResult::NoTransition, state);
+            }
+
+            let http = context.http().unwrap();
+            let summary = state.start_vote(http).await;
+            if outcome.send(summary).is_err() {
+                tracing::warn!("StartVote-Command no longer waits for the Outcome");
+            }
+
+            (TransitionResult::NoTransition, state)
+        }
+        Some(Event::CloseVote { sender, outcome }) => {
+            // Only the Moderators of this Round can close its Vote
+            if !state.mods.contains(sender) {
+                return (TransitionResult::NoTransition, state);
+            }
+
+            let http = context.http().unwrap();
+            let summary = state.close_vote(http).await;
+            if outcome.send(summary).is_err() {
+                tracing::warn!("CloseVote-Command no longer waits for the Outcome");
+            }
+
+            (TransitionResult::NoTransition, state)
+        }
+        Some(Event::CheckOverdue {
+            threshold,
+            ping,
+            outcome,
+        }) => {
+            let now = SystemTime::now();
+            if !crate::overdue::is_overdue(state.inner.started_at, now, *threshold) {
+                return (TransitionResult::NoTransition, state);
+            }
+
+            if *ping && !state.inner.overdue_reminded {
+                let running = now
+                    .duration_since(state.inner.started_at)
+                    .unwrap_or_default();
+                let content = crate::overdue::reminder_content(state.mods.iter(), running);
+
+                let http = context.http().unwrap();
+                match state.inner.moderator_channel.say(http, &content).await {
+                    Ok(_) => state.inner.overdue_reminded = true,
+                    Err(e) => {
+                        tracing::error!("Reminding Mods of overdue Round: {:?}", e)
+                    }
+                };
+            }
+
+            if outcome.send(()).is_err() {
+                tracing::warn!("Overdue-Check no longer waits for the Outcome");
+            }
+
+            (TransitionResult::NoTransition, state)
+        }
+        Some(Event::Broadcast {
+            sender,
+            content,
+            outcome,
+        }) => {
+            // Only the Moderators of this Round can broadcast to its Channels
+            if !state.mods.contains(sender) {
+                return (TransitionResult::NoTransition, state);
+            }
+
+            let http = context.http().unwrap();
+            let summary = state.broadcast(http, content).await;
+            if outcome.send(summary).is_err() {
+                tracing::warn!("Broadcast-Command no longer waits for the Outcome");
+            }
+
+            (TransitionResult::NoTransition, state)
+        }
+        Some(Event::AddReaction { reaction }) => {
+            let on_ballot = state
+                .inner
+                .vote
+                .as_ref()
+                .is_some_and(|open| open.ballot == reaction.message_id);
+            if on_ballot {
+                let http = context.http().unwrap();
+                state.cast_vote(http, reaction).await;
+                return (TransitionResult::NoTransition, state);
+            }
+            // Reactions on the Ballots of already closed Votes are ignored
+            if reaction.message_id != state.message.message_id {
+                return (TransitionResult::NoTransition, state);
+            }
+
+            let user_id = reaction.user_id.unwrap();
+            if !state.mods.contains(&user_id) {
+                reject_non_mod(&context, reaction, user_id).await;
+                return (TransitionResult::NoTransition, state);
+            }
+
+            let emoji = &reaction.emoji;
+
+            if Reactions::Stop == emoji {
+                let http = context.http().unwrap();
+
+                let everyone_role_id = state.get_everyone_role();
+                let dead_role_id = state.get_dead_player_role(http).await.unwrap();
+
+                let channels: Vec<ChannelId> = state
+                    .inner
+                    .channels
+                    .ids()
+                    .chain(std::iter::once(state.inner.graveyard_channel))
+                    .chain(state.inner.announcement_channel)
+                    .chain(state.inner.vote_channel)
+                    .collect();
+
+                rounds::stop::stop(
+                    everyone_role_id,
+                    dead_role_id,
+                    http,
+                    state.message.guild_id,
+                    || state.inner.players.iter(),
+                    &channels,
+                )
+                .await;
+
+                if state.inner.pinned {
+                    if let Err(e) = state.message.unpin(http).await {
+                        tracing::error!("Unpinning the Control-Message: {:?}", e);
+                    }
+                }
+
+                let content = state.options.labeled("Round is over");
+                if let Err(e) = state.message.update(http, content, &[]).await {
+                    tracing::error!("Updating Message with final State: {:?}", e);
+                }
+
+                let record = RoundRecord::new(
+                    &state.inner.players,
+                    &state.inner.dead,
+                    state.inner.started_at,
+                    SystemTime::now(),
+                )
+                .with_label(state.options.label.clone());
+                let storage = context.storage().unwrap();
+                if let Err(e) = storage.store_round(state.message.guild_id, &record).await {
+                    tracing::error!("Storing the Round in the History: {:?}", e);
+                }
+
+                (TransitionResult::Done(()), state)
+            } else {
+                (TransitionResult::NoTransition, state)
+            }
+        }
+        _ => (TransitionResult::NoTransition, state),
+    }
+}
+
+/// Closes the Registration and moves the Round on to the Ready-Check or to selecting the Roles
+async fn finish_registration(
+    context: &Context,
+    state: RegisterPlayersState,
+) -> (
+    TransitionResult<PlayersRegistered, Arc<TransitionError>>,
+    RegisterPlayersState,
+) {
+    let settings = state.load_settings(context).await;
+    if settings.ready_check {
+        let http = context.http().unwrap();
+        return match ReadyCheckState::from_registered(http, state.clone()).await {
+            Ok(n) => (
+                TransitionResult::Done(PlayersRegistered::Checking(n)),
+                state,
+            ),
+            Err(e) => {
+                tracing::error!("Starting the Ready-Check: {:?}", e);
+                let error = state
+                    .setup_failed(http, WerewolfSetupError::ReadyCheckFailed)
+                    .await;
+                (TransitionResult::Error(error), state)
+            }
+        };
+    }
+
+    match select_roles(context, state.clone()).await {
+        Ok(n) => {
+            // Passes the Round through the ReadyCheck-State without waiting for another Event
+            crate::NOTIFY_SM_QUEUE.notify(state.message.message_id, state.message.guild_id);
+
+            (
+                TransitionResult::Done(PlayersRegistered::Selecting(n)),
+                state,
+            )
+        }
+        Err(e) => (TransitionResult::Error(e), state),
+    }
+}
+
+/// Hands the Event on to the State after a State that the Round only passes through, as such a
+/// State finishes on the first Event it receives instead of handling it. The Notifications that
+/// move the Round through these States are not handed on
+fn forward_event(context: &Context, message_id: MessageId) {
+    if let None | Some(Event::Notify) = context.event() {
+        return;
+    }
+
+    crate::SMMAP.requeue(message_id, context.clone());
+}
+
+/// Updates the Countdown of a timed Registration and closes it once the Time is up
+async fn registration_tick(
+    context: &Context,
+    mut state: RegisterPlayersState,
+) -> (
+    TransitionResult<PlayersRegistered, Arc<TransitionError>>,
+    RegisterPlayersState,
+) {
+    let now = SystemTime::now();
+    let remaining = match state.inner.window.as_ref().and_then(|w| w.remaining(now)) {
+        Some(r) => r,
+        None => return (TransitionResult::NoTransition, state),
+    };
+
+    if remaining.is_zero() {
+        if !state.inner.players.is_empty() {
+            return finish_registration(context, state).await;
+        }
+
+        // Nobody registered, so the Mods have to decide whether to reopen the Registration
+        tracing::info!("Registration closed without any registered Players");
+        if let Some(window) = state.inner.window.as_mut() {
+            window.closes_at = None;
+        }
+    } else {
+        crate::NOTIFY_SM_QUEUE.notify_after(
+            remaining.min(REGISTRATION_TICK),
+            state.message.message_id,
+            state.message.guild_id,
+        );
+    }
+
+    let content = state
+        .options
+        .labeled(registration_content(state.inner.window.as_ref(), now));
+    if let Err(e) = state
+        .message
+        .edit_content(context.http().unwrap(), content)
+        .await
+    {
+        tracing::error!("Updating Registration-Countdown: {:?}", e);
+    }
+
+    (TransitionResult::NoTransition, state)
+}
+
+pub async fn create(
+    ctx: &serenity::client::Context,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    mods: BTreeSet<UserId>,
+    bot_user_id: UserId,
+    options: RoundOptions,
+    registration: Option<Duration>,
 ) -> Result<MessageStateMachine<(), ()>, serenity::Error> {
-    let entry_content = format!(
-        "Starting new Round\n{}: Enter as Player\n{}: Start the Round (mods only)",
-        Reactions::Entry,
-        Reactions::Confirm
-    );
+    let now = SystemTime::now();
+    let window = registration.map(|length| RegistrationWindow::open(length, now));
+
+    let mut reactions = vec![Reactions::Entry, Reactions::Confirm];
+    if window.is_some() {
+        reactions.push(Reactions::Extend);
+    }
     let entry_msg = channel_id
         .send_message(ctx.http().as_ref(), |m| {
-            m.content(entry_content)
-                .reactions(&[Reactions::Entry, Reactions::Confirm])
+            m.content(options.labeled(registration_content(window.as_ref(), now)))
+                .reactions(reactions)
         })
         .await?;
 
+    if let Some(length) = registration {
+        crate::NOTIFY_SM_QUEUE.notify_after(length.min(REGISTRATION_TICK), entry_msg.id, guild_id);
+    }
+
     let msg = StateMessage {
         guild_id,
         channel_id,
@@ -352,9 +2100,11 @@ pub async fn create(
         mods,
         message: msg,
         bot_user: bot_user_id,
+        options,
 
         inner: RegisterPlayers {
             players: Vec::new(),
+            window,
         },
     };
 
@@ -366,44 +2116,71 @@ pub async fn create(
                     let user_id = reaction.user_id.unwrap();
                     let emoji = &reaction.emoji;
 
-                    if Reactions::Entry == emoji {
-                        state.inner.players.push(user_id);
-                    } else if Reactions::Confirm == emoji {
-                        if !state.mods.contains(&user_id) {
+                    match state
+                        .mod_confirmation(Reactions::Confirm)
+                        .answer(user_id, emoji)
+                    {
+                        Answer::Confirmed => {
+                            if state.inner.players.is_empty() {
+                                tracing::error!(
+                                    "Tried to start a Round: {}",
+                                    WerewolfSetupError::NoPlayers
+                                );
+
+                                return (TransitionResult::NoTransition, state);
+                            }
+
+                            return finish_registration(&context, state).await;
+                        }
+                        Answer::NotAllowed => {
                             tracing::error!(
                                 "User({:?}) tried to start Round as non Moderator",
                                 user_id
                             );
+                            reject_non_mod(&context, reaction, user_id).await;
 
                             return (TransitionResult::NoTransition, state);
                         }
+                        Answer::OtherReaction => {}
+                    };
 
-                        if state.inner.players.is_empty() {
-                            tracing::error!("Tried to start a Round with no registered Players");
+                    if Reactions::Entry == emoji {
+                        state.register(&context, user_id);
+                    } else if Reactions::Extend == emoji && state.inner.window.is_some() {
+                        if !state.mods.contains(&user_id) {
+                            tracing::error!(
+                                "User({:?}) tried to extend the Registration as non Moderator",
+                                user_id
+                            );
+                            reject_non_mod(&context, reaction, user_id).await;
 
                             return (TransitionResult::NoTransition, state);
                         }
 
-                        let storage = context.storage().unwrap();
-                        let roles = storage.load_roles(state.message.guild_id).await.unwrap();
-
-                        let next_state = match SelectRolesState::from_first(
-                            context.http().unwrap(),
-                            state.clone(),
-                            roles,
-                        )
-                        .await
-                        {
-                            Ok(n) => n,
-                            Err(_) => {
-                                return (
-                                    TransitionResult::Error(Arc::new(TransitionError::Serenity)),
-                                    state,
-                                );
-                            }
+                        let now = SystemTime::now();
+                        let reopened = match state.inner.window.as_mut() {
+                            Some(window) => window.extend(now),
+                            None => false,
                         };
+                        if reopened {
+                            // The Countdown stopped once the Registration closed
+                            crate::NOTIFY_SM_QUEUE.notify_after(
+                                REGISTRATION_TICK,
+                                state.message.message_id,
+                                state.message.guild_id,
+                            );
+                        }
 
-                        return (TransitionResult::Done(next_state), state);
+                        let content = state
+                            .options
+                            .labeled(registration_content(state.inner.window.as_ref(), now));
+                        if let Err(e) = state
+                            .message
+                            .edit_content(context.http().unwrap(), content)
+                            .await
+                        {
+                            tracing::error!("Updating Registration-Countdown: {:?}", e);
+                        }
                     }
 
                     (TransitionResult::NoTransition, state)
@@ -426,174 +2203,364 @@ pub async fn create(
 
                     (TransitionResult::NoTransition, state)
                 }
-                _ => (TransitionResult::NoTransition, state),
-            }
-        },
-    )
-    .chain(WithLazyState::new(
-        |arg: &SelectRolesState| arg.clone(),
-        |context: Context, mut state: SelectRolesState, _: SelectRolesState| async move {
-            match context.event() {
-                Some(Event::AddReaction { reaction }) => {
-                    let user_id = reaction.user_id.unwrap();
-                    if !state.mods.contains(&user_id) {
-                        tracing::error!("User({:?}) tried to select a Role", user_id);
-
+                Some(Event::Notify) => registration_tick(&context, state).await,
+                Some(Event::Proceed {
+                    sender,
+                    channel,
+                    outcome,
+                }) if *channel == state.message.channel_id => {
+                    if !state.mods.contains(sender) {
+                        let _ = outcome.send(
+                            "Only the Moderators of the Round can end its Registration".to_string(),
+                        );
                         return (TransitionResult::NoTransition, state);
                     }
 
-                    let emoji = &reaction.emoji;
-
-                    if Reactions::PreviousPage == emoji {
-                        state.inner.role_page -= 1;
-                        if let Err(e) = state.update_msg(context.http().unwrap()).await {
-                            tracing::error!("Updating Role-List Message: {:?}", e);
-                        }
-                    } else if Reactions::NextPage == emoji {
-                        state.inner.role_page += 1;
-                        if let Err(e) = state.update_msg(context.http().unwrap()).await {
-                            tracing::error!("Updating Role-List Message: {:?}", e);
-                        }
-                    } else if Reactions::Confirm == emoji {
-                        let next_state = match RoleCountsState::new(
-                            context.http().unwrap(),
-                            state.clone(),
-                        )
-                        .await
-                        {
-                            Ok(n) => n,
-                            Err(e) => {
-                                tracing::error!("Transitioning to next State: {:?}", e);
-                                return (
-                                    TransitionResult::Error(Arc::new(TransitionError::Serenity)),
-                                    state,
-                                );
-                            }
-                        };
-
-                        return (TransitionResult::Done(next_state), state);
-                    } else {
-                        if let Some(role) = state.find_role(emoji).cloned() {
-                            state.inner.selected_roles.insert(role.clone());
-                        }
-                    }
-                }
-                Some(Event::RemoveReaction { reaction }) => {
-                    let user_id = reaction.user_id.unwrap();
-                    if !state.mods.contains(&user_id) {
+                    if state.inner.players.is_empty() {
+                        let _ = outcome.send(WerewolfSetupError::NoPlayers.to_string());
                         return (TransitionResult::NoTransition, state);
                     }
 
-                    let emoji = &reaction.emoji;
+                    let (result, state) = finish_registration(&context, state).await;
+                    let content = match result {
+                        TransitionResult::Error(_) => "Could not end the Registration",
+                        _ => "Ended the Registration",
+                    };
+                    let _ = outcome.send(content.to_string());
 
-                    if let Some(role) = state.find_role(emoji) {
-                        let cloned = role.clone();
-                        state.inner.selected_roles.remove(&cloned);
-                    }
+                    (result, state)
                 }
-                _ => return (TransitionResult::NoTransition, state),
-            };
-
-            (TransitionResult::NoTransition, state)
+                _ => (TransitionResult::NoTransition, state),
+            }
         },
-    ))
-    .chain(WithLazyState::new(
-        |state: &RoleCountsState| state.clone(),
-        |context: Context, mut state: RoleCountsState, _: RoleCountsState| async move {
-            match context.event() {
-                Some(Event::Notify) => {
-                    if state.inner.role_messages.is_empty() {
-                        return match RunningState::new(context.http().unwrap(), state.clone()).await
-                        {
-                            Ok(n_state) => (TransitionResult::Done(n_state), state),
-                            Err(e) => (
-                                TransitionResult::Error(TransitionError::Generic(e).arced()),
-                                state,
-                            ),
-                        };
+    )
+    .named("RegisterPlayers")
+    .chain(
+        WithLazyState::new(
+            |registered: &PlayersRegistered| registered.clone(),
+            |context: Context, registered: PlayersRegistered, _: PlayersRegistered| async move {
+                match registered {
+                    // The Ready-Check is disabled, so this State is simply passed through
+                    PlayersRegistered::Selecting(state) => {
+                        forward_event(&context, state.message.message_id);
+                        (
+                            TransitionResult::Done(state.clone()),
+                            PlayersRegistered::Selecting(state),
+                        )
                     }
+                    PlayersRegistered::Checking(state) => {
+                        let (result, state) = ready_check_transition(context, state).await;
+                        (result, PlayersRegistered::Checking(state))
+                    }
+                }
+            },
+        )
+        .named("ReadyCheck"),
+    )
+    .chain(
+        WithLazyState::new(
+            |arg: &SelectRolesState| arg.clone(),
+            |context: Context, mut state: SelectRolesState, _: SelectRolesState| async move {
+                match context.event() {
+                    Some(Event::AddReaction { reaction }) => {
+                        let user_id = reaction.user_id.unwrap();
+                        if !state.mods.contains(&user_id) {
+                            tracing::error!("User({:?}) tried to select a Role", user_id);
+                            reject_non_mod(&context, reaction, user_id).await;
 
-                    let (role, count) = match state.inner.count_queue.pop() {
-                        Some(e) => e,
-                        None => return (TransitionResult::NoTransition, state),
-                    };
+                            return (TransitionResult::NoTransition, state);
+                        }
 
-                    state.inner.role_messages.remove(&role);
+                        let emoji = &reaction.emoji;
 
-                    state.inner.roles.insert(role, count);
+                        if Reactions::PreviousPage == emoji {
+                            state.inner.role_page -= 1;
+                            if let Err(e) = state.update_msg(context.http().unwrap()).await {
+                                tracing::error!("Updating Role-List Message: {:?}", e);
+                            }
+                        } else if Reactions::NextPage == emoji {
+                            state.inner.role_page += 1;
+                            if let Err(e) = state.update_msg(context.http().unwrap()).await {
+                                tracing::error!("Updating Role-List Message: {:?}", e);
+                            }
+                        } else if Reactions::Confirm == emoji {
+                            let counts =
+                                if !state.inner.selected_roles.iter().any(|r| r.multi_player()) {
+                                    Some(
+                                        state
+                                            .inner
+                                            .selected_roles
+                                            .iter()
+                                            .map(|r| (r.clone(), 1))
+                                            .collect(),
+                                    )
+                                } else {
+                                    state.repeated_counts()
+                                };
+                            if let Some(counts) = counts {
+                                // The Mods can still change the Selection, so they are told
+                                // about a Mismatch instead of failing the Round
+                                let balance =
+                                    roles::slot_balance(state.inner.players.len(), &counts);
+                                if balance != roles::SlotBalance::Matching {
+                                    let http = context.http().unwrap();
+                                    let removal =
+                                        dry_run::mutate("Removing Confirm-Reaction", (), async {
+                                            reaction.delete(http).await
+                                        });
+                                    if let Err(e) = removal.await {
+                                        tracing::error!("Removing Confirm-Reaction: {:?}", e);
+                                    }
+
+                                    util::msgs::send_content(
+                                        state.message.channel_id,
+                                        http,
+                                        &balance.to_string(),
+                                    )
+                                    .await;
+                                    return (TransitionResult::NoTransition, state);
+                                }
+
+                                return match start_with_counts(&context, state.clone(), counts)
+                                    .await
+                                {
+                                    Ok(n_state) => (
+                                        TransitionResult::Done(RolesSelected::Started(n_state)),
+                                        state,
+                                    ),
+                                    Err(e) => (
+                                        TransitionResult::Error(
+                                            TransitionError::Generic(Arc::new(e)).arced(),
+                                        ),
+                                        state,
+                                    ),
+                                };
+                            }
 
-                    if state.inner.role_messages.is_empty() {
-                        match RunningState::new(context.http().unwrap(), state.clone()).await {
-                            Ok(n_state) => (TransitionResult::Done(n_state), state),
-                            Err(e) => (
-                                TransitionResult::Error(TransitionError::Generic(e).arced()),
+                            let next_state = match RoleCountsState::new(
+                                &context,
+                                state.clone(),
+                                user_id,
+                            )
+                            .await
+                            {
+                                Ok(n) => n,
+                                Err(e) => {
+                                    tracing::error!("Transitioning to next State: {:?}", e);
+                                    let error = state
+                                        .setup_failed(
+                                            context.http().unwrap(),
+                                            WerewolfSetupError::RoleCountsFailed,
+                                        )
+                                        .await;
+                                    return (TransitionResult::Error(error), state);
+                                }
+                            };
+
+                            return (
+                                TransitionResult::Done(RolesSelected::Counting(next_state)),
                                 state,
-                            ),
+                            );
+                        } else {
+                            if let Some(role) = state.find_role(emoji).cloned() {
+                                state.inner.selected_roles.insert(role.clone());
+                            }
                         }
-                    } else {
-                        (TransitionResult::NoTransition, state)
                     }
-                }
-                _ => (TransitionResult::NoTransition, state),
-            }
-        },
-    ))
-    .chain(WithLazyState::new(
-        |state: &RunningState| state.clone(),
-        |context: Context, state: RunningState, _: RunningState| async move {
-            match context.event() {
-                Some(Event::AddReaction { reaction }) => {
-                    let user_id = reaction.user_id.unwrap();
-                    if !state.mods.contains(&user_id) {
-                        return (TransitionResult::NoTransition, state);
+                    Some(Event::RemoveReaction { reaction }) => {
+                        let user_id = reaction.user_id.unwrap();
+                        if !state.mods.contains(&user_id) {
+                            return (TransitionResult::NoTransition, state);
+                        }
+
+                        let emoji = &reaction.emoji;
+
+                        if let Some(role) = state.find_role(emoji) {
+                            let cloned = role.clone();
+                            state.inner.selected_roles.remove(&cloned);
+                        }
                     }
+                    _ => return (TransitionResult::NoTransition, state),
+                };
 
-                    let emoji = &reaction.emoji;
+                (TransitionResult::NoTransition, state)
+            },
+        )
+        .named("SelectRoles"),
+    )
+    .chain(
+        WithLazyState::new(
+            |selected: &RolesSelected| selected.clone(),
+            roles_selected_transition,
+        )
+        .named("RoleCounts"),
+    )
+    .chain(
+        WithLazyState::new(
+            |entered: &CountsEntered| entered.clone(),
+            counts_entered_transition,
+        )
+        .named("Running"),
+    );
 
-                    if Reactions::Stop == emoji {
-                        let http = context.http().unwrap();
+    Ok(MessageStateMachine::new(guild_id, entry_msg.id, sm))
+}
 
-                        let everyone_role_id = state.get_everyone_role(http).await.unwrap();
-                        let dead_role_id = state.get_dead_player_role(http).await.unwrap();
+async fn roles_selected_transition(
+    context: Context,
+    selected: RolesSelected,
+    _: RolesSelected,
+) -> (
+    TransitionResult<CountsEntered, Arc<TransitionError>>,
+    RolesSelected,
+) {
+    match selected {
+        // The Round has already been started, so this State is simply passed through
+        RolesSelected::Started(state) => {
+            forward_event(&context, state.message.message_id);
+            (
+                TransitionResult::Done(CountsEntered::Started(state.clone())),
+                RolesSelected::Started(state),
+            )
+        }
+        RolesSelected::Counting(state) => {
+            let (result, state) = role_counts_transition(context, state).await;
+            (result, RolesSelected::Counting(state))
+        }
+    }
+}
 
-                        rounds::stop::stop(
-                            everyone_role_id,
-                            dead_role_id,
-                            http,
-                            state.message.guild_id,
-                            || state.inner.players.iter(),
-                            &state.inner.channels,
-                        )
-                        .await;
+async fn counts_entered_transition(
+    context: Context,
+    entered: CountsEntered,
+    _: CountsEntered,
+) -> (TransitionResult<(), Arc<TransitionError>>, CountsEntered) {
+    match entered {
+        // The Round was cancelled while entering the Role-Counts
+        CountsEntered::Cancelled => (TransitionResult::Done(()), entered),
+        CountsEntered::Started(state) => {
+            let (result, state) = running_transition(context, state).await;
+            (result, CountsEntered::Started(state))
+        }
+    }
+}
 
-                        if let Err(e) = state.message.update(http, "Round is over", &[]).await {
-                            tracing::error!("Updating Message with final State: {:?}", e);
-                        }
+/// Recreates the State-Machine of a Round, whose Role-Counts were still being entered when the
+/// Bot stopped, starting with the Counts that are still missing
+fn resumed_counts_sm(
+    pending: PendingCounts,
+) -> (
+    MessageStateMachine<(), ()>,
+    Vec<MessageStateMachine<(), ()>>,
+) {
+    let slots = Arc::new(RoleSlots::new(
+        pending.players.len(),
+        pending.allocated_slots(),
+    ));
+    let state = RoleCountsState::from_pending(pending);
+    let (guild_id, round_msg_id) = (state.message.guild_id, state.message.message_id);
+
+    let role_sms = state
+        .inner
+        .role_messages
+        .iter()
+        .map(|(role, (channel_id, message_id))| {
+            role_count_sm(
+                guild_id,
+                RoleCountState {
+                    channel_id: *channel_id,
+                    message_id: *message_id,
+                    round_msg_id,
+                    round_guild_id: guild_id,
+                    round_mods: state.mods.clone(),
+                    role: role.clone(),
+                    count_queue: state.inner.count_queue.clone(),
+                    slots: slots.clone(),
+                    cancelled: state.inner.cancelled.clone(),
+                },
+            )
+        })
+        .collect();
+
+    let sm = WithState::new(state, |context: Context, state: RoleCountsState, _: ()| {
+        role_counts_transition(context, state)
+    })
+    .named("RoleCounts")
+    .chain(
+        WithLazyState::new(
+            |entered: &CountsEntered| entered.clone(),
+            counts_entered_transition,
+        )
+        .named("Running"),
+    );
 
-                        (TransitionResult::Done(()), state)
-                    } else {
-                        (TransitionResult::NoTransition, state)
-                    }
+    (
+        MessageStateMachine::new(guild_id, round_msg_id, sm),
+        role_sms,
+    )
+}
+
+/// Continues all the Rounds of the Guild, whose Role-Counts were still being entered when the
+/// Bot stopped
+pub async fn resume_counts(http: &Http, storage: &Storage, guild_id: GuildId) {
+    let pending = match storage.load_pending_counts(guild_id).await {
+        Ok(p) => p,
+        Err(e) => {
+            tracing::error!("Loading the pending Role-Counts: {:?}", e);
+            return;
+        }
+    };
+
+    for counts in pending {
+        let (round_msg, round_channel) = (counts.message, counts.channel);
+        let status = RoleCountsState::from_pending(counts.clone()).inner.status();
+
+        let result = crate::SMMAP
+            .create_round(guild_id, || async {
+                let (sm, role_sms) = resumed_counts_sm(counts);
+                for role_sm in role_sms {
+                    crate::SMMAP.add(role_sm.message_id(), role_sm);
                 }
-                _ => (TransitionResult::NoTransition, state),
+
+                Ok::<_, ()>(sm)
+            })
+            .await;
+        if let Err(e) = result {
+            tracing::error!("Resuming the Round({:?}): {:?}", round_msg, e);
+            if let Err(e) = storage.clear_pending_counts(guild_id, round_msg).await {
+                tracing::error!("Removing the pending Role-Counts: {:?}", e);
             }
-        },
-    ));
+            continue;
+        }
 
-    Ok(MessageStateMachine::new(guild_id, entry_msg.id, sm))
+        tracing::info!("Resumed entering the Role-Counts of Round({:?})", round_msg);
+        let message = StateMessage {
+            guild_id,
+            channel_id: round_channel,
+            message_id: round_msg,
+        };
+        if let Err(e) = message.update(http, status, &[Reactions::Stop]).await {
+            tracing::error!("Updating Message with current Status: {:?}", e);
+        }
+    }
 }
 
 #[derive(Debug)]
 struct RoleCountState {
     channel_id: ChannelId,
-    current_msg: Message,
+    message_id: MessageId,
     round_msg_id: MessageId,
     round_guild_id: GuildId,
     round_mods: BTreeSet<UserId>,
     role: WereWolfRoleConfig,
     count_queue: Arc<crossbeam::queue::SegQueue<(WereWolfRoleConfig, usize)>>,
+    slots: Arc<RoleSlots>,
+    /// Set once the Round has been cancelled, which stops this State-Machine on the next
+    /// Notification
+    cancelled: Arc<AtomicBool>,
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn create_role_sm(
     http: &Http,
     guild_id: GuildId,
@@ -603,6 +2570,8 @@ async fn create_role_sm(
     round_mods: BTreeSet<UserId>,
     role: WereWolfRoleConfig,
     count_queue: Arc<crossbeam::queue::SegQueue<(WereWolfRoleConfig, usize)>>,
+    slots: Arc<RoleSlots>,
+    cancelled: Arc<AtomicBool>,
 ) -> Result<MessageStateMachine<(), ()>, serenity::Error> {
     let msg_content = format!(
         "Reply with the Number of Players that should be assigned to the '{}'-Role",
@@ -612,24 +2581,35 @@ async fn create_role_sm(
         .send_message(http, |m| m.content(&msg_content))
         .await?;
 
-    let message_id = msg.id;
-
     let tmp_state = RoleCountState {
         channel_id,
-        current_msg: msg,
+        message_id: msg.id,
         round_msg_id,
         round_guild_id,
         round_mods,
         role,
         count_queue,
+        slots,
+        cancelled,
     };
 
+    Ok(role_count_sm(guild_id, tmp_state))
+}
+
+fn role_count_sm(guild_id: GuildId, state: RoleCountState) -> MessageStateMachine<(), ()> {
+    let message_id = state.message_id;
+
     let sm = WithState::new(
-        tmp_state,
+        state,
         |context: Context, state: RoleCountState, _: ()| async move {
             match context.event() {
+                // The Message has already been removed while cancelling the Round
+                Some(Event::Notify) if state.cancelled.load(Ordering::SeqCst) => {
+                    (TransitionResult::Done(()), state)
+                }
                 Some(Event::Reply { message }) => {
                     if !state.round_mods.contains(&message.author.id) {
+                        notify_non_mod(&context, message.channel_id, message.author.id).await;
                         return (TransitionResult::NoTransition, state);
                     }
 
@@ -642,11 +2622,39 @@ async fn create_role_sm(
                         }
                     };
 
+                    // Roles that mask another Role are checked once all the Counts are known
+                    if !state.role.masks_role() {
+                        if let Err(remaining) = state.slots.allocate(parsed) {
+                            util::msgs::send_content(
+                                message.channel_id,
+                                context.http().unwrap(),
+                                &format!(
+                                    "Can't assign {} Players to the '{}'-Role, only {} Player(s) left without a Role",
+                                    parsed,
+                                    state.role.name(),
+                                    remaining
+                                ),
+                            )
+                            .await;
+
+                            return (TransitionResult::NoTransition, state);
+                        }
+                    }
+
                     let http = context.http().unwrap();
-                    if let Err(e) = message.delete(http).await {
-                        tracing::error!("Deleting Response to Role-Count: {:?}", e);
+                    // Replies of other Users can't be deleted in Direct Messages
+                    if message.guild_id.is_some() {
+                        let deletion = dry_run::mutate("Deleting Response to Role-Count", (), async {
+                            message.delete(http).await
+                        });
+                        if let Err(e) = deletion.await {
+                            tracing::error!("Deleting Response to Role-Count: {:?}", e);
+                        }
                     }
-                    if let Err(e) = state.current_msg.delete(http).await {
+                    let deletion = dry_run::mutate("Deleting Role-Count Message", (), async {
+                        state.channel_id.delete_message(http, state.message_id).await
+                    });
+                    if let Err(e) = deletion.await {
                         tracing::error!("Deleting Role-Count Message: {:?}", e);
                     }
 
@@ -659,7 +2667,452 @@ async fn create_role_sm(
                 _ => (TransitionResult::NoTransition, state),
             }
         },
-    );
+    )
+    .named("RoleCount");
+
+    MessageStateMachine::new(guild_id, message_id, sm)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sms::StateMachineMap;
+
+    use super::*;
+
+    fn count_state(cancelled: Arc<AtomicBool>) -> RoleCountState {
+        RoleCountState {
+            channel_id: ChannelId(2),
+            message_id: MessageId(3),
+            round_msg_id: MessageId(1),
+            round_guild_id: GuildId(13),
+            round_mods: BTreeSet::new(),
+            role: WereWolfRoleConfig::new("Werewolf", "🐺", true, false, Vec::new()),
+            count_queue: Arc::new(crossbeam::queue::SegQueue::new()),
+            slots: Arc::new(RoleSlots::new(4, 0)),
+            cancelled,
+        }
+    }
+
+    #[test]
+    fn ready_check() {
+        let mut check = ReadyCheck {
+            players: vec![UserId(1), UserId(2)],
+            ready: BTreeSet::new(),
+        };
+
+        // Only registered Players can be ready
+        assert!(!check.mark_ready(UserId(3)));
+        assert!(check.mark_ready(UserId(1)));
+        assert!(!check.all_ready());
+        assert!(check
+            .content()
+            .starts_with("Ready-Check (1/2 Players are ready)"));
+
+        assert!(check.mark_ready(UserId(2)));
+        assert!(check.all_ready());
+
+        // Players can take back that they are ready
+        assert!(check.unmark_ready(UserId(2)));
+        assert!(!check.unmark_ready(UserId(2)));
+        assert!(!check.all_ready());
+        assert!(check
+            .content()
+            .starts_with("Ready-Check (1/2 Players are ready)"));
+    }
+
+    #[tokio::test]
+    async fn proceed_ready_check_mods_only() {
+        let state = ReadyCheckState {
+            mods: vec![UserId(4)].into_iter().collect(),
+            message: StateMessage {
+                guild_id: GuildId(13),
+                channel_id: ChannelId(2),
+                message_id: MessageId(1),
+            },
+            bot_user: UserId(7),
+            options: RoundOptions::default(),
+            inner: ReadyCheck {
+                players: vec![UserId(1), UserId(2)],
+                ready: BTreeSet::new(),
+            },
+        };
+        let (outcome_tx, mut outcome_rx) = tokio::sync::mpsc::unbounded_channel();
+        let proceed = |sender: UserId, channel: ChannelId| {
+            Context::new(
+                Some(Arc::new(Http::new_with_token(""))),
+                Some(Event::Proceed {
+                    sender,
+                    channel,
+                    outcome: outcome_tx.clone(),
+                }),
+                None,
+                GuildId(13),
+            )
+        };
+
+        // A Round in another Channel ignores the Command
+        let (result, state) = ready_check_transition(proceed(UserId(4), ChannelId(3)), state).await;
+        assert!(matches!(result, TransitionResult::NoTransition));
+        assert!(outcome_rx.try_recv().is_err());
+
+        let (result, _) = ready_check_transition(proceed(UserId(1), ChannelId(2)), state).await;
+        assert!(matches!(result, TransitionResult::NoTransition));
+        assert_eq!(
+            "Only the Moderators of the Round can skip its Ready-Check",
+            outcome_rx.try_recv().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn ready_check_players_only() {
+        let state = ReadyCheckState {
+            mods: vec![UserId(4)].into_iter().collect(),
+            message: StateMessage {
+                guild_id: GuildId(13),
+                channel_id: ChannelId(2),
+                message_id: MessageId(1),
+            },
+            bot_user: UserId(7),
+            options: RoundOptions::default(),
+            inner: ReadyCheck {
+                players: vec![UserId(1), UserId(2)],
+                ready: BTreeSet::new(),
+            },
+        };
+        let reaction: Reaction = serde_json::from_value(serde_json::json!({
+            "channel_id": "2",
+            "message_id": "1",
+            "user_id": "4",
+            "guild_id": "13",
+            "emoji": { "id": null, "name": Reactions::Ready.to_str() },
+        }))
+        .unwrap();
+        let context = Context::new(
+            Some(Arc::new(Http::new_with_token(""))),
+            Some(Event::AddReaction { reaction }),
+            None,
+            GuildId(13),
+        );
+
+        // The Mod is not one of the Players, so they can't be ready
+        let (result, state) = ready_check_transition(context, state).await;
+        assert!(matches!(result, TransitionResult::NoTransition));
+        assert!(state.inner.ready.is_empty());
+    }
+
+    #[test]
+    fn everyone_role_is_guild() {
+        let state = GeneralWerewolfState {
+            mods: BTreeSet::new(),
+            message: StateMessage {
+                guild_id: GuildId(13),
+                channel_id: ChannelId(2),
+                message_id: MessageId(1),
+            },
+            bot_user: UserId(7),
+            options: RoundOptions::default(),
+            inner: (),
+        };
+
+        // Resolved without asking Discord, as the @everyone Role shares its ID with the Guild
+        assert_eq!(RoleId(13), state.get_everyone_role());
+    }
+
+    #[test]
+    fn repeat_last_counts() {
+        let wolf = WereWolfRoleConfig::new("Werewolf", "🐺", true, false, Vec::new());
+        let villager = WereWolfRoleConfig::new("Villager", "🧑", true, false, Vec::new());
+        let seer = WereWolfRoleConfig::new("Seer", "🔮", false, false, Vec::new());
+        let doppel = WereWolfRoleConfig::new("Doppelganger", "🎭", false, true, Vec::new());
+
+        let repeated: BTreeMap<_, _> = vec![(wolf.clone(), 2), (villager.clone(), 2)]
+            .into_iter()
+            .collect();
+        let mut state = SelectRolesState {
+            mods: BTreeSet::new(),
+            message: StateMessage {
+                guild_id: GuildId(13),
+                channel_id: ChannelId(2),
+                message_id: MessageId(1),
+            },
+            bot_user: UserId(7),
+            options: RoundOptions::default(),
+            inner: SelectRoles {
+                players: (1..=5).map(UserId).collect(),
+                all_roles: vec![wolf.clone(), villager.clone(), seer.clone(), doppel.clone()],
+                role_page: 0,
+                selection: SelectionMode::Emoji,
+                selected_roles: repeated.keys().cloned().collect(),
+                repeated: Some(repeated),
+            },
+        };
+
+        // The last Round only had 4 Players
+        assert_eq!(None, state.repeated_counts());
+
+        // Single Roles don't need a Count and masking Roles don't take up a Player
+        state.inner.selected_roles.insert(seer.clone());
+        state.inner.selected_roles.insert(doppel.clone());
+        let expected: BTreeMap<_, _> = vec![(wolf, 2), (villager, 2), (seer, 1), (doppel, 1)]
+            .into_iter()
+            .collect();
+        assert_eq!(Some(expected), state.repeated_counts());
+
+        // A newly selected Role has no Count from the last Round
+        state.inner.selected_roles.insert(WereWolfRoleConfig::new(
+            "Hunter",
+            "🏹",
+            true,
+            false,
+            Vec::new(),
+        ));
+        assert_eq!(None, state.repeated_counts());
+    }
+
+    #[test]
+    fn registration_window() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let mut window = RegistrationWindow::open(Duration::from_secs(300), start);
+
+        let later = start + Duration::from_secs(100);
+        assert_eq!(Some(Duration::from_secs(200)), window.remaining(later));
+        assert_eq!(
+            Some(Duration::from_secs(0)),
+            window.remaining(start + Duration::from_secs(400))
+        );
+
+        // Extending an open Registration adds to the current Deadline
+        assert!(!window.extend(later));
+        assert_eq!(Some(Duration::from_secs(500)), window.remaining(later));
+
+        // A closed Registration is reopened from now on
+        window.closes_at = None;
+        assert_eq!(None, window.remaining(later));
+        assert!(window.extend(later));
+        assert_eq!(Some(Duration::from_secs(300)), window.remaining(later));
+    }
+
+    #[test]
+    fn registration_window_overflow() {
+        let start = SystemTime::UNIX_EPOCH;
+        let mut window = RegistrationWindow::open(Duration::MAX, start);
+        assert_eq!(Some(Duration::from_secs(0)), window.remaining(start));
+
+        assert!(!window.extend(start));
+        assert_eq!(Some(Duration::from_secs(0)), window.remaining(start));
+    }
+
+    #[test]
+    fn registration_countdown() {
+        let start = SystemTime::UNIX_EPOCH;
+        assert_eq!(
+            "Starting new Round\n✅: Enter as Player\n🆗: Start the Round (mods only)",
+            registration_content(None, start)
+        );
+
+        let mut window = RegistrationWindow::open(Duration::from_secs(300), start);
+        let content = registration_content(Some(&window), start + Duration::from_millis(29_600));
+        assert!(content.ends_with(
+            "⏳: Extend the Registration by 5m 0s (mods only)\nRegistration closes in 4m 30s"
+        ));
+
+        window.closes_at = None;
+        assert!(registration_content(Some(&window), start)
+            .ends_with("Registration closed without any Players"));
+    }
+
+    #[test]
+    fn pin_limit() {
+        let response = |code| {
+            serenity::Error::Http(Box::new(HttpError::UnsuccessfulRequest(
+                serenity::http::error::ErrorResponse {
+                    status_code: serenity::http::StatusCode::BAD_REQUEST,
+                    url: "https://discord.com/api/v8/channels/1/pins/2"
+                        .parse()
+                        .unwrap(),
+                    error: serde_json::from_value(
+                        serde_json::json!({ "code": code, "message": "" }),
+                    )
+                    .unwrap(),
+                },
+            )))
+        };
+
+        assert!(pin_limit_reached(&response(30003)));
+        assert!(!pin_limit_reached(&response(50013)));
+        assert!(!pin_limit_reached(&serenity::Error::Other("Pinning")));
+    }
 
-    Ok(MessageStateMachine::new(guild_id, message_id, sm))
+    #[test]
+    fn bot_not_registered() {
+        let mut state = RegisterPlayersState {
+            mods: BTreeSet::new(),
+            message: StateMessage {
+                guild_id: GuildId(13),
+                channel_id: ChannelId(2),
+                message_id: MessageId(1),
+            },
+            bot_user: UserId(7),
+            options: RoundOptions::default(),
+            inner: RegisterPlayers {
+                players: Vec::new(),
+                window: None,
+            },
+        };
+        let context = Context::new(None, None, None, GuildId(13)).with_bot_id(UserId(8));
+
+        assert!(!state.register(&context, UserId(8)));
+        assert!(!state.register(&context, UserId(7)));
+        assert!(state.register(&context, UserId(3)));
+        assert_eq!(vec![UserId(3)], state.inner.players);
+    }
+
+    #[test]
+    fn pending_counts_round_trip() {
+        let wolf = WereWolfRoleConfig::new("Werewolf", "🐺", true, false, Vec::new());
+        let seer = WereWolfRoleConfig::new("Seer", "🔮", false, false, Vec::new());
+        let pending = PendingCounts {
+            guild: GuildId(13),
+            channel: ChannelId(2),
+            message: MessageId(1),
+            mods: vec![UserId(4)].into_iter().collect(),
+            bot_user: UserId(7),
+            options: RoundOptions::default(),
+            players: vec![UserId(10), UserId(11), UserId(12)],
+            counted: vec![(seer, 1)],
+            pending: vec![PendingRole {
+                role: wolf,
+                channel: ChannelId(2),
+                message: MessageId(3),
+            }],
+            counted_roles: 1,
+            via_dm: false,
+        };
+
+        let state = RoleCountsState::from_pending(pending.clone());
+        assert_eq!(
+            "Configuring Roles... (Configured 0/1 Roles)",
+            state.inner.status()
+        );
+        assert_eq!(pending, state.pending());
+
+        let (round_sm, role_sms) = resumed_counts_sm(pending);
+        assert_eq!(MessageId(1), round_sm.message_id());
+        assert_eq!(Some("RoleCounts"), round_sm.state_name());
+        assert_eq!(
+            vec![MessageId(3)],
+            role_sms
+                .iter()
+                .map(|sm| sm.message_id())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn reaction_passed_through_role_counts() {
+        use crate::messages::SingleState;
+
+        let message = StateMessage {
+            guild_id: GuildId(13),
+            channel_id: ChannelId(2),
+            message_id: MessageId(434),
+        };
+        let running = RunningState {
+            mods: BTreeSet::new(),
+            message: message.clone(),
+            bot_user: UserId(7),
+            options: RoundOptions::default(),
+            inner: Running {
+                players: Distribution::new(BTreeMap::new()),
+                moderator_channel: ChannelId(3),
+                graveyard_channel: ChannelId(4),
+                channels: ChannelRegistry::default(),
+                dead: BTreeSet::new(),
+                started_at: SystemTime::UNIX_EPOCH,
+                announce_channel: ChannelId(2),
+                announcement_channel: None,
+                phase: Phase::default(),
+                narrator: Narrator::default(),
+                overdue_reminded: false,
+                pinned: false,
+                vote_channel: None,
+                vote: None,
+            },
+        };
+
+        // The Round was started right after selecting the Roles and the next Running-State only
+        // records the Reactions it receives
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let sm = SingleState::new(move |_: Context, _: ()| {
+            let running = running.clone();
+            async move { TransitionResult::Done(RolesSelected::Started(running)) }
+        })
+        .chain(WithLazyState::new(
+            |selected: &RolesSelected| selected.clone(),
+            roles_selected_transition,
+        ))
+        .chain(SingleState::new(
+            move |context: Context, _: CountsEntered| {
+                if let Some(Event::AddReaction { reaction }) = context.event() {
+                    tx.send(reaction.emoji.clone()).unwrap();
+                }
+                async move { TransitionResult::<(), _>::NoTransition }
+            },
+        ));
+        crate::SMMAP.add(
+            message.message_id,
+            MessageStateMachine::new(message.guild_id, message.message_id, sm),
+        );
+
+        let reaction: Reaction = serde_json::from_value(serde_json::json!({
+            "channel_id": "2",
+            "message_id": "434",
+            "user_id": "5",
+            "guild_id": "13",
+            "emoji": { "id": null, "name": "✅" },
+        }))
+        .unwrap();
+
+        crate::SMMAP
+            .update(
+                message.message_id,
+                Context::new(None, Some(Event::Notify), None, GuildId(13)),
+            )
+            .await;
+        crate::SMMAP
+            .update(
+                message.message_id,
+                Context::new(
+                    None,
+                    Some(Event::AddReaction { reaction }),
+                    None,
+                    GuildId(13),
+                ),
+            )
+            .await;
+
+        let received = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+            .await
+            .unwrap();
+        assert_eq!(Some(ReactionType::Unicode("✅".to_string())), received);
+    }
+
+    #[tokio::test]
+    async fn cancel_during_counts() {
+        let map = StateMachineMap::new();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        map.add(
+            MessageId(3),
+            role_count_sm(GuildId(13), count_state(cancelled.clone())),
+        );
+
+        let notify = || Context::new(None, Some(Event::Notify), None, GuildId(13));
+
+        map.update(MessageId(3), notify()).await;
+        assert_eq!(Some(GuildId(13)), map.guild_of(MessageId(3)));
+
+        cancelled.store(true, Ordering::SeqCst);
+        map.update(MessageId(3), notify()).await;
+        assert_eq!(None, map.guild_of(MessageId(3)));
+    }
 }
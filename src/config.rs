@@ -0,0 +1,222 @@
+use std::{env, fmt::Debug, str::FromStr, time::Duration};
+
+use serenity::client::bridge::gateway::GatewayIntents;
+
+use crate::{aliases::CommandAliases, overdue::OverdueAction, storage::discord::StorageFormat};
+
+/// The Deployment-wide Configuration of the Bot, which is loaded from Environment-Variables
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// The maximum Number of Rounds that can be running on a single Guild at the same time
+    pub max_rounds_per_guild: usize,
+    /// The maximum Number of Roles that can be stored for a single Guild
+    pub max_roles_per_guild: usize,
+    /// The maximum Number of Messages scanned in the Settings-Channel of a Guild
+    pub settings_scan_limit: usize,
+    /// The Format in which the Roles are stored in the Settings-Channel
+    pub settings_format: StorageFormat,
+    /// The Name of the Category in which new Settings-Channels are created, `None` to create them
+    /// at the Root of the Guild
+    pub settings_category: Option<String>,
+    /// Whether or not new Settings-Channels are hidden from `@everyone`
+    pub hide_settings_channel: bool,
+    /// The Marker in front of every Message that stores a single Role, empty to accept every
+    /// Message with a valid Role
+    pub settings_role_marker: String,
+    /// The Number of Settings-Channels across which the Roles are spread
+    pub settings_role_channels: usize,
+    /// Whether or not mutating Discord-Operations are only logged instead of executed, see
+    /// [`crate::util::dry_run`] for the affected Operations
+    pub dry_run: bool,
+    /// Additional Aliases for the Commands, on top of the built-in ones
+    pub command_aliases: CommandAliases,
+    /// The Number of Minutes after which a running Round is considered overdue, `0` disables the
+    /// Check
+    pub overdue_round_minutes: u64,
+    /// What happens with Rounds that are overdue
+    pub overdue_round_action: OverdueAction,
+    /// The Number of Seconds after which the cached Roles are reloaded from the Storage, `0`
+    /// disables the periodic Refresh
+    pub role_refresh_seconds: u64,
+    /// The Number of Minutes after which an unfinished add-role Session is discarded, `0` keeps
+    /// them around until they are finished
+    pub add_role_timeout_minutes: u64,
+    /// The Number of Days after which unused Channels in the Inactive-Category are deleted, `0`
+    /// keeps them forever
+    pub inactive_channel_retention_days: u64,
+    /// The maximum Number of Channels that are set up at the same time when starting a Round
+    pub channel_setup_concurrency: usize,
+    /// The Number of Milliseconds waited between the Operations on a Channel and its Permissions,
+    /// when starting or stopping a Round
+    pub channel_operation_delay_ms: u64,
+    /// Whether Players are marked as dead once they receive the Dead-Role or leave the Guild
+    pub dead_role_detection: bool,
+}
+
+/// The Features of the Bot that need Gateway-Intents on top of the Guilds, Messages and Reactions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Feature {
+    /// Resolving the Mods of a Guild, by listing the Members that have one of the Mod-Roles
+    ModRoles,
+    /// Marking Players as dead once they receive the Dead-Role or leave the Guild
+    DeadRoleDetection,
+}
+
+impl Feature {
+    /// The Gateway-Intents the Feature needs to work
+    pub fn intents(&self) -> GatewayIntents {
+        match self {
+            // Listing the Members of a Guild is restricted by the privileged Intent
+            Self::ModRoles => GatewayIntents::GUILD_MEMBERS,
+            // The Member-Updates and -Removals are only sent with the privileged Intent
+            Self::DeadRoleDetection => GatewayIntents::GUILD_MEMBERS,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_rounds_per_guild: 1,
+            max_roles_per_guild: 250,
+            settings_scan_limit: 5000,
+            settings_format: StorageFormat::PerRole,
+            settings_category: None,
+            hide_settings_channel: false,
+            settings_role_marker: crate::storage::discord::DEFAULT_ROLE_MARKER.to_string(),
+            settings_role_channels: 1,
+            dry_run: false,
+            command_aliases: CommandAliases::default(),
+            overdue_round_minutes: 0,
+            overdue_round_action: OverdueAction::Metric,
+            role_refresh_seconds: 0,
+            add_role_timeout_minutes: 30,
+            inactive_channel_retention_days: 0,
+            channel_setup_concurrency: 4,
+            channel_operation_delay_ms: 50,
+            dead_role_detection: true,
+        }
+    }
+}
+
+/// Loads and parses the Environment-Variable with the given Name, falls back to the default Value
+/// if the Variable is not set or could not be parsed
+fn env_or<T>(name: &str, default: T) -> T
+where
+    T: FromStr,
+    T::Err: Debug,
+{
+    match env::var(name) {
+        Ok(raw) => match raw.parse() {
+            Ok(v) => v,
+            Err(e) => {
+                tracing::error!("Parsing Environment-Variable '{}': {:?}", name, e);
+                default
+            }
+        },
+        Err(_) => default,
+    }
+}
+
+impl Config {
+    /// Loads the Configuration from the Environment-Variables, every Option that is not set will
+    /// use its default Value
+    pub fn from_env() -> Self {
+        let default = Self::default();
+
+        Self {
+            max_rounds_per_guild: env_or("MAX_ROUNDS_PER_GUILD", default.max_rounds_per_guild),
+            max_roles_per_guild: env_or("MAX_ROLES_PER_GUILD", default.max_roles_per_guild),
+            settings_scan_limit: env_or("SETTINGS_SCAN_LIMIT", default.settings_scan_limit),
+            settings_format: env_or("SETTINGS_FORMAT", default.settings_format),
+            settings_category: env::var("SETTINGS_CATEGORY")
+                .ok()
+                .filter(|c| !c.is_empty())
+                .or(default.settings_category),
+            hide_settings_channel: env_or("HIDE_SETTINGS_CHANNEL", default.hide_settings_channel),
+            settings_role_marker: env::var("SETTINGS_ROLE_MARKER")
+                .unwrap_or(default.settings_role_marker),
+            settings_role_channels: env_or(
+                "SETTINGS_ROLE_CHANNELS",
+                default.settings_role_channels,
+            ),
+            dry_run: env_or("DRY_RUN", default.dry_run),
+            command_aliases: env_or("COMMAND_ALIASES", default.command_aliases),
+            overdue_round_minutes: env_or("OVERDUE_ROUND_MINUTES", default.overdue_round_minutes),
+            overdue_round_action: env_or("OVERDUE_ROUND_ACTION", default.overdue_round_action),
+            role_refresh_seconds: env_or("ROLE_REFRESH_SECONDS", default.role_refresh_seconds),
+            add_role_timeout_minutes: env_or(
+                "ADD_ROLE_TIMEOUT_MINUTES",
+                default.add_role_timeout_minutes,
+            ),
+            inactive_channel_retention_days: env_or(
+                "INACTIVE_CHANNEL_RETENTION_DAYS",
+                default.inactive_channel_retention_days,
+            ),
+            channel_setup_concurrency: env_or(
+                "CHANNEL_SETUP_CONCURRENCY",
+                default.channel_setup_concurrency,
+            ),
+            channel_operation_delay_ms: env_or(
+                "CHANNEL_OPERATION_DELAY_MS",
+                default.channel_operation_delay_ms,
+            ),
+            dead_role_detection: env_or("DEAD_ROLE_DETECTION", default.dead_role_detection),
+        }
+    }
+
+    /// The Delay between the Operations on a Channel and its Permissions
+    pub fn channel_operation_delay(&self) -> Duration {
+        Duration::from_millis(self.channel_operation_delay_ms)
+    }
+
+    /// The Features enabled by this Configuration, the Mod-Roles are needed by every Command
+    /// that is restricted to Mods and can't be disabled
+    pub fn features(&self) -> Vec<Feature> {
+        let mut features = vec![Feature::ModRoles];
+        if self.dead_role_detection {
+            features.push(Feature::DeadRoleDetection);
+        }
+
+        features
+    }
+
+    /// The Gateway-Intents needed for the enabled Features
+    pub fn intents(&self) -> GatewayIntents {
+        let base = GatewayIntents::GUILDS
+            | GatewayIntents::GUILD_MESSAGES
+            | GatewayIntents::GUILD_MESSAGE_REACTIONS
+            | GatewayIntents::DIRECT_MESSAGES;
+
+        self.features()
+            .iter()
+            .fold(base, |intents, feature| intents | feature.intents())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intents_default() {
+        let intents = Config::default().intents();
+
+        assert!(intents.contains(GatewayIntents::GUILD_MEMBERS));
+        assert!(intents.contains(GatewayIntents::GUILD_MESSAGE_REACTIONS));
+    }
+
+    #[test]
+    fn intents_without_dead_role_detection() {
+        let config = Config {
+            dead_role_detection: false,
+            ..Config::default()
+        };
+        assert_eq!(vec![Feature::ModRoles], config.features());
+
+        // The Mods are still resolved from the Member-List
+        let intents = config.intents();
+        assert!(intents.contains(GatewayIntents::GUILD_MEMBERS));
+        assert!(intents.contains(GatewayIntents::GUILDS | GatewayIntents::GUILD_MESSAGES));
+    }
+}
@@ -1,9 +1,16 @@
-use std::sync::Arc;
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use async_trait::async_trait;
 use lazy_static::lazy_static;
 use serenity::{
-    client::{bridge::gateway::GatewayIntents, Context, EventHandler},
+    client::{Context, EventHandler},
     framework::standard::{
         macros::{command, group},
         Args, CommandResult, StandardFramework,
@@ -23,14 +30,23 @@ pub const MOD_ROLE_NAME: &str = "Game Master";
 pub const DEAD_ROLE_NAME: &str = "W-Dead";
 
 lazy_static! {
-    static ref SMMAP: sms::StateMachineMap = sms::StateMachineMap::new();
+    static ref CONFIG: config::Config = config::Config::from_env();
+    static ref SMMAP: sms::StateMachineMap =
+        sms::StateMachineMap::with_round_limit(CONFIG.max_rounds_per_guild)
+            .with_observer(metrics::TransitionMetrics);
     static ref NOTIFY_SM_QUEUE: notifier::NotifyQueue = notifier::NotifyQueue::new();
 }
 
+mod aliases;
+mod archival;
+mod config;
+
 mod notifier;
+mod overdue;
 
 mod roles;
 mod rounds;
+mod settings;
 
 mod reactions;
 pub use reactions::Reactions;
@@ -45,6 +61,7 @@ mod commands;
 pub mod metrics;
 
 pub mod messages;
+pub mod shutdown;
 pub mod sms;
 
 struct BotStorage;
@@ -58,6 +75,9 @@ struct Handler {
     id: UserId,
 
     ready_metric: prometheus::IntGauge,
+    /// Whether the interrupted Rounds have already been resumed, as the Ready-Event is also
+    /// received after reconnecting
+    resumed: AtomicBool,
 }
 
 impl Handler {
@@ -71,10 +91,15 @@ impl Handler {
 
         registry.register(Box::new(ready_metric.clone())).unwrap();
 
-        Self { id, ready_metric }
+        Self {
+            id,
+            ready_metric,
+            resumed: AtomicBool::new(false),
+        }
     }
 
     async fn update_sm(
+        &self,
         guild_id: GuildId,
         message_id: MessageId,
         http: &Arc<Http>,
@@ -86,7 +111,8 @@ impl Handler {
             Some(event),
             Some(storage.clone()),
             guild_id,
-        );
+        )
+        .with_bot_id(self.id);
 
         SMMAP.update(message_id, context).await;
     }
@@ -108,13 +134,23 @@ impl EventHandler for Handler {
     async fn ready(
         &self,
         ctx: serenity::client::Context,
-        _data_about_bot: serenity::model::prelude::Ready,
+        data_about_bot: serenity::model::prelude::Ready,
     ) {
         ctx.set_activity(Activity::listening(PREFIX)).await;
 
         self.ready_metric.set(1);
 
         tracing::info!("Bot is ready");
+
+        if !self.resumed.swap(true, Ordering::SeqCst) {
+            let storage = get_storage(&*ctx.data.read().await).clone();
+            let http = ctx.http.clone();
+            tokio::spawn(async move {
+                for guild in data_about_bot.guilds {
+                    commands::resume_counts(&http, &storage, guild.id()).await;
+                }
+            });
+        }
     }
 
     #[tracing::instrument(skip(self, ctx, add_reaction))]
@@ -131,9 +167,14 @@ impl EventHandler for Handler {
             }
         };
 
+        // Reactions on Confirmation-Messages don't belong to any State-Machine
+        if util::confirm::handle_reaction(&add_reaction) {
+            return;
+        }
+
         let data = ctx.data.read().await;
         let storage = data.get::<BotStorage>().unwrap();
-        Self::update_sm(
+        self.update_sm(
             add_reaction.guild_id.unwrap(),
             add_reaction.message_id,
             &ctx.http,
@@ -166,7 +207,7 @@ impl EventHandler for Handler {
         let data = ctx.data.read().await;
         let storage = data.get::<BotStorage>().unwrap();
 
-        Self::update_sm(
+        self.update_sm(
             removed_reaction.guild_id.unwrap(),
             removed_reaction.message_id,
             &ctx.http,
@@ -186,11 +227,18 @@ impl EventHandler for Handler {
         };
         let reply_id = ref_message.id;
 
+        // Replies in Direct Messages have no Guild, so they are routed to the Guild of the
+        // State-Machine they belong to
+        let guild_id = match new_message.guild_id.or_else(|| SMMAP.guild_of(reply_id)) {
+            Some(g) => g,
+            None => return,
+        };
+
         let data = ctx.data.read().await;
         let storage = data.get::<BotStorage>().unwrap();
 
-        Self::update_sm(
-            new_message.guild_id.unwrap(),
+        self.update_sm(
+            guild_id,
             reply_id,
             &ctx.http,
             storage,
@@ -201,22 +249,122 @@ impl EventHandler for Handler {
         .await;
     }
 
+    #[tracing::instrument(skip(self, ctx, _old_if_available, new))]
     async fn guild_member_update(
         &self,
-        _ctx: Context,
+        ctx: Context,
         _old_if_available: Option<serenity::model::guild::Member>,
-        _new: serenity::model::guild::Member,
+        new: serenity::model::guild::Member,
     ) {
+        if !CONFIG.dead_role_detection {
+            return;
+        }
+
+        // Forward the Update to all the running Rounds on the Guild, which will then check if
+        // the Member is one of their Players
+        let data = ctx.data.read().await;
+        let storage = data.get::<BotStorage>().unwrap();
+
+        for round_msg_id in SMMAP.running_games(new.guild_id).await {
+            self.update_sm(
+                new.guild_id,
+                round_msg_id,
+                &ctx.http,
+                storage,
+                messages::Event::MemberUpdate {
+                    member: new.clone(),
+                },
+            )
+            .await;
+        }
+    }
+
+    #[tracing::instrument(skip(self, ctx, user, _member_data_if_available))]
+    async fn guild_member_removal(
+        &self,
+        ctx: Context,
+        guild_id: GuildId,
+        user: serenity::model::user::User,
+        _member_data_if_available: Option<serenity::model::guild::Member>,
+    ) {
+        if !CONFIG.dead_role_detection {
+            return;
+        }
+
+        // The Rounds check on their own, whether the Member was one of their Players and if
+        // leaving Players should be treated as dead on the Guild
+        let data = ctx.data.read().await;
+        let storage = data.get::<BotStorage>().unwrap();
+
+        for round_msg_id in SMMAP.running_games(guild_id).await {
+            self.update_sm(
+                guild_id,
+                round_msg_id,
+                &ctx.http,
+                storage,
+                messages::Event::MemberLeft { user: user.id },
+            )
+            .await;
+        }
+    }
+
+    #[tracing::instrument(skip(self, ctx, interaction))]
+    async fn interaction_create(
+        &self,
+        ctx: Context,
+        interaction: serenity::model::interactions::Interaction,
+    ) {
+        util::confirm::handle_interaction(&ctx.http, interaction).await;
     }
 }
 
+// All the Commands work on a Guild, the Direct Messages the Bot receives are only Replies for
+// the Rounds and are handled by the Event-Handler instead
 #[group]
-#[commands(help, werewolf, add_role, remove_role, list_roles)]
+#[only_in(guilds)]
+#[commands(
+    help,
+    werewolf,
+    repeat,
+    add_role,
+    remove_role,
+    edit_role,
+    list_roles,
+    find_role,
+    stats,
+    settings,
+    backup_roles,
+    restore_roles,
+    debug_state,
+    reservations,
+    clear_reservation,
+    self_test,
+    reassign,
+    round_history,
+    validate_set,
+    broadcast,
+    next_phase,
+    proceed,
+    narration,
+    reveal,
+    game_channels,
+    permissions,
+    import_roles_url,
+    start_vote,
+    close_vote,
+    state_machines,
+    test_dm
+)]
 struct General;
 
 #[command]
-async fn werewolf(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
-    commands::werewolf(ctx, msg).await
+async fn werewolf(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    commands::werewolf(ctx, msg, args).await
+}
+
+#[command]
+async fn repeat(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    commands::repeat(ctx, msg, args).await
 }
 
 #[command]
@@ -230,6 +378,12 @@ async fn list_roles(ctx: &Context, msg: &Message, _args: Args) -> CommandResult
     commands::list_roles(ctx, msg).await
 }
 
+#[command]
+#[aliases("find-role")]
+async fn find_role(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    commands::find_role(ctx, msg, args).await
+}
+
 #[command]
 #[aliases("add-role")]
 async fn add_role(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
@@ -242,23 +396,182 @@ async fn remove_role(ctx: &Context, msg: &Message, args: Args) -> CommandResult
     commands::remove_role(ctx, msg, args).await
 }
 
+#[command]
+#[aliases("edit-role")]
+async fn edit_role(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    commands::edit_role(ctx, msg, args).await
+}
+
+#[command]
+async fn stats(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
+    commands::stats(ctx, msg).await
+}
+
+#[command]
+async fn settings(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    commands::settings(ctx, msg, args).await
+}
+
+#[command]
+#[aliases("backup-roles")]
+async fn backup_roles(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
+    commands::backup_roles(ctx, msg).await
+}
+
+#[command]
+#[aliases("restore-roles")]
+async fn restore_roles(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
+    commands::restore_roles(ctx, msg).await
+}
+
+#[command]
+#[aliases("debug-state")]
+async fn debug_state(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    commands::debug_state(ctx, msg, args).await
+}
+
+#[command]
+async fn reservations(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
+    commands::reservations(ctx, msg).await
+}
+
+#[command]
+#[aliases("clear-reservation")]
+async fn clear_reservation(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    commands::clear_reservation(ctx, msg, args).await
+}
+
+#[command]
+#[aliases("self-test")]
+async fn self_test(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
+    commands::self_test(ctx, msg).await
+}
+
+#[command]
+async fn reassign(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    commands::reassign(ctx, msg, args).await
+}
+
+#[command]
+#[aliases("round-history")]
+async fn round_history(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
+    commands::round_history(ctx, msg).await
+}
+
+#[command]
+#[aliases("validate-set")]
+async fn validate_set(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    commands::validate_set(ctx, msg, args).await
+}
+
+#[command]
+async fn broadcast(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    commands::broadcast(ctx, msg, args).await
+}
+
+#[command]
+#[aliases("next-phase")]
+async fn next_phase(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
+    commands::next_phase(ctx, msg).await
+}
+
+#[command]
+async fn proceed(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
+    commands::proceed(ctx, msg).await
+}
+
+#[command]
+async fn narration(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    commands::narration(ctx, msg, args).await
+}
+
+#[command]
+async fn reveal(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    commands::reveal(ctx, msg, args).await
+}
+
+#[command]
+#[aliases("game-channels")]
+async fn game_channels(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    commands::game_channels(ctx, msg, args).await
+}
+
+#[command]
+async fn permissions(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
+    commands::permissions(ctx, msg).await
+}
+
+#[command]
+#[aliases("import-roles-url")]
+async fn import_roles_url(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    commands::import_roles_url(ctx, msg, args).await
+}
+
+#[command]
+#[aliases("start-vote")]
+async fn start_vote(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
+    commands::start_vote(ctx, msg).await
+}
+
+#[command]
+#[aliases("close-vote")]
+async fn close_vote(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
+    commands::close_vote(ctx, msg).await
+}
+
+#[command]
+#[owners_only]
+#[aliases("state-machines")]
+async fn state_machines(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    commands::state_machines(ctx, msg, args).await
+}
+
+#[command]
+#[aliases("test-dm")]
+async fn test_dm(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
+    commands::test_dm(ctx, msg).await
+}
+
 /// Initialize the Client instance with all the needed Data
 /// to function properly
 async fn init_bot_data(client: &Client, http: Arc<Http>, bot_storage: storage::Storage) {
-    notifier::run_notifier(http, bot_storage.clone()).await;
+    notifier::run_notifier(http.clone(), bot_storage.clone()).await;
+
+    if CONFIG.role_refresh_seconds > 0 {
+        bot_storage.run_refresh(Duration::from_secs(CONFIG.role_refresh_seconds));
+    }
+
+    if CONFIG.overdue_round_minutes > 0 {
+        overdue::run_overdue_check(
+            http.clone(),
+            bot_storage.clone(),
+            Duration::from_secs(CONFIG.overdue_round_minutes * 60),
+            CONFIG.overdue_round_action,
+        );
+    }
+
+    if CONFIG.inactive_channel_retention_days > 0 {
+        archival::run_archival(
+            http,
+            Duration::from_secs(CONFIG.inactive_channel_retention_days * 24 * 60 * 60),
+        );
+    }
 
     let mut c_data = client.data.write().await;
     c_data.insert::<BotStorage>(bot_storage);
 }
 
-/// Actually starts the Bot itself
-pub async fn start(token: String) {
+/// Actually starts the Bot itself, it runs until the Shutdown is triggered or the Client stops
+/// on its own
+pub async fn start(token: String, shutdown: shutdown::Shutdown) {
     tracing::info!("Starting Bot...");
 
-    // Setup the general Framework for the Discord-Bot instance
-    let framework = StandardFramework::new()
-        .configure(|c| c.with_whitespace(false).prefix(PREFIX))
-        .group(&GENERAL_GROUP);
+    metrics::init();
+
+    util::dry_run::set_enabled(CONFIG.dry_run);
+    if CONFIG.dry_run {
+        tracing::warn!("Running in Dry-Run Mode, mutating Operations are only logged");
+    }
 
     // Create the HTTP-Instance for the Bot to use
     let http = Arc::new(Http::new_with_token(&token));
@@ -267,8 +580,36 @@ pub async fn start(token: String) {
         user.id
     };
 
-    let discord_storage = storage::discord::DiscordStorage::new(http.clone());
-    let bot_storage = storage::Storage::new(discord_storage);
+    // The Owner of the Bot is the only one allowed to use the Commands for Operators
+    let mut owners = HashSet::new();
+    match http.get_current_application_info().await {
+        Ok(info) => {
+            owners.insert(info.owner.id);
+        }
+        Err(e) => {
+            tracing::error!("Loading the Owner of the Bot: {:?}", e);
+        }
+    };
+
+    // Setup the general Framework for the Discord-Bot instance
+    let framework = StandardFramework::new()
+        .configure(|c| c.with_whitespace(false).prefix(PREFIX).owners(owners))
+        .group(aliases::with_aliases(
+            &GENERAL_GROUP,
+            &CONFIG.command_aliases,
+        ));
+
+    let mut discord_storage = storage::discord::DiscordStorage::new(http.clone())
+        .with_scan_limit(CONFIG.settings_scan_limit)
+        .with_format(CONFIG.settings_format)
+        .with_hidden_settings_channel(CONFIG.hide_settings_channel)
+        .with_role_marker(CONFIG.settings_role_marker.clone())
+        .with_role_channels(CONFIG.settings_role_channels);
+    if let Some(category) = &CONFIG.settings_category {
+        discord_storage = discord_storage.with_settings_category(category.clone());
+    }
+    let bot_storage =
+        storage::Storage::with_role_limit(discord_storage, CONFIG.max_roles_per_guild);
 
     let handler = Handler::new(bot_id, &metrics::REGISTRY);
 
@@ -276,18 +617,21 @@ pub async fn start(token: String) {
     let mut client = Client::builder(token)
         .event_handler(handler)
         .framework(framework)
-        .intents(
-            GatewayIntents::GUILD_MEMBERS
-                | GatewayIntents::GUILDS
-                | GatewayIntents::GUILD_MESSAGES
-                | GatewayIntents::GUILD_MESSAGE_REACTIONS,
-        )
+        .intents(CONFIG.intents())
         .await
         .unwrap();
 
     // Initialize the Bots inner State
     init_bot_data(&client, http, bot_storage).await;
 
+    let shard_manager = client.shard_manager.clone();
+    tokio::spawn(async move {
+        shutdown.wait().await;
+
+        tracing::info!("Shutting down Bot...");
+        shard_manager.lock().await.shutdown_all().await;
+    });
+
     // Actually run the Bot
     if let Err(e) = client.start().await {
         tracing::error!("Listening for Events: {:?}", e);
@@ -1,6 +1,6 @@
 use std::env;
 
-use werewolf_bot::{metrics, start};
+use werewolf_bot::{metrics, shutdown, start};
 
 fn main() {
     let token = env::var("BOT_TOKEN").expect("Needs a Discord-Bot-Token to operate");
@@ -27,8 +27,22 @@ fn main() {
 
     let metrics_port = 9100;
 
-    runtime.spawn(metrics::run_metrics_endpoint(metrics_port));
+    // The Bot and the Metrics-Endpoint both stop once the Process is asked to stop or once the
+    // Bot stopped on its own
+    let (trigger, shutdown) = shutdown::channel();
+
+    let metrics_server = runtime.spawn(metrics::run_metrics_endpoint(
+        metrics_port,
+        shutdown.clone(),
+    ));
 
     // Actually running the Bot
-    runtime.block_on(start(token));
+    runtime.block_on(async move {
+        shutdown::run_until(trigger, shutdown::signal(), start(token, shutdown)).await;
+
+        // Lets the Metrics-Endpoint finish the Requests that are still in Progress
+        if let Err(e) = metrics_server.await {
+            tracing::error!("Stopping Metrics-Endpoint: {:?}", e);
+        }
+    });
 }
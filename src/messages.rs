@@ -40,6 +40,10 @@ impl<I, O> MessageStateMachine<I, O> {
     pub fn message_id(&self) -> MessageId {
         self.message_id
     }
+    /// The Name of the currently active State of the inner State-Machine, if any
+    pub fn state_name(&self) -> Option<&'static str> {
+        self.sm.state_name()
+    }
 }
 
 #[async_trait]
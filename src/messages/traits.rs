@@ -7,11 +7,12 @@ use serenity::{
     http::Http,
     model::{
         channel::{Message, Reaction},
-        id::GuildId,
+        guild::Member,
+        id::{ChannelId, GuildId, UserId},
     },
 };
 
-use crate::storage::Storage;
+use crate::{roles::WereWolfRoleConfig, storage::Storage};
 
 #[derive(Clone)]
 pub enum TransitionError {
@@ -46,21 +47,95 @@ impl TransitionError {
     pub fn arced(self) -> Arc<Self> {
         Arc::new(self)
     }
+
+    /// The Name of the Variant, used to label the Error in the Metrics and Logs
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Serenity => "Serenity",
+            Self::Generic(_) => "Generic",
+            Self::WithReason { .. } => "WithReason",
+        }
+    }
 }
 
-#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
 pub enum Event {
     Notify,
-    AddReaction { reaction: Reaction },
-    RemoveReaction { reaction: Reaction },
-    Reply { message: Message },
+    AddReaction {
+        reaction: Reaction,
+    },
+    RemoveReaction {
+        reaction: Reaction,
+    },
+    Reply {
+        message: Message,
+    },
+    /// A Member of the Guild has been updated, like when their Roles changed
+    MemberUpdate {
+        member: Member,
+    },
+    /// A Member has left the Guild or has been removed from it
+    MemberLeft {
+        user: UserId,
+    },
+    /// A Mod wants to give a Player in a running Round a different Role, Rounds that the Player
+    /// takes part in report the Outcome using the `outcome` Sender
+    Reassign {
+        player: UserId,
+        role: WereWolfRoleConfig,
+        outcome: tokio::sync::mpsc::UnboundedSender<String>,
+    },
+    /// A Mod wants to post the Content in all the Role-Channels of the Rounds they moderate,
+    /// those Rounds report the Outcome using the `outcome` Sender
+    Broadcast {
+        sender: UserId,
+        content: String,
+        outcome: tokio::sync::mpsc::UnboundedSender<String>,
+    },
+    /// A Mod wants the Rounds they moderate to move on to their next Phase, those Rounds report
+    /// the Outcome using the `outcome` Sender
+    NextPhase {
+        sender: UserId,
+        outcome: tokio::sync::mpsc::UnboundedSender<String>,
+    },
+    /// A Mod wants to start an anonymous Vote between the alive Players of the Rounds they
+    /// moderate, those Rounds report the Outcome using the `outcome` Sender
+    StartVote {
+        sender: UserId,
+        outcome: tokio::sync::mpsc::UnboundedSender<String>,
+    },
+    /// A Mod wants to close the open Votes of the Rounds they moderate and publish their
+    /// Results, those Rounds report the Outcome using the `outcome` Sender
+    CloseVote {
+        sender: UserId,
+        outcome: tokio::sync::mpsc::UnboundedSender<String>,
+    },
+    /// A Mod wants the Round that is being set up in the Channel to end its Registration or
+    /// Ready-Check, as if it had been confirmed, the Round reports the Outcome using the `outcome`
+    /// Sender
+    Proceed {
+        sender: UserId,
+        channel: ChannelId,
+        outcome: tokio::sync::mpsc::UnboundedSender<String>,
+    },
+    /// Checks whether a running Round has been running for longer than the Threshold, overdue
+    /// Rounds report themselves using the `outcome` Sender and ping their Mods once if `ping`
+    /// is set
+    CheckOverdue {
+        threshold: std::time::Duration,
+        ping: bool,
+        outcome: tokio::sync::mpsc::UnboundedSender<()>,
+    },
 }
 
+#[derive(Clone)]
 pub struct Context {
     http: Option<Arc<Http>>,
     event: Option<Event>,
     storage: Option<Storage>,
     guild_id: GuildId,
+    bot_id: Option<UserId>,
 }
 
 impl Context {
@@ -75,21 +150,37 @@ impl Context {
             event,
             storage,
             guild_id,
+            bot_id: None,
         }
     }
 
+    /// Sets the ID of the Bot itself, which lets the State-Machines ignore Events caused by the
+    /// Bot
+    pub fn with_bot_id(mut self, bot_id: UserId) -> Self {
+        self.bot_id = Some(bot_id);
+        self
+    }
+
     pub fn event(&self) -> Option<&Event> {
         self.event.as_ref()
     }
     pub fn http(&self) -> Option<&Http> {
         self.http.as_ref().map(|h| h.as_ref())
     }
+    /// The shared Http-Client, useful for Work that needs to outlive the current Event
+    pub fn shared_http(&self) -> Option<Arc<Http>> {
+        self.http.clone()
+    }
     pub fn storage(&self) -> Option<&Storage> {
         self.storage.as_ref()
     }
     pub fn guild_id(&self) -> GuildId {
         self.guild_id
     }
+    /// Whether the User is the Bot itself, always false if the ID of the Bot is not known
+    pub fn is_bot(&self, user: UserId) -> bool {
+        self.bot_id == Some(user)
+    }
 }
 
 impl Default for Context {
@@ -1,9 +1,111 @@
+use crate::shutdown::Shutdown;
 use lazy_static::lazy_static;
 use prometheus::Encoder;
 
 lazy_static! {
     pub static ref REGISTRY: prometheus::Registry =
         prometheus::Registry::new_custom(Some("waswolf".to_string()), None).unwrap();
+    /// The Number of Rounds that are currently reserved or running across all Guilds
+    pub static ref RUNNING_ROUNDS: prometheus::IntGauge = {
+        let gauge = prometheus::IntGauge::with_opts(prometheus::Opts::new(
+            "running_rounds",
+            "The Number of currently running Rounds",
+        ))
+        .unwrap();
+
+        REGISTRY.register(Box::new(gauge.clone())).unwrap();
+
+        gauge
+    };
+    /// The Number of Rounds that have been running for longer than the configured Threshold
+    pub static ref OVERDUE_ROUNDS: prometheus::IntGauge = {
+        let gauge = prometheus::IntGauge::with_opts(prometheus::Opts::new(
+            "overdue_rounds",
+            "The Number of Rounds that have been running for too long",
+        ))
+        .unwrap();
+
+        REGISTRY.register(Box::new(gauge.clone())).unwrap();
+
+        gauge
+    };
+    /// The Number of State-Machines that failed, by the Kind of their Error
+    pub static ref TRANSITION_ERRORS: prometheus::IntCounterVec = {
+        let counter = prometheus::IntCounterVec::new(
+            prometheus::Opts::new(
+                "transition_errors",
+                "The Number of State-Machines that failed with an Error",
+            ),
+            &["kind"],
+        )
+        .unwrap();
+
+        REGISTRY.register(Box::new(counter.clone())).unwrap();
+
+        counter
+    };
+    /// The Number of Events handled by the State-Machines, by the Outcome of their Transition
+    pub static ref TRANSITIONS: prometheus::IntCounterVec = {
+        let counter = prometheus::IntCounterVec::new(
+            prometheus::Opts::new(
+                "transitions",
+                "The Number of Events handled by the State-Machines",
+            ),
+            &["outcome"],
+        )
+        .unwrap();
+
+        REGISTRY.register(Box::new(counter.clone())).unwrap();
+
+        counter
+    };
+    /// The Point in Time at which the Bot was started
+    pub static ref STARTED: std::time::Instant = std::time::Instant::now();
+}
+
+/// Initializes all the global Metrics, so that they are registered and report their initial
+/// Values even before they are first updated
+pub fn init() {
+    lazy_static::initialize(&RUNNING_ROUNDS);
+    lazy_static::initialize(&TRANSITION_ERRORS);
+    lazy_static::initialize(&TRANSITIONS);
+    lazy_static::initialize(&OVERDUE_ROUNDS);
+    lazy_static::initialize(&STARTED);
+}
+
+/// Counts the Outcomes of all the Transitions in the [`TRANSITIONS`] Metric
+pub struct TransitionMetrics;
+
+impl crate::sms::TransitionObserver for TransitionMetrics {
+    fn observe(
+        &self,
+        _message_id: serenity::model::id::MessageId,
+        outcome: crate::sms::TransitionOutcome,
+    ) {
+        TRANSITIONS.with_label_values(&[outcome.name()]).inc();
+    }
+}
+
+/// The Time that has passed since the Bot was started
+pub fn uptime() -> std::time::Duration {
+    STARTED.elapsed()
+}
+
+/// Loads the current Value of the Gauge with the given Name (without the Namespace-Prefix of the
+/// Registry) from the Registry
+pub fn gauge_value(registry: &prometheus::Registry, name: &str) -> Option<f64> {
+    registry
+        .gather()
+        .iter()
+        .find(|family| {
+            family
+                .get_name()
+                .split_once('_')
+                .map(|(_, metric_name)| metric_name == name)
+                .unwrap_or(false)
+        })
+        .and_then(|family| family.get_metric().first())
+        .map(|metric| metric.get_gauge().get_value())
 }
 
 async fn handle(_req: hyper::Request<hyper::Body>) -> Result<hyper::Response<hyper::Body>, String> {
@@ -16,17 +118,80 @@ async fn handle(_req: hyper::Request<hyper::Body>) -> Result<hyper::Response<hyp
     Ok(hyper::Response::new(hyper::Body::from(buffer)))
 }
 
-#[tracing::instrument]
-pub async fn run_metrics_endpoint(port: u16) {
-    let addr = std::net::SocketAddr::from(([0, 0, 0, 0], port));
-
+/// Serves the Metrics using the Listener until the Shutdown is triggered, Requests that are
+/// still in Progress at that Point are completed before the Server stops
+async fn serve(listener: std::net::TcpListener, shutdown: Shutdown) -> Result<(), hyper::Error> {
     let make_service = hyper::service::make_service_fn(|_conn| async {
         Ok::<_, String>(hyper::service::service_fn(handle))
     });
 
-    let server = hyper::Server::bind(&addr).serve(make_service);
+    hyper::Server::from_tcp(listener)?
+        .serve(make_service)
+        .with_graceful_shutdown(shutdown.wait())
+        .await
+}
+
+#[tracing::instrument(skip(shutdown))]
+pub async fn run_metrics_endpoint(port: u16, shutdown: Shutdown) {
+    let addr = std::net::SocketAddr::from(([0, 0, 0, 0], port));
 
-    if let Err(e) = server.await {
+    let listener = match std::net::TcpListener::bind(addr) {
+        Ok(l) => l,
+        Err(e) => {
+            tracing::error!("Binding Webserver: {:?}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = serve(listener, shutdown).await {
         tracing::error!("Running Webserver: {:?}", e);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gauge_value_registered() {
+        let registry = prometheus::Registry::new_custom(Some("test".to_string()), None).unwrap();
+        let gauge = prometheus::IntGauge::with_opts(prometheus::Opts::new("example", "An Example"))
+            .unwrap();
+        registry.register(Box::new(gauge.clone())).unwrap();
+
+        gauge.set(13);
+
+        assert_eq!(Some(13.0), gauge_value(&registry, "example"));
+    }
+
+    #[tokio::test]
+    async fn endpoint_stops_on_shutdown() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (trigger, shutdown) = crate::shutdown::channel();
+
+        let server = tokio::spawn(serve(listener, shutdown));
+
+        let response = reqwest::get(format!("http://{}/metrics", addr))
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+
+        trigger.trigger();
+        let result = tokio::time::timeout(std::time::Duration::from_secs(5), server)
+            .await
+            .expect("The Server stops once the Shutdown is triggered")
+            .unwrap();
+        assert!(result.is_ok());
+
+        // The Port is released again
+        assert!(std::net::TcpListener::bind(addr).is_ok());
+    }
+
+    #[test]
+    fn gauge_value_missing() {
+        let registry = prometheus::Registry::new_custom(Some("test".to_string()), None).unwrap();
+
+        assert_eq!(None, gauge_value(&registry, "example"));
+    }
+}
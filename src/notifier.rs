@@ -1,10 +1,9 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use serenity::{
     http::Http,
     model::id::{GuildId, MessageId},
 };
-use statemachines::{AsyncTransition, TransitionResult};
 use tokio::sync::OnceCell;
 
 use crate::{
@@ -26,6 +25,19 @@ impl NotifyQueue {
     pub fn notify(&self, msg_id: MessageId, guild_id: GuildId) {
         self.queue.get().unwrap().send((msg_id, guild_id)).unwrap();
     }
+
+    /// Notifies the State-Machine of the Message once the Delay has passed
+    pub fn notify_after(&self, delay: Duration, msg_id: MessageId, guild_id: GuildId) {
+        let queue = self.queue.get().unwrap().clone();
+
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+
+            if queue.send((msg_id, guild_id)).is_err() {
+                tracing::error!("Sending delayed Notification");
+            }
+        });
+    }
 }
 
 pub async fn run_notifier(http: Arc<Http>, storage: Storage) {
@@ -57,12 +69,6 @@ async fn background_notifier(
             guild_id,
         );
 
-        match crate::SMMAP.try_lock_update(msg_id, context).await {
-            Ok(_) => {}
-            Err(_) => {
-                crate::NOTIFY_SM_QUEUE.notify(msg_id, guild_id);
-                continue;
-            }
-        };
+        crate::SMMAP.update(msg_id, context).await;
     }
 }
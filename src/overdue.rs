@@ -0,0 +1,168 @@
+use std::{
+    fmt::Display,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use serenity::http::Http;
+
+use crate::{
+    messages::{Context, Event},
+    storage::Storage,
+};
+
+/// How often the running Rounds are checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// What happens once a Round has been running for longer than the configured Threshold
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OverdueAction {
+    /// Only the Metric for the overdue Rounds is updated
+    Metric,
+    /// The Moderators of the Round are pinged once, in addition to the Metric
+    Ping,
+}
+
+impl Display for OverdueAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Metric => write!(f, "metric"),
+            Self::Ping => write!(f, "ping"),
+        }
+    }
+}
+
+impl FromStr for OverdueAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "metric" => Ok(Self::Metric),
+            "ping" => Ok(Self::Ping),
+            other => Err(format!("Unknown Overdue-Action '{}'", other)),
+        }
+    }
+}
+
+/// Whether a Round, that was started at the given Point in Time, has been running for longer
+/// than the Threshold
+pub fn is_overdue(started_at: SystemTime, now: SystemTime, threshold: Duration) -> bool {
+    now.duration_since(started_at)
+        .map(|running| running > threshold)
+        .unwrap_or(false)
+}
+
+/// The Message used to remind the Moderators about a Round that has been running for a long Time
+pub fn reminder_content<'m, I>(mods: I, running: Duration) -> String
+where
+    I: Iterator<Item = &'m serenity::model::id::UserId>,
+{
+    let mentions: Vec<String> = mods.map(|m| format!("<@{}>", m.0)).collect();
+
+    format!(
+        "{} This Round has been running for {} Minutes, stop it once it is over so its Channels are cleaned up",
+        mentions.join(" "),
+        running.as_secs() / 60
+    )
+}
+
+/// Starts the Background-Task that regularly checks all the running Rounds and reports the ones
+/// that have been running for longer than the Threshold
+pub fn run_overdue_check(
+    http: Arc<Http>,
+    storage: Storage,
+    threshold: Duration,
+    action: OverdueAction,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            check_rounds(&http, &storage, threshold, action).await;
+        }
+    });
+}
+
+async fn check_rounds(
+    http: &Arc<Http>,
+    storage: &Storage,
+    threshold: Duration,
+    action: OverdueAction,
+) {
+    let (overdue_tx, mut overdue_rx) = tokio::sync::mpsc::unbounded_channel();
+    for (guild_id, round) in crate::SMMAP.all_running_games().await {
+        let context = Context::new(
+            Some(http.clone()),
+            Some(Event::CheckOverdue {
+                threshold,
+                ping: action == OverdueAction::Ping,
+                outcome: overdue_tx.clone(),
+            }),
+            Some(storage.clone()),
+            guild_id,
+        );
+
+        crate::SMMAP.update(round, context).await;
+    }
+    drop(overdue_tx);
+
+    let mut overdue = 0;
+    while overdue_rx.recv().await.is_some() {
+        overdue += 1;
+    }
+
+    if overdue > 0 {
+        tracing::warn!(
+            "{} Round(s) have been running for longer than {:?}",
+            overdue,
+            threshold
+        );
+    }
+    crate::metrics::OVERDUE_ROUNDS.set(overdue);
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::model::id::UserId;
+
+    use super::*;
+
+    #[test]
+    fn overdue_after_threshold() {
+        let started = SystemTime::UNIX_EPOCH;
+        let threshold = Duration::from_secs(60);
+
+        assert!(!is_overdue(
+            started,
+            started + Duration::from_secs(30),
+            threshold
+        ));
+        assert!(!is_overdue(started, started + threshold, threshold));
+        assert!(is_overdue(
+            started,
+            started + Duration::from_secs(61),
+            threshold
+        ));
+        // A Clock that went backwards should never mark a Round as overdue
+        assert!(!is_overdue(started + threshold, started, Duration::ZERO));
+    }
+
+    #[test]
+    fn parse_action() {
+        assert_eq!(Ok(OverdueAction::Metric), "metric".parse());
+        assert_eq!(Ok(OverdueAction::Ping), "ping".parse());
+        assert!("other".parse::<OverdueAction>().is_err());
+    }
+
+    #[test]
+    fn reminder_mentions_mods() {
+        let mods = [UserId(1), UserId(2)];
+
+        assert_eq!(
+            "<@1> <@2> This Round has been running for 90 Minutes, stop it once it is over so its Channels are cleaned up",
+            reminder_content(mods.iter(), Duration::from_secs(90 * 60 + 30))
+        );
+    }
+}
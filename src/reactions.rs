@@ -2,6 +2,43 @@ use std::fmt::Display;
 
 use serenity::model::channel::ReactionType;
 
+/// Checks if the given Character only modifies the Presentation of the preceding Emoji, like the
+/// Variation-Selectors or the Skin-Tone Modifiers
+fn is_presentation_modifier(c: char) -> bool {
+    matches!(c, '\u{fe0e}' | '\u{fe0f}' | '\u{1f3fb}'..='\u{1f3ff}')
+}
+
+/// Normalizes the Emoji by removing all Variation-Selectors and Skin-Tone Modifiers, also
+/// inside of ZWJ-Sequences. Everything else, like the Joiners themselves, Hair-Components or
+/// Gender-Signs, is kept as these actually make up different Emoji
+pub fn normalize_emoji(emoji: &str) -> String {
+    let normalized: String = emoji
+        .chars()
+        .filter(|c| !is_presentation_modifier(*c))
+        .collect();
+
+    // A Modifier on its own is still a valid Emoji and should not be collapsed to nothing
+    if normalized.is_empty() {
+        emoji.to_string()
+    } else {
+        normalized
+    }
+}
+
+/// Checks if the two Emoji are the same, ignoring differences in their Skin-Tone or
+/// Variation-Selectors
+pub fn emoji_eq(first: &str, second: &str) -> bool {
+    first == second || normalize_emoji(first) == normalize_emoji(second)
+}
+
+/// Checks if the Reaction matches the given Emoji, using [`emoji_eq`] for Unicode Reactions
+pub fn reaction_matches(reaction: &ReactionType, emoji: &str) -> bool {
+    match reaction {
+        ReactionType::Unicode(value) => emoji_eq(value, emoji),
+        other => other.unicode_eq(emoji),
+    }
+}
+
 /// The Reactions used by the Bot
 #[derive(Debug, PartialEq, Clone)]
 pub enum Reactions {
@@ -9,6 +46,8 @@ pub enum Reactions {
     ModEntry,
     Confirm,
     Stop,
+    Ready,
+    Extend,
     NextPage,
     PreviousPage,
     Yes,
@@ -23,6 +62,8 @@ impl Reactions {
             Self::ModEntry => "🇲",
             Self::Confirm => "🆗",
             Self::Stop => "🛑",
+            Self::Ready => "👍",
+            Self::Extend => "⏳",
             Self::NextPage => "👉",
             Self::PreviousPage => "👈",
             Self::Yes => "🇾",
@@ -51,12 +92,12 @@ impl From<&Reactions> for ReactionType {
 
 impl PartialEq<ReactionType> for Reactions {
     fn eq(&self, other: &ReactionType) -> bool {
-        other.unicode_eq(self.to_str())
+        reaction_matches(other, self.to_str())
     }
 }
 impl PartialEq<&ReactionType> for Reactions {
     fn eq(&self, other: &&ReactionType) -> bool {
-        other.unicode_eq(self.to_str())
+        reaction_matches(other, self.to_str())
     }
 }
 
@@ -68,4 +109,43 @@ mod tests {
     fn equals() {
         assert!(Reactions::Entry == ReactionType::from('✅'));
     }
+
+    #[test]
+    fn skin_tone_variants() {
+        // Thumbs-Up with and without a medium Skin-Tone
+        assert!(emoji_eq("\u{1f44d}", "\u{1f44d}\u{1f3fd}"));
+        assert!(emoji_eq("\u{1f44d}\u{1f3fb}", "\u{1f44d}\u{1f3ff}"));
+        assert!(reaction_matches(
+            &ReactionType::Unicode("\u{1f44d}\u{1f3fe}".to_string()),
+            "\u{1f44d}"
+        ));
+    }
+
+    #[test]
+    fn zwj_variants() {
+        // Male Mage with a dark Skin-Tone and without the Variation-Selector
+        assert!(emoji_eq(
+            "\u{1f9d9}\u{200d}\u{2642}\u{fe0f}",
+            "\u{1f9d9}\u{1f3ff}\u{200d}\u{2642}"
+        ));
+        // Keycaps with and without the Variation-Selector
+        assert!(emoji_eq("1\u{fe0f}\u{20e3}", "1\u{20e3}"));
+    }
+
+    #[test]
+    fn different_emoji() {
+        // Male and Female Mage
+        assert!(!emoji_eq(
+            "\u{1f9d9}\u{200d}\u{2642}\u{fe0f}",
+            "\u{1f9d9}\u{200d}\u{2640}\u{fe0f}"
+        ));
+        // Mage and the Male Mage
+        assert!(!emoji_eq("\u{1f9d9}", "\u{1f9d9}\u{200d}\u{2642}\u{fe0f}"));
+        // Person and Person with red Hair
+        assert!(!emoji_eq("\u{1f9d1}", "\u{1f9d1}\u{200d}\u{1f9b0}"));
+        // Different Skin-Tone Modifiers on their own
+        assert!(!emoji_eq("\u{1f3fb}", "\u{1f3ff}"));
+        // Keycap and the plain Digit
+        assert!(!emoji_eq("1", "1\u{fe0f}\u{20e3}"));
+    }
 }
@@ -1,32 +1,18 @@
 use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
-use serenity::model::channel::Message;
 
 mod cfg_reactions;
-pub use cfg_reactions::reactions;
-
-mod roles_msg;
-pub use roles_msg::get_roles_msg;
+pub use cfg_reactions::{find_role, page_roles, reactions, SelectionMode};
 
 mod distribute;
-pub use distribute::{distribute_roles, DistributeError};
-
-use crate::rounds::BotContext;
-
-pub async fn cfg_role_msg_reactions(
-    message: &Message,
-    ctx: &dyn BotContext,
-    roles: &[WereWolfRoleConfig],
-    page: usize,
-) {
-    let reactions = cfg_reactions::reactions(roles, page);
-    for reaction in reactions {
-        if let Err(e) = message.react(ctx.get_http(), reaction).await {
-            tracing::error!("Adding Reaction: {:?}", e);
-        }
-    }
-}
+pub use distribute::{
+    distribute_roles, slot_balance, validate_roles, DistributeError, Distribution, PlayerLink,
+    SlotBalance,
+};
+
+pub mod templates;
+pub mod validation;
 
 /// The Config for a Custom Werewolf Role
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -47,6 +33,21 @@ pub struct WereWolfRoleConfig {
     /// own Chat
     #[serde(default)]
     other_role_channels: Vec<String>,
+    /// The Team this Role belongs to, like "Village" or "Werewolves"
+    #[serde(default)]
+    team: Option<String>,
+    /// The Name of another Role this Role is based on, its extra Channels are added to the ones
+    /// of this Role and its Team is used if this Role has none, see [`templates`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    base: Option<String>,
+    /// Whether or not every Player with this Role links two other Players at the Start of a
+    /// Round, like the Lovers chosen by Cupid, who then share their own private Channel
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    links_players: bool,
+    /// The Message posted in the Channel of this Role at the Start of a Round, like an
+    /// Explanation of the Ability of the Role
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    channel_intro: Option<String>,
 }
 
 impl Display for WereWolfRoleConfig {
@@ -55,13 +56,25 @@ impl Display for WereWolfRoleConfig {
             f,
             "{}({}) - Multiple Players: {} - Contains another Role: {} - Accesses other Channels: {:?}",
             self.name, self.emoji, self.mutli_player, self.masks_role, self.other_role_channels
-        )
+        )?;
+
+        if let Some(team) = self.team.as_ref() {
+            write!(f, " - Team: {}", team)?;
+        }
+        if let Some(base) = self.base.as_ref() {
+            write!(f, " - Based on: {}", base)?;
+        }
+        if self.links_players {
+            write!(f, " - Links two Players")?;
+        }
+
+        Ok(())
     }
 }
 
 impl PartialOrd for WereWolfRoleConfig {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.name.partial_cmp(&other.name)
+        Some(self.cmp(other))
     }
 }
 impl Ord for WereWolfRoleConfig {
@@ -89,9 +102,58 @@ impl WereWolfRoleConfig {
             mutli_player,
             masks_role,
             other_role_channels,
+            team: None,
+            base: None,
+            links_players: false,
+            channel_intro: None,
         }
     }
 
+    /// Sets the Team this Role belongs to
+    pub fn with_team<T>(mut self, team: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.team = Some(team.into());
+        self
+    }
+
+    /// Bases this Role on the Role with the given Name
+    pub fn with_base<B>(mut self, base: B) -> Self
+    where
+        B: Into<String>,
+    {
+        self.base = Some(base.into());
+        self
+    }
+
+    /// Removes the Base of this Role again, so it only uses its own Configuration
+    pub fn without_base(mut self) -> Self {
+        self.base = None;
+        self
+    }
+
+    /// Makes every Player with this Role link two other Players at the Start of a Round
+    pub fn with_linked_players(mut self) -> Self {
+        self.links_players = true;
+        self
+    }
+
+    /// Sets the Message that is posted in the Channel of this Role at the Start of a Round
+    pub fn with_channel_intro<I>(mut self, intro: I) -> Self
+    where
+        I: Into<String>,
+    {
+        self.channel_intro = Some(intro.into());
+        self
+    }
+
+    /// Removes the Channel-Intro of this Role again
+    pub fn without_channel_intro(mut self) -> Self {
+        self.channel_intro = None;
+        self
+    }
+
     /// The Name of the Role
     pub fn name(&self) -> &str {
         &self.name
@@ -112,38 +174,67 @@ impl WereWolfRoleConfig {
         self.masks_role
     }
 
+    /// The Team this Role belongs to, if any
+    pub fn team(&self) -> Option<&str> {
+        self.team.as_deref()
+    }
+
+    /// Whether or not this Role links two other Players at the Start of a Round
+    pub fn links_players(&self) -> bool {
+        self.links_players
+    }
+
+    /// The Message posted in the Channel of this Role at the Start of a Round, if any
+    pub fn channel_intro(&self) -> Option<&str> {
+        self.channel_intro.as_deref()
+    }
+
     /// Creates an actual Role-Instance from this Config, will use the provided function to get
     /// another Role if this Config needs/masks another Role
     pub fn to_instance<F>(&self, get_masked: &mut F) -> WereWolfRoleInstance
     where
         F: FnMut() -> WereWolfRoleConfig,
     {
-        if !self.masks_role {
-            return WereWolfRoleInstance::new(
+        let masked_role = if self.masks_role {
+            let other_role = get_masked();
+            Some(Box::new(other_role.to_instance(get_masked)))
+        } else {
+            None
+        };
+
+        WereWolfRoleInstance {
+            team: self.team.clone(),
+            ..WereWolfRoleInstance::new(
                 self.name.clone(),
-                None,
+                masked_role,
                 self.other_role_channels.clone(),
-            );
+            )
         }
-
-        let other_role = get_masked();
-        let other_instance = other_role.to_instance(get_masked);
-
-        WereWolfRoleInstance::new(
-            self.name.clone(),
-            Some(Box::new(other_instance)),
-            self.other_role_channels.clone(),
-        )
     }
 
     /// Gets the List of all Channel Names that this Role needs access to
     pub fn channels(&self) -> impl Iterator<Item = String> {
         std::iter::once(self.name.clone()).chain(self.other_role_channels.clone())
     }
+
+    /// The extra Channels of this Role that don't belong to any of the given Roles, these are
+    /// either Typos or intentional standalone Channels
+    pub fn unmatched_extra_channels<'r, I>(&self, roles: I) -> Vec<&str>
+    where
+        I: IntoIterator<Item = &'r WereWolfRoleConfig>,
+    {
+        let names: Vec<&str> = roles.into_iter().map(|r| r.name()).collect();
+
+        self.other_role_channels
+            .iter()
+            .filter(|c| !names.iter().any(|n| n.eq_ignore_ascii_case(c)))
+            .map(|c| c.as_str())
+            .collect()
+    }
 }
 
 /// An actual Instance of a Role, which is intended to be used for a running Round
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WereWolfRoleInstance {
     /// The Name of the Role
     name: String,
@@ -151,6 +242,8 @@ pub struct WereWolfRoleInstance {
     masked_role: Option<Box<Self>>,
     /// A List of extra Channels that this Role needs access to
     extra_channels: Vec<String>,
+    /// The Team of the Role
+    team: Option<String>,
 }
 
 impl WereWolfRoleInstance {
@@ -160,6 +253,7 @@ impl WereWolfRoleInstance {
             name,
             masked_role,
             extra_channels,
+            team: None,
         }
     }
 
@@ -182,19 +276,61 @@ impl WereWolfRoleInstance {
     pub fn name(&self) -> &str {
         &self.name
     }
+    /// The Team of the Role this instance is based upon, if any
+    pub fn team(&self) -> Option<&str> {
+        self.team.as_deref()
+    }
     /// The Role that is masked by this Role, if any
     pub fn masked_role(&self) -> Option<&Self> {
         match &self.masked_role {
-            Some(r) => Some(&r),
+            Some(r) => Some(r),
             None => None,
         }
     }
 }
 
+impl Display for WereWolfRoleInstance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.masked_role() {
+            Some(other) => write!(f, "{} ({})", self.name, other.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn instance_display() {
+        let simple = WereWolfRoleInstance::new("Test".to_string(), None, Vec::new());
+        assert_eq!("Test", simple.to_string());
+
+        let masked = WereWolfRoleInstance::new(
+            "Test".to_string(),
+            Some(Box::new(simple.clone())),
+            Vec::new(),
+        );
+        assert_eq!("Test (Test)", masked.to_string());
+    }
+
+    #[test]
+    fn instance_serde_roundtrip() {
+        let mut masked = WereWolfRoleInstance::new("Werewolf".to_string(), None, Vec::new());
+        masked.team = Some("Werewolves".to_string());
+        let instance = WereWolfRoleInstance::new(
+            "Thief".to_string(),
+            Some(Box::new(masked)),
+            vec!["Night".to_string()],
+        );
+
+        let serialized = serde_json::to_string(&instance).unwrap();
+        let deserialized: WereWolfRoleInstance = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(instance, deserialized);
+    }
+
     #[test]
     fn channels_simple() {
         let instance = WereWolfRoleInstance::new("Test".to_string(), None, Vec::new());
@@ -259,4 +395,54 @@ mod tests {
 
         assert_eq!(expected, result);
     }
+
+    #[test]
+    fn to_instance_team() {
+        let config =
+            WereWolfRoleConfig::new("root", "", false, false, Vec::new()).with_team("Village");
+
+        let result = config.to_instance(&mut || panic!("We dont want to mask another Role"));
+
+        assert_eq!(Some("Village"), result.team());
+    }
+
+    #[test]
+    fn unmatched_extra_channels() {
+        let roles = vec![
+            WereWolfRoleConfig::new("Werewolf", "", true, false, Vec::new()),
+            WereWolfRoleConfig::new("Seer", "", false, false, Vec::new()),
+        ];
+        let role = WereWolfRoleConfig::new(
+            "Wolfchild",
+            "",
+            false,
+            false,
+            vec![
+                "werewolf".to_string(),
+                "Werwolf".to_string(),
+                "Lovers".to_string(),
+            ],
+        );
+
+        assert_eq!(
+            vec!["Werwolf", "Lovers"],
+            role.unmatched_extra_channels(&roles)
+        );
+        assert_eq!(
+            Vec::<&str>::new(),
+            roles[0].unmatched_extra_channels(&roles)
+        );
+    }
+
+    #[test]
+    fn deserialize_without_team() {
+        let raw = r#"{"name":"root","emoji":":)","mutli_player":false,"masks_role":false}"#;
+
+        let result: WereWolfRoleConfig = serde_json::from_str(raw).unwrap();
+
+        assert_eq!(
+            WereWolfRoleConfig::new("root", ":)", false, false, Vec::new()),
+            result
+        );
+    }
 }
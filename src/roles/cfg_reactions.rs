@@ -1,19 +1,95 @@
-use crate::Reactions;
+use serenity::model::channel::ReactionType;
+
+use crate::{reactions::reaction_matches, Reactions};
 
 use super::WereWolfRoleConfig;
 
 const MAX_REACTIONS: usize = 17;
 
+/// The Reactions used to select the Roles on a Page when using [`SelectionMode::Numbered`]
+const NUMBER_REACTIONS: [&str; 13] = [
+    "1\u{fe0f}\u{20e3}",
+    "2\u{fe0f}\u{20e3}",
+    "3\u{fe0f}\u{20e3}",
+    "4\u{fe0f}\u{20e3}",
+    "5\u{fe0f}\u{20e3}",
+    "6\u{fe0f}\u{20e3}",
+    "7\u{fe0f}\u{20e3}",
+    "8\u{fe0f}\u{20e3}",
+    "9\u{fe0f}\u{20e3}",
+    "\u{1f51f}",
+    "0\u{fe0f}\u{20e3}",
+    "#\u{fe0f}\u{20e3}",
+    "*\u{fe0f}\u{20e3}",
+];
+
+/// How the Roles are selected using Reactions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionMode {
+    /// Every Role is selected using its own Emoji
+    Emoji,
+    /// The Roles on a Page are selected using numbered Reactions, independent of their Emoji
+    Numbered,
+}
+
+impl SelectionMode {
+    /// The Number of Roles displayed on a single Page
+    pub fn page_size(&self) -> usize {
+        match self {
+            Self::Emoji => MAX_REACTIONS,
+            Self::Numbered => NUMBER_REACTIONS.len(),
+        }
+    }
+
+    /// The Reaction used to select the Role at the given Index on the current Page
+    pub fn role_reaction(&self, role: &WereWolfRoleConfig, index: usize) -> Reactions {
+        match self {
+            Self::Emoji => Reactions::Custom(role.emoji().to_string()),
+            Self::Numbered => Reactions::Custom(NUMBER_REACTIONS[index].to_string()),
+        }
+    }
+}
+
 /// Checks if the given Page is the last Page for the Role selection
-fn is_last_page(role_count: usize, page: usize) -> bool {
+fn is_last_page(role_count: usize, page: usize, page_size: usize) -> bool {
     if role_count == 0 {
         return true;
     }
-    page >= (role_count - 1) / MAX_REACTIONS
+    page >= (role_count - 1) / page_size
+}
+
+/// The Roles that are displayed on the given Page
+pub fn page_roles(
+    roles: &[WereWolfRoleConfig],
+    page: usize,
+    mode: SelectionMode,
+) -> &[WereWolfRoleConfig] {
+    let start = (page * mode.page_size()).min(roles.len());
+    let end = (start + mode.page_size()).min(roles.len());
+
+    &roles[start..end]
+}
+
+/// Finds the Role that belongs to the given Reaction on the current Page
+pub fn find_role<'r>(
+    roles: &'r [WereWolfRoleConfig],
+    page: usize,
+    mode: SelectionMode,
+    emoji: &ReactionType,
+) -> Option<&'r WereWolfRoleConfig> {
+    match mode {
+        SelectionMode::Emoji => roles.iter().find(|r| reaction_matches(emoji, r.emoji())),
+        SelectionMode::Numbered => {
+            let index = NUMBER_REACTIONS
+                .iter()
+                .position(|n| reaction_matches(emoji, n))?;
+            page_roles(roles, page, mode).get(index)
+        }
+    }
 }
 
 /// Generates the list of reactions for the given List of Roles and the correct Page
-pub fn reactions(roles: &[WereWolfRoleConfig], page: usize) -> Vec<Reactions> {
+pub fn reactions(roles: &[WereWolfRoleConfig], page: usize, mode: SelectionMode) -> Vec<Reactions> {
     let mut result = Vec::new();
 
     // If it is not the first Page, we first add the PreviousPage Reaction as all
@@ -23,18 +99,12 @@ pub fn reactions(roles: &[WereWolfRoleConfig], page: usize) -> Vec<Reactions> {
     }
 
     // Add the correct Reactions for all the Roles
-    for raw_index in 0..MAX_REACTIONS {
-        let index = raw_index + page * MAX_REACTIONS;
-
-        let role = match roles.get(index) {
-            Some(r) => r,
-            None => break,
-        };
-        result.push(Reactions::Custom(role.emoji().to_string()));
+    for (index, role) in page_roles(roles, page, mode).iter().enumerate() {
+        result.push(mode.role_reaction(role, index));
     }
 
     // If it is not the last page, we need to add a button to navigate to the next page
-    if !is_last_page(roles.len(), page) {
+    if !is_last_page(roles.len(), page, mode.page_size()) {
         result.push(Reactions::NextPage);
     }
 
@@ -48,15 +118,15 @@ mod tests {
 
     #[test]
     fn is_last_page_true() {
-        assert!(is_last_page(15, 0));
-        assert!(is_last_page(17, 0));
-        assert!(is_last_page(18, 1));
-        assert!(is_last_page(34, 1));
+        assert!(is_last_page(15, 0, MAX_REACTIONS));
+        assert!(is_last_page(17, 0, MAX_REACTIONS));
+        assert!(is_last_page(18, 1, MAX_REACTIONS));
+        assert!(is_last_page(34, 1, MAX_REACTIONS));
     }
     #[test]
     fn is_last_page_false() {
-        assert!(!is_last_page(18, 0));
-        assert!(!is_last_page(35, 1));
+        assert!(!is_last_page(18, 0, MAX_REACTIONS));
+        assert!(!is_last_page(35, 1, MAX_REACTIONS));
     }
 
     #[test]
@@ -64,7 +134,7 @@ mod tests {
         let roles = &[];
         let page = 0;
 
-        let result = reactions(roles, page);
+        let result = reactions(roles, page, SelectionMode::Emoji);
         let expected: Vec<Reactions> = vec![Reactions::Confirm];
         assert_eq!(expected, result);
     }
@@ -74,7 +144,7 @@ mod tests {
         let roles = vec![WereWolfRoleConfig::new("Werewolf", ":)", false, false, Vec::new()); 30];
         let page = 0;
 
-        let result = reactions(&roles, page);
+        let result = reactions(&roles, page, SelectionMode::Emoji);
         let expected: Vec<Reactions> = {
             let mut tmp = vec![Reactions::Custom(":)".to_string()); 17];
             tmp.push(Reactions::NextPage);
@@ -88,7 +158,7 @@ mod tests {
         let roles = vec![WereWolfRoleConfig::new("Werewolf", ":)", false, false, Vec::new()); 50];
         let page = 1;
 
-        let result = reactions(&roles, page);
+        let result = reactions(&roles, page, SelectionMode::Emoji);
         let expected: Vec<Reactions> = {
             let mut tmp = vec![Reactions::PreviousPage];
             tmp.extend(vec![Reactions::Custom(":)".to_string()); 17]);
@@ -104,7 +174,7 @@ mod tests {
             vec![WereWolfRoleConfig::new("Werewolf", ":)", false, false, Vec::new()); 17 * 3];
         let page = 2;
 
-        let result = reactions(&roles, page);
+        let result = reactions(&roles, page, SelectionMode::Emoji);
         let expected: Vec<Reactions> = {
             let mut tmp = vec![Reactions::PreviousPage];
             tmp.extend(vec![Reactions::Custom(":)".to_string()); 17]);
@@ -113,4 +183,95 @@ mod tests {
         };
         assert_eq!(expected, result);
     }
+
+    #[test]
+    fn numbered_pages() {
+        let roles: Vec<_> = (0..20)
+            .map(|i| WereWolfRoleConfig::new(format!("Role-{}", i), ":)", false, false, Vec::new()))
+            .collect();
+
+        let first = reactions(&roles, 0, SelectionMode::Numbered);
+        assert_eq!(15, first.len());
+        assert_eq!(Reactions::Custom("1\u{fe0f}\u{20e3}".to_string()), first[0]);
+        assert_eq!(Reactions::NextPage, first[13]);
+
+        let second = reactions(&roles, 1, SelectionMode::Numbered);
+        assert_eq!(Reactions::PreviousPage, second[0]);
+        assert_eq!(9, second.len());
+        assert_eq!(Reactions::Confirm, second[8]);
+    }
+
+    #[test]
+    fn find_numbered_role() {
+        let roles: Vec<_> = (0..20)
+            .map(|i| WereWolfRoleConfig::new(format!("Role-{}", i), ":)", false, false, Vec::new()))
+            .collect();
+        let two = ReactionType::Unicode("2\u{fe0f}\u{20e3}".to_string());
+
+        assert_eq!(
+            Some("Role-1"),
+            find_role(&roles, 0, SelectionMode::Numbered, &two).map(|r| r.name())
+        );
+        assert_eq!(
+            Some("Role-14"),
+            find_role(&roles, 1, SelectionMode::Numbered, &two).map(|r| r.name())
+        );
+        assert_eq!(
+            None,
+            find_role(
+                &roles,
+                1,
+                SelectionMode::Numbered,
+                &ReactionType::Unicode("\u{1f51f}".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn find_emoji_role() {
+        let roles = vec![
+            WereWolfRoleConfig::new("Werewolf", "a", false, false, Vec::new()),
+            WereWolfRoleConfig::new("Seer", "b", false, false, Vec::new()),
+        ];
+
+        assert_eq!(
+            Some("Seer"),
+            find_role(
+                &roles,
+                0,
+                SelectionMode::Emoji,
+                &ReactionType::Unicode("b".to_string())
+            )
+            .map(|r| r.name())
+        );
+    }
+
+    #[test]
+    fn find_emoji_role_skin_tone() {
+        let roles = vec![
+            WereWolfRoleConfig::new("Werewolf", "\u{1f44d}", false, false, Vec::new()),
+            WereWolfRoleConfig::new("Seer", "\u{1f44e}\u{1f3fb}", false, false, Vec::new()),
+        ];
+
+        assert_eq!(
+            Some("Werewolf"),
+            find_role(
+                &roles,
+                0,
+                SelectionMode::Emoji,
+                &ReactionType::Unicode("\u{1f44d}\u{1f3fd}".to_string())
+            )
+            .map(|r| r.name())
+        );
+        assert_eq!(
+            Some("Seer"),
+            find_role(
+                &roles,
+                0,
+                SelectionMode::Emoji,
+                &ReactionType::Unicode("\u{1f44e}".to_string())
+            )
+            .map(|r| r.name())
+        );
+    }
 }
@@ -1,6 +1,7 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, fmt::Display};
 
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use serenity::model::id::UserId;
 
 use super::{WereWolfRoleConfig, WereWolfRoleInstance};
@@ -15,6 +16,210 @@ pub enum DistributeError {
         masking_roles: usize,
         normal_roles: usize,
     },
+    /// A Role was pinned to a User that is not participating in the Round
+    PinnedUnknownPlayer { player: UserId },
+    /// A pinned Role is not part of the Round or was pinned more often than it is available
+    PinnedRoleUnavailable { role: String },
+    /// The pinned Roles left too few 'normal' Roles for the remaining masking Roles
+    PinnedTooManyMaskedRoles {
+        masking_roles: usize,
+        normal_roles: usize,
+    },
+    /// A Role that links two other Players is part of a Round with less than three Players
+    NotEnoughPlayersToLink { role: String },
+}
+
+impl Display for DistributeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MismatchedCount {
+                available_roles,
+                player_count,
+            } => write!(
+                f,
+                "configured {} Roles to assign but has {} Players",
+                available_roles, player_count
+            ),
+            Self::TooManyMaskedRoles {
+                masking_roles,
+                normal_roles,
+            } => write!(f, "configured {} Roles that mask/need another Role, but only configured {} 'normal' Roles", masking_roles, normal_roles),
+            Self::PinnedUnknownPlayer { player } => write!(
+                f,
+                "assigned a Role to <@{}>, who is not participating in the Round",
+                player.0
+            ),
+            Self::PinnedRoleUnavailable { role } => write!(
+                f,
+                "assigned the Role '{}' to more Players than it is configured for",
+                role
+            ),
+            Self::PinnedTooManyMaskedRoles {
+                masking_roles,
+                normal_roles,
+            } => write!(f, "after assigning the fixed Roles, there are {} Roles that mask/need another Role, but only {} 'normal' Roles left", masking_roles, normal_roles),
+            Self::NotEnoughPlayersToLink { role } => write!(
+                f,
+                "the Role '{}' links two other Players, but there are not enough Players",
+                role
+            ),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ReassignError {
+    /// The User is not a Player in the Round
+    UnknownPlayer { player: UserId },
+    /// Roles that mask another Role can't be assigned afterwards, as the masked Role would be
+    /// missing from the Round
+    MaskingRole { role: String },
+    /// The Role can only be given to a single Player and another Player already has it
+    RoleTaken { role: String, player: UserId },
+}
+
+impl Display for ReassignError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownPlayer { player } => {
+                write!(f, "<@{}> is not participating in the Round", player.0)
+            }
+            Self::MaskingRole { role } => write!(
+                f,
+                "the Role '{}' masks another Role and can't be assigned during a Round",
+                role
+            ),
+            Self::RoleTaken { role, player } => write!(
+                f,
+                "the Role '{}' is already assigned to <@{}>",
+                role, player.0
+            ),
+        }
+    }
+}
+
+/// Two Players that were linked by the Role of another Player, who share their own Channel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerLink {
+    /// The Name of the Role that linked the Players
+    pub role: String,
+    /// The Name of the Channel shared by the linked Players
+    pub channel: String,
+    /// The Player whose Role linked the other two Players
+    pub linked_by: UserId,
+    pub players: [UserId; 2],
+}
+
+/// The Result of distributing the Roles among the Players of a Round
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Distribution {
+    players: BTreeMap<UserId, WereWolfRoleInstance>,
+    #[serde(default)]
+    links: Vec<PlayerLink>,
+}
+
+impl Distribution {
+    /// Creates a new Distribution from the given Player-Role Assignments
+    pub fn new(players: BTreeMap<UserId, WereWolfRoleInstance>) -> Self {
+        Self {
+            players,
+            links: Vec::new(),
+        }
+    }
+
+    /// The Players that were linked at the Start of the Round
+    pub fn links(&self) -> &[PlayerLink] {
+        &self.links
+    }
+
+    /// Iterates over all the Players and their assigned Roles
+    pub fn iter(&self) -> impl Iterator<Item = (&UserId, &WereWolfRoleInstance)> {
+        self.players.iter()
+    }
+
+    /// All the Players that are part of the Distribution
+    pub fn players(&self) -> impl Iterator<Item = &UserId> {
+        self.players.keys()
+    }
+
+    /// The Number of Players in the Distribution
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    /// Whether or not the Distribution contains no Players
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    /// The Role that was assigned to the given User, if the User is a Player
+    pub fn role_of(&self, user: &UserId) -> Option<&WereWolfRoleInstance> {
+        self.players.get(user)
+    }
+
+    /// The Names of all the Channels the given User needs access to, this is empty if the User is
+    /// not a Player
+    pub fn channels_for(&self, user: &UserId) -> Vec<String> {
+        self.players
+            .get(user)
+            .map(|r| r.channels())
+            .unwrap_or_default()
+    }
+
+    /// All the Players whose Role belongs to the given Team
+    pub fn players_on_team(&self, team: &str) -> Vec<UserId> {
+        self.players
+            .iter()
+            .filter(|(_, role)| role.team() == Some(team))
+            .map(|(user, _)| *user)
+            .collect()
+    }
+
+    /// Assigns the given Role to the Player, replacing their previous Role which is returned.
+    ///
+    /// Roles that can only be held by a single Player are not handed out twice, so the Counts
+    /// of the Round stay consistent
+    pub fn reassign(
+        &mut self,
+        user: UserId,
+        role: &WereWolfRoleConfig,
+    ) -> Result<WereWolfRoleInstance, ReassignError> {
+        if !self.players.contains_key(&user) {
+            return Err(ReassignError::UnknownPlayer { player: user });
+        }
+        if role.masks_role() {
+            return Err(ReassignError::MaskingRole {
+                role: role.name().to_string(),
+            });
+        }
+
+        if !role.multi_player() {
+            let holder = self.players.iter().find(|(other, instance)| {
+                **other != user
+                    && (instance.name() == role.name()
+                        || instance.masked_role().map(|m| m.name()) == Some(role.name()))
+            });
+            if let Some((holder, _)) = holder {
+                return Err(ReassignError::RoleTaken {
+                    role: role.name().to_string(),
+                    player: *holder,
+                });
+            }
+        }
+
+        let instance =
+            role.to_instance(&mut || unreachable!("The Role does not mask another Role"));
+        Ok(self
+            .players
+            .insert(user, instance)
+            .expect("The Player was checked to be part of the Distribution"))
+    }
+}
+
+impl From<Distribution> for BTreeMap<UserId, WereWolfRoleInstance> {
+    fn from(distribution: Distribution) -> Self {
+        distribution.players
+    }
 }
 
 fn get_roles<'i, I, F>(roles: I, check: F) -> Vec<WereWolfRoleConfig>
@@ -34,12 +239,20 @@ where
     result
 }
 
-/// This will actually distribute the Roles among the Players
+/// Removes a single Role with the given Name from the List of Roles
+fn take_role(roles: &mut Vec<WereWolfRoleConfig>, name: &str) -> Option<WereWolfRoleConfig> {
+    let index = roles.iter().position(|r| r.name() == name)?;
+    Some(roles.remove(index))
+}
+
+/// This will actually distribute the Roles among the Players, the Players in `pinned` always
+/// receive the Role with the given Name
 fn distribute<R>(
     mut participants: Vec<UserId>,
     roles: BTreeMap<WereWolfRoleConfig, usize>,
+    pinned: &BTreeMap<UserId, String>,
     rng: &mut R,
-) -> Result<BTreeMap<UserId, WereWolfRoleInstance>, DistributeError>
+) -> Result<Distribution, DistributeError>
 where
     R: Rng,
 {
@@ -60,7 +273,41 @@ where
         });
     }
 
+    let mut pinned_roles = Vec::with_capacity(pinned.len());
+    for (user, role_name) in pinned.iter() {
+        let position = participants
+            .iter()
+            .position(|p| p == user)
+            .ok_or(DistributeError::PinnedUnknownPlayer { player: *user })?;
+
+        let role = take_role(&mut nested_roles, role_name)
+            .or_else(|| take_role(&mut non_nested_roles, role_name))
+            .ok_or_else(|| DistributeError::PinnedRoleUnavailable {
+                role: role_name.clone(),
+            })?;
+
+        participants.remove(position);
+        pinned_roles.push((*user, role));
+    }
+
+    let masking_count =
+        nested_roles.len() + pinned_roles.iter().filter(|(_, r)| r.masks_role()).count();
+    if masking_count > non_nested_roles.len() {
+        return Err(DistributeError::PinnedTooManyMaskedRoles {
+            masking_roles: masking_count,
+            normal_roles: non_nested_roles.len(),
+        });
+    }
+
     let mut result = BTreeMap::new();
+    for (user, role) in pinned_roles {
+        let instance = role.to_instance(&mut || {
+            let index: usize = rng.gen_range(0..non_nested_roles.len());
+            non_nested_roles.remove(index)
+        });
+
+        result.insert(user, instance);
+    }
     for nested_roles_remaining in (1..=nested_roles.len()).rev() {
         let index = rng.gen_range(0..nested_roles_remaining);
         let nested_role = nested_roles.remove(index);
@@ -90,15 +337,614 @@ where
         result.insert(user, instance);
     }
 
-    Ok(result)
+    let links = link_players(&result, &roles, rng)?;
+
+    Ok(Distribution {
+        players: result,
+        links,
+    })
+}
+
+/// Links two random other Players for every Player, whose Role or masked Role links Players.
+/// Every Link gets its own Channel, named after the Role that created it
+fn link_players<R>(
+    players: &BTreeMap<UserId, WereWolfRoleInstance>,
+    roles: &BTreeMap<WereWolfRoleConfig, usize>,
+    rng: &mut R,
+) -> Result<Vec<PlayerLink>, DistributeError>
+where
+    R: Rng,
+{
+    let links_players = |name: &str| roles.keys().any(|r| r.name() == name && r.links_players());
+
+    let mut links: Vec<PlayerLink> = Vec::new();
+    for (user, instance) in players.iter() {
+        let linking_roles = std::iter::once(instance)
+            .chain(instance.masked_role())
+            .filter(|r| links_players(r.name()));
+
+        for role in linking_roles {
+            let mut candidates: Vec<UserId> =
+                players.keys().filter(|p| *p != user).copied().collect();
+            if candidates.len() < 2 {
+                return Err(DistributeError::NotEnoughPlayersToLink {
+                    role: role.name().to_string(),
+                });
+            }
+
+            let first = candidates.remove(rng.gen_range(0..candidates.len()));
+            let second = candidates.remove(rng.gen_range(0..candidates.len()));
+
+            let previous = links.iter().filter(|l| l.role == role.name()).count();
+            let channel = match previous {
+                0 => format!("{}-Link", role.name()),
+                n => format!("{}-Link-{}", role.name(), n + 1),
+            };
+
+            links.push(PlayerLink {
+                role: role.name().to_string(),
+                channel,
+                linked_by: *user,
+                players: [first, second],
+            });
+        }
+    }
+
+    Ok(links)
 }
 
-/// This will distribute the given Roles to the Players
+/// This will distribute the given Roles to the Players, while making sure that the pinned
+/// Players receive the Role with the given Name
 pub fn distribute_roles(
     participants: Vec<UserId>,
     roles: BTreeMap<WereWolfRoleConfig, usize>,
-) -> Result<BTreeMap<UserId, WereWolfRoleInstance>, DistributeError> {
+    pinned: &BTreeMap<UserId, String>,
+) -> Result<Distribution, DistributeError> {
     let mut rng = rand::thread_rng();
 
-    distribute(participants, roles, &mut rng)
+    distribute(participants, roles, pinned, &mut rng)
+}
+
+/// How the Number of Player-Slots of the Roles compares to the Number of Players, the same
+/// Comparison that makes distributing the Roles fail with [`DistributeError::MismatchedCount`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SlotBalance {
+    /// Every Player gets exactly one Role
+    Matching,
+    /// There are more Player-Slots than Players
+    TooManySlots(usize),
+    /// There are Players that would not get a Role
+    TooFewSlots(usize),
+}
+
+impl Display for SlotBalance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Matching => write!(f, "Every Player gets a Role"),
+            Self::TooManySlots(delta) => write!(
+                f,
+                "The Roles have {} more Player-Slot(s) than there are Players, deselect Roles or lower their Counts",
+                delta
+            ),
+            Self::TooFewSlots(delta) => write!(
+                f,
+                "The Roles need {} more Player-Slot(s) so every Player gets a Role, select more Roles or raise their Counts",
+                delta
+            ),
+        }
+    }
+}
+
+/// Compares the Player-Slots of the Roles to the Number of Players, Roles that mask another Role
+/// don't take up a Slot of their own
+pub fn slot_balance(
+    player_count: usize,
+    roles: &BTreeMap<WereWolfRoleConfig, usize>,
+) -> SlotBalance {
+    let slots: usize = roles
+        .iter()
+        .filter(|(role, _)| !role.masks_role())
+        .fold(0usize, |total, (_, count)| total.saturating_add(*count));
+
+    if slots > player_count {
+        SlotBalance::TooManySlots(slots - player_count)
+    } else if slots < player_count {
+        SlotBalance::TooFewSlots(player_count - slots)
+    } else {
+        SlotBalance::Matching
+    }
+}
+
+/// Checks if the Roles could be distributed among the given Number of Players, without actually
+/// distributing them to anyone. The Checks of [`distribute_roles`] are done on the Counts alone,
+/// as the Number of Players and the Counts are entered by the Users and could be arbitrarily large
+pub fn validate_roles(
+    player_count: usize,
+    roles: BTreeMap<WereWolfRoleConfig, usize>,
+) -> Result<(), DistributeError> {
+    let count_where = |check: fn(&WereWolfRoleConfig) -> bool| {
+        roles
+            .iter()
+            .filter(|(role, _)| check(role))
+            .fold(0usize, |total, (_, count)| total.saturating_add(*count))
+    };
+    let normal_roles = count_where(|r| !r.masks_role());
+    let masking_roles = count_where(|r| r.masks_role());
+
+    if normal_roles != player_count {
+        return Err(DistributeError::MismatchedCount {
+            available_roles: normal_roles,
+            player_count,
+        });
+    }
+    if masking_roles > normal_roles {
+        return Err(DistributeError::TooManyMaskedRoles {
+            masking_roles,
+            normal_roles,
+        });
+    }
+
+    // Every Role is given to a Player, so any linking Role needs two other Players
+    match roles
+        .iter()
+        .find(|(role, count)| **count > 0 && role.links_players())
+    {
+        Some((role, _)) if player_count < 3 => Err(DistributeError::NotEnoughPlayersToLink {
+            role: role.name().to_string(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_distribution() -> Distribution {
+        let villager =
+            WereWolfRoleConfig::new("Villager", "", false, false, Vec::new()).with_team("Village");
+        let wolf = WereWolfRoleConfig::new("Werewolf", "", false, false, vec!["Night".to_string()])
+            .with_team("Werewolves");
+
+        let mut players = BTreeMap::new();
+        players.insert(UserId(1), villager.to_instance(&mut || unreachable!()));
+        players.insert(UserId(2), villager.to_instance(&mut || unreachable!()));
+        players.insert(UserId(3), wolf.to_instance(&mut || unreachable!()));
+
+        Distribution::new(players)
+    }
+
+    #[test]
+    fn slot_balance_matching() {
+        let mut roles = BTreeMap::new();
+        roles.insert(
+            WereWolfRoleConfig::new("Villager", "", true, false, Vec::new()),
+            2,
+        );
+        // Masking Roles don't need a Slot of their own
+        roles.insert(
+            WereWolfRoleConfig::new("Doppelganger", "", false, true, Vec::new()),
+            1,
+        );
+
+        assert_eq!(SlotBalance::Matching, slot_balance(2, &roles));
+        assert!(validate_roles(2, roles).is_ok());
+    }
+
+    #[test]
+    fn slot_balance_over() {
+        let mut roles = BTreeMap::new();
+        roles.insert(
+            WereWolfRoleConfig::new("Villager", "", true, false, Vec::new()),
+            5,
+        );
+
+        assert_eq!(SlotBalance::TooManySlots(2), slot_balance(3, &roles));
+        assert_eq!(
+            "The Roles have 2 more Player-Slot(s) than there are Players, deselect Roles or lower their Counts",
+            slot_balance(3, &roles).to_string()
+        );
+        assert!(matches!(
+            validate_roles(3, roles),
+            Err(DistributeError::MismatchedCount { .. })
+        ));
+    }
+
+    #[test]
+    fn slot_balance_under() {
+        let mut roles = BTreeMap::new();
+        roles.insert(
+            WereWolfRoleConfig::new("Villager", "", true, false, Vec::new()),
+            2,
+        );
+        roles.insert(
+            WereWolfRoleConfig::new("Doppelganger", "", false, true, Vec::new()),
+            1,
+        );
+
+        assert_eq!(SlotBalance::TooFewSlots(1), slot_balance(3, &roles));
+        assert_eq!(
+            "The Roles need 1 more Player-Slot(s) so every Player gets a Role, select more Roles or raise their Counts",
+            slot_balance(3, &roles).to_string()
+        );
+        assert!(matches!(
+            validate_roles(3, roles),
+            Err(DistributeError::MismatchedCount { .. })
+        ));
+    }
+
+    #[test]
+    fn role_of() {
+        let distribution = test_distribution();
+
+        assert_eq!(
+            Some("Werewolf"),
+            distribution.role_of(&UserId(3)).map(|r| r.name())
+        );
+        assert_eq!(None, distribution.role_of(&UserId(4)));
+    }
+
+    #[test]
+    fn channels_for() {
+        let distribution = test_distribution();
+
+        assert_eq!(
+            vec!["Werewolf".to_string(), "Night".to_string()],
+            distribution.channels_for(&UserId(3))
+        );
+        assert_eq!(Vec::<String>::new(), distribution.channels_for(&UserId(4)));
+    }
+
+    #[test]
+    fn players_on_team() {
+        let distribution = test_distribution();
+
+        assert_eq!(
+            vec![UserId(1), UserId(2)],
+            distribution.players_on_team("Village")
+        );
+        assert_eq!(vec![UserId(3)], distribution.players_on_team("Werewolves"));
+        assert_eq!(Vec::<UserId>::new(), distribution.players_on_team("Other"));
+    }
+
+    #[test]
+    fn validate_playable() {
+        let mut roles = BTreeMap::new();
+        roles.insert(
+            WereWolfRoleConfig::new("Villager", "", true, false, Vec::new()),
+            2,
+        );
+        roles.insert(
+            WereWolfRoleConfig::new("Thief", "", false, true, Vec::new()),
+            1,
+        );
+
+        assert!(validate_roles(2, roles).is_ok());
+    }
+
+    #[test]
+    fn validate_unplayable() {
+        let mut roles = BTreeMap::new();
+        roles.insert(
+            WereWolfRoleConfig::new("Villager", "", true, false, Vec::new()),
+            1,
+        );
+        roles.insert(
+            WereWolfRoleConfig::new("Thief", "", true, true, Vec::new()),
+            2,
+        );
+
+        assert!(matches!(
+            validate_roles(2, roles.clone()),
+            Err(DistributeError::MismatchedCount {
+                available_roles: 1,
+                player_count: 2
+            })
+        ));
+        assert!(matches!(
+            validate_roles(1, roles),
+            Err(DistributeError::TooManyMaskedRoles {
+                masking_roles: 2,
+                normal_roles: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn validate_large_counts() {
+        let mut roles = BTreeMap::new();
+        roles.insert(
+            WereWolfRoleConfig::new("Villager", "", true, false, Vec::new()),
+            usize::MAX,
+        );
+        roles.insert(
+            WereWolfRoleConfig::new("Werewolf", "", true, false, Vec::new()),
+            usize::MAX,
+        );
+
+        assert!(matches!(
+            validate_roles(usize::MAX - 1, roles.clone()),
+            Err(DistributeError::MismatchedCount { .. })
+        ));
+        assert_eq!(
+            SlotBalance::TooManySlots(1),
+            slot_balance(usize::MAX - 1, &roles)
+        );
+        assert!(validate_roles(usize::MAX, roles).is_ok());
+    }
+
+    #[test]
+    fn reassign_player() {
+        let mut distribution = test_distribution();
+        let seer = WereWolfRoleConfig::new("Seer", "", false, false, Vec::new());
+
+        let previous = distribution.reassign(UserId(1), &seer).unwrap();
+
+        assert_eq!("Villager", previous.name());
+        assert_eq!(
+            Some("Seer"),
+            distribution.role_of(&UserId(1)).map(|r| r.name())
+        );
+        assert_eq!(3, distribution.len());
+    }
+
+    #[test]
+    fn reassign_invalid() {
+        let mut distribution = test_distribution();
+        let wolf = WereWolfRoleConfig::new("Werewolf", "", false, false, Vec::new());
+        let thief = WereWolfRoleConfig::new("Thief", "", false, true, Vec::new());
+
+        assert_eq!(
+            Err(ReassignError::UnknownPlayer { player: UserId(4) }),
+            distribution.reassign(UserId(4), &wolf)
+        );
+        assert_eq!(
+            Err(ReassignError::RoleTaken {
+                role: "Werewolf".to_string(),
+                player: UserId(3)
+            }),
+            distribution.reassign(UserId(1), &wolf)
+        );
+        assert_eq!(
+            Err(ReassignError::MaskingRole {
+                role: "Thief".to_string()
+            }),
+            distribution.reassign(UserId(1), &thief)
+        );
+        assert_eq!(test_distribution(), distribution);
+    }
+
+    #[test]
+    fn distribute_all_players() {
+        let mut roles = BTreeMap::new();
+        roles.insert(
+            WereWolfRoleConfig::new("Villager", "", true, false, Vec::new()),
+            2,
+        );
+        roles.insert(
+            WereWolfRoleConfig::new("Seer", "", false, false, Vec::new()),
+            1,
+        );
+
+        let result = distribute(
+            vec![UserId(1), UserId(2), UserId(3)],
+            roles,
+            &BTreeMap::new(),
+            &mut rand::thread_rng(),
+        )
+        .unwrap();
+
+        assert_eq!(3, result.len());
+        assert_eq!(
+            vec![&UserId(1), &UserId(2), &UserId(3)],
+            result.players().collect::<Vec<_>>()
+        );
+    }
+
+    fn link_roles(cupids: usize) -> BTreeMap<WereWolfRoleConfig, usize> {
+        let mut roles = BTreeMap::new();
+        roles.insert(
+            WereWolfRoleConfig::new("Villager", "", true, false, Vec::new()),
+            3,
+        );
+        roles.insert(
+            WereWolfRoleConfig::new("Cupid", "", true, false, Vec::new()).with_linked_players(),
+            cupids,
+        );
+        roles
+    }
+
+    #[test]
+    fn distribute_links_two_players() {
+        let participants: Vec<UserId> = (1..=3 + 2).map(UserId).collect();
+
+        for _ in 0..20 {
+            let result = distribute(
+                participants.clone(),
+                link_roles(2),
+                &BTreeMap::new(),
+                &mut rand::thread_rng(),
+            )
+            .unwrap();
+
+            let channels: Vec<&str> = result.links().iter().map(|l| l.channel.as_str()).collect();
+            assert_eq!(vec!["Cupid-Link", "Cupid-Link-2"], channels);
+
+            for link in result.links() {
+                assert_eq!(
+                    Some("Cupid"),
+                    result.role_of(&link.linked_by).map(|r| r.name())
+                );
+                // Two distinct Players, that don't include the Player who linked them
+                assert_ne!(link.players[0], link.players[1]);
+                assert!(!link.players.contains(&link.linked_by));
+                assert!(link.players.iter().all(|p| result.role_of(p).is_some()));
+            }
+        }
+    }
+
+    #[test]
+    fn distribute_without_links() {
+        let result = distribute(
+            (1..=3).map(UserId).collect(),
+            link_roles(0),
+            &BTreeMap::new(),
+            &mut rand::thread_rng(),
+        )
+        .unwrap();
+
+        assert!(result.links().is_empty());
+    }
+
+    #[test]
+    fn link_not_enough_players() {
+        let mut roles = BTreeMap::new();
+        roles.insert(
+            WereWolfRoleConfig::new("Villager", "", false, false, Vec::new()),
+            1,
+        );
+        roles.insert(
+            WereWolfRoleConfig::new("Cupid", "", false, false, Vec::new()).with_linked_players(),
+            1,
+        );
+
+        let result = distribute(
+            vec![UserId(1), UserId(2)],
+            roles,
+            &BTreeMap::new(),
+            &mut rand::thread_rng(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(DistributeError::NotEnoughPlayersToLink { role }) if role == "Cupid"
+        ));
+    }
+
+    fn pin_roles() -> BTreeMap<WereWolfRoleConfig, usize> {
+        let mut roles = BTreeMap::new();
+        roles.insert(
+            WereWolfRoleConfig::new("Villager", "", true, false, Vec::new()),
+            2,
+        );
+        roles.insert(
+            WereWolfRoleConfig::new("Seer", "", false, false, Vec::new()),
+            1,
+        );
+        roles.insert(
+            WereWolfRoleConfig::new("Thief", "", false, true, Vec::new()),
+            1,
+        );
+        roles
+    }
+
+    #[test]
+    fn distribute_pinned() {
+        let mut pinned = BTreeMap::new();
+        pinned.insert(UserId(2), "Seer".to_string());
+        pinned.insert(UserId(3), "Thief".to_string());
+
+        for _ in 0..20 {
+            let result = distribute(
+                vec![UserId(1), UserId(2), UserId(3)],
+                pin_roles(),
+                &pinned,
+                &mut rand::thread_rng(),
+            )
+            .unwrap();
+
+            assert_eq!(3, result.len());
+            assert_eq!(Some("Seer"), result.role_of(&UserId(2)).map(|r| r.name()));
+            assert_eq!(Some("Thief"), result.role_of(&UserId(3)).map(|r| r.name()));
+        }
+    }
+
+    #[test]
+    fn distribute_pinned_unknown_player() {
+        let mut pinned = BTreeMap::new();
+        pinned.insert(UserId(4), "Seer".to_string());
+
+        let result = distribute(
+            vec![UserId(1), UserId(2), UserId(3)],
+            pin_roles(),
+            &pinned,
+            &mut rand::thread_rng(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(DistributeError::PinnedUnknownPlayer { player: UserId(4) })
+        ));
+    }
+
+    #[test]
+    fn distribute_pinned_unavailable_role() {
+        let mut pinned = BTreeMap::new();
+        pinned.insert(UserId(1), "Seer".to_string());
+        pinned.insert(UserId(2), "Seer".to_string());
+
+        let result = distribute(
+            vec![UserId(1), UserId(2), UserId(3)],
+            pin_roles(),
+            &pinned,
+            &mut rand::thread_rng(),
+        );
+        assert!(matches!(
+            result,
+            Err(DistributeError::PinnedRoleUnavailable { .. })
+        ));
+
+        let mut pinned = BTreeMap::new();
+        pinned.insert(UserId(1), "Hunter".to_string());
+
+        let result = distribute(
+            vec![UserId(1), UserId(2), UserId(3)],
+            pin_roles(),
+            &pinned,
+            &mut rand::thread_rng(),
+        );
+        assert!(matches!(
+            result,
+            Err(DistributeError::PinnedRoleUnavailable { .. })
+        ));
+    }
+
+    #[test]
+    fn distribute_pinned_too_many_masked() {
+        let mut pinned = BTreeMap::new();
+        pinned.insert(UserId(1), "Villager".to_string());
+        pinned.insert(UserId(2), "Villager".to_string());
+        pinned.insert(UserId(3), "Seer".to_string());
+
+        let result = distribute(
+            vec![UserId(1), UserId(2), UserId(3)],
+            pin_roles(),
+            &pinned,
+            &mut rand::thread_rng(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(DistributeError::PinnedTooManyMaskedRoles {
+                masking_roles: 1,
+                normal_roles: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn distribution_serde_roundtrip() {
+        let mut players = BTreeMap::new();
+        players.insert(
+            UserId(1),
+            WereWolfRoleConfig::new("Seer", "", false, false, Vec::new())
+                .to_instance(&mut || unreachable!()),
+        );
+        let distribution = Distribution::new(players);
+
+        let serialized = serde_json::to_string(&distribution).unwrap();
+        let deserialized: Distribution = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(distribution, deserialized);
+    }
 }
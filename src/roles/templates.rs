@@ -0,0 +1,234 @@
+use std::{collections::HashMap, fmt::Display};
+
+use serenity::model::id::GuildId;
+
+use super::WereWolfRoleConfig;
+
+#[derive(Debug, PartialEq)]
+pub enum TemplateError {
+    /// A Role in the Chain of Bases is based on another Role that does not exist
+    MissingBase { role: String, base: String },
+    /// The Role is, directly or indirectly, based on itself
+    Cycle { role: String },
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingBase { role, base } => write!(
+                f,
+                "The Role '{}' is based on the Role '{}', which does not exist",
+                role, base
+            ),
+            Self::Cycle { role } => write!(f, "The Role '{}' is based on itself", role),
+        }
+    }
+}
+
+/// Merges the Role into its already merged Base, the extra Channels of both are combined and the
+/// Team and Channel-Intro of the Base are used unless the Role has its own
+fn merge(base: WereWolfRoleConfig, role: &WereWolfRoleConfig) -> WereWolfRoleConfig {
+    let mut channels = base.other_role_channels;
+    for channel in role.other_role_channels.iter() {
+        if !channels.contains(channel) {
+            channels.push(channel.clone());
+        }
+    }
+
+    WereWolfRoleConfig {
+        other_role_channels: channels,
+        team: role.team.clone().or(base.team),
+        channel_intro: role.channel_intro.clone().or(base.channel_intro),
+        ..role.clone()
+    }
+}
+
+/// Merges the Role with all of its Bases, starting with the Base that is not based on any other
+/// Role
+fn resolve_role(
+    role: &WereWolfRoleConfig,
+    by_name: &HashMap<&str, &WereWolfRoleConfig>,
+) -> Result<WereWolfRoleConfig, TemplateError> {
+    let mut chain = vec![role];
+    while let Some(base) = chain.last().unwrap().base.as_deref() {
+        let base_role = match by_name.get(base) {
+            Some(b) => *b,
+            None => {
+                return Err(TemplateError::MissingBase {
+                    role: chain.last().unwrap().name.clone(),
+                    base: base.to_string(),
+                })
+            }
+        };
+        if chain.iter().any(|r| r.name == base_role.name) {
+            return Err(TemplateError::Cycle {
+                role: role.name.clone(),
+            });
+        }
+
+        chain.push(base_role);
+    }
+
+    let mut chain = chain.into_iter().rev();
+    let root = chain.next().unwrap().clone();
+    Ok(chain.fold(root, merge))
+}
+
+/// Merges every Role, that is based on another Role, with its Base. Roles whose Bases can't be
+/// resolved are returned as they are, together with the Reason why they could not be resolved
+pub fn resolve_templates(
+    roles: Vec<WereWolfRoleConfig>,
+) -> (Vec<WereWolfRoleConfig>, Vec<TemplateError>) {
+    let by_name: HashMap<&str, &WereWolfRoleConfig> =
+        roles.iter().map(|r| (r.name.as_str(), r)).collect();
+
+    let mut errors = Vec::new();
+    let resolved = roles
+        .iter()
+        .map(|role| match resolve_role(role, &by_name) {
+            Ok(r) => r,
+            Err(e) => {
+                errors.push(e);
+                role.clone()
+            }
+        })
+        .collect();
+
+    (resolved, errors)
+}
+
+/// Merges the stored Roles of the Guild with their Bases, right before they are used in a Round
+/// or shown to the Users. The stored Roles are never resolved, so Changes to a Base are picked up
+/// by all the Roles based on it
+pub fn resolve_guild_roles(
+    guild: GuildId,
+    roles: Vec<WereWolfRoleConfig>,
+) -> Vec<WereWolfRoleConfig> {
+    let (roles, errors) = resolve_templates(roles);
+    for error in errors {
+        tracing::error!("Resolving Role-Templates of Guild {:?}: {}", guild, error);
+    }
+    roles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(name: &str, channels: &[&str]) -> WereWolfRoleConfig {
+        WereWolfRoleConfig::new(
+            name,
+            "🐺",
+            false,
+            false,
+            channels.iter().map(|c| c.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn merges_base() {
+        let roles = vec![
+            role("Evil", &["Werewolf", "Night"]).with_team("Werewolves"),
+            role("White-Wolf", &["Night", "Moon"]).with_base("Evil"),
+        ];
+
+        let (resolved, errors) = resolve_templates(roles);
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            role("White-Wolf", &["Werewolf", "Night", "Moon"])
+                .with_base("Evil")
+                .with_team("Werewolves"),
+            resolved[1]
+        );
+        // The Base itself is not changed
+        assert_eq!(
+            role("Evil", &["Werewolf", "Night"]).with_team("Werewolves"),
+            resolved[0]
+        );
+    }
+
+    #[test]
+    fn own_team_wins() {
+        let roles = vec![
+            role("Evil", &[]).with_team("Werewolves"),
+            role("Traitor", &[]).with_base("Evil").with_team("Solo"),
+        ];
+
+        let (resolved, _) = resolve_templates(roles);
+
+        assert_eq!(Some("Solo"), resolved[1].team());
+    }
+
+    #[test]
+    fn nested_bases() {
+        let roles = vec![
+            role("Wolf", &["Werewolf"]).with_base("Evil"),
+            role("Evil", &["Night"]).with_team("Werewolves"),
+            role("Big-Wolf", &["Big"]).with_base("Wolf"),
+        ];
+
+        let (resolved, errors) = resolve_templates(roles);
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            role("Big-Wolf", &["Night", "Werewolf", "Big"])
+                .with_base("Wolf")
+                .with_team("Werewolves"),
+            resolved[2]
+        );
+    }
+
+    #[test]
+    fn missing_base() {
+        let roles = vec![
+            role("Wolf", &["Werewolf"]).with_base("Evil"),
+            role("Big-Wolf", &["Big"]).with_base("Wolf"),
+        ];
+
+        let (resolved, errors) = resolve_templates(roles.clone());
+
+        // The Roles are kept as they are
+        assert_eq!(roles, resolved);
+        assert_eq!(
+            vec![
+                TemplateError::MissingBase {
+                    role: "Wolf".to_string(),
+                    base: "Evil".to_string()
+                },
+                TemplateError::MissingBase {
+                    role: "Wolf".to_string(),
+                    base: "Evil".to_string()
+                }
+            ],
+            errors
+        );
+    }
+
+    #[test]
+    fn cyclic_bases() {
+        let roles = vec![
+            role("A", &["a"]).with_base("B"),
+            role("B", &["b"]).with_base("A"),
+            role("C", &["c"]).with_base("C"),
+        ];
+
+        let (resolved, errors) = resolve_templates(roles.clone());
+
+        assert_eq!(roles, resolved);
+        assert_eq!(
+            vec![
+                TemplateError::Cycle {
+                    role: "A".to_string()
+                },
+                TemplateError::Cycle {
+                    role: "B".to_string()
+                },
+                TemplateError::Cycle {
+                    role: "C".to_string()
+                }
+            ],
+            errors
+        );
+    }
+}
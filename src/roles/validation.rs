@@ -0,0 +1,329 @@
+use std::fmt::Display;
+
+use serenity::model::channel::ReactionType;
+
+/// The maximum Length of a Role-Name, Discord allows at most 100 Characters for Channel-Names
+pub const MAX_NAME_LENGTH: usize = 100;
+
+/// The maximum Length of the Channel-Intro of a Role. A Discord-Message can hold 2000
+/// Characters, but the Intro is also stored as part of the Role, which needs room for the other
+/// Fields of the Role as well
+pub const MAX_INTRO_LENGTH: usize = 1000;
+
+/// The maximum Number of Characters in a single Unicode-Emoji, which leaves enough room for
+/// ZWJ-Sequences with Skin-Tones like the Families or Couples
+const MAX_EMOJI_CHARS: usize = 16;
+
+/// The Unicode-Codepoint used for Keycap-Emoji, like 1️⃣
+const KEYCAP: char = '\u{20E3}';
+/// The Zero-Width-Joiner used to combine multiple Emoji into a single one
+const ZWJ: char = '\u{200D}';
+
+#[derive(Debug, PartialEq)]
+pub enum InvalidRoleName {
+    Empty,
+    TooLong {
+        length: usize,
+    },
+    /// The Name contains a Character that can't be used in the Name of a Channel
+    InvalidCharacter(char),
+}
+
+impl Display for InvalidRoleName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "The Name of the Role must not be empty"),
+            Self::TooLong { length } => write!(
+                f,
+                "The Name of the Role is {} Characters long, but at most {} are allowed",
+                length, MAX_NAME_LENGTH
+            ),
+            Self::InvalidCharacter(c) => write!(
+                f,
+                "The Name of the Role contains '{}', but only Letters, Numbers, '-' and '_' can be used in Channel-Names",
+                c
+            ),
+        }
+    }
+}
+
+/// Checks that the Name can be used for a Role and its Channel
+pub fn validate_name(name: &str) -> Result<(), InvalidRoleName> {
+    let length = name.chars().count();
+    if length == 0 {
+        return Err(InvalidRoleName::Empty);
+    }
+    if length > MAX_NAME_LENGTH {
+        return Err(InvalidRoleName::TooLong { length });
+    }
+
+    match name
+        .chars()
+        .find(|c| !(c.is_alphanumeric() || *c == '-' || *c == '_'))
+    {
+        Some(c) => Err(InvalidRoleName::InvalidCharacter(c)),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InvalidRoleEmoji {
+    Empty,
+    TooLong,
+    /// The String contains more than one Emoji
+    MultipleEmoji,
+    /// The Emoji contains regular Text or Whitespace
+    NotAnEmoji,
+    /// A Custom-Emoji without an ID, which can't be used for Reactions
+    UnknownCustom,
+}
+
+impl Display for InvalidRoleEmoji {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "The Emoji must not be empty"),
+            Self::TooLong | Self::MultipleEmoji => write!(f, "The Emoji must be a single Emoji"),
+            Self::NotAnEmoji => write!(f, "The Emoji must not contain any Text"),
+            Self::UnknownCustom => write!(f, "The Custom-Emoji is not known"),
+        }
+    }
+}
+
+/// Whether the Character only modifies the Emoji before it, instead of starting a new one
+fn is_modifier(c: char) -> bool {
+    matches!(c,
+        ZWJ | KEYCAP
+        // Variation-Selectors
+        | '\u{FE0E}' | '\u{FE0F}'
+        // Skin-Tones
+        | '\u{1F3FB}'..='\u{1F3FF}'
+        // Tags, used for the Subdivision-Flags
+        | '\u{E0020}'..='\u{E007F}')
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+/// Counts the Emoji in the String, treating Characters joined by a ZWJ, Modifiers and Pairs of
+/// Regional-Indicators (Flags) as a single Emoji
+fn emoji_count(emoji: &str) -> usize {
+    let mut count = 0;
+    let mut previous: Option<char> = None;
+    let mut open_flag = false;
+
+    for c in emoji.chars() {
+        let continues =
+            is_modifier(c) || previous == Some(ZWJ) || (open_flag && is_regional_indicator(c));
+
+        if continues {
+            open_flag = false;
+        } else {
+            count += 1;
+            open_flag = is_regional_indicator(c);
+        }
+        previous = Some(c);
+    }
+
+    count
+}
+
+/// Checks that the Unicode-String is a single Emoji and not some other Text
+fn validate_unicode_emoji(emoji: &str) -> Result<(), InvalidRoleEmoji> {
+    if emoji.is_empty() {
+        return Err(InvalidRoleEmoji::Empty);
+    }
+    if emoji.chars().count() > MAX_EMOJI_CHARS {
+        return Err(InvalidRoleEmoji::TooLong);
+    }
+
+    // Keycap-Emoji are the only Emoji that contain ASCII-Characters
+    let keycap = emoji.ends_with(KEYCAP);
+    let text = emoji
+        .chars()
+        .any(|c| c.is_whitespace() || c.is_control() || (c.is_ascii() && !keycap));
+    if text {
+        return Err(InvalidRoleEmoji::NotAnEmoji);
+    }
+    if emoji_count(emoji) > 1 {
+        return Err(InvalidRoleEmoji::MultipleEmoji);
+    }
+
+    Ok(())
+}
+
+/// Checks that the Reaction can be used as the Emoji of a Role
+pub fn validate_emoji(reaction: &ReactionType) -> Result<(), InvalidRoleEmoji> {
+    match reaction {
+        ReactionType::Unicode(raw) => validate_unicode_emoji(raw),
+        ReactionType::Custom { name: Some(_), .. } => Ok(()),
+        _ => Err(InvalidRoleEmoji::UnknownCustom),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InvalidChannelIntro {
+    Empty,
+    TooLong { length: usize },
+}
+
+impl Display for InvalidChannelIntro {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "The Channel-Intro must not be empty"),
+            Self::TooLong { length } => write!(
+                f,
+                "The Channel-Intro is {} Characters long, but at most {} are allowed",
+                length, MAX_INTRO_LENGTH
+            ),
+        }
+    }
+}
+
+/// Checks that the Intro can be posted in the Channel of a Role and stored with it
+pub fn validate_intro(intro: &str) -> Result<(), InvalidChannelIntro> {
+    let length = intro.trim().chars().count();
+    if length == 0 {
+        return Err(InvalidChannelIntro::Empty);
+    }
+    if length > MAX_INTRO_LENGTH {
+        return Err(InvalidChannelIntro::TooLong { length });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::model::id::EmojiId;
+
+    use super::*;
+
+    #[test]
+    fn valid_names() {
+        assert_eq!(Ok(()), validate_name("Werewolf"));
+        assert_eq!(Ok(()), validate_name("little-red_riding-hood"));
+        assert_eq!(Ok(()), validate_name("Dorfbewohnerin"));
+        assert_eq!(Ok(()), validate_name("Mädchen"));
+    }
+
+    #[test]
+    fn empty_name() {
+        assert_eq!(Err(InvalidRoleName::Empty), validate_name(""));
+    }
+
+    #[test]
+    fn long_name() {
+        let name = "a".repeat(MAX_NAME_LENGTH + 1);
+        assert_eq!(
+            Err(InvalidRoleName::TooLong {
+                length: MAX_NAME_LENGTH + 1
+            }),
+            validate_name(&name)
+        );
+        assert_eq!(Ok(()), validate_name(&"a".repeat(MAX_NAME_LENGTH)));
+    }
+
+    #[test]
+    fn invalid_characters() {
+        assert_eq!(
+            Err(InvalidRoleName::InvalidCharacter('!')),
+            validate_name("Seer!")
+        );
+        assert_eq!(
+            Err(InvalidRoleName::InvalidCharacter(' ')),
+            validate_name("Big Wolf")
+        );
+        assert_eq!(
+            Err(InvalidRoleName::InvalidCharacter('#')),
+            validate_name("#wolf")
+        );
+    }
+
+    #[test]
+    fn valid_emoji() {
+        assert_eq!(Ok(()), validate_unicode_emoji("🐺"));
+        assert_eq!(Ok(()), validate_unicode_emoji("👍🏽"));
+        assert_eq!(Ok(()), validate_unicode_emoji("👩‍👩‍👧‍👦"));
+        assert_eq!(Ok(()), validate_unicode_emoji("1️⃣"));
+        assert_eq!(Ok(()), validate_unicode_emoji("🇩🇪"));
+        assert_eq!(Ok(()), validate_unicode_emoji("❤️"));
+        assert_eq!(
+            Ok(()),
+            validate_emoji(&ReactionType::Custom {
+                animated: false,
+                id: EmojiId(13),
+                name: Some("wolf".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn empty_emoji() {
+        assert_eq!(Err(InvalidRoleEmoji::Empty), validate_unicode_emoji(""));
+    }
+
+    #[test]
+    fn long_emoji() {
+        assert_eq!(
+            Err(InvalidRoleEmoji::TooLong),
+            validate_unicode_emoji(&"🐺".repeat(MAX_EMOJI_CHARS + 1))
+        );
+    }
+
+    #[test]
+    fn multiple_emoji() {
+        assert_eq!(
+            Err(InvalidRoleEmoji::MultipleEmoji),
+            validate_unicode_emoji("🐺🐺")
+        );
+        assert_eq!(
+            Err(InvalidRoleEmoji::MultipleEmoji),
+            validate_unicode_emoji("🇩🇪🇫")
+        );
+        assert_eq!(
+            Err(InvalidRoleEmoji::MultipleEmoji),
+            validate_unicode_emoji("👍🏽🐺")
+        );
+    }
+
+    #[test]
+    fn text_emoji() {
+        assert_eq!(
+            Err(InvalidRoleEmoji::NotAnEmoji),
+            validate_unicode_emoji("wolf")
+        );
+        assert_eq!(
+            Err(InvalidRoleEmoji::NotAnEmoji),
+            validate_unicode_emoji("🐺 ")
+        );
+        assert_eq!(
+            Err(InvalidRoleEmoji::NotAnEmoji),
+            validate_unicode_emoji(":)")
+        );
+    }
+
+    #[test]
+    fn unknown_custom_emoji() {
+        assert_eq!(
+            Err(InvalidRoleEmoji::UnknownCustom),
+            validate_emoji(&ReactionType::Custom {
+                animated: false,
+                id: EmojiId(13),
+                name: None,
+            })
+        );
+    }
+
+    #[test]
+    fn intro_length() {
+        assert_eq!(Ok(()), validate_intro("You have two Potions"));
+        assert_eq!(Ok(()), validate_intro(&"ä".repeat(MAX_INTRO_LENGTH)));
+
+        assert_eq!(Err(InvalidChannelIntro::Empty), validate_intro(" \n "));
+        assert_eq!(
+            Err(InvalidChannelIntro::TooLong { length: 2001 }),
+            validate_intro(&"a".repeat(2001))
+        );
+    }
+}
@@ -1,9 +1,12 @@
+pub mod archive;
 mod channels;
+pub mod counts;
+pub mod death;
+pub mod history;
+pub mod options;
+pub mod phase;
+pub mod registry;
+pub mod slots;
 pub mod start;
 pub mod stop;
-
-mod states;
-pub use states::*;
-
-mod traits;
-pub use traits::*;
+pub mod vote;
@@ -0,0 +1,182 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
+
+use serenity::{
+    http::Http,
+    model::{
+        channel::{GuildChannel, PermissionOverwriteType},
+        id::{ChannelId, GuildId, UserId},
+    },
+};
+
+use crate::util::dry_run;
+
+use super::channels;
+
+/// The first Millisecond of 2015, which Discord uses as the Epoch for the Timestamps in its IDs
+const DISCORD_EPOCH_MS: u64 = 1_420_070_400_000;
+
+/// The Point in Time at which the Object with the given ID was created
+pub fn snowflake_time(id: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_millis((id >> 22) + DISCORD_EPOCH_MS)
+}
+
+/// The last Point in Time at which the Channel was used, which is either the Time of its last
+/// Message or its Creation if nothing was ever written in it
+fn last_activity(channel: &GuildChannel) -> SystemTime {
+    let created = snowflake_time(channel.id.0);
+    match channel.last_message_id {
+        Some(last) => snowflake_time(last.0).max(created),
+        None => created,
+    }
+}
+
+/// Whether the Channel was set up by the Bot, every Channel of a Round gets a
+/// Permission-Overwrite for the Bot itself, which is kept once the Round is over
+fn set_up_by_bot(channel: &GuildChannel, bot_id: UserId) -> bool {
+    channel
+        .permission_overwrites
+        .iter()
+        .any(|o| o.kind == PermissionOverwriteType::Member(bot_id))
+}
+
+/// The Channels in the Inactive-Category, that were set up by the Bot and have not been used
+/// for longer than the Retention
+fn expired_channels(
+    guild_channel: &HashMap<ChannelId, GuildChannel>,
+    bot_id: UserId,
+    retention: Duration,
+    now: SystemTime,
+) -> Vec<&GuildChannel> {
+    let category = match channels::find_inactive_category(guild_channel) {
+        Some(c) => c,
+        None => return Vec::new(),
+    };
+
+    let mut result: Vec<&GuildChannel> = guild_channel
+        .values()
+        .filter(|c| c.category_id == Some(category))
+        .filter(|c| set_up_by_bot(c, bot_id))
+        .filter(|c| {
+            now.duration_since(last_activity(c))
+                .map(|unused| unused > retention)
+                .unwrap_or(false)
+        })
+        .collect();
+    result.sort_by_key(|c| c.id);
+    result
+}
+
+/// Deletes all the Channels of the Guild that have been unused in the Inactive-Category for
+/// longer than the Retention
+#[tracing::instrument(skip(ctx, bot_id))]
+pub async fn archive(ctx: &Http, guild: GuildId, bot_id: UserId, retention: Duration) {
+    let guild_channel = match guild.channels(ctx).await {
+        Ok(g) => g,
+        Err(e) => {
+            tracing::error!("Loading Channels for Guild: {:?}", e);
+            return;
+        }
+    };
+
+    for channel in expired_channels(&guild_channel, bot_id, retention, SystemTime::now()) {
+        tracing::info!(
+            "Deleting inactive Channel '{}' ({:?})",
+            channel.name,
+            channel.id
+        );
+
+        let deletion = dry_run::mutate("Deleting inactive Channel", (), async {
+            channel.id.delete(ctx).await.map(|_| ())
+        });
+        if let Err(e) = deletion.await {
+            tracing::error!("Deleting inactive Channel: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn guild_channel(
+        id: u64,
+        category: Option<u64>,
+        bot_overwrite: bool,
+        last_message: Option<u64>,
+    ) -> (ChannelId, GuildChannel) {
+        let kind = if category.is_some() { 0 } else { 4 };
+        let overwrites = if bot_overwrite {
+            serde_json::json!([{ "id": "9", "type": 1, "allow": "1024", "deny": "0" }])
+        } else {
+            serde_json::json!([])
+        };
+        let channel = serde_json::from_value(serde_json::json!({
+            "id": id.to_string(),
+            "guild_id": "1",
+            "type": kind,
+            "name": if category.is_some() { "werewolf" } else { "W-Inactive" },
+            "permission_overwrites": overwrites,
+            "position": 0,
+            "parent_id": category.map(|c| c.to_string()),
+            "last_message_id": last_message.map(|m| m.to_string()),
+        }))
+        .unwrap();
+
+        (ChannelId(id), channel)
+    }
+
+    /// An ID, whose Timestamp is the given Number of Days after the Discord-Epoch
+    fn id_at(days: u64) -> u64 {
+        (days * DAY.as_millis() as u64) << 22
+    }
+
+    #[test]
+    fn snowflake_timestamp() {
+        assert_eq!(
+            SystemTime::UNIX_EPOCH + Duration::from_millis(1_462_015_105_796),
+            snowflake_time(175928847299117063)
+        );
+    }
+
+    #[test]
+    fn only_unused_bot_channels() {
+        let category = id_at(0) + 1;
+        let guild_channel: HashMap<ChannelId, GuildChannel> = vec![
+            guild_channel(category, None, false, None),
+            // Unused for a long time
+            guild_channel(id_at(1), Some(category), true, None),
+            // Recently used
+            guild_channel(id_at(2), Some(category), true, Some(id_at(9))),
+            // Not set up by the Bot
+            guild_channel(id_at(3), Some(category), false, None),
+            // Not in the Inactive-Category
+            guild_channel(id_at(4), Some(13), true, None),
+        ]
+        .into_iter()
+        .collect();
+
+        let now = snowflake_time(id_at(10));
+        let expired: Vec<ChannelId> = expired_channels(&guild_channel, UserId(9), 5 * DAY, now)
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+
+        assert_eq!(vec![ChannelId(id_at(1))], expired);
+    }
+
+    #[test]
+    fn no_inactive_category() {
+        let guild_channel: HashMap<ChannelId, GuildChannel> =
+            vec![guild_channel(id_at(1), Some(13), true, None)]
+                .into_iter()
+                .collect();
+
+        let now = snowflake_time(id_at(10));
+        assert!(expired_channels(&guild_channel, UserId(9), DAY, now).is_empty());
+    }
+}
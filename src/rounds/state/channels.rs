@@ -1,17 +1,23 @@
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+};
 
 use serenity::{
     http::Http,
     model::{
         channel::{ChannelType, GuildChannel, PermissionOverwrite, PermissionOverwriteType},
-        id::{ChannelId, GuildId, UserId},
+        id::{ChannelId, GuildId, RoleId, UserId},
         Permissions,
     },
 };
 
-use crate::roles::WereWolfRoleConfig;
+use crate::{
+    roles::{PlayerLink, WereWolfRoleConfig},
+    util::{concurrency, dry_run, pacing::Pacer},
+};
 
-use super::BotContext;
+use super::registry::ChannelRegistry;
 
 #[derive(Debug)]
 pub enum GetChannelError {
@@ -19,6 +25,78 @@ pub enum GetChannelError {
     CreatingChannel(serenity::Error),
 }
 
+impl Display for GetChannelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UpdatingPermissions => write!(f, "Updating Permissions of existing Channel"),
+            Self::CreatingChannel(e) => write!(f, "Creating Channel ({})", e),
+        }
+    }
+}
+
+/// Keeps track of all the Channels that were newly created while setting up a Round, so that
+/// they can be removed again if the Setup fails midway
+#[derive(Debug, Default)]
+pub struct CreatedChannels {
+    ids: Vec<ChannelId>,
+}
+
+impl CreatedChannels {
+    pub fn new() -> Self {
+        Self { ids: Vec::new() }
+    }
+
+    fn push(&mut self, id: ChannelId) {
+        self.ids.push(id);
+    }
+
+    /// The Channels in the Order in which they should be deleted, which is the reverse of the
+    /// Order they were created in, so that Channels are removed before their Category
+    fn rollback_order(&self) -> impl Iterator<Item = &ChannelId> {
+        self.ids.iter().rev()
+    }
+
+    /// Deletes all the tracked Channels again, Channels that were only reused are not affected
+    pub async fn rollback(self, ctx: &Http) {
+        for id in self.rollback_order() {
+            tracing::info!("Rolling back created Channel {:?}", id);
+
+            let deletion = dry_run::mutate("Deleting Channel", (), async {
+                id.delete(ctx).await.map(|_| ())
+            });
+            if let Err(e) = deletion.await {
+                tracing::error!("Deleting Channel during Rollback: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Normalizes the Name of a Channel the same way Discord does for Text-Channels, which are
+/// always lowercase and use '-' instead of Whitespace
+fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .map(|part| part.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Whether the existing Name refers to the same Channel as the given Name, once both are
+/// normalized
+fn names_match(existing: &str, name: &str) -> bool {
+    normalize_name(existing) == normalize_name(name)
+}
+
+/// Finds the Text-Channel with the given Name
+fn find_channel(name: &str, guild_channel: &HashMap<ChannelId, GuildChannel>) -> Option<ChannelId> {
+    guild_channel
+        .iter()
+        .find(|(_, channel)| match channel.kind {
+            ChannelType::Text => names_match(&channel.name, name),
+            _ => false,
+        })
+        .map(|(id, _)| *id)
+}
+
 /// Attempts to get a Channel from a Guild, by either reusing an already
 /// existing one or creating a new one.
 /// Either way the given Permissions are applied to the Channel.
@@ -28,31 +106,39 @@ async fn get_channel(
     guild_id: &GuildId,
     guild_channel: &HashMap<ChannelId, GuildChannel>,
     default_permissions: &[PermissionOverwrite],
+    created: &mut CreatedChannels,
 ) -> Result<ChannelId, GetChannelError> {
-    let guild_channel_id_result = guild_channel
-        .iter()
-        .find(|(_, channel)| channel.name == channel_name);
-    let id = match guild_channel_id_result {
-        Some((id, _)) => {
+    let id = match find_channel(channel_name, guild_channel) {
+        Some(id) => {
             // Deny everyone access to the channel
+            let mut pacer = Pacer::channel_operations();
             for permission in default_permissions.iter() {
-                id.create_permission(ctx, permission)
-                    .await
-                    .map_err(|_| GetChannelError::UpdatingPermissions)?;
+                pacer.pace().await;
+                dry_run::mutate("Updating Channel-Permissions", (), async {
+                    id.create_permission(ctx, permission).await
+                })
+                .await
+                .map_err(|_| GetChannelError::UpdatingPermissions)?;
             }
 
-            *id
+            id
         }
         None => {
-            guild_id
-                .create_channel(ctx, |c| {
-                    c.name(channel_name)
-                        .kind(ChannelType::Text)
-                        .permissions(default_permissions.to_vec())
-                })
-                .await
-                .map_err(GetChannelError::CreatingChannel)?
-                .id
+            let id = dry_run::mutate("Creating Channel", dry_run::placeholder_channel(), async {
+                guild_id
+                    .create_channel(ctx, |c| {
+                        c.name(normalize_name(channel_name))
+                            .kind(ChannelType::Text)
+                            .permissions(default_permissions.to_vec())
+                    })
+                    .await
+                    .map(|c| c.id)
+            })
+            .await
+            .map_err(GetChannelError::CreatingChannel)?;
+            created.push(id);
+
+            id
         }
     };
     Ok(id)
@@ -66,33 +152,58 @@ fn channel_access_permissions(user: UserId) -> PermissionOverwrite {
     }
 }
 
+/// Allows the given User to read, but not write in, whatever this is applied to
+fn channel_read_permissions(user: UserId) -> PermissionOverwrite {
+    PermissionOverwrite {
+        allow: Permissions::READ_MESSAGES,
+        deny: Permissions::SEND_MESSAGES,
+        kind: PermissionOverwriteType::Member(user),
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum GetCategoryError {
     CreatingCategory,
 }
 
+/// Finds the Category with the given Name, ignoring the Case of the Name as the Categories could
+/// also have been created manually with a different Case
+fn find_category(
+    name: &str,
+    guild_channel: &HashMap<ChannelId, GuildChannel>,
+) -> Option<ChannelId> {
+    guild_channel
+        .iter()
+        .find(|(_, channel)| match channel.kind {
+            ChannelType::Category => names_match(&channel.name, name),
+            _ => false,
+        })
+        .map(|(id, _)| *id)
+}
+
 /// Gets or creates a Category with the given Name
 async fn get_category(
     name: &str,
     ctx_http: &Http,
     guild: &GuildId,
     guild_channel: &HashMap<ChannelId, GuildChannel>,
+    created: &mut CreatedChannels,
 ) -> Result<ChannelId, GetCategoryError> {
-    let guild_channel_id_result = guild_channel
-        .iter()
-        .find(|(_, channel)| match channel.kind {
-            ChannelType::Category => channel.name == name,
-            _ => false,
-        });
-
-    let id = match guild_channel_id_result {
-        Some((id, _)) => *id,
+    let id = match find_category(name, guild_channel) {
+        Some(id) => id,
         None => {
-            let category = guild
-                .create_channel(ctx_http, |c| c.name(name).kind(ChannelType::Category))
+            let category_id =
+                dry_run::mutate("Creating Category", dry_run::placeholder_channel(), async {
+                    guild
+                        .create_channel(ctx_http, |c| c.name(name).kind(ChannelType::Category))
+                        .await
+                        .map(|c| c.id)
+                })
                 .await
                 .map_err(|_| GetCategoryError::CreatingCategory)?;
-            category.id
+            created.push(category_id);
+
+            category_id
         }
     };
     Ok(id)
@@ -105,25 +216,29 @@ pub async fn setup_active_category(
     ctx: &Http,
     guild: &GuildId,
     guild_channel: &HashMap<ChannelId, GuildChannel>,
+    created: &mut CreatedChannels,
 ) -> Result<ChannelId, GetCategoryError> {
-    get_category(
-        &ACTIVE_CATEGORY_NAME.to_lowercase(),
-        ctx,
-        guild,
-        guild_channel,
-    )
-    .await
+    get_category(ACTIVE_CATEGORY_NAME, ctx, guild, guild_channel, created).await
+}
+/// Finds the Category into which the Channels are moved once their Round is over, without
+/// creating it if it does not exist
+pub fn find_inactive_category(
+    guild_channel: &HashMap<ChannelId, GuildChannel>,
+) -> Option<ChannelId> {
+    find_category(INACTIVE_CATEGORY_NAME, guild_channel)
 }
+
 pub async fn setup_inactive_category(
     ctx: &Http,
     guild: &GuildId,
     guild_channel: &HashMap<ChannelId, GuildChannel>,
 ) -> Result<ChannelId, GetCategoryError> {
     get_category(
-        &INACTIVE_CATEGORY_NAME.to_lowercase(),
+        INACTIVE_CATEGORY_NAME,
         ctx,
         guild,
         guild_channel,
+        &mut CreatedChannels::new(),
     )
     .await
 }
@@ -133,6 +248,22 @@ pub enum SetupChannelError {
     GetChannel(GetChannelError),
     MoveChannel,
     UpdatingChannelPermissions,
+    /// The Channels of the Guild could not be loaded
+    LoadingChannels,
+    /// The Category for the active Channels could not be set up
+    SettingUpCategory,
+}
+
+impl Display for SetupChannelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GetChannel(e) => write!(f, "Getting Channel: {}", e),
+            Self::MoveChannel => write!(f, "Moving Channel into Category"),
+            Self::UpdatingChannelPermissions => write!(f, "Updating Channel Permissions"),
+            Self::LoadingChannels => write!(f, "Loading Guild Channels"),
+            Self::SettingUpCategory => write!(f, "Setting up Category for active Channels"),
+        }
+    }
 }
 
 impl From<GetChannelError> for SetupChannelError {
@@ -141,6 +272,7 @@ impl From<GetChannelError> for SetupChannelError {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn setup_channel<I>(
     name: &str,
     guild: &GuildId,
@@ -149,37 +281,97 @@ async fn setup_channel<I>(
     default_permissions: &[PermissionOverwrite],
     extra_users: I,
     ctx: &Http,
+    created: &mut CreatedChannels,
 ) -> Result<ChannelId, SetupChannelError>
 where
     I: Iterator<Item = UserId>,
 {
-    let lowercase_name = name.to_lowercase();
-
     let channel_id = get_channel(
-        &lowercase_name,
+        name,
         ctx,
         guild,
         guild_channel,
         default_permissions,
+        created,
     )
     .await?;
 
-    channel_id
-        .edit(ctx, |c| c.category(category_id))
-        .await
-        .map_err(|_| SetupChannelError::MoveChannel)?;
+    dry_run::mutate("Moving Channel into Category", (), async {
+        channel_id
+            .edit(ctx, |c| c.category(category_id))
+            .await
+            .map(|_| ())
+    })
+    .await
+    .map_err(|_| SetupChannelError::MoveChannel)?;
 
+    let mut pacer = Pacer::channel_operations();
     for user in extra_users {
+        pacer.pace().await;
         let access_permissions = channel_access_permissions(user);
-        channel_id
-            .create_permission(ctx, &access_permissions)
-            .await
-            .map_err(|_| SetupChannelError::UpdatingChannelPermissions)?;
+        dry_run::mutate("Granting Channel-Access", (), async {
+            channel_id.create_permission(ctx, &access_permissions).await
+        })
+        .await
+        .map_err(|_| SetupChannelError::UpdatingChannelPermissions)?;
     }
 
     Ok(channel_id)
 }
 
+/// Sets up the Channels with the given Names, with at most `concurrency` of them being set up at
+/// the same time. Every created Channel is tracked, even if setting up another Channel failed
+#[allow(clippy::too_many_arguments)]
+async fn setup_channels(
+    names: Vec<String>,
+    guild: &GuildId,
+    guild_channel: &HashMap<ChannelId, GuildChannel>,
+    category_id: ChannelId,
+    default_permissions: &[PermissionOverwrite],
+    moderators: &BTreeSet<UserId>,
+    ctx: &Http,
+    concurrency: usize,
+    created: &mut CreatedChannels,
+) -> Result<Vec<(String, ChannelId)>, SetupChannelError> {
+    let results = concurrency::bounded(names, concurrency, |name| async move {
+        let mut channel_created = CreatedChannels::new();
+        let result = setup_channel(
+            &name,
+            guild,
+            guild_channel,
+            category_id,
+            default_permissions,
+            moderators.iter().copied(),
+            ctx,
+            &mut channel_created,
+        )
+        .await;
+
+        (name, channel_created, result)
+    })
+    .await;
+
+    let mut channels = Vec::new();
+    let mut first_error = None;
+    for (name, channel_created, result) in results {
+        created.ids.extend(channel_created.ids);
+
+        match result {
+            Ok(id) => channels.push((name, id)),
+            Err(e) => {
+                tracing::error!("Setting up Channel '{}': {}", name, e);
+                first_error.get_or_insert(e);
+            }
+        };
+    }
+
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(channels),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn setup_role_channels(
     roles: impl Iterator<Item = &WereWolfRoleConfig>,
     default_permissions: Vec<PermissionOverwrite>,
@@ -188,33 +380,77 @@ pub async fn setup_role_channels(
     category_id: &ChannelId,
     ctx: &Http,
     moderators: &BTreeSet<UserId>,
-) -> Result<BTreeMap<String, ChannelId>, SetupChannelError> {
-    let mut role_channel: BTreeMap<String, ChannelId> = BTreeMap::new();
+    concurrency: usize,
+    created: &mut CreatedChannels,
+) -> Result<ChannelRegistry, SetupChannelError> {
+    let mut names: Vec<String> = Vec::new();
+    for c_role_name in roles.flat_map(|r| r.channels()) {
+        if !names.contains(&c_role_name) {
+            names.push(c_role_name);
+        }
+    }
 
-    for role in roles {
-        for c_role_name in role.channels() {
-            if role_channel.contains_key(&c_role_name) {
-                continue;
-            }
+    let channels = setup_channels(
+        names,
+        &guild,
+        guild_channel,
+        *category_id,
+        &default_permissions,
+        moderators,
+        ctx,
+        concurrency,
+        created,
+    )
+    .await?;
 
-            let channel_name = c_role_name.to_lowercase();
+    let mut role_channel = ChannelRegistry::new();
+    for (name, channel_id) in channels {
+        role_channel.insert(name, channel_id);
+    }
 
-            let channel_id = setup_channel(
-                &channel_name,
-                &guild,
-                guild_channel,
-                *category_id,
-                &default_permissions,
-                moderators.iter().copied(),
-                ctx,
-            )
-            .await?;
+    Ok(role_channel)
+}
 
-            role_channel.insert(c_role_name, channel_id);
+/// Sets up the shared Channels of the linked Players, which are added to the Channels of the Roles
+/// so they are cleaned up together once the Round is over
+#[allow(clippy::too_many_arguments)]
+pub async fn setup_link_channels(
+    links: &[PlayerLink],
+    default_permissions: Vec<PermissionOverwrite>,
+    guild: GuildId,
+    guild_channel: &HashMap<ChannelId, GuildChannel>,
+    category_id: &ChannelId,
+    ctx: &Http,
+    moderators: &BTreeSet<UserId>,
+    concurrency: usize,
+    registry: &mut ChannelRegistry,
+    created: &mut CreatedChannels,
+) -> Result<(), SetupChannelError> {
+    let mut names: Vec<String> = Vec::new();
+    for link in links {
+        if !registry.contains(&link.channel) && !names.contains(&link.channel) {
+            names.push(link.channel.clone());
         }
     }
 
-    Ok(role_channel)
+    let channels = setup_channels(
+        names,
+        &guild,
+        guild_channel,
+        *category_id,
+        &default_permissions,
+        moderators,
+        ctx,
+        concurrency,
+        created,
+    )
+    .await?;
+
+    for (name, channel_id) in channels {
+        registry.insert(name, channel_id);
+    }
+
+    Ok(())
 }
 
 const MOD_CHANNEL_NAME: &str = "Moderator";
@@ -226,6 +462,7 @@ pub async fn setup_moderator_channel(
     category_id: &ChannelId,
     ctx: &Http,
     moderators: &BTreeSet<UserId>,
+    created: &mut CreatedChannels,
 ) -> Result<ChannelId, SetupChannelError> {
     setup_channel(
         MOD_CHANNEL_NAME,
@@ -235,6 +472,247 @@ pub async fn setup_moderator_channel(
         &default_permissions,
         moderators.iter().copied(),
         ctx,
+        created,
+    )
+    .await
+}
+
+/// Prefixed like the Categories of the Bot, so an existing Announcement-Channel of the Guild is
+/// never taken over for a Round
+const ANNOUNCEMENT_CHANNEL_NAME: &str = "W-Announcements";
+
+/// Sets up the Channel for Announcements, in which the Moderators can write and the Players can
+/// only read
+#[allow(clippy::too_many_arguments)]
+pub async fn setup_announcement_channel<'p, P>(
+    default_permissions: Vec<PermissionOverwrite>,
+    guild: GuildId,
+    guild_channel: &HashMap<ChannelId, GuildChannel>,
+    category_id: &ChannelId,
+    ctx: &Http,
+    moderators: &BTreeSet<UserId>,
+    players: P,
+    created: &mut CreatedChannels,
+) -> Result<ChannelId, SetupChannelError>
+where
+    P: Iterator<Item = &'p UserId>,
+{
+    setup_read_only_channel(
+        ANNOUNCEMENT_CHANNEL_NAME,
+        default_permissions,
+        guild,
+        guild_channel,
+        category_id,
+        ctx,
+        moderators,
+        players,
+        created,
     )
     .await
 }
+
+/// Sets up a Channel with the given Name, in which the Moderators can write and the Players can
+/// only read
+#[allow(clippy::too_many_arguments)]
+pub async fn setup_read_only_channel<'p, P>(
+    name: &str,
+    default_permissions: Vec<PermissionOverwrite>,
+    guild: GuildId,
+    guild_channel: &HashMap<ChannelId, GuildChannel>,
+    category_id: &ChannelId,
+    ctx: &Http,
+    moderators: &BTreeSet<UserId>,
+    players: P,
+    created: &mut CreatedChannels,
+) -> Result<ChannelId, SetupChannelError>
+where
+    P: Iterator<Item = &'p UserId>,
+{
+    let channel_id = setup_channel(
+        name,
+        &guild,
+        guild_channel,
+        *category_id,
+        &default_permissions,
+        moderators.iter().copied(),
+        ctx,
+        created,
+    )
+    .await?;
+
+    let mut pacer = Pacer::channel_operations();
+    for player in players.filter(|p| !moderators.contains(p)) {
+        pacer.pace().await;
+        let read_permissions = channel_read_permissions(*player);
+        dry_run::mutate("Granting Read-Access", (), async {
+            channel_id.create_permission(ctx, &read_permissions).await
+        })
+        .await
+        .map_err(|_| SetupChannelError::UpdatingChannelPermissions)?;
+    }
+
+    Ok(channel_id)
+}
+
+const GRAVEYARD_CHANNEL_NAME: &str = "Graveyard";
+
+/// Replaces the Overwrite for the Dead-Role in the given Permissions, so that dead Players can
+/// also write in the Channel
+fn graveyard_permissions(
+    default_permissions: Vec<PermissionOverwrite>,
+    dead_role: RoleId,
+) -> Vec<PermissionOverwrite> {
+    let dead_permissions = PermissionOverwrite {
+        allow: Permissions::READ_MESSAGES | Permissions::SEND_MESSAGES,
+        deny: Permissions { bits: 0 },
+        kind: PermissionOverwriteType::Role(dead_role),
+    };
+
+    default_permissions
+        .into_iter()
+        .filter(|p| p.kind != PermissionOverwriteType::Role(dead_role))
+        .chain(std::iter::once(dead_permissions))
+        .collect()
+}
+
+/// Sets up the Channel for the dead Players, which can be read and written to by everyone with
+/// the Dead-Role as well as the Moderators
+#[allow(clippy::too_many_arguments)]
+pub async fn setup_graveyard_channel(
+    default_permissions: Vec<PermissionOverwrite>,
+    dead_role: RoleId,
+    guild: GuildId,
+    guild_channel: &HashMap<ChannelId, GuildChannel>,
+    category_id: &ChannelId,
+    ctx: &Http,
+    moderators: &BTreeSet<UserId>,
+    created: &mut CreatedChannels,
+) -> Result<ChannelId, SetupChannelError> {
+    setup_channel(
+        GRAVEYARD_CHANNEL_NAME,
+        &guild,
+        guild_channel,
+        *category_id,
+        &graveyard_permissions(default_permissions, dead_role),
+        moderators.iter().copied(),
+        ctx,
+        created,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guild_channel(id: u64, kind: &str, name: &str) -> (ChannelId, GuildChannel) {
+        let kind = match kind {
+            "text" => 0,
+            "category" => 4,
+            _ => unreachable!(),
+        };
+        let channel = serde_json::from_value(serde_json::json!({
+            "id": id.to_string(),
+            "guild_id": "1",
+            "type": kind,
+            "name": name,
+            "permission_overwrites": [],
+            "position": 0,
+        }))
+        .unwrap();
+
+        (ChannelId(id), channel)
+    }
+
+    #[test]
+    fn category_mixed_case() {
+        let channels: HashMap<ChannelId, GuildChannel> = vec![
+            guild_channel(2, "text", "w-active"),
+            guild_channel(3, "category", "W-Active"),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            Some(ChannelId(3)),
+            find_category(&ACTIVE_CATEGORY_NAME.to_lowercase(), &channels)
+        );
+        assert_eq!(None, find_category("w-inactive", &channels));
+    }
+
+    #[test]
+    fn normalized_names() {
+        assert_eq!("moderator", normalize_name("Moderator"));
+        assert_eq!("big-bad-wolf", normalize_name(" Big  Bad\tWolf "));
+        assert_eq!("w-active", normalize_name("W-Active"));
+
+        assert!(names_match("w-active", ACTIVE_CATEGORY_NAME));
+        assert!(names_match("Little Girl", "little-girl"));
+        assert!(!names_match("werewolf", "werewolves"));
+    }
+
+    #[test]
+    fn channel_normalized() {
+        let channels: HashMap<ChannelId, GuildChannel> = vec![
+            guild_channel(2, "category", "moderator"),
+            guild_channel(3, "text", "moderator"),
+            guild_channel(4, "text", "little-girl"),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(Some(ChannelId(3)), find_channel("Moderator", &channels));
+        assert_eq!(Some(ChannelId(4)), find_channel("Little Girl", &channels));
+        assert_eq!(None, find_channel("Graveyard", &channels));
+    }
+
+    #[test]
+    fn rollback_reverse_order() {
+        let mut created = CreatedChannels::new();
+        created.push(ChannelId(1));
+        created.push(ChannelId(2));
+        created.push(ChannelId(3));
+
+        let result: Vec<ChannelId> = created.rollback_order().copied().collect();
+
+        assert_eq!(vec![ChannelId(3), ChannelId(2), ChannelId(1)], result);
+    }
+
+    #[test]
+    fn announcement_read_only() {
+        let permissions = channel_read_permissions(UserId(13));
+
+        assert!(permissions.allow.contains(Permissions::READ_MESSAGES));
+        assert!(permissions.deny.contains(Permissions::SEND_MESSAGES));
+        assert_eq!(
+            PermissionOverwriteType::Member(UserId(13)),
+            permissions.kind
+        );
+    }
+
+    #[test]
+    fn graveyard_dead_can_write() {
+        let defaults = vec![
+            PermissionOverwrite {
+                allow: Permissions { bits: 0 },
+                deny: Permissions::READ_MESSAGES,
+                kind: PermissionOverwriteType::Role(RoleId(1)),
+            },
+            PermissionOverwrite {
+                allow: Permissions::READ_MESSAGES,
+                deny: Permissions { bits: 0 },
+                kind: PermissionOverwriteType::Role(RoleId(2)),
+            },
+        ];
+
+        let result = graveyard_permissions(defaults, RoleId(2));
+
+        assert_eq!(2, result.len());
+        assert_eq!(PermissionOverwriteType::Role(RoleId(1)), result[0].kind);
+        assert_eq!(PermissionOverwriteType::Role(RoleId(2)), result[1].kind);
+        assert_eq!(
+            Permissions::READ_MESSAGES | Permissions::SEND_MESSAGES,
+            result[1].allow
+        );
+    }
+}
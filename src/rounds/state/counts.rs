@@ -0,0 +1,88 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{ChannelId, GuildId, MessageId, UserId};
+
+use crate::roles::WereWolfRoleConfig;
+
+use super::options::RoundOptions;
+
+/// A Role whose Count has not been entered yet, together with the Message asking for it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingRole {
+    pub role: WereWolfRoleConfig,
+    pub channel: ChannelId,
+    pub message: MessageId,
+}
+
+/// The State of a Round while the Counts of its Roles are being entered, which is stored so that
+/// the Round can continue where it left off after the Bot restarted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingCounts {
+    pub guild: GuildId,
+    /// The Channel of the Message that controls the Round
+    pub channel: ChannelId,
+    /// The Message that controls the Round
+    pub message: MessageId,
+    pub mods: BTreeSet<UserId>,
+    pub bot_user: UserId,
+    pub options: RoundOptions,
+    pub players: Vec<UserId>,
+    /// The Roles whose Counts are already known
+    pub counted: Vec<(WereWolfRoleConfig, usize)>,
+    /// The Roles whose Counts are still missing
+    pub pending: Vec<PendingRole>,
+    /// The Number of Roles whose Count had to be entered by the Mods
+    pub counted_roles: usize,
+    /// Whether or not the Counts are entered using Direct Messages
+    pub via_dm: bool,
+}
+
+impl PendingCounts {
+    /// The Number of Player-Slots that are already taken by the known Counts, Roles that mask
+    /// another Role don't take up a Slot of their own
+    pub fn allocated_slots(&self) -> usize {
+        self.counted
+            .iter()
+            .filter(|(role, _)| !role.masks_role())
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allocated_slots() {
+        let counts = PendingCounts {
+            guild: GuildId(1),
+            channel: ChannelId(2),
+            message: MessageId(3),
+            mods: BTreeSet::new(),
+            bot_user: UserId(4),
+            options: RoundOptions::default(),
+            players: (10..16).map(UserId).collect(),
+            counted: vec![
+                (
+                    WereWolfRoleConfig::new("Villager", "🧑", true, false, Vec::new()),
+                    3,
+                ),
+                (
+                    WereWolfRoleConfig::new("Seer", "🔮", false, false, Vec::new()),
+                    1,
+                ),
+                (
+                    WereWolfRoleConfig::new("Doppelganger", "🎭", false, true, Vec::new()),
+                    1,
+                ),
+            ],
+            pending: Vec::new(),
+            counted_roles: 2,
+            via_dm: false,
+        };
+
+        assert_eq!(4, counts.allocated_slots());
+    }
+}
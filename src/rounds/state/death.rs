@@ -0,0 +1,69 @@
+use serenity::{
+    http::Http,
+    model::{
+        channel::PermissionOverwriteType,
+        id::{ChannelId, UserId},
+    },
+};
+
+use crate::{roles::WereWolfRoleInstance, util::dry_run};
+
+/// Removes the Player-specific Permissions from the given Channels, after which the Player only
+/// has the Permissions granted to them through their Roles, like the Dead-Role
+pub async fn clear_permissions<'c, I>(ctx: &Http, user: UserId, channels: I)
+where
+    I: Iterator<Item = &'c ChannelId>,
+{
+    for channel in channels {
+        let removal = dry_run::mutate("Removing Permissions of dead Player", (), async {
+            channel
+                .delete_permission(ctx, PermissionOverwriteType::Member(user))
+                .await
+        });
+        if let Err(e) = removal.await {
+            tracing::error!("Removing Permissions of dead Player: {:?}", e);
+        }
+    }
+}
+
+/// The Message posted in the Graveyard to reveal the Role of a Player that died
+pub fn reveal_content(user: UserId, role: &WereWolfRoleInstance) -> String {
+    let mut result = format!("<@{}> has died, they were the {}", user.0, role);
+
+    if let Some(team) = role.team() {
+        result.push_str(&format!(" - Team: {}", team));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::roles::WereWolfRoleConfig;
+
+    use super::*;
+
+    #[test]
+    fn reveal_with_team() {
+        let role = WereWolfRoleConfig::new("Seer", "", false, false, Vec::new())
+            .with_team("Village")
+            .to_instance(&mut || unreachable!());
+
+        assert_eq!(
+            "<@13> has died, they were the Seer - Team: Village",
+            reveal_content(UserId(13), &role)
+        );
+    }
+
+    #[test]
+    fn reveal_masked() {
+        let masked = WereWolfRoleConfig::new("Werewolf", "", false, false, Vec::new());
+        let role = WereWolfRoleConfig::new("Thief", "", false, true, Vec::new())
+            .to_instance(&mut || masked.clone());
+
+        assert_eq!(
+            "<@13> has died, they were the Thief (Werewolf)",
+            reveal_content(UserId(13), &role)
+        );
+    }
+}
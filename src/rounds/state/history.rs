@@ -0,0 +1,240 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use serenity::model::id::UserId;
+
+use crate::roles::{Distribution, WereWolfRoleConfig};
+
+/// The Outcome of a single Player in a finished Round
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerRecord {
+    pub user: UserId,
+    /// The Role of the Player, including the masked Role if there is one
+    pub role: String,
+    pub team: Option<String>,
+    pub died: bool,
+}
+
+/// The Record of a finished Round, which is kept for the History of the Guild
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoundRecord {
+    /// When the Round was started, in Seconds since the Unix-Epoch
+    pub started_at: u64,
+    /// When the Round ended, in Seconds since the Unix-Epoch
+    pub ended_at: u64,
+    pub players: Vec<PlayerRecord>,
+    /// The Team that won the Round, if it could be detected
+    pub winner: Option<String>,
+    /// The Number of Players for each Role of the Round, which allows the Round to be repeated
+    #[serde(default)]
+    pub roles: BTreeMap<String, usize>,
+    /// The Label the Mods gave the Round, if any
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// The Seconds since the Unix-Epoch for the given Time
+pub fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Detects the winning Team, which is the case if all the surviving Players belong to the same
+/// Team
+fn detect_winner(players: &[PlayerRecord]) -> Option<String> {
+    let mut survivors = players.iter().filter(|p| !p.died);
+
+    let team = survivors.next()?.team.clone()?;
+    if survivors.all(|p| p.team.as_deref() == Some(team.as_str())) {
+        Some(team)
+    } else {
+        None
+    }
+}
+
+impl RoundRecord {
+    /// Creates the Record for a Round that ran from `started_at` until `ended_at`
+    pub fn new(
+        players: &Distribution,
+        dead: &BTreeSet<UserId>,
+        started_at: SystemTime,
+        ended_at: SystemTime,
+    ) -> Self {
+        // A masking Role also takes up one of the Roles it can mask
+        let mut roles = BTreeMap::new();
+        for (_, role) in players.iter() {
+            for name in std::iter::once(role)
+                .chain(role.masked_role())
+                .map(|r| r.name())
+            {
+                *roles.entry(name.to_string()).or_insert(0) += 1;
+            }
+        }
+
+        let players: Vec<PlayerRecord> = players
+            .iter()
+            .map(|(user, role)| PlayerRecord {
+                user: *user,
+                role: role.to_string(),
+                team: role.team().map(|t| t.to_string()),
+                died: dead.contains(user),
+            })
+            .collect();
+        let winner = detect_winner(&players);
+
+        Self {
+            started_at: unix_seconds(started_at),
+            ended_at: unix_seconds(ended_at),
+            players,
+            winner,
+            roles,
+            label: None,
+        }
+    }
+
+    /// Sets the Label of the Round
+    pub fn with_label(mut self, label: Option<String>) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// The Roles of the Round with their Counts, skipping the Roles that no longer exist
+    pub fn resolve_roles(
+        &self,
+        all_roles: &[WereWolfRoleConfig],
+    ) -> BTreeMap<WereWolfRoleConfig, usize> {
+        all_roles
+            .iter()
+            .filter_map(|role| self.roles.get(role.name()).map(|c| (role.clone(), *c)))
+            .collect()
+    }
+
+    /// How long the Round lasted
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.ended_at.saturating_sub(self.started_at))
+    }
+}
+
+impl Display for RoundRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(label) = &self.label {
+            write!(f, "**{}**: ", label)?;
+        }
+        write!(
+            f,
+            "<t:{}:f> - {} Players",
+            self.started_at,
+            self.players.len()
+        )?;
+        match &self.winner {
+            Some(team) => write!(f, ", won by {}", team),
+            None => write!(f, ", no Winner detected"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::roles::WereWolfRoleConfig;
+
+    use super::*;
+
+    fn test_distribution() -> Distribution {
+        let villager =
+            WereWolfRoleConfig::new("Villager", "", true, false, Vec::new()).with_team("Village");
+        let wolf =
+            WereWolfRoleConfig::new("Werewolf", "", false, false, Vec::new()).with_team("Wolves");
+
+        let mut players = BTreeMap::new();
+        players.insert(UserId(1), villager.to_instance(&mut || unreachable!()));
+        players.insert(UserId(2), villager.to_instance(&mut || unreachable!()));
+        players.insert(UserId(3), wolf.to_instance(&mut || unreachable!()));
+        Distribution::new(players)
+    }
+
+    #[test]
+    fn winner_detected() {
+        let dead: BTreeSet<_> = vec![UserId(3)].into_iter().collect();
+        let start = UNIX_EPOCH + Duration::from_secs(100);
+
+        let record = RoundRecord::new(
+            &test_distribution(),
+            &dead,
+            start,
+            start + Duration::from_secs(60),
+        );
+
+        assert_eq!(Some("Village".to_string()), record.winner);
+        assert_eq!(Duration::from_secs(60), record.duration());
+        assert!(record.players[2].died);
+        assert_eq!("Werewolf", record.players[2].role);
+    }
+
+    #[test]
+    fn role_counts() {
+        let mut distribution: BTreeMap<_, _> = test_distribution().into();
+        let villager = WereWolfRoleConfig::new("Villager", "", true, false, Vec::new());
+        let doppel = WereWolfRoleConfig::new("Doppelganger", "", false, true, Vec::new());
+        distribution.insert(UserId(4), doppel.to_instance(&mut || villager.clone()));
+
+        let record = RoundRecord::new(
+            &Distribution::new(distribution),
+            &BTreeSet::new(),
+            UNIX_EPOCH,
+            UNIX_EPOCH,
+        );
+
+        let expected: BTreeMap<String, usize> = vec![
+            ("Doppelganger".to_string(), 1),
+            ("Villager".to_string(), 3),
+            ("Werewolf".to_string(), 1),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(expected, record.roles);
+
+        // Roles that no longer exist are skipped
+        let resolved = record.resolve_roles(&[villager.clone(), doppel.clone()]);
+        let expected: BTreeMap<_, _> = vec![(villager, 3), (doppel, 1)].into_iter().collect();
+        assert_eq!(expected, resolved);
+    }
+
+    #[test]
+    fn no_winner() {
+        let record = RoundRecord::new(
+            &test_distribution(),
+            &BTreeSet::new(),
+            UNIX_EPOCH,
+            UNIX_EPOCH,
+        );
+
+        assert_eq!(None, record.winner);
+        assert_eq!(
+            "<t:0:f> - 3 Players, no Winner detected",
+            record.to_string()
+        );
+    }
+
+    #[test]
+    fn labeled_round() {
+        let record = RoundRecord::new(
+            &test_distribution(),
+            &BTreeSet::new(),
+            UNIX_EPOCH,
+            UNIX_EPOCH,
+        )
+        .with_label(Some("Semifinal 2".to_string()));
+
+        assert_eq!(
+            "**Semifinal 2**: <t:0:f> - 3 Players, no Winner detected",
+            record.to_string()
+        );
+    }
+}
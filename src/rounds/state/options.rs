@@ -0,0 +1,239 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use serenity::model::id::UserId;
+
+/// The maximum Length of the Label of a Round, so it still fits into the Status-Messages
+pub const MAX_LABEL_LENGTH: usize = 100;
+
+/// The Options for a single Round, which are given as Arguments to the `werewolf` Command
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoundOptions {
+    /// Roles that are assigned to specific Players instead of being randomly distributed, maps
+    /// the Player to the Name of the Role
+    pub pinned: BTreeMap<UserId, String>,
+    /// Whether or not dead Players lose all access to the Channels of the Round, instead of
+    /// being able to read them as Spectators
+    pub hide_from_dead: bool,
+    /// Whether or not everyone can read the Channels of the Roles, while only the Players of
+    /// each Role can write in them
+    pub public_channels: bool,
+    /// Whether the Roles and their Counts of the last Round are used again, which is set by the
+    /// `repeat` Command instead of an Argument
+    pub repeat: bool,
+    /// The Label of the Round, like `Semifinal 2`, which is shown in its Status-Messages and
+    /// stored in the Round-History
+    pub label: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseOptionsError {
+    /// The Option is not known
+    UnknownOption(String),
+    /// The same Player was assigned a Role more than once
+    DuplicatePin(UserId),
+    /// The Label is longer than [`MAX_LABEL_LENGTH`]
+    LabelTooLong { length: usize },
+}
+
+impl Display for ParseOptionsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownOption(raw) => write!(f, "Unknown Option '{}'", raw),
+            Self::DuplicatePin(user) => {
+                write!(f, "<@{}> has been assigned more than one Role", user.0)
+            }
+            Self::LabelTooLong { length } => write!(
+                f,
+                "The Label is {} Characters long, but at most {} are allowed",
+                length, MAX_LABEL_LENGTH
+            ),
+        }
+    }
+}
+
+impl RoundOptions {
+    /// Parses the Options from the given Arguments
+    ///
+    /// # Supported Options
+    /// * `@Player=Role`: Always assigns the Role to the mentioned Player
+    /// * `spectators=on|off`: Whether dead Players can still read the Channels of the Round
+    /// * `channels=public|private`: Whether everyone can read the Channels of the Roles
+    /// * `label=Name`: The Label of the Round, which has to be quoted if it contains Spaces
+    pub fn parse<'a, I>(args: I) -> Result<Self, ParseOptionsError>
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        let mut result = Self::default();
+
+        for arg in args {
+            let (key, value) = match arg.split_once('=') {
+                Some(parts) => parts,
+                None => return Err(ParseOptionsError::UnknownOption(arg.to_string())),
+            };
+
+            if key == "spectators" {
+                result.hide_from_dead = match value {
+                    "on" => false,
+                    "off" => true,
+                    _ => return Err(ParseOptionsError::UnknownOption(arg.to_string())),
+                };
+                continue;
+            }
+            if key == "label" {
+                let label = value.trim();
+                if label.is_empty() {
+                    return Err(ParseOptionsError::UnknownOption(arg.to_string()));
+                }
+                let length = label.chars().count();
+                if length > MAX_LABEL_LENGTH {
+                    return Err(ParseOptionsError::LabelTooLong { length });
+                }
+                result.label = Some(label.to_string());
+                continue;
+            }
+            if key == "channels" {
+                result.public_channels = match value {
+                    "public" => true,
+                    "private" => false,
+                    _ => return Err(ParseOptionsError::UnknownOption(arg.to_string())),
+                };
+                continue;
+            }
+
+            match serenity::utils::parse_username(key) {
+                Some(user) if !value.is_empty() => {
+                    let user = UserId(user);
+                    if result.pinned.insert(user, value.to_string()).is_some() {
+                        return Err(ParseOptionsError::DuplicatePin(user));
+                    }
+                }
+                _ => return Err(ParseOptionsError::UnknownOption(arg.to_string())),
+            };
+        }
+
+        Ok(result)
+    }
+
+    /// Puts the Label of the Round, if it has one, in front of the Content of a Status-Message
+    pub fn labeled<C>(&self, content: C) -> String
+    where
+        C: Display,
+    {
+        match &self.label {
+            Some(label) => format!("**{}**: {}", label, content),
+            None => content.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_empty() {
+        assert_eq!(
+            Ok(RoundOptions::default()),
+            RoundOptions::parse(std::iter::empty())
+        );
+    }
+
+    #[test]
+    fn parse_pins() {
+        let args = vec!["<@13>=Seer", "<@!14>=Village Idiot"];
+
+        let mut expected = RoundOptions::default();
+        expected.pinned.insert(UserId(13), "Seer".to_string());
+        expected
+            .pinned
+            .insert(UserId(14), "Village Idiot".to_string());
+
+        assert_eq!(Ok(expected), RoundOptions::parse(args.into_iter()));
+    }
+
+    #[test]
+    fn parse_spectators() {
+        let hidden = RoundOptions::parse(vec!["spectators=off"].into_iter()).unwrap();
+        assert!(hidden.hide_from_dead);
+
+        let visible = RoundOptions::parse(vec!["spectators=on"].into_iter()).unwrap();
+        assert!(!visible.hide_from_dead);
+
+        assert_eq!(
+            Err(ParseOptionsError::UnknownOption(
+                "spectators=maybe".to_string()
+            )),
+            RoundOptions::parse(vec!["spectators=maybe"].into_iter())
+        );
+    }
+
+    #[test]
+    fn parse_channels() {
+        let public = RoundOptions::parse(vec!["channels=public"].into_iter()).unwrap();
+        assert!(public.public_channels);
+
+        let private = RoundOptions::parse(vec!["channels=private"].into_iter()).unwrap();
+        assert!(!private.public_channels);
+
+        assert_eq!(
+            Err(ParseOptionsError::UnknownOption(
+                "channels=open".to_string()
+            )),
+            RoundOptions::parse(vec!["channels=open"].into_iter())
+        );
+    }
+
+    #[test]
+    fn parse_label() {
+        let labeled = RoundOptions::parse(vec!["label=Semifinal 2"].into_iter()).unwrap();
+        assert_eq!(Some("Semifinal 2".to_string()), labeled.label);
+        assert_eq!(
+            "**Semifinal 2**: Round is over",
+            labeled.labeled("Round is over")
+        );
+
+        let unlabeled = RoundOptions::default();
+        assert_eq!("Round is over", unlabeled.labeled("Round is over"));
+
+        assert_eq!(
+            Err(ParseOptionsError::UnknownOption("label= ".to_string())),
+            RoundOptions::parse(vec!["label= "].into_iter())
+        );
+
+        let longest = format!("label={}", "ä".repeat(MAX_LABEL_LENGTH));
+        assert!(RoundOptions::parse(vec![longest.as_str()].into_iter()).is_ok());
+        let too_long = format!("label={}", "a".repeat(MAX_LABEL_LENGTH + 1));
+        assert_eq!(
+            Err(ParseOptionsError::LabelTooLong { length: 101 }),
+            RoundOptions::parse(vec![too_long.as_str()].into_iter())
+        );
+    }
+
+    #[test]
+    fn parse_duplicate_pin() {
+        let args = vec!["<@13>=Seer", "<@13>=Werewolf"];
+
+        assert_eq!(
+            Err(ParseOptionsError::DuplicatePin(UserId(13))),
+            RoundOptions::parse(args.into_iter())
+        );
+    }
+
+    #[test]
+    fn parse_unknown() {
+        assert_eq!(
+            Err(ParseOptionsError::UnknownOption("test".to_string())),
+            RoundOptions::parse(vec!["test"].into_iter())
+        );
+        assert_eq!(
+            Err(ParseOptionsError::UnknownOption("other=Seer".to_string())),
+            RoundOptions::parse(vec!["other=Seer"].into_iter())
+        );
+        assert_eq!(
+            Err(ParseOptionsError::UnknownOption("<@13>=".to_string())),
+            RoundOptions::parse(vec!["<@13>="].into_iter())
+        );
+    }
+}
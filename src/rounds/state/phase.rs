@@ -0,0 +1,104 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// The Phases that a running Round alternates between
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Phase {
+    Night,
+    Day,
+}
+
+impl Default for Phase {
+    /// A Round always starts with the first Night
+    fn default() -> Self {
+        Self::Night
+    }
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Night => write!(f, "Night"),
+            Self::Day => write!(f, "Day"),
+        }
+    }
+}
+
+impl Phase {
+    /// The Phase that follows after this one
+    pub fn next(self) -> Self {
+        match self {
+            Self::Night => Self::Day,
+            Self::Day => Self::Night,
+        }
+    }
+}
+
+/// Hands out the Lines of a Narration-Script one after another, on every Phase-Change of a Round
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Narrator {
+    lines: Vec<String>,
+    next: usize,
+}
+
+impl Narrator {
+    pub fn new(lines: Vec<String>) -> Self {
+        Self { lines, next: 0 }
+    }
+
+    /// The next Line of the Script, `None` once all the Lines have been used
+    pub fn next_line(&mut self) -> Option<&str> {
+        let line = self.lines.get(self.next)?;
+        self.next += 1;
+        Some(line.as_str())
+    }
+
+    /// The Number of Lines that have not been used yet
+    pub fn remaining(&self) -> usize {
+        self.lines.len() - self.next
+    }
+}
+
+/// The Announcement that is posted once the Round entered the given Phase, including the
+/// Narration-Line if there is one left
+pub fn announcement(phase: Phase, line: Option<&str>) -> String {
+    match line {
+        Some(l) => format!("**{} begins**\n{}", phase, l),
+        None => format!("**{} begins**", phase),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_alternate() {
+        let phase = Phase::default();
+        assert_eq!(Phase::Night, phase);
+        assert_eq!(Phase::Day, phase.next());
+        assert_eq!(Phase::Night, phase.next().next());
+    }
+
+    #[test]
+    fn narrator_runs_out() {
+        let mut narrator = Narrator::new(vec!["first".to_string(), "second".to_string()]);
+
+        assert_eq!(2, narrator.remaining());
+        assert_eq!(Some("first"), narrator.next_line());
+        assert_eq!(Some("second"), narrator.next_line());
+        assert_eq!(0, narrator.remaining());
+        assert_eq!(None, narrator.next_line());
+        assert_eq!(None, narrator.next_line());
+    }
+
+    #[test]
+    fn announcement_content() {
+        assert_eq!(
+            "**Day begins**\nThe Sun rises",
+            announcement(Phase::Day, Some("The Sun rises"))
+        );
+        assert_eq!("**Night begins**", announcement(Phase::Night, None));
+    }
+}
@@ -0,0 +1,189 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{ChannelId, UserId};
+
+use crate::roles::{PlayerLink, WereWolfRoleConfig, WereWolfRoleInstance};
+
+/// A Channel that a Role needs, but that was not created for the Round
+#[derive(Debug, PartialEq)]
+pub struct MissingChannel {
+    pub role: String,
+    pub channel: String,
+}
+
+impl Display for MissingChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The Round has no Channel '{}', which is needed by the '{}'-Role",
+            self.channel, self.role
+        )
+    }
+}
+
+/// The Channels that were created for the Roles of a Round, stored under the Names that the
+/// Roles use to refer to them
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ChannelRegistry {
+    channels: BTreeMap<String, ChannelId>,
+}
+
+impl ChannelRegistry {
+    pub fn new() -> Self {
+        Self {
+            channels: BTreeMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, name: String, channel: ChannelId) {
+        self.channels.insert(name, channel);
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.channels.contains_key(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// All the Channels together with their Names
+    pub fn iter(&self) -> impl Iterator<Item = (&str, ChannelId)> {
+        self.channels.iter().map(|(name, id)| (name.as_str(), *id))
+    }
+
+    /// The Channel stored under the given Name
+    pub fn get(&self, name: &str) -> Option<ChannelId> {
+        self.channels.get(name).copied()
+    }
+
+    /// The IDs of all the Channels
+    pub fn ids(&self) -> impl Iterator<Item = ChannelId> + '_ {
+        self.channels.values().copied()
+    }
+
+    fn lookup<I>(&self, role: &str, names: I) -> Result<Vec<ChannelId>, MissingChannel>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        names
+            .into_iter()
+            .map(|name| match self.channels.get(&name) {
+                Some(id) => Ok(*id),
+                None => Err(MissingChannel {
+                    role: role.to_string(),
+                    channel: name,
+                }),
+            })
+            .collect()
+    }
+
+    /// All the Channels a Player with the given Role has access to, including the Channels of
+    /// the masked Role and the extra Channels
+    pub fn channels_of(
+        &self,
+        role: &WereWolfRoleInstance,
+    ) -> Result<Vec<ChannelId>, MissingChannel> {
+        self.lookup(role.name(), role.channels())
+    }
+
+    /// All the Channels a Player, that is given the configured Role, would have access to
+    pub fn channels_of_config(
+        &self,
+        role: &WereWolfRoleConfig,
+    ) -> Result<Vec<ChannelId>, MissingChannel> {
+        self.lookup(role.name(), role.channels())
+    }
+
+    /// The Channels of the Links the Player is one of the linked Players of, Links whose Channel
+    /// was not set up are skipped
+    pub fn link_channels_of(&self, links: &[PlayerLink], player: UserId) -> Vec<ChannelId> {
+        links
+            .iter()
+            .filter(|link| link.players.contains(&player))
+            .filter_map(|link| self.get(&link.channel))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> ChannelRegistry {
+        let mut registry = ChannelRegistry::new();
+        registry.insert("Werewolf".to_string(), ChannelId(1));
+        registry.insert("Seer".to_string(), ChannelId(2));
+        registry
+    }
+
+    #[test]
+    fn channels_of_role() {
+        let wolf = WereWolfRoleConfig::new("Werewolf", "", false, false, vec!["Seer".to_string()]);
+        let instance = wolf.to_instance(&mut || unreachable!());
+
+        assert_eq!(
+            Ok(vec![ChannelId(1), ChannelId(2)]),
+            registry().channels_of(&instance)
+        );
+        assert_eq!(
+            Ok(vec![ChannelId(1), ChannelId(2)]),
+            registry().channels_of_config(&wolf)
+        );
+    }
+
+    #[test]
+    fn link_channels() {
+        let mut registry = registry();
+        registry.insert("Cupid-Link".to_string(), ChannelId(3));
+
+        let links = vec![
+            PlayerLink {
+                role: "Cupid".to_string(),
+                channel: "Cupid-Link".to_string(),
+                linked_by: UserId(10),
+                players: [UserId(11), UserId(12)],
+            },
+            PlayerLink {
+                role: "Cupid".to_string(),
+                channel: "Cupid-Link-2".to_string(),
+                linked_by: UserId(13),
+                players: [UserId(11), UserId(14)],
+            },
+        ];
+
+        assert_eq!(
+            vec![ChannelId(3)],
+            registry.link_channels_of(&links, UserId(11))
+        );
+        // The Player that linked the others has no Access to their Channel
+        assert!(registry.link_channels_of(&links, UserId(10)).is_empty());
+    }
+
+    #[test]
+    fn missing_channel() {
+        let witch = WereWolfRoleConfig::new("Witch", "", false, false, vec!["Seer".to_string()]);
+        let instance = witch.to_instance(&mut || unreachable!());
+
+        let expected = MissingChannel {
+            role: "Witch".to_string(),
+            channel: "Witch".to_string(),
+        };
+        assert_eq!(Err(expected), registry().channels_of(&instance));
+    }
+
+    #[test]
+    fn serialize() {
+        let registry = registry();
+
+        let raw = serde_json::to_string(&registry).unwrap();
+        assert_eq!(r#"{"Seer":2,"Werewolf":1}"#, raw);
+        let parsed: ChannelRegistry = serde_json::from_str(&raw).unwrap();
+
+        assert_eq!(registry, parsed);
+        assert_eq!(2, parsed.len());
+        assert!(parsed.contains("Seer"));
+    }
+}
@@ -0,0 +1,71 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Tracks how many of the Player-Slots of a Round have already been allocated to Roles, while
+/// the Number of Players per Role is still being entered
+#[derive(Debug)]
+pub struct RoleSlots {
+    players: usize,
+    allocated: AtomicUsize,
+}
+
+impl RoleSlots {
+    /// Creates the Slots for the given Number of Players, with `allocated` Slots already taken
+    pub fn new(players: usize, allocated: usize) -> Self {
+        Self {
+            players,
+            allocated: AtomicUsize::new(allocated),
+        }
+    }
+
+    /// Attempts to allocate `count` Slots
+    ///
+    /// # Returns
+    /// `Ok` if enough Slots were left and they are now allocated
+    /// `Err` with the Number of remaining Slots, if there are not enough Slots left
+    pub fn allocate(&self, count: usize) -> Result<(), usize> {
+        self.allocated
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |allocated| {
+                let total = allocated.checked_add(count)?;
+                if total > self.players {
+                    None
+                } else {
+                    Some(total)
+                }
+            })
+            .map(|_| ())
+            .map_err(|allocated| self.players.saturating_sub(allocated))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allocate_up_to_players() {
+        let slots = RoleSlots::new(5, 1);
+
+        assert_eq!(Ok(()), slots.allocate(3));
+        assert_eq!(Ok(()), slots.allocate(1));
+        assert_eq!(Ok(()), slots.allocate(0));
+        assert_eq!(Err(0), slots.allocate(1));
+    }
+
+    #[test]
+    fn allocate_overshoot() {
+        let slots = RoleSlots::new(5, 1);
+
+        // A rejected Allocation does not take up any Slots
+        assert_eq!(Err(4), slots.allocate(5));
+        assert_eq!(Ok(()), slots.allocate(4));
+        assert_eq!(Err(0), slots.allocate(1));
+    }
+
+    #[test]
+    fn already_overallocated() {
+        let slots = RoleSlots::new(2, 3);
+
+        assert_eq!(Err(0), slots.allocate(1));
+        assert_eq!(Err(0), slots.allocate(usize::MAX));
+    }
+}
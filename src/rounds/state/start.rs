@@ -1,5 +1,5 @@
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, HashMap},
     error::Error,
     fmt::Display,
 };
@@ -13,16 +13,24 @@ use serenity::{
     },
 };
 
-use crate::roles::{self, WereWolfRoleConfig, WereWolfRoleInstance};
+use crate::{
+    roles::{self, Distribution, WereWolfRoleConfig, WereWolfRoleInstance},
+    util::{
+        dry_run,
+        pacing::Pacer,
+        permissions::{self, CheckPermissionsError},
+    },
+};
 
 use super::{
     channels::{self, SetupChannelError},
-    RoleCounts,
+    options::RoundOptions,
+    registry::{ChannelRegistry, MissingChannel},
 };
 
 /// Generates the Permission-Settings to allow the given User to access
 /// whatever this is applied to
-fn channel_access_permissions(user: UserId) -> PermissionOverwrite {
+pub(crate) fn channel_access_permissions(user: UserId) -> PermissionOverwrite {
     PermissionOverwrite {
         allow: Permissions::READ_MESSAGES | Permissions::SEND_MESSAGES,
         deny: Permissions { bits: 0 },
@@ -30,43 +38,296 @@ fn channel_access_permissions(user: UserId) -> PermissionOverwrite {
     }
 }
 
+/// The Permissions that are applied to all the Channels of a Round, dead Players are only
+/// allowed to read the Channels if `dead_spectate` is set
+pub(crate) fn default_permissions(
+    bot_id: UserId,
+    everyone_role: RoleId,
+    dead_role_id: RoleId,
+    dead_spectate: bool,
+) -> Vec<PermissionOverwrite> {
+    let mut result = vec![
+        PermissionOverwrite {
+            allow: Permissions::READ_MESSAGES,
+            deny: Permissions { bits: 0 },
+            kind: PermissionOverwriteType::Member(bot_id),
+        },
+        PermissionOverwrite {
+            allow: Permissions { bits: 0 },
+            deny: Permissions::READ_MESSAGES,
+            kind: PermissionOverwriteType::Role(everyone_role),
+        },
+    ];
+
+    if dead_spectate {
+        result.push(PermissionOverwrite {
+            allow: Permissions::READ_MESSAGES,
+            deny: Permissions { bits: 0 },
+            kind: PermissionOverwriteType::Role(dead_role_id),
+        });
+    }
+
+    result
+}
+
+/// The Permissions for the Channels that dead Players only see if `dead_visible` is set, which
+/// are the default Permissions without the Read-Access for the Dead-Role
+fn spectated_permissions(
+    default_permissions: &[PermissionOverwrite],
+    dead_role_id: RoleId,
+    dead_visible: bool,
+) -> Vec<PermissionOverwrite> {
+    default_permissions
+        .iter()
+        .filter(|overwrite| {
+            dead_visible || overwrite.kind != PermissionOverwriteType::Role(dead_role_id)
+        })
+        .cloned()
+        .collect()
+}
+
+/// The Permissions for the Channels of the Roles, in public Rounds everyone can read the Channels
+/// but only the Players with access to the Channel can write in it
+fn role_channel_permissions(
+    default_permissions: &[PermissionOverwrite],
+    everyone_role: RoleId,
+    public: bool,
+) -> Vec<PermissionOverwrite> {
+    default_permissions
+        .iter()
+        .cloned()
+        .map(|overwrite| {
+            if !public || overwrite.kind != PermissionOverwriteType::Role(everyone_role) {
+                return overwrite;
+            }
+
+            PermissionOverwrite {
+                allow: Permissions::READ_MESSAGES,
+                deny: Permissions::SEND_MESSAGES,
+                kind: overwrite.kind,
+            }
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 pub enum StartError {
+    Permissions(CheckPermissionsError),
     LoadingChannels,
     SettingUpCategory,
     SettingUpChannels(SetupChannelError),
     SettingUpModeratorChannel,
+    SettingUpGraveyardChannel,
+    SettingUpAnnouncementChannel,
     DistributingRoles(roles::DistributeError),
     AssignRolePermissions,
+    /// A Role of a Player needs a Channel that was not set up for the Round
+    MissingChannel(MissingChannel),
 }
 
 impl Display for StartError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Self::Permissions(e) => write!(f, "Checking Permissions: {}", e),
             Self::LoadingChannels => write!(f, "Loading Guild Channels"),
             Self::SettingUpCategory => write!(f, "Setting up Category for active Roles"),
-            Self::SettingUpChannels(_) => write!(f, "Setting up Channels for active Roles"),
+            Self::SettingUpChannels(e) => {
+                write!(f, "Setting up Channels for active Roles: {}", e)
+            }
             Self::SettingUpModeratorChannel => write!(f, "Setting up Channel for the Moderators"),
-            Self::DistributingRoles(err) => match err {
-                roles::DistributeError::MismatchedCount {
-                    available_roles,
-                    player_count,
-                } => write!(f, "Distributing Roles to Players, configured {} Roles to assign but has {} Players", available_roles, player_count),
-                roles::DistributeError::TooManyMaskedRoles { masking_roles, normal_roles } => write!(f, "Distributing Roles to Players, configured {} Roles that mask/need another Role, but only configured {} 'normal' Roles", masking_roles, normal_roles),
-            },
+            Self::SettingUpGraveyardChannel => {
+                write!(f, "Setting up Channel for the dead Players")
+            }
+            Self::SettingUpAnnouncementChannel => {
+                write!(f, "Setting up Channel for the Announcements")
+            }
+            Self::DistributingRoles(err) => write!(f, "Distributing Roles to Players, {}", err),
             Self::AssignRolePermissions => {
                 write!(f, "Assigning Role-Permissions to Users and Channels")
             }
+            Self::MissingChannel(e) => write!(f, "Setting up Channels for active Roles: {}", e),
         }
     }
 }
 impl Error for StartError {}
 
+/// A Round that has been successfully set up
+pub struct StartedRound {
+    /// The Roles assigned to each Player
+    pub players: Distribution,
+    /// The Channel for the Moderators of the Round
+    pub moderator_channel: ChannelId,
+    /// The Channel for all the dead Players
+    pub graveyard_channel: ChannelId,
+    /// The Channels for the Roles
+    pub channels: ChannelRegistry,
+    /// The read-only Channel for the Players, if it is enabled for the Guild
+    pub announcement_channel: Option<ChannelId>,
+}
+
+/// Generates a Warning for all the extra Channels of the Roles that don't belong to any of the
+/// Roles in the Round, returns `None` if there are no such Channels
+fn unmatched_channels_warning<'r, I>(roles: I) -> Option<String>
+where
+    I: Iterator<Item = &'r WereWolfRoleConfig> + Clone,
+{
+    let unmatched: Vec<String> = roles
+        .clone()
+        .flat_map(|role| {
+            role.unmatched_extra_channels(roles.clone())
+                .into_iter()
+                .map(move |c| format!("'{}' (from '{}')", c, role.name()))
+        })
+        .collect();
+
+    if unmatched.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "Warning: The extra Channels {} don't belong to any Role in this Round and were created as standalone Channels",
+        unmatched.join(", ")
+    ))
+}
+
 pub struct StartSource {
     pub participants: Vec<UserId>,
     pub roles: BTreeMap<WereWolfRoleConfig, usize>,
     pub guild: GuildId,
     pub mods: BTreeSet<UserId>,
+    pub options: RoundOptions,
+    /// Whether the Players are mentioned in the Role-Overview for the Moderators
+    pub mention_players: bool,
+    /// Whether the Nicknames of the Players are used in the Role-Overview, if they are not
+    /// mentioned
+    pub use_nicknames: bool,
+    /// Whether a read-only Channel for Announcements to the Players is created
+    pub announcement_channel: bool,
+    /// Whether dead Players can read the Role-Channels and the Moderator-Channel, otherwise they
+    /// can only read the Graveyard- and the Announcement-Channel
+    pub dead_see_role_channels: bool,
+}
+
+/// The Maximum Number of Members that can be loaded with a single Request
+const MEMBER_PAGE_SIZE: u64 = 1000;
+
+/// Loads the Nicknames of the given Players, Players without a Nickname or that could not be
+/// loaded are missing from the Result
+pub(crate) async fn load_nicknames(
+    ctx: &Http,
+    guild: GuildId,
+    players: &BTreeSet<&UserId>,
+) -> HashMap<UserId, String> {
+    let mut result = HashMap::new();
+    let mut found = 0;
+
+    let mut after = None;
+    loop {
+        let members = match guild.members(ctx, Some(MEMBER_PAGE_SIZE), after).await {
+            Ok(m) => m,
+            Err(e) => {
+                tracing::error!("Loading Members for their Nicknames: {:?}", e);
+                break;
+            }
+        };
+
+        after = members.last().map(|m| m.user.id);
+        let page_size = members.len();
+        for member in members {
+            if !players.contains(&member.user.id) {
+                continue;
+            }
+
+            found += 1;
+            if let Some(nick) = member.nick {
+                result.insert(member.user.id, nick);
+            }
+        }
+
+        if found == players.len() || (page_size as u64) < MEMBER_PAGE_SIZE {
+            break;
+        }
+    }
+
+    result
+}
+
+/// The Permissions that give every Player access to the Channels of their Role, fails if any of
+/// these Channels was not set up for the Round
+fn player_grants(
+    participants: &Distribution,
+    channels: &ChannelRegistry,
+) -> Result<Vec<(ChannelId, PermissionOverwrite)>, StartError> {
+    let mut grants = Vec::new();
+    for (user_id, role) in participants.iter() {
+        let role_channels = channels
+            .channels_of(role)
+            .map_err(StartError::MissingChannel)?;
+
+        grants.extend(
+            role_channels
+                .into_iter()
+                .map(|c| (c, channel_access_permissions(*user_id))),
+        );
+    }
+
+    for link in participants.links() {
+        let channel = channels.get(&link.channel).ok_or_else(|| {
+            StartError::MissingChannel(MissingChannel {
+                role: link.role.clone(),
+                channel: link.channel.clone(),
+            })
+        })?;
+
+        grants.extend(
+            link.players
+                .iter()
+                .map(|p| (channel, channel_access_permissions(*p))),
+        );
+    }
+
+    Ok(grants)
+}
+
+/// The Intro-Messages of the Roles together with the Channel of the Role they are posted in,
+/// Roles without an Intro or whose Channel was not set up are skipped
+fn channel_intros<'r, I>(roles: I, channels: &ChannelRegistry) -> Vec<(ChannelId, &'r str)>
+where
+    I: IntoIterator<Item = &'r WereWolfRoleConfig>,
+{
+    roles
+        .into_iter()
+        .filter_map(|role| {
+            let intro = role.channel_intro()?;
+            let channel = channels.get(role.name())?;
+            Some((channel, intro))
+        })
+        .collect()
+}
+
+/// A single Line in the Role-Overview for the Moderators, the Emoji of the Role is shown in front
+/// of it if available
+fn role_overview_line(player: &str, role: &WereWolfRoleInstance, emoji: Option<&str>) -> String {
+    match emoji {
+        Some(emoji) if !emoji.is_empty() => format!("{}: {} {}\n", player, emoji, role),
+        _ => format!("{}: {}\n", player, role),
+    }
+}
+
+/// The Message that informs the Moderators about how the started Round works
+fn moderator_info(dead_role_name: &str) -> String {
+    format!(
+        "```
+The Round has now been started and all the required Setup has been completed
+
+If a Player has died, they should be given the '{}'-Role and the Bot will then update the Configuration \
+to allow that Player to see all Channels again and watch the Round from the 'Outside'.
+
+Once the Round is over, the Bot will automatically remove all the Round-Relevant Roles from the Players again \
+and reorganize the relevant Channels to prepare for the next Round.
+```",
+        dead_role_name
+    )
 }
 /*
 impl From<&RoundState<RoleCounts>> for StartSource {
@@ -91,39 +352,36 @@ pub async fn start<S>(
     dead_role_id: RoleId,
     everyone_role: RoleId,
     ctx: &Http,
-) -> Result<
-    (
-        BTreeMap<UserId, WereWolfRoleInstance>,
-        ChannelId,
-        BTreeMap<String, ChannelId>,
-    ),
-    StartError,
->
+) -> Result<StartedRound, StartError>
 where
     S: Into<StartSource>,
 {
     let source = raw_source.into();
 
-    let default_permissions: Vec<PermissionOverwrite> = vec![
-        PermissionOverwrite {
-            allow: Permissions::READ_MESSAGES,
-            deny: Permissions { bits: 0 },
-            kind: PermissionOverwriteType::Member(bot_id),
-        },
-        PermissionOverwrite {
-            allow: Permissions { bits: 0 },
-            deny: Permissions::READ_MESSAGES,
-            kind: PermissionOverwriteType::Role(everyone_role),
-        },
-        PermissionOverwrite {
-            allow: Permissions::READ_MESSAGES,
-            deny: Permissions { bits: 0 },
-            kind: PermissionOverwriteType::Role(dead_role_id),
-        },
-    ];
+    // Check the Permissions upfront to avoid leaving a half set up Round behind
+    permissions::check_permissions(source.guild, bot_id, ctx)
+        .await
+        .map_err(StartError::Permissions)?;
 
-    let participants = roles::distribute_roles(source.participants.clone(), source.roles.clone())
-        .map_err(StartError::DistributingRoles)?;
+    let default_permissions = default_permissions(
+        bot_id,
+        everyone_role,
+        dead_role_id,
+        !source.options.hide_from_dead,
+    );
+    // The Graveyard- and the Announcement-Channel always use the default Permissions
+    let spectated_permissions = spectated_permissions(
+        &default_permissions,
+        dead_role_id,
+        source.dead_see_role_channels,
+    );
+
+    let participants = roles::distribute_roles(
+        source.participants.clone(),
+        source.roles.clone(),
+        &source.options.pinned,
+    )
+    .map_err(StartError::DistributingRoles)?;
 
     let guild_channel = source
         .guild
@@ -131,92 +389,442 @@ where
         .await
         .map_err(|_| StartError::LoadingChannels)?;
 
-    let active_category_id = channels::setup_active_category(ctx, &source.guild, &guild_channel)
+    // All the Channels created from here on are tracked, so they can be removed again if any of
+    // the following Steps fail
+    let mut created = channels::CreatedChannels::new();
+    let setup_result: Result<_, StartError> = async {
+        let active_category_id =
+            channels::setup_active_category(ctx, &source.guild, &guild_channel, &mut created)
+                .await
+                .map_err(|_| StartError::SettingUpCategory)?;
+
+        let role_iter = source.roles.keys();
+        let mut role_channel = channels::setup_role_channels(
+            role_iter,
+            role_channel_permissions(
+                &spectated_permissions,
+                everyone_role,
+                source.options.public_channels,
+            ),
+            source.guild,
+            &guild_channel,
+            &active_category_id,
+            ctx,
+            &source.mods,
+            crate::CONFIG.channel_setup_concurrency,
+            &mut created,
+        )
         .await
-        .map_err(|_| StartError::SettingUpCategory)?;
-
-    let role_iter = source.roles.iter().map(|(role, _)| role);
-    let role_channel = channels::setup_role_channels(
-        role_iter,
-        default_permissions.clone(),
-        source.guild,
-        &guild_channel,
-        &active_category_id,
-        ctx,
-        &source.mods,
-    )
-    .await
-    .map_err(StartError::SettingUpChannels)?;
-
-    let mod_channel = channels::setup_moderator_channel(
-        default_permissions,
-        source.guild,
-        &guild_channel,
-        &active_category_id,
-        ctx,
-        &source.mods,
-    )
-    .await
-    .map_err(|_| StartError::SettingUpModeratorChannel)?;
+        .map_err(StartError::SettingUpChannels)?;
+        // The Channels of the linked Players are always private
+        channels::setup_link_channels(
+            participants.links(),
+            spectated_permissions.clone(),
+            source.guild,
+            &guild_channel,
+            &active_category_id,
+            ctx,
+            &source.mods,
+            crate::CONFIG.channel_setup_concurrency,
+            &mut role_channel,
+            &mut created,
+        )
+        .await
+        .map_err(StartError::SettingUpChannels)?;
+        // Fails before any of the remaining Channels are set up, if a Role needs a Channel that
+        // was not created
+        let grants = player_grants(&participants, &role_channel)?;
 
-    // Set the Permissions for the Users and their corresponding Role-Channels
-    for (user_id, role) in participants.iter() {
-        let access_permissions = channel_access_permissions(*user_id);
+        let graveyard_channel = channels::setup_graveyard_channel(
+            default_permissions.clone(),
+            dead_role_id,
+            source.guild,
+            &guild_channel,
+            &active_category_id,
+            ctx,
+            &source.mods,
+            &mut created,
+        )
+        .await
+        .map_err(|_| StartError::SettingUpGraveyardChannel)?;
+
+        let announcement_channel = if source.announcement_channel {
+            let channel = channels::setup_announcement_channel(
+                default_permissions.clone(),
+                source.guild,
+                &guild_channel,
+                &active_category_id,
+                ctx,
+                &source.mods,
+                participants.players(),
+                &mut created,
+            )
+            .await
+            .map_err(|_| StartError::SettingUpAnnouncementChannel)?;
+
+            Some(channel)
+        } else {
+            None
+        };
+
+        let mod_channel = channels::setup_moderator_channel(
+            spectated_permissions,
+            source.guild,
+            &guild_channel,
+            &active_category_id,
+            ctx,
+            &source.mods,
+            &mut created,
+        )
+        .await
+        .map_err(|_| StartError::SettingUpModeratorChannel)?;
+
+        // Set the Permissions for the Users and their corresponding Role-Channels
+        let mut pacer = Pacer::channel_operations();
+        for (channel, access_permissions) in grants.iter() {
+            pacer.pace().await;
+            dry_run::mutate("Granting Channel-Access", (), async {
+                channel.create_permission(ctx, access_permissions).await
+            })
+            .await
+            .map_err(|_| StartError::AssignRolePermissions)?;
+        }
 
-        for tmp_c in role.channels() {
-            let channel = role_channel
-                .get(&tmp_c)
-                .expect("There should be a Channel for the Role available");
+        // The Intros only give the Players some Context, so the Round is started without them
+        // if they can't be posted
+        for (channel, intro) in channel_intros(source.roles.keys(), &role_channel) {
+            if let Err(e) = channel.say(ctx, intro).await {
+                tracing::error!("Posting Intro in Role-Channel: {:?}", e);
+            }
+        }
 
-            channel
-                .create_permission(ctx, &access_permissions)
+        // The Mod Message to inform the Moderators about all the Roles
+        {
+            let info_msg = moderator_info(dead_role_name);
+            mod_channel
+                .say(ctx, info_msg)
                 .await
-                .map_err(|_| StartError::AssignRolePermissions)?;
+                .map_err(|_| StartError::SettingUpModeratorChannel)?;
+
+            let msg = {
+                let mut tmp = "Roles:\n".to_string();
+                let mut names = HashMap::new();
+
+                // The Members are loaded all at once, instead of once per Player
+                let nicknames = if !source.mention_players && source.use_nicknames {
+                    load_nicknames(ctx, source.guild, &participants.players().collect()).await
+                } else {
+                    HashMap::new()
+                };
+
+                for (user_id, role) in participants.iter() {
+                    let player = if source.mention_players {
+                        format!("<@{}>", user_id.0)
+                    } else if let Some(nick) = nicknames.get(user_id) {
+                        nick.clone()
+                    } else {
+                        user_id
+                            .to_user(ctx)
+                            .await
+                            .map_err(|_| StartError::SettingUpModeratorChannel)?
+                            .name
+                    };
+                    let emoji = source
+                        .roles
+                        .keys()
+                        .find(|r| r.name() == role.name())
+                        .map(|r| r.emoji());
+
+                    tmp.push_str(&role_overview_line(&player, role, emoji));
+                    names.insert(*user_id, player);
+                }
+
+                if !participants.links().is_empty() {
+                    tmp.push_str("\nLinks:\n");
+                    for link in participants.links() {
+                        tmp.push_str(&format!(
+                            "{} & {} (linked by {})\n",
+                            names[&link.players[0]],
+                            names[&link.players[1]],
+                            names[&link.linked_by]
+                        ));
+                    }
+                }
+
+                tmp
+            };
+            mod_channel
+                .say(ctx, msg)
+                .await
+                .map_err(|_| StartError::SettingUpModeratorChannel)?;
+
+            // The Warning is only a Hint for the Mods, so the Round still starts without it
+            if let Some(warning) = unmatched_channels_warning(source.roles.keys()) {
+                if let Err(e) = mod_channel.say(ctx, warning).await {
+                    tracing::error!("Sending Warning about unmatched Channels: {:?}", e);
+                }
+            }
         }
+
+        Ok((
+            mod_channel,
+            graveyard_channel,
+            role_channel,
+            announcement_channel,
+        ))
     }
+    .await;
 
-    // The Mod Message to inform the Moderators about all the Roles
-    {
-        let info_msg = format!("```
-The Round has now been started and all the required Setup has been completed
+    match setup_result {
+        Ok((moderator_channel, graveyard_channel, channels, announcement_channel)) => {
+            Ok(StartedRound {
+                players: participants,
+                moderator_channel,
+                graveyard_channel,
+                channels,
+                announcement_channel,
+            })
+        }
+        Err(e) => {
+            tracing::error!("Setting up Round failed, rolling back: {:?}", e);
+            created.rollback(ctx).await;
+            Err(e)
+        }
+    }
+}
 
-If a Player has died, they should be given the '{}'-Role and the Bot will then update the Configuration \
-to allow that Player to see all Channels again and watch the Round from the 'Outside'.
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-Once the Round is over, the Bot will automatically remove all the Round-Relevant Roles from the Players again \
-and reorganize the relevant Channels to prepare for the next Round.
-            ```", dead_role_name
+    #[test]
+    fn overview_line() {
+        let seer = WereWolfRoleConfig::new("Seer", "\u{1f52e}", false, false, Vec::new())
+            .to_instance(&mut || unreachable!());
+
+        assert_eq!(
+            "<@13>: \u{1f52e} Seer\n",
+            role_overview_line("<@13>", &seer, Some("\u{1f52e}"))
         );
-        mod_channel
-            .say(ctx, info_msg)
-            .await
-            .map_err(|_| StartError::SettingUpModeratorChannel)?;
+        assert_eq!("Player: Seer\n", role_overview_line("Player", &seer, None));
+        assert_eq!(
+            "Player: Seer\n",
+            role_overview_line("Player", &seer, Some(""))
+        );
+    }
 
-        let msg = {
-            let mut tmp = "Roles:\n".to_string();
+    #[test]
+    fn moderator_info_not_indented() {
+        let info = moderator_info("Dead");
 
-            for (user_id, role) in participants.iter() {
-                let user = user_id
-                    .to_user(ctx)
-                    .await
-                    .map_err(|_| StartError::SettingUpModeratorChannel)?;
-                let name = user.name;
+        assert!(info.contains("given the 'Dead'-Role"));
+        assert!(info.lines().all(|l| !l.starts_with(' ')));
+    }
 
-                let role_name = match role.masked_role() {
-                    Some(other) => format!("{} ({})", role.name(), other.name()),
-                    None => format!("{}", role.name()),
-                };
+    fn overwrite_parts(
+        overwrites: &[PermissionOverwrite],
+    ) -> Vec<(Permissions, Permissions, PermissionOverwriteType)> {
+        overwrites
+            .iter()
+            .map(|o| (o.allow, o.deny, o.kind))
+            .collect()
+    }
 
-                tmp.push_str(&format!("{}: {}\n", name, role_name));
-            }
+    #[test]
+    fn role_channels_private() {
+        let defaults = default_permissions(UserId(1), RoleId(2), RoleId(3), true);
+        let overwrites = role_channel_permissions(&defaults, RoleId(2), false);
 
-            tmp
-        };
-        mod_channel
-            .say(ctx, msg)
-            .await
-            .map_err(|_| StartError::SettingUpModeratorChannel)?;
+        assert_eq!(overwrite_parts(&defaults), overwrite_parts(&overwrites));
     }
 
-    Ok((participants, mod_channel, role_channel))
+    #[test]
+    fn role_channels_public() {
+        for dead_spectate in [true, false] {
+            let defaults = default_permissions(UserId(1), RoleId(2), RoleId(3), dead_spectate);
+            let overwrites = role_channel_permissions(&defaults, RoleId(2), true);
+
+            assert_eq!(defaults.len(), overwrites.len());
+            let everyone = overwrites
+                .iter()
+                .find(|o| o.kind == PermissionOverwriteType::Role(RoleId(2)))
+                .unwrap();
+            assert_eq!(Permissions::READ_MESSAGES, everyone.allow);
+            assert_eq!(Permissions::SEND_MESSAGES, everyone.deny);
+
+            // All the other Overwrites are not affected
+            let others = |o: &[PermissionOverwrite]| {
+                overwrite_parts(o)
+                    .into_iter()
+                    .filter(|(_, _, kind)| *kind != PermissionOverwriteType::Role(RoleId(2)))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(others(&defaults), others(&overwrites));
+        }
+    }
+
+    #[test]
+    fn grants_linked_players() {
+        let villager = WereWolfRoleConfig::new("Villager", "", true, false, Vec::new());
+        let cupid =
+            WereWolfRoleConfig::new("Cupid", "", false, false, Vec::new()).with_linked_players();
+        let mut roles = BTreeMap::new();
+        roles.insert(villager, 2);
+        roles.insert(cupid, 1);
+
+        let mut pinned = BTreeMap::new();
+        pinned.insert(UserId(3), "Cupid".to_string());
+        let participants =
+            roles::distribute_roles(vec![UserId(1), UserId(2), UserId(3)], roles, &pinned).unwrap();
+
+        let mut channels = ChannelRegistry::new();
+        channels.insert("Villager".to_string(), ChannelId(1));
+        channels.insert("Cupid".to_string(), ChannelId(2));
+        channels.insert("Cupid-Link".to_string(), ChannelId(3));
+
+        let grants = player_grants(&participants, &channels).unwrap();
+        let mut link_grants: Vec<PermissionOverwriteType> = grants
+            .iter()
+            .filter(|(c, _)| *c == ChannelId(3))
+            .map(|(_, o)| o.kind)
+            .collect();
+        link_grants.sort_by_key(|k| match k {
+            PermissionOverwriteType::Member(u) => u.0,
+            _ => 0,
+        });
+
+        // Only the two Players linked by Cupid can access the shared Channel
+        assert_eq!(
+            vec![
+                PermissionOverwriteType::Member(UserId(1)),
+                PermissionOverwriteType::Member(UserId(2))
+            ],
+            link_grants
+        );
+
+        // The Link-Channel must have been set up as well
+        let mut without_link = ChannelRegistry::new();
+        without_link.insert("Villager".to_string(), ChannelId(1));
+        without_link.insert("Cupid".to_string(), ChannelId(2));
+        assert!(matches!(
+            player_grants(&participants, &without_link),
+            Err(StartError::MissingChannel(MissingChannel { channel, .. })) if channel == "Cupid-Link"
+        ));
+    }
+
+    #[test]
+    fn grants_missing_channel() {
+        let villager = WereWolfRoleConfig::new("Villager", "", true, false, Vec::new());
+        let thief = WereWolfRoleConfig::new("Thief", "", false, true, Vec::new());
+
+        let mut players = BTreeMap::new();
+        players.insert(UserId(1), villager.to_instance(&mut || unreachable!()));
+        players.insert(UserId(2), thief.to_instance(&mut || villager.clone()));
+        let participants = Distribution::new(players);
+
+        let mut channels = ChannelRegistry::new();
+        channels.insert("Thief".to_string(), ChannelId(2));
+
+        let result = player_grants(&participants, &channels);
+        assert!(matches!(
+            result,
+            Err(StartError::MissingChannel(MissingChannel { channel, .. })) if channel == "Villager"
+        ));
+
+        channels.insert("Villager".to_string(), ChannelId(1));
+        let grants = player_grants(&participants, &channels).unwrap();
+        let granted: Vec<ChannelId> = grants.iter().map(|(c, _)| *c).collect();
+        assert_eq!(vec![ChannelId(1), ChannelId(2), ChannelId(1)], granted);
+        assert_eq!(PermissionOverwriteType::Member(UserId(2)), grants[2].1.kind);
+    }
+
+    fn dead_overwrite(overwrites: &[PermissionOverwrite]) -> Option<&PermissionOverwrite> {
+        overwrites
+            .iter()
+            .find(|o| o.kind == PermissionOverwriteType::Role(RoleId(3)))
+    }
+
+    #[test]
+    fn permissions_dead_spectate() {
+        let overwrites = default_permissions(UserId(1), RoleId(2), RoleId(3), true);
+
+        assert_eq!(3, overwrites.len());
+        let dead = dead_overwrite(&overwrites).unwrap();
+        assert_eq!(Permissions::READ_MESSAGES, dead.allow);
+    }
+
+    #[test]
+    fn spectated_dead_visible() {
+        let defaults = default_permissions(UserId(1), RoleId(2), RoleId(3), true);
+        let overwrites = spectated_permissions(&defaults, RoleId(3), true);
+
+        assert_eq!(overwrite_parts(&defaults), overwrite_parts(&overwrites));
+    }
+
+    #[test]
+    fn spectated_dead_hidden() {
+        let defaults = default_permissions(UserId(1), RoleId(2), RoleId(3), true);
+        let overwrites = spectated_permissions(&defaults, RoleId(3), false);
+
+        assert_eq!(2, overwrites.len());
+        assert!(dead_overwrite(&overwrites).is_none());
+        // The Bot and everyone else are not affected
+        assert_eq!(
+            overwrite_parts(&defaults[..2]),
+            overwrite_parts(&overwrites)
+        );
+
+        // Public Role-Channels still don't grant the Dead-Role anything on its own
+        let public = role_channel_permissions(&overwrites, RoleId(2), true);
+        assert!(dead_overwrite(&public).is_none());
+    }
+
+    #[test]
+    fn permissions_dead_hidden() {
+        let overwrites = default_permissions(UserId(1), RoleId(2), RoleId(3), false);
+
+        assert_eq!(2, overwrites.len());
+        assert!(dead_overwrite(&overwrites).is_none());
+        assert!(overwrites
+            .iter()
+            .any(|o| o.kind == PermissionOverwriteType::Role(RoleId(2))
+                && o.deny == Permissions::READ_MESSAGES));
+    }
+
+    #[test]
+    fn intros_for_configured_roles() {
+        let roles = vec![
+            WereWolfRoleConfig::new("Seer", "🔮", false, false, Vec::new())
+                .with_channel_intro("Each Night you can look at the Role of one Player"),
+            WereWolfRoleConfig::new("Werewolf", "🐺", true, false, Vec::new()),
+            // The Channel of this Role was not set up
+            WereWolfRoleConfig::new("Witch", "🧪", false, false, Vec::new())
+                .with_channel_intro("You have two Potions"),
+        ];
+        let mut registry = ChannelRegistry::new();
+        registry.insert("Seer".to_string(), ChannelId(1));
+        registry.insert("Werewolf".to_string(), ChannelId(2));
+
+        assert_eq!(
+            vec![(
+                ChannelId(1),
+                "Each Night you can look at the Role of one Player"
+            )],
+            channel_intros(&roles, &registry)
+        );
+    }
+
+    #[test]
+    fn unmatched_warning() {
+        let roles = [
+            WereWolfRoleConfig::new("Werewolf", "", true, false, Vec::new()),
+            WereWolfRoleConfig::new("Wolfchild", "", false, false, vec!["Werwolf".to_string()]),
+        ];
+
+        assert_eq!(None, unmatched_channels_warning(roles[..1].iter()));
+        assert_eq!(
+            Some("Warning: The extra Channels 'Werwolf' (from 'Wolfchild') don't belong to any Role in this Round and were created as standalone Channels".to_string()),
+            unmatched_channels_warning(roles.iter())
+        );
+    }
 }
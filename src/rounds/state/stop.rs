@@ -1,5 +1,3 @@
-use std::collections::BTreeMap;
-
 use serenity::{
     http::Http,
     model::{
@@ -8,7 +6,10 @@ use serenity::{
     },
 };
 
-use crate::roles::WereWolfRoleInstance;
+use crate::{
+    roles::WereWolfRoleInstance,
+    util::{dry_run, pacing::Pacer},
+};
 
 use super::channels;
 
@@ -20,7 +21,7 @@ pub async fn stop<'pi, PI, PIT>(
     ctx: &Http,
     guild: GuildId,
     participants: PIT,
-    channels: &BTreeMap<String, ChannelId>,
+    channels: &[ChannelId],
 ) where
     PI: Iterator<Item = (&'pi UserId, &'pi WereWolfRoleInstance)>,
     PIT: Fn() -> PI,
@@ -42,22 +43,29 @@ pub async fn stop<'pi, PI, PIT>(
         };
 
     // Cleanup all the Role-Channels
-    for (_, channel) in channels.iter() {
+    let mut pacer = Pacer::channel_operations();
+    for channel in channels.iter() {
         // Reset the special Permission-Settings for Players in the current
         // Channel
         for (user, _) in participants() {
-            if let Err(e) = channel
-                .delete_permission(ctx, PermissionOverwriteType::Member(*user))
-                .await
-            {
+            pacer.pace().await;
+            let removal = dry_run::mutate("Removing Player-Permissions", (), async {
+                channel
+                    .delete_permission(ctx, PermissionOverwriteType::Member(*user))
+                    .await
+            });
+            if let Err(e) = removal.await {
                 tracing::error!("Removing Restrictive-Permission for Player: {:?}", e);
             }
         }
 
-        if let Err(e) = channel
-            .delete_permission(ctx, PermissionOverwriteType::Role(everyone_role_id))
-            .await
-        {
+        pacer.pace().await;
+        let removal = dry_run::mutate("Removing @everyone-Permissions", (), async {
+            channel
+                .delete_permission(ctx, PermissionOverwriteType::Role(everyone_role_id))
+                .await
+        });
+        if let Err(e) = removal.await {
             tracing::error!(
                 "Removing Restrictive-Permission for @everyone-Role: {:?}",
                 e
@@ -65,10 +73,14 @@ pub async fn stop<'pi, PI, PIT>(
         }
 
         // Move the Channel back to the Inactive-Category
-        if let Err(e) = channel
-            .edit(ctx, |c| c.category(inactive_category_id))
-            .await
-        {
+        pacer.pace().await;
+        let moving = dry_run::mutate("Moving Channel into Inactive-Category", (), async {
+            channel
+                .edit(ctx, |c| c.category(inactive_category_id))
+                .await
+                .map(|_| ())
+        });
+        if let Err(e) = moving.await {
             tracing::error!("Moving Channel back into Inactive-Category: {:?}", e);
         }
     }
@@ -76,6 +88,7 @@ pub async fn stop<'pi, PI, PIT>(
     // Clean-Up all the Players "settings":
     // * Remove the Dead-Role if applied
     for (t_user, _) in participants() {
+        pacer.pace().await;
         let mut member = match guild.member(ctx, t_user).await {
             Ok(m) => m,
             Err(e) => {
@@ -84,7 +97,10 @@ pub async fn stop<'pi, PI, PIT>(
             }
         };
 
-        if let Err(e) = member.remove_role(ctx, dead_role_id).await {
+        let removal = dry_run::mutate("Removing Dead-Role from Member", (), async {
+            member.remove_role(ctx, dead_role_id).await
+        });
+        if let Err(e) = removal.await {
             tracing::error!("Removing 'W-Dead' Role: {:?}", e);
         }
     }
@@ -0,0 +1,460 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+use serde::{Deserialize, Serialize};
+use serenity::{
+    http::Http,
+    model::{
+        channel::ReactionType,
+        id::{ChannelId, GuildId, RoleId, UserId},
+    },
+};
+
+use super::{channels, start};
+
+/// The maximum Number of Candidates in a single Vote, as every Candidate needs their own
+/// Reaction on the Ballot
+pub const MAX_CANDIDATES: usize = 20;
+
+/// The Name of the Channel in which the Votes take place, prefixed like the Categories of the Bot
+/// so an existing Vote-Channel of the Guild is never taken over for a Round
+const VOTE_CHANNEL_NAME: &str = "W-Vote";
+
+/// The Emoji for the Candidate at the given Index, which are the Regional-Indicators 🇦 to 🇹
+pub fn candidate_emoji(index: usize) -> Option<String> {
+    if index >= MAX_CANDIDATES {
+        return None;
+    }
+
+    std::char::from_u32(0x1F1E6 + index as u32).map(|c| c.to_string())
+}
+
+#[derive(Debug, PartialEq)]
+pub enum VoteError {
+    /// There are less than two alive Players to vote for
+    NotEnoughCandidates,
+    /// There are more alive Players than there are Reactions for them
+    TooManyCandidates { count: usize },
+}
+
+impl Display for VoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotEnoughCandidates => {
+                write!(f, "A Vote needs at least two alive Players")
+            }
+            Self::TooManyCandidates { count } => write!(
+                f,
+                "A Vote supports at most {} Players, but there are {} alive Players",
+                MAX_CANDIDATES, count
+            ),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CastError {
+    /// Only the alive Players of the Round can vote
+    NotEligible,
+    /// The Reaction does not belong to any of the Candidates
+    UnknownCandidate,
+}
+
+/// The Result of a Vote once it is closed
+#[derive(Debug, PartialEq)]
+pub enum VoteOutcome {
+    /// Nobody voted at all
+    NoVotes,
+    /// The Candidate with the most Votes
+    Chosen(UserId),
+    /// Multiple Candidates got the most Votes, nobody is chosen and the Mods decide between them
+    Tie(Vec<UserId>),
+}
+
+/// The Number of Votes for every Candidate that got at least one Vote, ordered by the Number of
+/// Votes and then by the Order of the Candidates on the Ballot
+#[derive(Debug, PartialEq)]
+pub struct Tally {
+    pub counts: Vec<(UserId, usize)>,
+    pub outcome: VoteOutcome,
+}
+
+/// An anonymous Vote between the alive Players of a Round. Only alive Players can vote and be
+/// voted for, every Voter has a single Vote that they can change until the Vote is closed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vote {
+    /// The Players that can be voted for, in the Order they are shown on the Ballot
+    candidates: Vec<UserId>,
+    /// The Players that are allowed to vote
+    voters: BTreeSet<UserId>,
+    /// The current Vote of every Voter
+    ballots: BTreeMap<UserId, UserId>,
+}
+
+impl Vote {
+    /// Starts a new Vote between the given alive Players
+    pub fn new<I>(alive: I) -> Result<Self, VoteError>
+    where
+        I: IntoIterator<Item = UserId>,
+    {
+        let voters: BTreeSet<UserId> = alive.into_iter().collect();
+        if voters.len() < 2 {
+            return Err(VoteError::NotEnoughCandidates);
+        }
+        if voters.len() > MAX_CANDIDATES {
+            return Err(VoteError::TooManyCandidates {
+                count: voters.len(),
+            });
+        }
+
+        Ok(Self {
+            candidates: voters.iter().copied().collect(),
+            voters,
+            ballots: BTreeMap::new(),
+        })
+    }
+
+    /// The Emojis of all the Candidates, in the Order they are shown on the Ballot
+    pub fn emojis(&self) -> Vec<String> {
+        (0..self.candidates.len())
+            .filter_map(candidate_emoji)
+            .collect()
+    }
+
+    /// The Candidate that the Reaction stands for
+    pub fn candidate_for(&self, emoji: &ReactionType) -> Option<UserId> {
+        let raw = match emoji {
+            ReactionType::Unicode(raw) => raw,
+            _ => return None,
+        };
+
+        self.candidates
+            .iter()
+            .enumerate()
+            .find(|(index, _)| candidate_emoji(*index).as_deref() == Some(raw.as_str()))
+            .map(|(_, candidate)| *candidate)
+    }
+
+    /// Records the Vote of the Voter for the Candidate, replacing their previous Vote
+    pub fn cast(&mut self, voter: UserId, candidate: UserId) -> Result<(), CastError> {
+        if !self.voters.contains(&voter) {
+            return Err(CastError::NotEligible);
+        }
+        if !self.voters.contains(&candidate) {
+            return Err(CastError::UnknownCandidate);
+        }
+
+        self.ballots.insert(voter, candidate);
+        Ok(())
+    }
+
+    /// Removes a Player that died during the Vote. Their own Vote is discarded and the Votes for
+    /// them are discarded as well, so their Voters can vote again. They stay on the Ballot, so
+    /// the Letters of the other Candidates don't change
+    pub fn remove_player(&mut self, player: UserId) {
+        self.voters.remove(&player);
+        self.ballots
+            .retain(|voter, candidate| *voter != player && *candidate != player);
+    }
+
+    /// The Number of Voters that have already voted
+    pub fn voted(&self) -> usize {
+        self.ballots.len()
+    }
+
+    pub fn tally(&self) -> Tally {
+        let mut counts: Vec<(UserId, usize)> = self
+            .candidates
+            .iter()
+            .map(|c| (*c, self.ballots.values().filter(|v| *v == c).count()))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        // The Sort is stable, so Candidates with the same Count keep their Order on the Ballot
+        counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        let outcome = match counts.first() {
+            None => VoteOutcome::NoVotes,
+            Some((_, most)) => {
+                let leading: Vec<UserId> = counts
+                    .iter()
+                    .filter(|(_, count)| count == most)
+                    .map(|(candidate, _)| *candidate)
+                    .collect();
+
+                if leading.len() == 1 {
+                    VoteOutcome::Chosen(leading[0])
+                } else {
+                    VoteOutcome::Tie(leading)
+                }
+            }
+        };
+
+        Tally { counts, outcome }
+    }
+
+    /// The Content of the Ballot, which never shows who voted for whom
+    pub fn ballot_content(&self) -> String {
+        let mut result = "**Vote**: React with the Letter of the Player you want to vote for, your Reaction is removed right away so nobody else can see your Vote. You can change your Vote by reacting again\n\n".to_string();
+        for (index, candidate) in self.candidates.iter().enumerate() {
+            let emoji = match candidate_emoji(index) {
+                Some(e) => e,
+                None => continue,
+            };
+
+            if self.voters.contains(candidate) {
+                result.push_str(&format!("{} <@{}>\n", emoji, candidate.0));
+            } else {
+                result.push_str(&format!("{} ~~<@{}>~~\n", emoji, candidate.0));
+            }
+        }
+        result.push_str(&format!(
+            "\n{} of {} Players have voted",
+            self.voted(),
+            self.voters.len()
+        ));
+
+        result
+    }
+
+    /// The Breakdown of who voted for whom, which is only shown to the Mods
+    pub fn breakdown(&self) -> String {
+        let mut result = "Votes:\n".to_string();
+        for (voter, candidate) in self.ballots.iter() {
+            result.push_str(&format!("<@{}> → <@{}>\n", voter.0, candidate.0));
+        }
+
+        let abstained: Vec<String> = self
+            .voters
+            .iter()
+            .filter(|v| !self.ballots.contains_key(v))
+            .map(|v| format!("<@{}>", v.0))
+            .collect();
+        if !abstained.is_empty() {
+            result.push_str(&format!("Did not vote: {}\n", abstained.join(", ")));
+        }
+
+        result
+    }
+}
+
+impl Display for Tally {
+    /// The public Result, which only contains the Number of Votes per Candidate
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "**Vote closed**")?;
+        for (candidate, count) in self.counts.iter() {
+            writeln!(f, "<@{}>: {} Vote(s)", candidate.0, count)?;
+        }
+
+        match &self.outcome {
+            VoteOutcome::NoVotes => write!(f, "Nobody voted"),
+            VoteOutcome::Chosen(candidate) => write!(f, "<@{}> was chosen", candidate.0),
+            VoteOutcome::Tie(candidates) => {
+                let mentions: Vec<String> =
+                    candidates.iter().map(|c| format!("<@{}>", c.0)).collect();
+                write!(
+                    f,
+                    "Tie between {}, the Moderators decide how it is resolved",
+                    mentions.join(", ")
+                )
+            }
+        }
+    }
+}
+
+/// Sets up the Channel for a Vote, which the Voters can only read and react in
+#[allow(clippy::too_many_arguments)]
+pub async fn setup_channel<'v, V>(
+    ctx: &Http,
+    guild: GuildId,
+    bot_id: UserId,
+    everyone_role: RoleId,
+    dead_role: RoleId,
+    dead_spectate: bool,
+    moderators: &BTreeSet<UserId>,
+    voters: V,
+) -> Result<ChannelId, channels::SetupChannelError>
+where
+    V: Iterator<Item = &'v UserId>,
+{
+    let guild_channel = guild
+        .channels(ctx)
+        .await
+        .map_err(|_| channels::SetupChannelError::LoadingChannels)?;
+
+    let mut created = channels::CreatedChannels::new();
+    let result = async {
+        let category = channels::setup_active_category(ctx, &guild, &guild_channel, &mut created)
+            .await
+            .map_err(|_| channels::SetupChannelError::SettingUpCategory)?;
+
+        channels::setup_read_only_channel(
+            VOTE_CHANNEL_NAME,
+            start::default_permissions(bot_id, everyone_role, dead_role, dead_spectate),
+            guild,
+            &guild_channel,
+            &category,
+            ctx,
+            moderators,
+            voters,
+            &mut created,
+        )
+        .await
+    }
+    .await;
+
+    if result.is_err() {
+        created.rollback(ctx).await;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vote(players: u64) -> Vote {
+        Vote::new((1..=players).map(UserId)).unwrap()
+    }
+
+    #[test]
+    fn candidate_emojis() {
+        assert_eq!(Some("🇦".to_string()), candidate_emoji(0));
+        assert_eq!(Some("🇹".to_string()), candidate_emoji(19));
+        assert_eq!(None, candidate_emoji(20));
+
+        let vote = vote(3);
+        assert_eq!(
+            Some(UserId(2)),
+            vote.candidate_for(&ReactionType::Unicode("🇧".to_string()))
+        );
+        assert_eq!(
+            None,
+            vote.candidate_for(&ReactionType::Unicode("🇩".to_string()))
+        );
+    }
+
+    #[test]
+    fn candidate_limits() {
+        assert_eq!(
+            Err(VoteError::NotEnoughCandidates),
+            Vote::new(vec![UserId(1)])
+        );
+        assert_eq!(
+            Err(VoteError::TooManyCandidates { count: 21 }),
+            Vote::new((1..=21).map(UserId))
+        );
+        assert!(Vote::new((1..=20).map(UserId)).is_ok());
+    }
+
+    #[test]
+    fn only_alive_players_vote() {
+        let mut vote = vote(3);
+
+        assert_eq!(Err(CastError::NotEligible), vote.cast(UserId(9), UserId(1)));
+        assert_eq!(
+            Err(CastError::UnknownCandidate),
+            vote.cast(UserId(1), UserId(9))
+        );
+        assert_eq!(Ok(()), vote.cast(UserId(1), UserId(2)));
+        assert_eq!(1, vote.voted());
+    }
+
+    #[test]
+    fn changed_vote_counts_once() {
+        let mut vote = vote(3);
+        vote.cast(UserId(1), UserId(2)).unwrap();
+        vote.cast(UserId(1), UserId(3)).unwrap();
+
+        assert_eq!(
+            Tally {
+                counts: vec![(UserId(3), 1)],
+                outcome: VoteOutcome::Chosen(UserId(3)),
+            },
+            vote.tally()
+        );
+    }
+
+    #[test]
+    fn tally_chosen() {
+        let mut vote = vote(4);
+        vote.cast(UserId(1), UserId(2)).unwrap();
+        vote.cast(UserId(2), UserId(3)).unwrap();
+        vote.cast(UserId(3), UserId(2)).unwrap();
+
+        assert_eq!(
+            Tally {
+                counts: vec![(UserId(2), 2), (UserId(3), 1)],
+                outcome: VoteOutcome::Chosen(UserId(2)),
+            },
+            vote.tally()
+        );
+    }
+
+    #[test]
+    fn tally_tie() {
+        let mut vote = vote(4);
+        vote.cast(UserId(1), UserId(4)).unwrap();
+        vote.cast(UserId(2), UserId(3)).unwrap();
+        vote.cast(UserId(4), UserId(1)).unwrap();
+
+        assert_eq!(
+            Tally {
+                counts: vec![(UserId(1), 1), (UserId(3), 1), (UserId(4), 1)],
+                outcome: VoteOutcome::Tie(vec![UserId(1), UserId(3), UserId(4)]),
+            },
+            vote.tally()
+        );
+    }
+
+    #[test]
+    fn tally_no_votes() {
+        assert_eq!(
+            Tally {
+                counts: Vec::new(),
+                outcome: VoteOutcome::NoVotes,
+            },
+            vote(2).tally()
+        );
+    }
+
+    #[test]
+    fn dead_player_removed() {
+        let mut vote = vote(3);
+        vote.cast(UserId(1), UserId(2)).unwrap();
+        vote.cast(UserId(2), UserId(3)).unwrap();
+        vote.cast(UserId(3), UserId(3)).unwrap();
+
+        vote.remove_player(UserId(2));
+
+        // The Vote of the dead Player and the Votes for them are gone
+        assert_eq!(
+            Tally {
+                counts: vec![(UserId(3), 1)],
+                outcome: VoteOutcome::Chosen(UserId(3)),
+            },
+            vote.tally()
+        );
+        assert_eq!(Err(CastError::NotEligible), vote.cast(UserId(2), UserId(1)));
+        assert_eq!(
+            Err(CastError::UnknownCandidate),
+            vote.cast(UserId(1), UserId(2))
+        );
+    }
+
+    #[test]
+    fn public_result_is_anonymous() {
+        let mut vote = vote(3);
+        vote.cast(UserId(1), UserId(2)).unwrap();
+
+        assert_eq!(
+            "**Vote closed**\n<@2>: 1 Vote(s)\n<@2> was chosen",
+            vote.tally().to_string()
+        );
+        assert_eq!(
+            "Votes:\n<@1> → <@2>\nDid not vote: <@2>, <@3>\n",
+            vote.breakdown()
+        );
+    }
+}
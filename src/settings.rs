@@ -0,0 +1,398 @@
+use std::{fmt::Display, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use serenity::model::id::ChannelId;
+
+/// The longest Registration that can be configured, which is a whole Day
+pub const MAX_REGISTRATION_MINUTES: u64 = 24 * 60;
+/// The maximum Length of the serialized Settings, so that they still fit into a single Message
+/// of the Discord-Storage together with the Record they are wrapped in
+pub const MAX_SERIALIZED_LENGTH: usize = 1900;
+
+/// The Settings of the Bot that can be configured for each Guild individually
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuildSettings {
+    /// Select the Roles for a Round using numbered Reactions instead of the Emoji of each Role
+    pub numbered_reactions: bool,
+    /// Reveal the Role of a Player in the Graveyard-Channel once they died
+    pub reveal_dead_roles: bool,
+    /// Delete the Setup-Message once the Round started and control the Round using a new
+    /// Message in the Moderator-Channel
+    pub delete_setup_message: bool,
+    /// Ask the Mod, that started the Round, for the Number of Players per Role using Direct
+    /// Messages instead of the public Channel
+    pub dm_role_counts: bool,
+    /// Let every registered Player confirm that they are ready, before the Roles for a Round can
+    /// be selected
+    pub ready_check: bool,
+    /// The Number of Minutes for which the Registration of a new Round stays open before it
+    /// automatically moves on to selecting the Roles, the Mods close it themselves if this is 0
+    pub registration_minutes: u64,
+    /// The Names of the Roles whose Members are allowed to manage the Bot and its Rounds
+    pub mod_roles: Vec<String>,
+    /// Mention the Players in the Role-Overview for the Moderators instead of only writing out
+    /// their Names
+    pub mention_players: bool,
+    /// Use the Nicknames of the Players on the Server instead of their Usernames, when they are
+    /// not mentioned
+    pub use_nicknames: bool,
+    /// Treat Players that leave the Guild during a Round as dead, instead of keeping their Role
+    /// and Channels around
+    pub kill_leaving_players: bool,
+    /// Create a separate Channel for Announcements, which all the Players of a Round can read but
+    /// only the Moderators can write in
+    pub announcement_channel: bool,
+    /// The Lines that are posted one after another whenever a Round moves on to its next Phase
+    pub narration: Vec<String>,
+    /// The Channels in which new Rounds can be started, Rounds can be started anywhere if this
+    /// is empty
+    pub game_channels: Vec<ChannelId>,
+    /// Pin the Message that controls the Round while it is running, so the Mods can always find
+    /// it in busy Channels
+    pub pin_control_message: bool,
+    /// Let dead Players read the Channels of all the Roles, instead of only the Graveyard- and
+    /// the Announcement-Channel
+    pub dead_see_role_channels: bool,
+}
+
+impl Default for GuildSettings {
+    fn default() -> Self {
+        Self {
+            numbered_reactions: false,
+            reveal_dead_roles: false,
+            delete_setup_message: false,
+            dm_role_counts: false,
+            ready_check: false,
+            registration_minutes: 0,
+            mod_roles: vec![crate::MOD_ROLE_NAME.to_string()],
+            mention_players: true,
+            use_nicknames: true,
+            kill_leaving_players: false,
+            announcement_channel: false,
+            narration: Vec::new(),
+            game_channels: Vec::new(),
+            pin_control_message: false,
+            dead_see_role_channels: true,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SetSettingError {
+    /// There is no Setting with the given Name
+    UnknownSetting(String),
+    /// The Value could not be parsed for the Setting
+    InvalidValue { setting: String, value: String },
+    /// The Value is larger than the Maximum allowed for the Setting
+    TooLarge { setting: String, max: u64 },
+}
+
+impl Display for SetSettingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownSetting(name) => write!(f, "Unknown Setting '{}'", name),
+            Self::InvalidValue { setting, value } => {
+                write!(f, "Invalid Value '{}' for Setting '{}'", value, setting)
+            }
+            Self::TooLarge { setting, max } => {
+                write!(
+                    f,
+                    "The Value for Setting '{}' can be at most {}",
+                    setting, max
+                )
+            }
+        }
+    }
+}
+
+/// Parses a comma-separated List of Role-Names, which must contain at least one Name
+fn parse_role_list(setting: &str, value: &str) -> Result<Vec<String>, SetSettingError> {
+    let names: Vec<String> = value
+        .split(',')
+        .map(|n| n.trim())
+        .filter(|n| !n.is_empty())
+        .map(|n| n.to_string())
+        .collect();
+
+    if names.is_empty() {
+        return Err(SetSettingError::InvalidValue {
+            setting: setting.to_string(),
+            value: value.to_string(),
+        });
+    }
+
+    Ok(names)
+}
+
+fn parse_bool(setting: &str, value: &str) -> Result<bool, SetSettingError> {
+    match value.to_lowercase().as_str() {
+        "on" | "true" | "yes" => Ok(true),
+        "off" | "false" | "no" => Ok(false),
+        _ => Err(SetSettingError::InvalidValue {
+            setting: setting.to_string(),
+            value: value.to_string(),
+        }),
+    }
+}
+
+fn parse_number(setting: &str, value: &str) -> Result<u64, SetSettingError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| SetSettingError::InvalidValue {
+            setting: setting.to_string(),
+            value: value.to_string(),
+        })
+}
+
+impl GuildSettings {
+    /// Whether new Rounds can be started in the given Channel
+    pub fn allows_rounds_in(&self, channel: ChannelId) -> bool {
+        self.game_channels.is_empty() || self.game_channels.contains(&channel)
+    }
+
+    /// How long the Registration of a new Round stays open, if it is closed automatically
+    pub fn registration_window(&self) -> Option<Duration> {
+        // Settings stored before the Maximum existed could still be larger
+        match self.registration_minutes.min(MAX_REGISTRATION_MINUTES) {
+            0 => None,
+            minutes => minutes.checked_mul(60).map(Duration::from_secs),
+        }
+    }
+
+    /// Whether the Settings are too large to be stored, which can only happen because of the
+    /// Narration-Script as all the other Settings have a bounded Size
+    pub fn exceeds_storage_limit(&self) -> bool {
+        serde_json::to_string(self)
+            .map(|s| s.chars().count() > MAX_SERIALIZED_LENGTH)
+            .unwrap_or(true)
+    }
+
+    /// All the Settings with their Name and current Value
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("numbered-reactions", self.numbered_reactions.to_string()),
+            ("reveal-dead-roles", self.reveal_dead_roles.to_string()),
+            (
+                "delete-setup-message",
+                self.delete_setup_message.to_string(),
+            ),
+            ("dm-role-counts", self.dm_role_counts.to_string()),
+            ("ready-check", self.ready_check.to_string()),
+            (
+                "registration-minutes",
+                self.registration_minutes.to_string(),
+            ),
+            ("mod-roles", self.mod_roles.join(", ")),
+            ("mention-players", self.mention_players.to_string()),
+            ("use-nicknames", self.use_nicknames.to_string()),
+            (
+                "kill-leaving-players",
+                self.kill_leaving_players.to_string(),
+            ),
+            (
+                "announcement-channel",
+                self.announcement_channel.to_string(),
+            ),
+            ("pin-control-message", self.pin_control_message.to_string()),
+            (
+                "dead-see-role-channels",
+                self.dead_see_role_channels.to_string(),
+            ),
+        ]
+    }
+
+    /// Updates the Setting with the given Name to the new Value
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), SetSettingError> {
+        match name {
+            "numbered-reactions" => {
+                self.numbered_reactions = parse_bool(name, value)?;
+            }
+            "reveal-dead-roles" => {
+                self.reveal_dead_roles = parse_bool(name, value)?;
+            }
+            "delete-setup-message" => {
+                self.delete_setup_message = parse_bool(name, value)?;
+            }
+            "dm-role-counts" => {
+                self.dm_role_counts = parse_bool(name, value)?;
+            }
+            "ready-check" => {
+                self.ready_check = parse_bool(name, value)?;
+            }
+            "registration-minutes" => {
+                let minutes = parse_number(name, value)?;
+                if minutes > MAX_REGISTRATION_MINUTES {
+                    return Err(SetSettingError::TooLarge {
+                        setting: name.to_string(),
+                        max: MAX_REGISTRATION_MINUTES,
+                    });
+                }
+
+                self.registration_minutes = minutes;
+            }
+            "mod-roles" => {
+                self.mod_roles = parse_role_list(name, value)?;
+            }
+            "mention-players" => {
+                self.mention_players = parse_bool(name, value)?;
+            }
+            "use-nicknames" => {
+                self.use_nicknames = parse_bool(name, value)?;
+            }
+            "kill-leaving-players" => {
+                self.kill_leaving_players = parse_bool(name, value)?;
+            }
+            "announcement-channel" => {
+                self.announcement_channel = parse_bool(name, value)?;
+            }
+            "pin-control-message" => {
+                self.pin_control_message = parse_bool(name, value)?;
+            }
+            "dead-see-role-channels" => {
+                self.dead_see_role_channels = parse_bool(name, value)?;
+            }
+            _ => return Err(SetSettingError::UnknownSetting(name.to_string())),
+        };
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_defaults() {
+        let result: GuildSettings = serde_json::from_str("{}").unwrap();
+
+        assert_eq!(GuildSettings::default(), result);
+    }
+
+    #[test]
+    fn storage_limit() {
+        let mut settings = GuildSettings::default();
+        assert!(!settings.exceeds_storage_limit());
+
+        settings.narration = vec!["The Night falls".to_string(); 10];
+        assert!(!settings.exceeds_storage_limit());
+
+        settings.narration = vec!["The Night falls".repeat(10); 15];
+        assert!(settings.exceeds_storage_limit());
+    }
+
+    #[test]
+    fn set_valid() {
+        let mut settings = GuildSettings::default();
+
+        settings.set("numbered-reactions", "on").unwrap();
+        assert!(settings.numbered_reactions);
+
+        settings.set("numbered-reactions", "false").unwrap();
+        assert!(!settings.numbered_reactions);
+
+        settings.set("dm-role-counts", "yes").unwrap();
+        assert!(settings.dm_role_counts);
+
+        settings.set("ready-check", "on").unwrap();
+        assert!(settings.ready_check);
+
+        settings.set("registration-minutes", "5").unwrap();
+        assert_eq!(
+            Some(Duration::from_secs(300)),
+            settings.registration_window()
+        );
+
+        settings.set("registration-minutes", "0").unwrap();
+        assert_eq!(None, settings.registration_window());
+
+        settings.set("mention-players", "off").unwrap();
+        assert!(!settings.mention_players);
+
+        settings.set("use-nicknames", "off").unwrap();
+        assert!(!settings.use_nicknames);
+
+        settings.set("kill-leaving-players", "on").unwrap();
+        assert!(settings.kill_leaving_players);
+
+        settings.set("announcement-channel", "true").unwrap();
+        assert!(settings.announcement_channel);
+
+        settings.set("pin-control-message", "on").unwrap();
+        assert!(settings.pin_control_message);
+
+        settings.set("dead-see-role-channels", "off").unwrap();
+        assert!(!settings.dead_see_role_channels);
+    }
+
+    #[test]
+    fn rounds_anywhere_by_default() {
+        let mut settings = GuildSettings::default();
+        assert!(settings.allows_rounds_in(ChannelId(1)));
+
+        settings.game_channels.push(ChannelId(2));
+        assert!(!settings.allows_rounds_in(ChannelId(1)));
+        assert!(settings.allows_rounds_in(ChannelId(2)));
+    }
+
+    #[test]
+    fn set_mod_roles() {
+        let mut settings = GuildSettings::default();
+        assert_eq!(vec!["Game Master".to_string()], settings.mod_roles);
+
+        settings.set("mod-roles", "Game Master, Admin").unwrap();
+        assert_eq!(
+            vec!["Game Master".to_string(), "Admin".to_string()],
+            settings.mod_roles
+        );
+
+        assert!(settings.set("mod-roles", " , ").is_err());
+    }
+
+    #[test]
+    fn set_invalid() {
+        let mut settings = GuildSettings::default();
+
+        assert_eq!(
+            Err(SetSettingError::UnknownSetting("other".to_string())),
+            settings.set("other", "on")
+        );
+        assert_eq!(
+            Err(SetSettingError::InvalidValue {
+                setting: "numbered-reactions".to_string(),
+                value: "maybe".to_string()
+            }),
+            settings.set("numbered-reactions", "maybe")
+        );
+        assert_eq!(
+            Err(SetSettingError::InvalidValue {
+                setting: "registration-minutes".to_string(),
+                value: "-2".to_string()
+            }),
+            settings.set("registration-minutes", "-2")
+        );
+        assert_eq!(
+            Err(SetSettingError::TooLarge {
+                setting: "registration-minutes".to_string(),
+                max: MAX_REGISTRATION_MINUTES
+            }),
+            settings.set("registration-minutes", "99999999999999999")
+        );
+        assert_eq!(0, settings.registration_minutes);
+    }
+
+    #[test]
+    fn registration_window_capped() {
+        let settings = GuildSettings {
+            registration_minutes: u64::MAX,
+            ..GuildSettings::default()
+        };
+
+        assert_eq!(
+            Some(Duration::from_secs(MAX_REGISTRATION_MINUTES * 60)),
+            settings.registration_window()
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::future::Future;
+
+use tokio::sync::watch;
+
+/// Triggers the Shutdown of everything that waits on the matching [`Shutdown`]
+#[derive(Debug)]
+pub struct ShutdownTrigger {
+    sender: watch::Sender<bool>,
+}
+
+/// Lets the long running Parts of the Bot, like the Discord-Client and the Metrics-Endpoint,
+/// wait until the Bot is shutting down
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    receiver: watch::Receiver<bool>,
+}
+
+/// Creates a new Trigger together with the Shutdown it controls
+pub fn channel() -> (ShutdownTrigger, Shutdown) {
+    let (sender, receiver) = watch::channel(false);
+    (ShutdownTrigger { sender }, Shutdown { receiver })
+}
+
+impl ShutdownTrigger {
+    pub fn trigger(&self) {
+        // Nobody is waiting anymore if all the Receivers are gone
+        let _ = self.sender.send(true);
+    }
+}
+
+impl Shutdown {
+    /// Waits until the Shutdown is triggered, which also counts as triggered once the Trigger
+    /// itself is dropped
+    pub async fn wait(mut self) {
+        while !*self.receiver.borrow() {
+            if self.receiver.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Runs the Bot until it stops, which happens either once `stop` completes and the Bot was shut
+/// down because of it, or when the Bot stops on its own, for example because of an invalid Token.
+/// The Shutdown is triggered in both cases, so the rest of the Process stops together with the Bot
+pub async fn run_until<B, S>(trigger: ShutdownTrigger, stop: S, bot: B)
+where
+    B: Future<Output = ()>,
+    S: Future<Output = ()>,
+{
+    tokio::pin!(bot);
+
+    tokio::select! {
+        _ = &mut bot => {
+            tracing::warn!("Bot stopped without being shut down");
+        }
+        _ = stop => {
+            trigger.trigger();
+            bot.await;
+        }
+    };
+
+    trigger.trigger();
+}
+
+/// Waits until the Process is asked to stop, either using Ctrl-C or using SIGTERM, which is
+/// what Docker sends when stopping the Container
+pub async fn signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = match signal(SignalKind::terminate()) {
+            Ok(s) => s,
+            Err(e) => {
+                tracing::error!("Listening for SIGTERM: {:?}", e);
+                let _ = tokio::signal::ctrl_c().await;
+                return;
+            }
+        };
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        };
+    }
+
+    #[cfg(not(unix))]
+    {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Listening for Ctrl-C: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn wait_until_triggered() {
+        let (trigger, shutdown) = channel();
+
+        let waiting = tokio::spawn(shutdown.clone().wait());
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), shutdown.clone().wait())
+                .await
+                .is_err()
+        );
+
+        trigger.trigger();
+        tokio::time::timeout(Duration::from_secs(1), waiting)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // Waiting after the Shutdown was triggered returns right away
+        tokio::time::timeout(Duration::from_secs(1), shutdown.wait())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn run_until_stopped() {
+        let (trigger, shutdown) = channel();
+
+        let bot_shutdown = shutdown.clone();
+        tokio::time::timeout(
+            Duration::from_secs(1),
+            run_until(trigger, async {}, bot_shutdown.wait()),
+        )
+        .await
+        .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(1), shutdown.wait())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn run_until_bot_stopped() {
+        let (trigger, shutdown) = channel();
+
+        // The Bot stops on its own, while nobody asked the Process to stop
+        tokio::time::timeout(
+            Duration::from_secs(1),
+            run_until(trigger, std::future::pending(), async {}),
+        )
+        .await
+        .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(1), shutdown.wait())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn dropped_trigger() {
+        let (trigger, shutdown) = channel();
+        drop(trigger);
+
+        tokio::time::timeout(Duration::from_secs(1), shutdown.wait())
+            .await
+            .unwrap();
+    }
+}
@@ -1,48 +1,327 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Display,
+    future::Future,
+    sync::Arc,
+};
 
 use lockfree::map::Map;
 use serenity::{
-    model::id::{GuildId, MessageId},
+    model::id::{GuildId, MessageId, UserId},
     prelude::Mutex,
 };
 use statemachines::{AsyncTransition, TransitionResult};
 
-use crate::messages::{Context, MessageStateMachine};
+use crate::{
+    messages::{Context, MessageStateMachine},
+    roles::WereWolfRoleInstance,
+};
+
+/// The Players of a running Round together with their Roles
+pub type Participants = BTreeMap<UserId, WereWolfRoleInstance>;
+
+#[derive(Debug, PartialEq)]
+pub enum ReserveError {
+    /// The Guild already has the maximum Number of Rounds running
+    LimitReached { limit: usize },
+}
+
+impl Display for ReserveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LimitReached { limit } => write!(
+                f,
+                "This Server already has {} ongoing Round(s), which is the maximum allowed at the same time",
+                limit
+            ),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CreateRoundError<E> {
+    /// The Round could not be reserved
+    Reserve(ReserveError),
+    /// Creating the State-Machine for the Round failed
+    Create(E),
+    /// The Reservation was cleared, while the Round was being created
+    ReservationCleared,
+}
+
+impl<E> Display for CreateRoundError<E>
+where
+    E: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Reserve(e) => write!(f, "{}", e),
+            Self::Create(e) => write!(f, "Setting up the Round: {}", e),
+            Self::ReservationCleared => {
+                write!(
+                    f,
+                    "The Reservation for the Round was cleared while setting it up"
+                )
+            }
+        }
+    }
+}
+
+/// A Round that counts towards the Round-Limit of a Guild
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reservation {
+    /// The Round has been reserved but is still being set up
+    Pending,
+    /// The Round is running and handled by the State-Machine of the Message
+    Active(MessageId),
+}
+
+impl Display for Reservation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pending => write!(f, "Setup in progress"),
+            Self::Active(id) => write!(f, "Active Round (Message {})", id),
+        }
+    }
+}
+
+/// Information about the current State of a State-Machine
+#[derive(Debug, PartialEq)]
+pub enum StateInfo {
+    /// There is no State-Machine for the Message
+    NotFound,
+    /// The State-Machine is currently processing an Event
+    Busy,
+    /// The State-Machine is waiting for Events in the given State
+    State(Option<&'static str>),
+}
+
+impl Display for StateInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "No State-Machine found"),
+            Self::Busy => write!(f, "Currently processing an Event"),
+            Self::State(Some(name)) => write!(f, "{}", name),
+            Self::State(None) => write!(f, "Unnamed State"),
+        }
+    }
+}
+
+/// The Variant of the Result of a single Transition, without the Output or Error itself
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransitionOutcome {
+    /// The State-Machine ignored the Event
+    NoTransition,
+    /// The State-Machine is finished
+    Done,
+    /// The State-Machine failed with an Error of the given Kind
+    Error { kind: &'static str },
+}
+
+impl TransitionOutcome {
+    /// The Name of the Outcome, used as a Label for Metrics
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::NoTransition => "no_transition",
+            Self::Done => "done",
+            Self::Error { .. } => "error",
+        }
+    }
+}
+
+/// Gets notified about the Outcome of every Transition of the State-Machines in the
+/// [`StateMachineMap`], which allows for Metrics and Logs about the Progress of all the
+/// State-Machines in a single Place
+pub trait TransitionObserver {
+    /// Called after the State-Machine of the given Message handled an Event
+    fn observe(&self, message_id: MessageId, outcome: TransitionOutcome);
+}
+
+/// A single State-Machine together with the Events that still need to be applied to it
+struct Entry {
+    /// The Guild the State-Machine belongs to, which is needed to route Events from Direct
+    /// Messages that don't have a Guild themselves
+    guild_id: GuildId,
+    sm: Mutex<MessageStateMachine<(), ()>>,
+    /// The Events that have been received but not yet applied, in the Order they were received
+    pending: std::sync::Mutex<VecDeque<Context>>,
+}
+
+impl Entry {
+    /// The current State of the State-Machine, without waiting for it if it is busy
+    fn state_info(&self) -> StateInfo {
+        match self.sm.try_lock() {
+            Ok(sm) => StateInfo::State(sm.state_name()),
+            Err(_) => StateInfo::Busy,
+        }
+    }
+}
+
+/// A single State-Machine stored in the [`StateMachineMap`]
+#[derive(Debug, PartialEq)]
+pub struct MachineSummary {
+    pub message_id: MessageId,
+    pub guild_id: GuildId,
+    pub state: StateInfo,
+}
 
 pub struct StateMachineMap {
-    map: Map<MessageId, Mutex<MessageStateMachine<(), ()>>>,
-    running_rounds: Mutex<BTreeMap<GuildId, Option<MessageId>>>,
+    map: Map<MessageId, Entry>,
+    /// All the Rounds per Guild, a Round that has been reserved but not yet marked is stored as
+    /// `None`
+    running_rounds: Mutex<BTreeMap<GuildId, Vec<Option<MessageId>>>>,
+    /// Other Messages that are also handled by a State-Machine, mapped to the MessageID under
+    /// which the State-Machine is stored
+    aliases: Map<MessageId, MessageId>,
+    /// The maximum Number of Rounds that can be running on a single Guild at the same time
+    round_limit: usize,
+    /// Guild-scoped Locks that serialize the Creation of new Rounds
+    creation_locks: Mutex<BTreeMap<GuildId, Arc<Mutex<()>>>>,
+    /// The Snapshots of the Participants that the running Rounds published, stored under the
+    /// same MessageID as their State-Machine
+    participants: Map<MessageId, Arc<Participants>>,
+    /// Notified about the Outcome of every Transition
+    observer: Option<Box<dyn TransitionObserver + Send + Sync>>,
+}
+
+impl Default for StateMachineMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Updates the Metric for the running Rounds based on the given Rounds
+fn update_round_metric(rounds: &BTreeMap<GuildId, Vec<Option<MessageId>>>) {
+    let count: usize = rounds.values().map(|r| r.len()).sum();
+    crate::metrics::RUNNING_ROUNDS.set(count as i64);
+}
+
+/// Removes the Round with the given MessageID from the Guild
+fn remove_round(
+    rounds: &mut BTreeMap<GuildId, Vec<Option<MessageId>>>,
+    guild: GuildId,
+    message_id: MessageId,
+) {
+    let guild_rounds = match rounds.get_mut(&guild) {
+        Some(r) => r,
+        None => return,
+    };
+
+    guild_rounds.retain(|r| r != &Some(message_id));
+
+    if guild_rounds.is_empty() {
+        rounds.remove(&guild);
+    }
+
+    update_round_metric(rounds);
 }
 
 impl StateMachineMap {
+    /// Creates a new Map that only allows a single running Round per Guild
     pub fn new() -> Self {
+        Self::with_round_limit(1)
+    }
+
+    /// Creates a new Map that allows up to `round_limit` Rounds to be running on a single Guild
+    /// at the same time
+    pub fn with_round_limit(round_limit: usize) -> Self {
         Self {
             map: Map::new(),
             running_rounds: Mutex::new(BTreeMap::new()),
+            aliases: Map::new(),
+            round_limit,
+            creation_locks: Mutex::new(BTreeMap::new()),
+            participants: Map::new(),
+            observer: None,
         }
     }
 
-    pub async fn reserve_running_game(&self, guild: GuildId) -> Result<(), ()> {
+    /// Notifies the given Observer about the Outcome of every Transition
+    pub fn with_observer<O>(mut self, observer: O) -> Self
+    where
+        O: TransitionObserver + Send + Sync + 'static,
+    {
+        self.observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Creates a new Round for the Guild using the given Function, while holding a Lock for the
+    /// Guild. The Round is reserved before calling `create` and then stored and marked once it
+    /// was created successfully, if `create` fails the Reservation is released again so the
+    /// Guild is never left with a Reservation for a Round that does not exist
+    pub async fn create_round<F, Fut, E>(
+        &self,
+        guild: GuildId,
+        create: F,
+    ) -> Result<MessageId, CreateRoundError<E>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<MessageStateMachine<(), ()>, E>>,
+    {
+        let guild_lock = {
+            let mut locks = self.creation_locks.lock().await;
+            locks.entry(guild).or_default().clone()
+        };
+        let _guard = guild_lock.lock().await;
+
+        self.reserve_running_game(guild)
+            .await
+            .map_err(CreateRoundError::Reserve)?;
+
+        match create().await {
+            Ok(sm) => {
+                let message_id = sm.message_id();
+                self.add(message_id, sm);
+
+                // Clearing a Reservation does not wait for the Guild-Lock, as it is used to get
+                // rid of Reservations whose Creation is stuck
+                if self.mark_running_game(guild, message_id).await.is_err() {
+                    self.remove(message_id);
+                    return Err(CreateRoundError::ReservationCleared);
+                }
+
+                Ok(message_id)
+            }
+            Err(e) => {
+                self.release_reservation(guild).await;
+                Err(CreateRoundError::Create(e))
+            }
+        }
+    }
+
+    /// Attempts to reserve a new Round for the Guild, which will later be marked using
+    /// [`mark_running_game`](Self::mark_running_game)
+    ///
+    /// # Returns
+    /// `Ok` if the Guild was below the Round-Limit and the Round is now reserved
+    /// `Err` if the Guild already reached the Round-Limit
+    pub async fn reserve_running_game(&self, guild: GuildId) -> Result<(), ReserveError> {
         let mut current_rounds = self.running_rounds.lock().await;
 
-        if current_rounds.contains_key(&guild) {
-            Err(())
-        } else {
-            current_rounds.insert(guild, None);
-            Ok(())
+        let guild_rounds = current_rounds.entry(guild).or_default();
+        if guild_rounds.len() >= self.round_limit {
+            return Err(ReserveError::LimitReached {
+                limit: self.round_limit,
+            });
         }
+
+        guild_rounds.push(None);
+        update_round_metric(&current_rounds);
+        Ok(())
     }
-    /// Checks if the given Guild is registered as having an ongoing Round,
-    /// if there is None we register it with the MessageID to allow for easier
-    /// removal later on
+    /// Registers the MessageID for a previously reserved Round of the Guild, to allow for
+    /// easier removal later on
     ///
     /// # Returns
-    /// `Ok` if the Guild had no previously running Round and is now marked
-    /// `Err` the Guild already has a running Round
+    /// `Ok` if the Guild had a reserved Round, which is now marked
+    /// `Err` the Guild had no reserved Round
     pub async fn mark_running_game(&self, guild: GuildId, message_id: MessageId) -> Result<(), ()> {
         let mut current_rounds = self.running_rounds.lock().await;
 
-        match current_rounds.get_mut(&guild) {
+        let reserved = current_rounds
+            .get_mut(&guild)
+            .and_then(|rounds| rounds.iter_mut().find(|r| r.is_none()));
+
+        match reserved {
             Some(internal) => {
                 *internal = Some(message_id);
                 Ok(())
@@ -50,94 +329,927 @@ impl StateMachineMap {
             None => Err(()),
         }
     }
-    /// Unmarks the given Guild and therefore allows for new Rounds to be started
-    pub async fn unmark_running_game(&self, guild: GuildId, message_id: MessageId) {
+    /// Releases a Reservation of the Guild that has not been marked yet
+    async fn release_reservation(&self, guild: GuildId) {
         let mut current_rounds = self.running_rounds.lock().await;
 
-        let raw_val = match current_rounds.get(&guild) {
-            Some(v) => v,
+        let guild_rounds = match current_rounds.get_mut(&guild) {
+            Some(r) => r,
             None => return,
         };
+        if let Some(index) = guild_rounds.iter().position(|r| r.is_none()) {
+            guild_rounds.remove(index);
+        }
+        if guild_rounds.is_empty() {
+            current_rounds.remove(&guild);
+        }
 
-        match raw_val {
-            Some(val) if *val == message_id => {}
-            _ => return,
-        };
+        update_round_metric(&current_rounds);
+    }
+
+    /// Unmarks the given Round and therefore allows for new Rounds to be started
+    pub async fn unmark_running_game(&self, guild: GuildId, message_id: MessageId) {
+        let mut current_rounds = self.running_rounds.lock().await;
+
+        remove_round(&mut current_rounds, guild, message_id);
+    }
+
+    /// The MessageIDs of all the marked Rounds on the given Guild
+    pub async fn running_games(&self, guild: GuildId) -> Vec<MessageId> {
+        let current_rounds = self.running_rounds.lock().await;
+
+        current_rounds
+            .get(&guild)
+            .map(|rounds| rounds.iter().flatten().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// All the running Rounds across all Guilds, together with the Guild they belong to
+    pub async fn all_running_games(&self) -> Vec<(GuildId, MessageId)> {
+        let current_rounds = self.running_rounds.lock().await;
 
-        current_rounds.remove(&guild);
+        current_rounds
+            .iter()
+            .flat_map(|(guild, rounds)| rounds.iter().flatten().map(move |id| (*guild, *id)))
+            .collect()
     }
 
-    pub fn get_map(&self) -> &Map<MessageId, Mutex<MessageStateMachine<(), ()>>> {
-        &self.map
+    /// All the Rounds of the Guild that count towards its Round-Limit
+    pub async fn reservations(&self, guild: GuildId) -> Vec<Reservation> {
+        let current_rounds = self.running_rounds.lock().await;
+
+        current_rounds
+            .get(&guild)
+            .map(|rounds| {
+                rounds
+                    .iter()
+                    .map(|r| match r {
+                        Some(id) => Reservation::Active(*id),
+                        None => Reservation::Pending,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Forcefully releases the given Reservation of the Guild, an active Round is also removed
+    /// together with its State-Machine so it no longer receives any Events
+    ///
+    /// # Returns
+    /// Whether or not the Guild had such a Reservation
+    pub async fn clear_reservation(&self, guild: GuildId, reservation: Reservation) -> bool {
+        match reservation {
+            Reservation::Pending => {
+                let has_pending = self
+                    .reservations(guild)
+                    .await
+                    .contains(&Reservation::Pending);
+                if has_pending {
+                    self.release_reservation(guild).await;
+                }
+                has_pending
+            }
+            Reservation::Active(message_id) => {
+                let mut current_rounds = self.running_rounds.lock().await;
+
+                let exists = current_rounds
+                    .get(&guild)
+                    .map(|rounds| rounds.contains(&Some(message_id)))
+                    .unwrap_or(false);
+                if exists {
+                    remove_round(&mut current_rounds, guild, message_id);
+                    self.remove(message_id);
+                }
+                exists
+            }
+        }
+    }
+
+    /// Registers the `alias` Message, so that all Updates for it are forwarded to the
+    /// State-Machine stored under `target`. The `target` may itself be an Alias
+    pub fn add_alias(&self, alias: MessageId, target: MessageId) {
+        let target = self.resolve(target);
+        self.aliases.insert(alias, target);
     }
 
+    /// Resolves the MessageID under which the State-Machine for the given Message is stored
+    fn resolve(&self, message_id: MessageId) -> MessageId {
+        match self.aliases.get(&message_id) {
+            Some(entry) => *entry.val(),
+            None => message_id,
+        }
+    }
+
+    /// Removes the State-Machine for the Message together with everything that belongs to it
+    fn remove(&self, message_id: MessageId) {
+        self.map.remove(&message_id);
+        self.remove_aliases(message_id);
+        self.participants.remove(&message_id);
+    }
+
+    /// Discards the State-Machine for the Message before it finished on its own, returns whether
+    /// there still was a State-Machine for the Message.
+    ///
+    /// This is only meant for short-lived Dialogs, Rounds should be stopped through their
+    /// State-Machine so that they are also cleaned up on the Guild
+    pub fn discard(&self, message_id: MessageId) -> bool {
+        let message_id = self.resolve(message_id);
+        let existed = self.map.get(&message_id).is_some();
+        self.remove(message_id);
+
+        existed
+    }
+
+    /// Stores the current Participants of the Round handled by the State-Machine for the given
+    /// Message, replacing the previous Snapshot
+    pub fn publish_participants(&self, message_id: MessageId, participants: Participants) {
+        let message_id = self.resolve(message_id);
+        self.participants.insert(message_id, Arc::new(participants));
+    }
+
+    /// The most recently published Participants of the Round handled by the State-Machine for the
+    /// given Message, if it is running
+    pub fn participants(&self, message_id: MessageId) -> Option<Arc<Participants>> {
+        let message_id = self.resolve(message_id);
+        self.participants
+            .get(&message_id)
+            .map(|entry| entry.val().clone())
+    }
+
+    /// Removes all the Aliases that point to the given Message
+    fn remove_aliases(&self, target: MessageId) {
+        let aliases: Vec<MessageId> = self
+            .aliases
+            .iter()
+            .filter(|entry| *entry.val() == target)
+            .map(|entry| *entry.key())
+            .collect();
+
+        for alias in aliases {
+            self.aliases.remove(&alias);
+        }
+    }
+
+    /// The Guild of the State-Machine that handles the given Message, if there is one
+    pub fn guild_of(&self, message_id: MessageId) -> Option<GuildId> {
+        let message_id = self.resolve(message_id);
+        self.map.get(&message_id).map(|entry| entry.val().guild_id)
+    }
+
+    /// Reports the current State of the State-Machine for the given Message, without waiting
+    /// for it if it is currently processing an Event
+    pub fn state_info(&self, message_id: MessageId) -> StateInfo {
+        let message_id = self.resolve(message_id);
+        match self.map.get(&message_id) {
+            Some(entry) => entry.val().state_info(),
+            None => StateInfo::NotFound,
+        }
+    }
+
+    /// Lists every State-Machine across all Guilds, ordered by their Guild and Message, which
+    /// allows for spotting State-Machines that are stuck or were never cleaned up
+    pub fn summaries(&self) -> Vec<MachineSummary> {
+        let mut summaries: Vec<MachineSummary> = self
+            .map
+            .iter()
+            .map(|entry| MachineSummary {
+                message_id: *entry.key(),
+                guild_id: entry.val().guild_id,
+                state: entry.val().state_info(),
+            })
+            .collect();
+        summaries.sort_by_key(|s| (s.guild_id, s.message_id));
+
+        summaries
+    }
+
+    /// Applies the Event in the Context to the State-Machine of the given Message.
+    ///
+    /// # Ordering
+    /// All the Events for a single State-Machine are applied strictly in the Order in which
+    /// `update` was called for them, no Event is dropped or reordered while the State-Machine
+    /// exists. If the State-Machine is currently busy with another Event, the new Event is queued
+    /// and applied by the Task that is currently processing the State-Machine, so this never
+    /// waits for other Events to be processed.
     pub async fn update(&self, message_id: MessageId, context: Context) {
-        let sm_mutex = match self.map.get(&message_id) {
+        let message_id = self.resolve(message_id);
+        let entry = match self.map.get(&message_id) {
             Some(s) => s,
             None => return,
         };
+        let entry = entry.val();
+
+        entry.pending.lock().unwrap().push_back(context);
 
-        let value = sm_mutex.val();
-        let mut sm = value.lock().await;
+        loop {
+            // If another Task currently holds the State-Machine, it will also apply the Event
+            // we just queued before releasing it
+            let mut sm = match entry.sm.try_lock() {
+                Ok(s) => s,
+                Err(_) => return,
+            };
+
+            loop {
+                let next = entry.pending.lock().unwrap().pop_front();
+                let context = match next {
+                    Some(c) => c,
+                    None => break,
+                };
 
-        self.update_inner(&mut sm, message_id, context).await;
+                let outcome = self.update_inner(&mut sm, message_id, context).await;
+                if outcome != TransitionOutcome::NoTransition {
+                    return;
+                }
+            }
+
+            drop(sm);
+
+            // An Event could have been queued after we checked the Queue the last time but
+            // before we released the State-Machine, in which case its Task could not acquire the
+            // State-Machine and we need to process it
+            if entry.pending.lock().unwrap().is_empty() {
+                return;
+            }
+        }
     }
-    pub async fn try_lock_update(&self, message_id: MessageId, context: Context) -> Result<(), ()> {
-        let sm_mutex = match self.map.get(&message_id) {
-            Some(s) => s,
-            None => return Ok(()),
-        };
 
-        let value = sm_mutex.val();
-        let mut sm = match value.try_lock() {
-            Ok(s) => s,
-            Err(_) => return Err(()),
-        };
+    /// Applies the Event in the Context to the State-Machine of the given Message and waits
+    /// until it has been applied, unlike `update` which returns right away if the State-Machine
+    /// is busy. Events that were queued before are applied first, so the Order of all the Events
+    /// is kept.
+    ///
+    /// Returns the Outcome of the Transition for this Event, or `None` if there is no
+    /// State-Machine for the Message (anymore) to apply it to
+    pub async fn drive(
+        &self,
+        message_id: MessageId,
+        context: Context,
+    ) -> Option<TransitionOutcome> {
+        let message_id = self.resolve(message_id);
+        let entry = self.map.get(&message_id)?;
+        let entry = entry.val();
 
-        self.update_inner(&mut sm, message_id, context).await;
-        Ok(())
+        let mut sm = entry.sm.lock().await;
+        loop {
+            let next = entry.pending.lock().unwrap().pop_front();
+            let queued = match next {
+                Some(c) => c,
+                None => break,
+            };
+
+            let outcome = self.update_inner(&mut sm, message_id, queued).await;
+            if outcome != TransitionOutcome::NoTransition {
+                return None;
+            }
+        }
+
+        Some(self.update_inner(&mut sm, message_id, context).await)
+    }
+
+    /// The Number of State-Machines that are currently stored, not counting their Aliases
+    pub fn len(&self) -> usize {
+        self.map.iter().count()
+    }
+
+    /// Whether there are no State-Machines stored at all
+    pub fn is_empty(&self) -> bool {
+        self.map.iter().next().is_none()
     }
 
+    /// Whether the Guild has a Round that is marked as running, Reservations are not counted
+    pub async fn has_running_round(&self, guild: GuildId) -> bool {
+        !self.running_games(guild).await.is_empty()
+    }
+
+    /// Applies the Context to the State-Machine and returns the Outcome, if the Outcome is not
+    /// `NoTransition` the State-Machine is finished and was therefore removed
     async fn update_inner(
         &self,
         sm: &mut MessageStateMachine<(), ()>,
         message_id: MessageId,
         context: Context,
-    ) {
-        match sm.transition(context, ()).await.as_ref() {
+    ) -> TransitionOutcome {
+        let result = sm.transition(context, ()).await;
+
+        let outcome = match result.as_ref() {
+            TransitionResult::NoTransition => TransitionOutcome::NoTransition,
+            TransitionResult::Done(_) => TransitionOutcome::Done,
+            TransitionResult::Error(e) => TransitionOutcome::Error { kind: e.kind() },
+        };
+        if let Some(observer) = self.observer.as_ref() {
+            observer.observe(message_id, outcome);
+        }
+
+        match result.as_ref() {
             TransitionResult::NoTransition => {}
             TransitionResult::Done(_) => {
-                self.map.remove(&message_id);
+                self.remove(message_id);
 
-                let guild_id = sm.guild_id();
-                let msg_id = sm.message_id();
                 let mut current_rounds = self.running_rounds.lock().await;
-                match current_rounds.get(&guild_id) {
-                    Some(running_msg_id) if running_msg_id == &Some(msg_id) => {
-                        current_rounds.remove(&guild_id);
-                    }
-                    _ => {}
-                };
+                remove_round(&mut current_rounds, sm.guild_id(), sm.message_id());
             }
             TransitionResult::Error(e) => {
-                tracing::error!("Transitioning: {:?}", e);
+                let kind = e.kind();
+                tracing::error!(kind, "Transitioning: {:?}", e);
+                crate::metrics::TRANSITION_ERRORS
+                    .with_label_values(&[kind])
+                    .inc();
 
-                self.map.remove(&message_id);
+                self.remove(message_id);
 
-                let guild_id = sm.guild_id();
-                let msg_id = sm.message_id();
                 let mut current_rounds = self.running_rounds.lock().await;
-                match current_rounds.get(&guild_id) {
-                    Some(running_msg_id) if running_msg_id == &Some(msg_id) => {
-                        current_rounds.remove(&guild_id);
-                    }
-                    _ => {}
-                };
+                remove_round(&mut current_rounds, sm.guild_id(), sm.message_id());
             }
         };
+
+        outcome
+    }
+
+    /// Queues the Event in the Context again, in front of all the other Events that are waiting
+    /// for the State-Machine, so it is applied right after the current Transition. This is meant
+    /// for States that only pass the State-Machine on to the next State, as they would otherwise
+    /// swallow the Event that moved them along
+    pub fn requeue(&self, message_id: MessageId, context: Context) {
+        let message_id = self.resolve(message_id);
+        if let Some(entry) = self.map.get(&message_id) {
+            entry.val().pending.lock().unwrap().push_front(context);
+        }
     }
 
     pub fn add(&self, message_id: MessageId, sm: MessageStateMachine<(), ()>) {
-        self.map.insert(message_id, Mutex::new(sm));
+        self.map.insert(
+            message_id,
+            Entry {
+                guild_id: sm.guild_id(),
+                sm: Mutex::new(sm),
+                pending: std::sync::Mutex::new(VecDeque::new()),
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reserve_single() {
+        let map = StateMachineMap::new();
+
+        assert_eq!(Ok(()), map.reserve_running_game(GuildId(13)).await);
+        assert_eq!(
+            Err(ReserveError::LimitReached { limit: 1 }),
+            map.reserve_running_game(GuildId(13)).await
+        );
+        assert_eq!(Ok(()), map.reserve_running_game(GuildId(14)).await);
+    }
+
+    #[tokio::test]
+    async fn reserve_limit() {
+        let map = StateMachineMap::with_round_limit(2);
+
+        assert_eq!(Ok(()), map.reserve_running_game(GuildId(13)).await);
+        assert_eq!(Ok(()), map.reserve_running_game(GuildId(13)).await);
+        assert_eq!(
+            Err(ReserveError::LimitReached { limit: 2 }),
+            map.reserve_running_game(GuildId(13)).await
+        );
+    }
+
+    #[tokio::test]
+    async fn running_games_marked_only() {
+        let map = StateMachineMap::with_round_limit(2);
+
+        map.reserve_running_game(GuildId(13)).await.unwrap();
+        map.mark_running_game(GuildId(13), MessageId(1))
+            .await
+            .unwrap();
+        map.reserve_running_game(GuildId(13)).await.unwrap();
+
+        assert_eq!(vec![MessageId(1)], map.running_games(GuildId(13)).await);
+        assert_eq!(
+            Vec::<MessageId>::new(),
+            map.running_games(GuildId(14)).await
+        );
+    }
+
+    #[test]
+    fn aliases() {
+        let map = StateMachineMap::new();
+
+        map.add_alias(MessageId(2), MessageId(1));
+        map.add_alias(MessageId(3), MessageId(1));
+        map.add_alias(MessageId(5), MessageId(4));
+
+        assert_eq!(MessageId(1), map.resolve(MessageId(2)));
+        assert_eq!(MessageId(1), map.resolve(MessageId(1)));
+        assert_eq!(MessageId(4), map.resolve(MessageId(5)));
+
+        map.remove_aliases(MessageId(1));
+
+        assert_eq!(MessageId(2), map.resolve(MessageId(2)));
+        assert_eq!(MessageId(3), map.resolve(MessageId(3)));
+        assert_eq!(MessageId(4), map.resolve(MessageId(5)));
+    }
+
+    #[tokio::test]
+    async fn reserve_after_unmark() {
+        let map = StateMachineMap::with_round_limit(2);
+
+        map.reserve_running_game(GuildId(13)).await.unwrap();
+        map.mark_running_game(GuildId(13), MessageId(1))
+            .await
+            .unwrap();
+        map.reserve_running_game(GuildId(13)).await.unwrap();
+        map.mark_running_game(GuildId(13), MessageId(2))
+            .await
+            .unwrap();
+
+        assert!(map.reserve_running_game(GuildId(13)).await.is_err());
+
+        map.unmark_running_game(GuildId(13), MessageId(1)).await;
+
+        assert_eq!(Ok(()), map.reserve_running_game(GuildId(13)).await);
+    }
+
+    #[test]
+    fn state_info() {
+        use crate::messages::SingleState;
+
+        let map = StateMachineMap::new();
+        let sm =
+            SingleState::new(
+                |_: Context, _: ()| async move { TransitionResult::<(), _>::NoTransition },
+            )
+            .named("Waiting");
+        map.add(
+            MessageId(1),
+            MessageStateMachine::new(GuildId(13), MessageId(1), sm),
+        );
+        map.add_alias(MessageId(2), MessageId(1));
+
+        assert_eq!(
+            StateInfo::State(Some("Waiting")),
+            map.state_info(MessageId(1))
+        );
+        assert_eq!(
+            StateInfo::State(Some("Waiting")),
+            map.state_info(MessageId(2))
+        );
+        assert_eq!(StateInfo::NotFound, map.state_info(MessageId(3)));
+
+        assert_eq!(Some(GuildId(13)), map.guild_of(MessageId(2)));
+        assert_eq!(None, map.guild_of(MessageId(3)));
+
+        // An Alias of an Alias is forwarded to the original State-Machine
+        map.add_alias(MessageId(4), MessageId(2));
+        assert_eq!(
+            StateInfo::State(Some("Waiting")),
+            map.state_info(MessageId(4))
+        );
+    }
+
+    #[test]
+    fn discard() {
+        let map = StateMachineMap::new();
+        map.add(MessageId(1), waiting_sm(GuildId(13), MessageId(1)));
+        map.add_alias(MessageId(2), MessageId(1));
+
+        assert!(map.discard(MessageId(2)));
+        assert_eq!(StateInfo::NotFound, map.state_info(MessageId(1)));
+        assert!(!map.discard(MessageId(1)));
+    }
+
+    #[tokio::test]
+    async fn ordered_updates() {
+        use crate::messages::WithState;
+
+        let map = StateMachineMap::new();
+        let applied = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let sm = WithState::new(
+            applied.clone(),
+            |context: Context, applied: Arc<std::sync::Mutex<Vec<GuildId>>>, _: ()| async move {
+                applied.lock().unwrap().push(context.guild_id());
+                (TransitionResult::<(), _>::NoTransition, applied)
+            },
+        );
+        map.add(
+            MessageId(1),
+            MessageStateMachine::new(GuildId(13), MessageId(1), sm),
+        );
+
+        // Simulate the State-Machine being busy while the first Events arrive
+        {
+            let entry = map.map.get(&MessageId(1)).unwrap();
+            let _busy = entry.val().sm.lock().await;
+
+            for id in 1..4 {
+                map.update(MessageId(1), Context::new(None, None, None, GuildId(id)))
+                    .await;
+            }
+            assert!(applied.lock().unwrap().is_empty());
+        }
+
+        map.update(MessageId(1), Context::new(None, None, None, GuildId(4)))
+            .await;
+
+        assert_eq!(
+            vec![GuildId(1), GuildId(2), GuildId(3), GuildId(4)],
+            *applied.lock().unwrap()
+        );
+    }
+
+    #[test]
+    fn summaries() {
+        let map = StateMachineMap::new();
+        assert!(map.summaries().is_empty());
+
+        map.add(MessageId(3), waiting_sm(GuildId(14), MessageId(3)));
+        map.add(MessageId(2), waiting_sm(GuildId(13), MessageId(2)));
+        map.add(MessageId(1), waiting_sm(GuildId(14), MessageId(1)));
+        // Aliases are not listed on their own
+        map.add_alias(MessageId(4), MessageId(1));
+
+        let listed: Vec<(GuildId, MessageId)> = map
+            .summaries()
+            .into_iter()
+            .map(|s| (s.guild_id, s.message_id))
+            .collect();
+        assert_eq!(
+            vec![
+                (GuildId(13), MessageId(2)),
+                (GuildId(14), MessageId(1)),
+                (GuildId(14), MessageId(3)),
+            ],
+            listed
+        );
+    }
+
+    fn waiting_sm(guild: GuildId, message_id: MessageId) -> MessageStateMachine<(), ()> {
+        use crate::messages::SingleState;
+
+        let sm =
+            SingleState::new(
+                |_: Context, _: ()| async move { TransitionResult::<(), _>::NoTransition },
+            );
+        MessageStateMachine::new(guild, message_id, sm)
+    }
+
+    #[tokio::test]
+    async fn requeued_event_reaches_next_state() {
+        use crate::messages::{Event, SingleState};
+
+        let map = Arc::new(StateMachineMap::new());
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let passing_map = map.clone();
+        let sm = SingleState::new(move |context: Context, _: ()| {
+            // Only passes the Event on, like a State that is skipped
+            passing_map.requeue(MessageId(1), context);
+            async move { TransitionResult::Done(()) }
+        })
+        .chain(SingleState::new(move |context: Context, _: ()| {
+            if let Some(Event::MemberLeft { user }) = context.event() {
+                tx.send(*user).unwrap();
+            }
+            async move { TransitionResult::<(), _>::NoTransition }
+        }));
+        map.add(
+            MessageId(1),
+            MessageStateMachine::new(GuildId(13), MessageId(1), sm),
+        );
+
+        let context = Context::new(
+            None,
+            Some(Event::MemberLeft { user: UserId(2) }),
+            None,
+            GuildId(13),
+        );
+        map.update(MessageId(1), context).await;
+
+        assert_eq!(Some(UserId(2)), rx.recv().await);
+    }
+
+    #[tokio::test]
+    async fn create_round_serialized() {
+        let map = Arc::new(StateMachineMap::new());
+
+        let first = {
+            let map = map.clone();
+            tokio::spawn(async move {
+                map.create_round(GuildId(13), || async {
+                    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                    Ok::<_, ()>(waiting_sm(GuildId(13), MessageId(1)))
+                })
+                .await
+            })
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+
+        let second = map
+            .create_round(GuildId(13), || async {
+                Ok::<_, ()>(waiting_sm(GuildId(13), MessageId(2)))
+            })
+            .await;
+
+        assert_eq!(Ok(MessageId(1)), first.await.unwrap());
+        assert_eq!(
+            Err(CreateRoundError::Reserve(ReserveError::LimitReached {
+                limit: 1
+            })),
+            second
+        );
+        assert_eq!(vec![MessageId(1)], map.running_games(GuildId(13)).await);
+    }
+
+    #[tokio::test]
+    async fn create_round_failure() {
+        let map = StateMachineMap::new();
+
+        let result = map
+            .create_round(GuildId(13), || async {
+                Err::<MessageStateMachine<(), ()>, _>("failed")
+            })
+            .await;
+        assert_eq!(Err(CreateRoundError::Create("failed")), result);
+
+        assert!(map.running_rounds.lock().await.get(&GuildId(13)).is_none());
+        assert_eq!(Ok(()), map.reserve_running_game(GuildId(13)).await);
+    }
+
+    #[tokio::test]
+    async fn create_round_after_failure() {
+        let map = StateMachineMap::new();
+
+        // A transient Error while sending the Setup-Message should not lock the Guild out
+        let failed = map
+            .create_round(GuildId(13), || async {
+                Err::<MessageStateMachine<(), ()>, _>(serenity::Error::Other("Sending Message"))
+            })
+            .await;
+        assert!(matches!(failed, Err(CreateRoundError::Create(_))));
+
+        let result = map
+            .create_round(GuildId(13), || async {
+                Ok::<_, serenity::Error>(waiting_sm(GuildId(13), MessageId(1)))
+            })
+            .await;
+        assert!(matches!(result, Ok(MessageId(1))));
+        assert_eq!(vec![MessageId(1)], map.running_games(GuildId(13)).await);
+    }
+
+    #[tokio::test]
+    async fn create_round_reservation_cleared() {
+        let map = StateMachineMap::new();
+
+        let result = map
+            .create_round(GuildId(13), || async {
+                map.clear_reservation(GuildId(13), Reservation::Pending)
+                    .await;
+                Ok::<_, ()>(waiting_sm(GuildId(13), MessageId(1)))
+            })
+            .await;
+
+        assert!(matches!(result, Err(CreateRoundError::ReservationCleared)));
+        assert_eq!(StateInfo::NotFound, map.state_info(MessageId(1)));
+        assert_eq!(
+            Vec::<Reservation>::new(),
+            map.reservations(GuildId(13)).await
+        );
+    }
+
+    #[tokio::test]
+    async fn clear_reservations() {
+        let map = StateMachineMap::with_round_limit(3);
+
+        map.create_round(GuildId(13), || async {
+            Ok::<_, ()>(waiting_sm(GuildId(13), MessageId(1)))
+        })
+        .await
+        .unwrap();
+        map.reserve_running_game(GuildId(13)).await.unwrap();
+
+        assert_eq!(
+            vec![Reservation::Active(MessageId(1)), Reservation::Pending],
+            map.reservations(GuildId(13)).await
+        );
+
+        assert!(
+            map.clear_reservation(GuildId(13), Reservation::Pending)
+                .await
+        );
+        assert!(
+            !map.clear_reservation(GuildId(13), Reservation::Pending)
+                .await
+        );
+        assert!(
+            map.clear_reservation(GuildId(13), Reservation::Active(MessageId(1)))
+                .await
+        );
+
+        assert_eq!(
+            Vec::<Reservation>::new(),
+            map.reservations(GuildId(13)).await
+        );
+        assert_eq!(StateInfo::NotFound, map.state_info(MessageId(1)));
+    }
+
+    #[tokio::test]
+    async fn all_running_games_across_guilds() {
+        let map = StateMachineMap::new();
+
+        map.reserve_running_game(GuildId(13)).await.unwrap();
+        map.mark_running_game(GuildId(13), MessageId(1))
+            .await
+            .unwrap();
+        map.reserve_running_game(GuildId(14)).await.unwrap();
+        map.mark_running_game(GuildId(14), MessageId(2))
+            .await
+            .unwrap();
+        map.reserve_running_game(GuildId(15)).await.unwrap();
+
+        assert_eq!(
+            vec![(GuildId(13), MessageId(1)), (GuildId(14), MessageId(2))],
+            map.all_running_games().await
+        );
+    }
+
+    #[tokio::test]
+    async fn mark_without_reserve() {
+        let map = StateMachineMap::new();
+
+        assert_eq!(
+            Err(()),
+            map.mark_running_game(GuildId(13), MessageId(1)).await
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_transition_counted() {
+        use crate::messages::{SingleState, TransitionError};
+
+        let map = StateMachineMap::new();
+        let sm = SingleState::new(|_: Context, _: ()| async move {
+            TransitionResult::<(), _>::Error(
+                TransitionError::WithReason {
+                    reason: "test".to_string(),
+                }
+                .arced(),
+            )
+        });
+        map.add(
+            MessageId(1),
+            MessageStateMachine::new(GuildId(13), MessageId(1), sm),
+        );
+
+        let counter = crate::metrics::TRANSITION_ERRORS.with_label_values(&["WithReason"]);
+        let before = counter.get();
+
+        map.update(MessageId(1), Context::default()).await;
+
+        assert!(counter.get() > before);
+        assert_eq!(StateInfo::NotFound, map.state_info(MessageId(1)));
+    }
+
+    #[tokio::test]
+    async fn participants_snapshot() {
+        let map = StateMachineMap::new();
+        map.add(MessageId(1), waiting_sm(GuildId(13), MessageId(1)));
+        map.add_alias(MessageId(2), MessageId(1));
+
+        assert_eq!(None, map.participants(MessageId(1)));
+
+        let mut participants = Participants::new();
+        participants.insert(
+            UserId(3),
+            crate::roles::WereWolfRoleConfig::new("Seer", "", false, false, Vec::new())
+                .to_instance(&mut || unreachable!()),
+        );
+        map.publish_participants(MessageId(2), participants.clone());
+
+        assert_eq!(
+            Some(&participants),
+            map.participants(MessageId(1)).as_deref()
+        );
+        assert_eq!(
+            Some(&participants),
+            map.participants(MessageId(2)).as_deref()
+        );
+
+        map.remove(MessageId(1));
+        assert_eq!(None, map.participants(MessageId(1)));
+    }
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        outcomes: Arc<std::sync::Mutex<Vec<(MessageId, TransitionOutcome)>>>,
+    }
+
+    impl TransitionObserver for RecordingObserver {
+        fn observe(&self, message_id: MessageId, outcome: TransitionOutcome) {
+            self.outcomes.lock().unwrap().push((message_id, outcome));
+        }
+    }
+
+    #[tokio::test]
+    async fn observer_sees_every_outcome() {
+        use crate::messages::{SingleState, TransitionError};
+
+        let observer = RecordingObserver::default();
+        let outcomes = observer.outcomes.clone();
+        let map = StateMachineMap::new().with_observer(observer);
+
+        // Ignores the first Event and finishes with the second one
+        let mut calls = 0;
+        let done_sm = SingleState::new(move |_: Context, _: ()| {
+            calls += 1;
+            let finished = calls > 1;
+            async move {
+                if finished {
+                    TransitionResult::Done(())
+                } else {
+                    TransitionResult::NoTransition
+                }
+            }
+        });
+        map.add(
+            MessageId(1),
+            MessageStateMachine::new(GuildId(13), MessageId(1), done_sm),
+        );
+        let failing_sm = SingleState::new(|_: Context, _: ()| async move {
+            TransitionResult::<(), _>::Error(TransitionError::Serenity.arced())
+        });
+        map.add(
+            MessageId(2),
+            MessageStateMachine::new(GuildId(13), MessageId(2), failing_sm),
+        );
+
+        map.update(MessageId(1), Context::default()).await;
+        map.update(MessageId(2), Context::default()).await;
+        map.update(MessageId(1), Context::default()).await;
+        // There is no State-Machine left to observe
+        map.update(MessageId(1), Context::default()).await;
+
+        assert_eq!(
+            vec![
+                (MessageId(1), TransitionOutcome::NoTransition),
+                (MessageId(2), TransitionOutcome::Error { kind: "Serenity" }),
+                (MessageId(1), TransitionOutcome::Done),
+            ],
+            *outcomes.lock().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn drive_until_done() {
+        use crate::messages::SingleState;
+
+        let map = StateMachineMap::new();
+        assert!(map.is_empty());
+
+        // Ignores the first Event and finishes with the second one
+        let mut calls = 0;
+        let sm = SingleState::new(move |_: Context, _: ()| {
+            calls += 1;
+            let finished = calls > 1;
+            async move {
+                if finished {
+                    TransitionResult::Done(())
+                } else {
+                    TransitionResult::NoTransition
+                }
+            }
+        });
+        map.reserve_running_game(GuildId(13)).await.unwrap();
+        map.add(
+            MessageId(1),
+            MessageStateMachine::new(GuildId(13), MessageId(1), sm),
+        );
+        map.add(MessageId(2), waiting_sm(GuildId(14), MessageId(2)));
+        map.mark_running_game(GuildId(13), MessageId(1))
+            .await
+            .unwrap();
+
+        assert_eq!(2, map.len());
+        assert!(map.has_running_round(GuildId(13)).await);
+        assert!(!map.has_running_round(GuildId(14)).await);
+
+        assert_eq!(
+            Some(TransitionOutcome::NoTransition),
+            map.drive(MessageId(1), Context::default()).await
+        );
+        assert_ne!(StateInfo::NotFound, map.state_info(MessageId(1)));
+
+        assert_eq!(
+            Some(TransitionOutcome::Done),
+            map.drive(MessageId(1), Context::default()).await
+        );
+
+        // The finished State-Machine is removed together with its Round
+        assert_eq!(1, map.len());
+        assert_eq!(StateInfo::NotFound, map.state_info(MessageId(1)));
+        assert!(!map.has_running_round(GuildId(13)).await);
+        assert_eq!(None, map.drive(MessageId(1), Context::default()).await);
     }
 }
@@ -1,13 +1,21 @@
 use async_trait::async_trait;
-use serenity::model::id::GuildId;
-use std::{error::Error, sync::Arc};
+use serenity::model::id::{GuildId, MessageId};
+use std::{error::Error, fmt::Display, sync::Arc, time::Duration};
 
-use crate::roles::WereWolfRoleConfig;
+use crate::{
+    roles::WereWolfRoleConfig,
+    rounds::{counts::PendingCounts, history::RoundRecord},
+    settings::GuildSettings,
+};
 
 pub mod discord;
 
 mod cache;
 
+// There is only the Discord-Backend for now, so nothing can be migrated yet
+#[allow(dead_code)]
+pub mod migrate;
+
 /// The Storage Backend that should be used to load, store and update Custom Werewolf Roles for a
 /// Guild
 #[async_trait]
@@ -32,25 +40,140 @@ pub trait StorageBackend {
         guild: GuildId,
         role_name: &str,
     ) -> Result<(), Box<dyn Error + Send>>;
+
+    /// Stores a Backup of the given Roles for the Guild
+    async fn store_backup(
+        &self,
+        guild: GuildId,
+        roles: &[WereWolfRoleConfig],
+    ) -> Result<(), Box<dyn Error + Send>>;
+
+    /// Attempts to load the most recent Backup of the Roles for the Guild, returns `None` if
+    /// there is no Backup
+    async fn load_backup(
+        &self,
+        guild: GuildId,
+    ) -> Result<Option<Vec<WereWolfRoleConfig>>, Box<dyn Error + Send>>;
+
+    /// Stores the Record of a finished Round in the History of the Guild
+    async fn store_round(
+        &self,
+        guild: GuildId,
+        round: &RoundRecord,
+    ) -> Result<(), Box<dyn Error + Send>>;
+
+    /// Loads the Records of the finished Rounds of the Guild, the most recent Round first
+    async fn load_rounds(&self, guild: GuildId) -> Result<Vec<RoundRecord>, Box<dyn Error + Send>>;
+
+    /// Attempts to load the Settings for the Guild, returns the default Settings if none have
+    /// been stored yet
+    async fn load_settings(&self, guild: GuildId) -> Result<GuildSettings, Box<dyn Error + Send>>;
+
+    /// Attempts to store the Settings for the Guild, replacing the previous Settings
+    async fn save_settings(
+        &self,
+        guild: GuildId,
+        settings: GuildSettings,
+    ) -> Result<(), Box<dyn Error + Send>>;
+
+    /// Stores the State of a Round whose Role-Counts are still being entered, replacing the
+    /// previously stored State of the same Round
+    async fn save_pending_counts(
+        &self,
+        guild: GuildId,
+        counts: &PendingCounts,
+    ) -> Result<(), Box<dyn Error + Send>>;
+
+    /// Removes the stored State of the Round with the given Message, once its Role-Counts are no
+    /// longer being entered
+    async fn clear_pending_counts(
+        &self,
+        guild: GuildId,
+        round: MessageId,
+    ) -> Result<(), Box<dyn Error + Send>>;
+
+    /// Loads the States of all the Rounds of the Guild, whose Role-Counts were still being
+    /// entered
+    async fn load_pending_counts(
+        &self,
+        guild: GuildId,
+    ) -> Result<Vec<PendingCounts>, Box<dyn Error + Send>>;
+}
+
+#[derive(Debug, PartialEq)]
+pub enum StorageError {
+    /// The Guild already has the maximum Number of Roles stored
+    RoleLimitReached { limit: usize },
+}
+
+impl Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RoleLimitReached { limit } => write!(
+                f,
+                "This Server already has {} Roles, which is the maximum allowed",
+                limit
+            ),
+        }
+    }
 }
+impl Error for StorageError {}
 
 #[derive(Clone)]
 pub struct Storage {
     backend: Arc<dyn StorageBackend + Send + Sync>,
     cache: Arc<cache::Cache>,
+    /// The maximum Number of Roles that can be stored for a single Guild
+    role_limit: usize,
 }
 
 impl Storage {
     /// Creates a new Storage Instance with the given Backend
     pub fn new<S>(backend: S) -> Self
+    where
+        S: StorageBackend + Send + Sync + 'static,
+    {
+        Self::with_role_limit(backend, usize::MAX)
+    }
+
+    /// Creates a new Storage Instance with the given Backend, that allows at most `role_limit`
+    /// Roles per Guild
+    pub fn with_role_limit<S>(backend: S, role_limit: usize) -> Self
     where
         S: StorageBackend + Send + Sync + 'static,
     {
         Self {
             backend: Arc::new(backend),
             cache: Arc::new(cache::Cache::new()),
+            role_limit,
+        }
+    }
+
+    /// Reloads the Roles of all the cached Guilds from the Backend, so that Changes that were
+    /// made directly in the Backend are picked up
+    pub async fn refresh_roles(&self) {
+        for guild in self.cache.cached_guilds() {
+            match self.backend.load_roles(guild).await {
+                Ok(r) => self.cache.populate(guild, r),
+                Err(e) => tracing::error!("Refreshing Roles of Guild {:?}: {:?}", guild, e),
+            };
         }
     }
+
+    /// Starts a Background-Task that refreshes the cached Roles in the given Interval
+    pub fn run_refresh(&self, interval: Duration) {
+        let storage = self.clone();
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            // The first Tick completes immediately, while the Cache is still fresh
+            ticks.tick().await;
+
+            loop {
+                ticks.tick().await;
+                storage.refresh_roles().await;
+            }
+        });
+    }
 }
 
 #[async_trait]
@@ -59,16 +182,15 @@ impl StorageBackend for Storage {
         &self,
         guild: GuildId,
     ) -> Result<Vec<WereWolfRoleConfig>, Box<dyn Error + Send>> {
-        if let Some(r) = self.cache.get_roles(guild) {
-            return Ok(r);
-        }
-
-        match self.backend.load_roles(guild).await {
-            Ok(r) => {
+        // The Roles are returned as they are stored, so that they can be written back without
+        // baking their Bases into them, see `templates::resolve_guild_roles`
+        match self.cache.get_roles(guild) {
+            Some(r) => Ok(r),
+            None => {
+                let r = self.backend.load_roles(guild).await?;
                 self.cache.populate(guild, r.clone());
                 Ok(r)
             }
-            Err(e) => Err(e),
         }
     }
 
@@ -77,6 +199,22 @@ impl StorageBackend for Storage {
         guild: GuildId,
         role: WereWolfRoleConfig,
     ) -> Result<(), Box<dyn Error + Send>> {
+        // The Cache holds the stored Roles of a Guild, so an unchanged Role does not need to be
+        // written again. Guilds that are not cached yet are always written
+        if let Some(cached) = self.cache.get_roles(guild) {
+            if cached.iter().find(|r| r.name() == role.name()) == Some(&role) {
+                return Ok(());
+            }
+        }
+
+        // Updating an existing Role does not count towards the Limit
+        let existing = self.load_roles(guild).await?;
+        if existing.len() >= self.role_limit && !existing.iter().any(|r| r.name() == role.name()) {
+            return Err(Box::new(StorageError::RoleLimitReached {
+                limit: self.role_limit,
+            }));
+        }
+
         self.cache.set_role(guild, role.clone());
         self.backend.set_role(guild, role).await
     }
@@ -89,4 +227,477 @@ impl StorageBackend for Storage {
         self.cache.remove_role(guild, role_name);
         self.backend.remove_role(guild, role_name).await
     }
+
+    async fn store_backup(
+        &self,
+        guild: GuildId,
+        roles: &[WereWolfRoleConfig],
+    ) -> Result<(), Box<dyn Error + Send>> {
+        self.backend.store_backup(guild, roles).await
+    }
+
+    async fn load_backup(
+        &self,
+        guild: GuildId,
+    ) -> Result<Option<Vec<WereWolfRoleConfig>>, Box<dyn Error + Send>> {
+        self.backend.load_backup(guild).await
+    }
+
+    async fn store_round(
+        &self,
+        guild: GuildId,
+        round: &RoundRecord,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        self.backend.store_round(guild, round).await
+    }
+
+    async fn load_rounds(&self, guild: GuildId) -> Result<Vec<RoundRecord>, Box<dyn Error + Send>> {
+        self.backend.load_rounds(guild).await
+    }
+
+    async fn load_settings(&self, guild: GuildId) -> Result<GuildSettings, Box<dyn Error + Send>> {
+        if let Some(s) = self.cache.get_settings(guild) {
+            return Ok(s);
+        }
+
+        let settings = self.backend.load_settings(guild).await?;
+        self.cache.set_settings(guild, settings.clone());
+        Ok(settings)
+    }
+
+    async fn save_settings(
+        &self,
+        guild: GuildId,
+        settings: GuildSettings,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        // Only cached once stored, otherwise the Settings would be lost on the next Restart
+        self.backend.save_settings(guild, settings.clone()).await?;
+        self.cache.set_settings(guild, settings);
+        Ok(())
+    }
+
+    async fn save_pending_counts(
+        &self,
+        guild: GuildId,
+        counts: &PendingCounts,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        self.backend.save_pending_counts(guild, counts).await
+    }
+
+    async fn clear_pending_counts(
+        &self,
+        guild: GuildId,
+        round: MessageId,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        self.backend.clear_pending_counts(guild, round).await
+    }
+
+    async fn load_pending_counts(
+        &self,
+        guild: GuildId,
+    ) -> Result<Vec<PendingCounts>, Box<dyn Error + Send>> {
+        self.backend.load_pending_counts(guild).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    };
+
+    use super::*;
+
+    /// A simple in-memory Backend for testing
+    #[derive(Default)]
+    struct MemoryBackend {
+        roles: Mutex<Vec<WereWolfRoleConfig>>,
+        settings: Mutex<Option<GuildSettings>>,
+        /// Makes storing the Settings fail
+        reject_settings: AtomicBool,
+    }
+
+    #[async_trait]
+    impl StorageBackend for MemoryBackend {
+        async fn load_roles(
+            &self,
+            _guild: GuildId,
+        ) -> Result<Vec<WereWolfRoleConfig>, Box<dyn Error + Send>> {
+            Ok(self.roles.lock().unwrap().clone())
+        }
+
+        async fn set_role(
+            &self,
+            _guild: GuildId,
+            role: WereWolfRoleConfig,
+        ) -> Result<(), Box<dyn Error + Send>> {
+            self.roles.lock().unwrap().push(role);
+            Ok(())
+        }
+
+        async fn remove_role(
+            &self,
+            _guild: GuildId,
+            role_name: &str,
+        ) -> Result<(), Box<dyn Error + Send>> {
+            self.roles.lock().unwrap().retain(|r| r.name() != role_name);
+            Ok(())
+        }
+
+        async fn store_backup(
+            &self,
+            _guild: GuildId,
+            _roles: &[WereWolfRoleConfig],
+        ) -> Result<(), Box<dyn Error + Send>> {
+            Ok(())
+        }
+
+        async fn load_backup(
+            &self,
+            _guild: GuildId,
+        ) -> Result<Option<Vec<WereWolfRoleConfig>>, Box<dyn Error + Send>> {
+            Ok(None)
+        }
+
+        async fn store_round(
+            &self,
+            _guild: GuildId,
+            _round: &RoundRecord,
+        ) -> Result<(), Box<dyn Error + Send>> {
+            Ok(())
+        }
+
+        async fn load_rounds(
+            &self,
+            _guild: GuildId,
+        ) -> Result<Vec<RoundRecord>, Box<dyn Error + Send>> {
+            Ok(Vec::new())
+        }
+
+        async fn load_settings(
+            &self,
+            _guild: GuildId,
+        ) -> Result<GuildSettings, Box<dyn Error + Send>> {
+            Ok(self.settings.lock().unwrap().clone().unwrap_or_default())
+        }
+
+        async fn save_settings(
+            &self,
+            _guild: GuildId,
+            settings: GuildSettings,
+        ) -> Result<(), Box<dyn Error + Send>> {
+            if self.reject_settings.load(Ordering::SeqCst) {
+                return Err(Box::new(std::io::Error::other("Rejected Settings")));
+            }
+
+            *self.settings.lock().unwrap() = Some(settings);
+            Ok(())
+        }
+
+        async fn save_pending_counts(
+            &self,
+            _guild: GuildId,
+            _counts: &PendingCounts,
+        ) -> Result<(), Box<dyn Error + Send>> {
+            Ok(())
+        }
+
+        async fn clear_pending_counts(
+            &self,
+            _guild: GuildId,
+            _round: MessageId,
+        ) -> Result<(), Box<dyn Error + Send>> {
+            Ok(())
+        }
+
+        async fn load_pending_counts(
+            &self,
+            _guild: GuildId,
+        ) -> Result<Vec<PendingCounts>, Box<dyn Error + Send>> {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Allows the Tests to modify the Backend while it is used by a Storage
+    struct SharedBackend(Arc<MemoryBackend>);
+
+    #[async_trait]
+    impl StorageBackend for SharedBackend {
+        async fn load_roles(
+            &self,
+            guild: GuildId,
+        ) -> Result<Vec<WereWolfRoleConfig>, Box<dyn Error + Send>> {
+            self.0.load_roles(guild).await
+        }
+
+        async fn set_role(
+            &self,
+            guild: GuildId,
+            role: WereWolfRoleConfig,
+        ) -> Result<(), Box<dyn Error + Send>> {
+            self.0.set_role(guild, role).await
+        }
+
+        async fn remove_role(
+            &self,
+            guild: GuildId,
+            role_name: &str,
+        ) -> Result<(), Box<dyn Error + Send>> {
+            self.0.remove_role(guild, role_name).await
+        }
+
+        async fn store_backup(
+            &self,
+            guild: GuildId,
+            roles: &[WereWolfRoleConfig],
+        ) -> Result<(), Box<dyn Error + Send>> {
+            self.0.store_backup(guild, roles).await
+        }
+
+        async fn load_backup(
+            &self,
+            guild: GuildId,
+        ) -> Result<Option<Vec<WereWolfRoleConfig>>, Box<dyn Error + Send>> {
+            self.0.load_backup(guild).await
+        }
+
+        async fn store_round(
+            &self,
+            guild: GuildId,
+            round: &RoundRecord,
+        ) -> Result<(), Box<dyn Error + Send>> {
+            self.0.store_round(guild, round).await
+        }
+
+        async fn load_rounds(
+            &self,
+            guild: GuildId,
+        ) -> Result<Vec<RoundRecord>, Box<dyn Error + Send>> {
+            self.0.load_rounds(guild).await
+        }
+
+        async fn load_settings(
+            &self,
+            guild: GuildId,
+        ) -> Result<GuildSettings, Box<dyn Error + Send>> {
+            self.0.load_settings(guild).await
+        }
+
+        async fn save_settings(
+            &self,
+            guild: GuildId,
+            settings: GuildSettings,
+        ) -> Result<(), Box<dyn Error + Send>> {
+            self.0.save_settings(guild, settings).await
+        }
+
+        async fn save_pending_counts(
+            &self,
+            guild: GuildId,
+            counts: &PendingCounts,
+        ) -> Result<(), Box<dyn Error + Send>> {
+            self.0.save_pending_counts(guild, counts).await
+        }
+
+        async fn clear_pending_counts(
+            &self,
+            guild: GuildId,
+            round: MessageId,
+        ) -> Result<(), Box<dyn Error + Send>> {
+            self.0.clear_pending_counts(guild, round).await
+        }
+
+        async fn load_pending_counts(
+            &self,
+            guild: GuildId,
+        ) -> Result<Vec<PendingCounts>, Box<dyn Error + Send>> {
+            self.0.load_pending_counts(guild).await
+        }
+    }
+
+    fn role(name: &str) -> WereWolfRoleConfig {
+        WereWolfRoleConfig::new(name, name, false, false, Vec::new())
+    }
+
+    #[tokio::test]
+    async fn role_limit_reached() {
+        let storage = Storage::with_role_limit(MemoryBackend::default(), 2);
+        let guild = GuildId(13);
+
+        storage.set_role(guild, role("first")).await.unwrap();
+        storage.set_role(guild, role("second")).await.unwrap();
+
+        let result = storage.set_role(guild, role("third")).await;
+        assert_eq!(
+            "This Server already has 2 Roles, which is the maximum allowed",
+            result.unwrap_err().to_string()
+        );
+        assert_eq!(2, storage.load_roles(guild).await.unwrap().len());
+
+        storage.remove_role(guild, "first").await.unwrap();
+        storage.set_role(guild, role("third")).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn unchanged_role_not_written() {
+        let backend = Arc::new(MemoryBackend::default());
+        let storage = Storage::new(SharedBackend(backend.clone()));
+        let guild = GuildId(13);
+
+        storage.set_role(guild, role("first")).await.unwrap();
+        storage.set_role(guild, role("first")).await.unwrap();
+        assert_eq!(1, backend.roles.lock().unwrap().len());
+
+        storage
+            .set_role(guild, role("first").with_team("Village"))
+            .await
+            .unwrap();
+        assert_eq!(2, backend.roles.lock().unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn reverted_role_written() {
+        let backend = Arc::new(MemoryBackend::default());
+        let storage = Storage::new(SharedBackend(backend.clone()));
+        let guild = GuildId(13);
+
+        storage.set_role(guild, role("first")).await.unwrap();
+        storage
+            .set_role(guild, role("first").with_team("Village"))
+            .await
+            .unwrap();
+
+        // Changing the Role back to an earlier Version is still written to the Backend
+        storage.set_role(guild, role("first")).await.unwrap();
+        assert_eq!(Some(&role("first")), backend.roles.lock().unwrap().last());
+        assert_eq!(
+            vec![role("first")],
+            storage.load_roles(guild).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn refresh_picks_up_changes() {
+        let backend = Arc::new(MemoryBackend::default());
+        let storage = Storage::new(SharedBackend(backend.clone()));
+        let guild = GuildId(13);
+
+        storage.set_role(guild, role("first")).await.unwrap();
+
+        // Changed directly in the Backend, bypassing the Cache
+        backend.roles.lock().unwrap().push(role("second"));
+        assert_eq!(1, storage.load_roles(guild).await.unwrap().len());
+
+        storage.refresh_roles().await;
+        assert_eq!(2, storage.load_roles(guild).await.unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn settings_cached() {
+        let backend = Arc::new(MemoryBackend::default());
+        let storage = Storage::new(SharedBackend(backend.clone()));
+        let guild = GuildId(13);
+
+        assert_eq!(
+            GuildSettings::default(),
+            storage.load_settings(guild).await.unwrap()
+        );
+
+        let mut settings = GuildSettings::default();
+        settings.set("ready-check", "on").unwrap();
+        storage
+            .save_settings(guild, settings.clone())
+            .await
+            .unwrap();
+        assert_eq!(Some(settings.clone()), *backend.settings.lock().unwrap());
+
+        // Changed directly in the Backend, the Storage keeps serving the cached Settings
+        *backend.settings.lock().unwrap() = Some(GuildSettings::default());
+        assert_eq!(settings, storage.load_settings(guild).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn failed_settings_not_cached() {
+        let backend = Arc::new(MemoryBackend::default());
+        let storage = Storage::new(SharedBackend(backend.clone()));
+        let guild = GuildId(13);
+
+        backend.reject_settings.store(true, Ordering::SeqCst);
+        let mut settings = GuildSettings::default();
+        settings.set("ready-check", "on").unwrap();
+        assert!(storage.save_settings(guild, settings).await.is_err());
+
+        assert_eq!(
+            GuildSettings::default(),
+            storage.load_settings(guild).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn load_keeps_templates() {
+        let storage = Storage::new(MemoryBackend::default());
+        let guild = GuildId(13);
+
+        let base = WereWolfRoleConfig::new("Evil", "😈", true, false, vec!["Night".to_string()])
+            .with_team("Werewolves");
+        storage.set_role(guild, base).await.unwrap();
+        storage
+            .set_role(guild, role("Wolf").with_base("Evil"))
+            .await
+            .unwrap();
+
+        // The stored Roles are returned, so writing them back does not bake in their Base
+        let roles = storage.load_roles(guild).await.unwrap();
+        let wolf = roles.iter().find(|r| r.name() == "Wolf").unwrap();
+        assert_eq!(&role("Wolf").with_base("Evil"), wolf);
+
+        let roles = crate::roles::templates::resolve_guild_roles(guild, roles);
+        let wolf = roles.iter().find(|r| r.name() == "Wolf").unwrap();
+        assert_eq!(Some("Werewolves"), wolf.team());
+        assert_eq!(
+            vec!["Wolf".to_string(), "Night".to_string()],
+            wolf.channels().collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn migrate_between_backends() {
+        let source = MemoryBackend::default();
+        let target = MemoryBackend::default();
+        let guild = GuildId(13);
+
+        source.set_role(guild, role("first")).await.unwrap();
+        source.set_role(guild, role("second")).await.unwrap();
+        source
+            .set_role(guild, role("third").with_team("Village"))
+            .await
+            .unwrap();
+        target.set_role(guild, role("second")).await.unwrap();
+        target.set_role(guild, role("third")).await.unwrap();
+
+        let report = migrate::migrate_roles(&source, &target, guild)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            migrate::MigrationReport {
+                migrated: 1,
+                unchanged: 1,
+                conflicts: vec!["third".to_string()],
+                failed: Vec::new(),
+            },
+            report
+        );
+        assert_eq!(
+            "Migrated 1 Roles, 1 were already up to date\nKept the existing Version of: third",
+            report.to_string()
+        );
+
+        // The Target keeps its own Version of the conflicting Role
+        let migrated = target.load_roles(guild).await.unwrap();
+        assert_eq!(vec![role("second"), role("third"), role("first")], migrated);
+        // The Source is left untouched
+        assert_eq!(3, source.load_roles(guild).await.unwrap().len());
+    }
 }
@@ -2,16 +2,18 @@ use std::{collections::HashMap, sync::RwLock};
 
 use serenity::model::id::GuildId;
 
-use crate::roles::WereWolfRoleConfig;
+use crate::{roles::WereWolfRoleConfig, settings::GuildSettings};
 
 pub struct Cache {
     roles: RwLock<HashMap<GuildId, Vec<WereWolfRoleConfig>>>,
+    settings: RwLock<HashMap<GuildId, GuildSettings>>,
 }
 
 impl Cache {
     pub fn new() -> Self {
         Self {
             roles: RwLock::new(HashMap::new()),
+            settings: RwLock::new(HashMap::new()),
         }
     }
 
@@ -19,17 +21,24 @@ impl Cache {
         self.roles.write().unwrap().insert(guild_id, roles);
     }
 
+    /// The Guilds whose Roles are currently cached
+    pub fn cached_guilds(&self) -> Vec<GuildId> {
+        self.roles.read().unwrap().keys().copied().collect()
+    }
+
     pub fn get_roles(&self, guild_id: GuildId) -> Option<Vec<WereWolfRoleConfig>> {
         self.roles.read().unwrap().get(&guild_id).cloned()
     }
 
+    /// Stores the Role, replacing the cached Role with the same Name
     pub fn set_role(&self, guild_id: GuildId, role: WereWolfRoleConfig) {
         let mut map = self.roles.write().unwrap();
 
         match map.get_mut(&guild_id) {
-            Some(m) => {
-                m.push(role);
-            }
+            Some(m) => match m.iter_mut().find(|r| r.name() == role.name()) {
+                Some(existing) => *existing = role,
+                None => m.push(role),
+            },
             None => {
                 map.insert(guild_id, vec![role]);
             }
@@ -55,6 +64,14 @@ impl Cache {
 
         guild_roles.remove(index);
     }
+
+    pub fn get_settings(&self, guild_id: GuildId) -> Option<GuildSettings> {
+        self.settings.read().unwrap().get(&guild_id).cloned()
+    }
+
+    pub fn set_settings(&self, guild_id: GuildId, settings: GuildSettings) {
+        self.settings.write().unwrap().insert(guild_id, settings);
+    }
 }
 
 #[cfg(test)]
@@ -89,6 +106,16 @@ mod tests {
 
         assert_eq!(Some(raw_input), loaded);
     }
+    #[test]
+    fn cached_guilds() {
+        let cache = Cache::new();
+        assert!(cache.cached_guilds().is_empty());
+
+        cache.populate(GuildId(13), vec![]);
+
+        assert_eq!(vec![GuildId(13)], cache.cached_guilds());
+    }
+
     #[test]
     fn set_get() {
         let cache = Cache::new();
@@ -103,6 +130,21 @@ mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn set_replaces_same_name() {
+        let cache = Cache::new();
+
+        let first = WereWolfRoleConfig::new("test", ":)", false, false, vec![]);
+        let other = WereWolfRoleConfig::new("other", ":(", false, false, vec![]);
+        let edited = WereWolfRoleConfig::new("test", ":D", true, false, vec![]);
+
+        cache.set_role(GuildId(13), first);
+        cache.set_role(GuildId(13), other.clone());
+        cache.set_role(GuildId(13), edited.clone());
+
+        assert_eq!(Some(vec![edited, other]), cache.get_roles(GuildId(13)));
+    }
+
     #[test]
     fn remove_empty() {
         let cache = Cache::new();
@@ -135,4 +177,20 @@ mod tests {
 
         assert_eq!(expected, cache.get_roles(GuildId(13)));
     }
+
+    #[test]
+    fn set_get_settings() {
+        let cache = Cache::new();
+
+        assert_eq!(None, cache.get_settings(GuildId(13)));
+
+        let settings = GuildSettings {
+            numbered_reactions: true,
+            ..Default::default()
+        };
+        cache.set_settings(GuildId(13), settings.clone());
+
+        assert_eq!(Some(settings), cache.get_settings(GuildId(13)));
+        assert_eq!(None, cache.get_settings(GuildId(14)));
+    }
 }
@@ -1,25 +1,66 @@
-use std::{error::Error, fmt::Display, future::ready, sync::Arc};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::Display,
+    future::{ready, Future},
+    pin::Pin,
+    sync::{Arc, RwLock},
+};
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use serenity::{
     futures::StreamExt,
-    http::Http,
+    http::{Http, HttpError},
     model::{
-        channel::{ChannelType, Message},
+        channel::{
+            ChannelType, GuildChannel, Message, PermissionOverwrite, PermissionOverwriteType,
+        },
         id::{ChannelId, GuildId, MessageId, UserId},
+        Permissions,
     },
 };
 
-use crate::roles::WereWolfRoleConfig;
+use tokio::sync::OnceCell;
+
+use crate::{
+    roles::WereWolfRoleConfig,
+    rounds::{counts::PendingCounts, history::RoundRecord},
+    settings::GuildSettings,
+    util,
+};
 
 use super::StorageBackend;
 
+mod records;
+use records::{BackupRecord, CountsRecord, HistoryRecord, RolesRecord};
+pub use records::{StorageFormat, DEFAULT_ROLE_MARKER};
+
 const SETTINGS_CHANNEL_NAME: &str = "W-Settings";
+/// The Name of the File used for Backups that are too large for a single Message
+const BACKUP_FILE_NAME: &str = "roles-backup.json";
+/// The Number of past Rounds that are kept in the Settings-Channel, older Rounds are deleted so
+/// they don't push the Roles and Settings past the Scan-Limit
+const MAX_STORED_ROUNDS: usize = 25;
+
+/// The Guild-Settings are wrapped in their own Key, so that the Message can't be mistaken for a
+/// Role and the other way around
+#[derive(Debug, Serialize, Deserialize)]
+struct SettingsRecord {
+    guild_settings: GuildSettings,
+}
+
+/// The Error-Code Discord uses for Requests against a Channel that does not exist (anymore)
+const UNKNOWN_CHANNEL_CODE: isize = 10003;
 
 #[derive(Debug)]
 pub enum DiscordError {
     ObtainSettingsChannel,
+    /// The Settings-Channel was deleted while it was being used
+    UnknownChannel,
     FindingRole,
+    /// The Settings don't fit into a single Message
+    SettingsTooLarge,
     Serde(serde_json::Error),
     SerenityError(serenity::Error),
 }
@@ -28,7 +69,9 @@ impl Display for DiscordError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::ObtainSettingsChannel => write!(f, "ObtainSettingsChannel"),
+            Self::UnknownChannel => write!(f, "UnknownChannel"),
             Self::FindingRole => write!(f, "FindingRole"),
+            Self::SettingsTooLarge => write!(f, "SettingsTooLarge"),
             Self::Serde(e) => write!(f, "Serde ({})", e),
             Self::SerenityError(e) => write!(f, "Serenity ({})", e),
         }
@@ -36,16 +79,290 @@ impl Display for DiscordError {
 }
 impl Error for DiscordError {}
 
+impl DiscordError {
+    /// Turns the Errors caused by a Channel that no longer exists into
+    /// [`DiscordError::UnknownChannel`]
+    fn classify(self) -> Self {
+        match &self {
+            Self::SerenityError(serenity::Error::Http(e)) => match e.as_ref() {
+                HttpError::UnsuccessfulRequest(resp) if resp.error.code == UNKNOWN_CHANNEL_CODE => {
+                    Self::UnknownChannel
+                }
+                _ => self,
+            },
+            _ => self,
+        }
+    }
+}
+
+/// Keeps track of the Messages in which the Roles of the Guilds are stored, together with the
+/// Channel they are in, so they don't have to be searched again when removing a Role
+#[derive(Debug, Default)]
+struct RoleMessages {
+    ids: RwLock<HashMap<GuildId, GuildRoleMessages>>,
+}
+
+/// The Channel and Message in which each Role of a single Guild is stored
+type GuildRoleMessages = HashMap<String, (ChannelId, MessageId)>;
+
+impl RoleMessages {
+    fn insert(&self, guild: GuildId, role_name: &str, channel: ChannelId, message: MessageId) {
+        self.ids
+            .write()
+            .unwrap()
+            .entry(guild)
+            .or_default()
+            .insert(role_name.to_string(), (channel, message));
+    }
+
+    fn get(&self, guild: GuildId, role_name: &str) -> Option<(ChannelId, MessageId)> {
+        self.ids
+            .read()
+            .unwrap()
+            .get(&guild)
+            .and_then(|roles| roles.get(role_name))
+            .copied()
+    }
+
+    fn remove(&self, guild: GuildId, role_name: &str) {
+        if let Some(roles) = self.ids.write().unwrap().get_mut(&guild) {
+            roles.remove(role_name);
+        }
+    }
+
+    /// Forgets all the Messages of the Guild, used once they are known to be stale
+    fn clear(&self, guild: GuildId) {
+        self.ids.write().unwrap().remove(&guild);
+    }
+}
+
+/// Only keeps the latest Message for every Role, as updating a Role stores it in a new Message.
+/// Returns the kept Messages, in their original Order, and the IDs of the outdated Messages
+fn dedup_role_messages(
+    messages: Vec<(MessageId, WereWolfRoleConfig)>,
+) -> (Vec<(MessageId, WereWolfRoleConfig)>, Vec<MessageId>) {
+    let mut latest: HashMap<String, MessageId> = HashMap::new();
+    for (msg_id, config) in messages.iter() {
+        let entry = latest.entry(config.name().to_string()).or_insert(*msg_id);
+        if *msg_id > *entry {
+            *entry = *msg_id;
+        }
+    }
+
+    let (kept, outdated): (Vec<_>, Vec<_>) = messages
+        .into_iter()
+        .partition(|(msg_id, config)| latest.get(config.name()) == Some(msg_id));
+    (kept, outdated.into_iter().map(|(id, _)| id).collect())
+}
+
+/// The Name of the additional Settings-Channel with the given Index, the Index `0` is the main
+/// Settings-Channel
+fn shard_channel_name(index: usize) -> String {
+    match index {
+        0 => SETTINGS_CHANNEL_NAME.to_string(),
+        n => format!("{}-{}", SETTINGS_CHANNEL_NAME, n),
+    }
+}
+
+/// The Index of the additional Settings-Channel with the given Name, `None` if the Channel is not
+/// an additional Settings-Channel
+fn shard_index(name: &str) -> Option<usize> {
+    let prefix_len = SETTINGS_CHANNEL_NAME.len() + 1;
+    if name.len() <= prefix_len || !name.is_char_boundary(prefix_len) {
+        return None;
+    }
+
+    let (prefix, index) = name.split_at(prefix_len);
+    if !prefix.eq_ignore_ascii_case(&format!("{}-", SETTINGS_CHANNEL_NAME)) {
+        return None;
+    }
+
+    index.parse().ok().filter(|i| *i > 0)
+}
+
+/// Finds all the additional Settings-Channels of the Guild, ordered by their Index
+fn find_shard_channels(channels: &HashMap<ChannelId, GuildChannel>) -> Vec<(usize, ChannelId)> {
+    let mut result: Vec<(usize, ChannelId)> = channels
+        .iter()
+        .filter(|(_, channel)| channel.kind == ChannelType::Text)
+        .filter_map(|(id, channel)| shard_index(channel.name()).map(|index| (index, *id)))
+        .collect();
+    result.sort();
+    result
+}
+
+/// The Index of the Settings-Channel in which the Role is stored, when the Roles are spread
+/// across the given Number of Channels. This only depends on the Name of the Role, so the Role
+/// always ends up in the same Channel
+fn shard_for(role_name: &str, channel_count: usize) -> usize {
+    // FNV-1a, as the Hash has to be stable across Restarts and Versions
+    let hash = role_name.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+
+    (hash % channel_count.max(1) as u64) as usize
+}
+
+/// Runs the Operation on the Settings-Channel returned by `obtain`. If the Channel was deleted
+/// in the meantime, the Channel is obtained again, which recreates it, and the Operation is
+/// retried once
+async fn retry_unknown_channel<O, OFut, F, Fut, T>(
+    mut obtain: O,
+    mut operation: F,
+) -> Result<T, DiscordError>
+where
+    O: FnMut() -> OFut,
+    OFut: Future<Output = Option<ChannelId>>,
+    F: FnMut(ChannelId) -> Fut,
+    Fut: Future<Output = Result<T, DiscordError>>,
+{
+    let channel_id = obtain().await.ok_or(DiscordError::ObtainSettingsChannel)?;
+
+    match operation(channel_id).await.map_err(DiscordError::classify) {
+        Err(DiscordError::UnknownChannel) => {
+            tracing::warn!(
+                "Settings-Channel {:?} no longer exists, retrying with a new Channel",
+                channel_id
+            );
+
+            let channel_id = obtain().await.ok_or(DiscordError::ObtainSettingsChannel)?;
+            operation(channel_id).await
+        }
+        other => other,
+    }
+}
+
+/// Gets the Value stored in the Cell or initializes it using the given Fetch-Function, which is
+/// therefore called at most once successfully
+async fn cached_id<F, Fut>(cell: &OnceCell<UserId>, fetch: F) -> Result<UserId, DiscordError>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<UserId, DiscordError>>,
+{
+    cell.get_or_try_init(fetch).await.copied()
+}
+
 /// The Discord Storage Backend
 pub struct DiscordStorage {
     http: Arc<Http>,
+    /// The ID of the Bot itself, which is only loaded once and then reused
+    bot_id: OnceCell<UserId>,
+    role_messages: RoleMessages,
+    /// The maximum Number of Messages to scan in the Settings-Channel, `None` for no Limit
+    scan_limit: Option<usize>,
+    /// How the Roles are stored in the Settings-Channel
+    format: StorageFormat,
+    /// The Name of the Category in which the Settings-Channel is created, `None` to create it at
+    /// the Root of the Guild
+    settings_category: Option<String>,
+    /// Whether or not the Settings-Channel is hidden from `@everyone`
+    hide_settings_channel: bool,
+    /// The Marker in front of every Message that stores a single Role
+    role_marker: String,
+    /// The Number of Settings-Channels across which the Roles are spread
+    role_channels: usize,
+}
+
+/// The Permissions for a newly created Settings-Channel, a hidden Channel can only be read by the
+/// Bot itself and the Users that are explicitly allowed to
+fn settings_channel_permissions(
+    guild: GuildId,
+    bot_id: UserId,
+    hidden: bool,
+) -> Vec<PermissionOverwrite> {
+    if !hidden {
+        return Vec::new();
+    }
+
+    vec![
+        PermissionOverwrite {
+            allow: Permissions::READ_MESSAGES | Permissions::SEND_MESSAGES,
+            deny: Permissions::empty(),
+            kind: PermissionOverwriteType::Member(bot_id),
+        },
+        PermissionOverwrite {
+            allow: Permissions::empty(),
+            deny: Permissions::READ_MESSAGES,
+            // The @everyone-Role always shares its ID with the Guild
+            kind: PermissionOverwriteType::Role(util::roles::everyone_role(guild)),
+        },
+    ]
 }
 
 impl DiscordStorage {
     /// Creates a new Discord Storage Instance with the given Http instance for making all the
     /// needed API Calls to Discord itself
     pub fn new(http: Arc<Http>) -> Self {
-        Self { http }
+        Self {
+            http,
+            bot_id: OnceCell::new(),
+            role_messages: RoleMessages::default(),
+            scan_limit: None,
+            format: StorageFormat::PerRole,
+            settings_category: None,
+            hide_settings_channel: false,
+            role_marker: records::DEFAULT_ROLE_MARKER.to_string(),
+            role_channels: 1,
+        }
+    }
+
+    /// Creates the Settings-Channel inside of the Category with the given Name, the Category is
+    /// created as well if it does not exist yet
+    pub fn with_settings_category<N>(mut self, name: N) -> Self
+    where
+        N: Into<String>,
+    {
+        self.settings_category = Some(name.into());
+        self
+    }
+
+    /// Sets whether or not newly created Settings-Channels are hidden from `@everyone`
+    pub fn with_hidden_settings_channel(mut self, hidden: bool) -> Self {
+        self.hide_settings_channel = hidden;
+        self
+    }
+
+    /// Sets the Format in which the Roles are stored
+    pub fn with_format(mut self, format: StorageFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets the Marker in front of every Message that stores a single Role, only Messages with
+    /// this Marker are loaded as Roles. An empty Marker accepts every Message with a valid Role
+    pub fn with_role_marker<M>(mut self, marker: M) -> Self
+    where
+        M: Into<String>,
+    {
+        self.role_marker = marker.into();
+        self
+    }
+
+    /// Spreads the Roles across the given Number of Settings-Channels, the additional Channels are
+    /// named `W-Settings-1`, `W-Settings-2` and so on. This only applies to the per-role Format,
+    /// the Roles are always loaded from all the existing Channels
+    pub fn with_role_channels(mut self, count: usize) -> Self {
+        self.role_channels = count.max(1);
+        self
+    }
+
+    /// Limits the Number of Messages that are scanned in the Settings-Channel when loading the
+    /// stored Data, which keeps the Loading fast in Channels with a long History
+    pub fn with_scan_limit(mut self, limit: usize) -> Self {
+        self.scan_limit = Some(limit);
+        self
+    }
+
+    /// The ID of the Bot, which is used to identify the Messages written by the Storage
+    async fn bot_id(&self) -> Result<UserId, DiscordError> {
+        cached_id(&self.bot_id, || async {
+            self.http
+                .get_current_user()
+                .await
+                .map(|u| u.id)
+                .map_err(DiscordError::SerenityError)
+        })
+        .await
     }
 
     /// Attempts to load the Settings Channel for the given Guild, returns an error if there
@@ -61,9 +378,13 @@ impl DiscordStorage {
             }
         };
 
+        // The Channel is found by its Name, regardless of the Category it is in
         let result = channels
             .into_iter()
-            .find(|(_, channel)| channel.name().eq_ignore_ascii_case(SETTINGS_CHANNEL_NAME))
+            .find(|(_, channel)| {
+                channel.kind == ChannelType::Text
+                    && channel.name().eq_ignore_ascii_case(SETTINGS_CHANNEL_NAME)
+            })
             .map(|(id, _)| id);
 
         match result {
@@ -75,14 +396,64 @@ impl DiscordStorage {
         }
     }
 
-    /// Attempts to create the Settings Channel for the Guild
+    /// Attempts to find the Category with the given Name or creates it if it does not exist yet
+    async fn obtain_category(&self, guild: GuildId, name: &str) -> Result<ChannelId, ()> {
+        let channels = match guild.channels(self.http.as_ref()).await {
+            Ok(c) => c,
+            Err(e) => {
+                tracing::error!("Loading Guild Channels: {:?}", e);
+                return Err(());
+            }
+        };
+
+        let existing = channels.into_iter().find(|(_, channel)| {
+            channel.kind == ChannelType::Category && channel.name().eq_ignore_ascii_case(name)
+        });
+        if let Some((id, _)) = existing {
+            return Ok(id);
+        }
+
+        match guild
+            .create_channel(self.http.as_ref(), |c| {
+                c.name(name).kind(ChannelType::Category)
+            })
+            .await
+        {
+            Ok(c) => Ok(c.id),
+            Err(e) => {
+                tracing::error!("Creating Settings Category: {:?}", e);
+                Err(())
+            }
+        }
+    }
+
+    /// Attempts to create the Settings Channel with the given Name for the Guild
     #[tracing::instrument(skip(self))]
-    async fn create_settings_channel(&self, guild: GuildId) -> Result<ChannelId, ()> {
+    async fn create_settings_channel(&self, guild: GuildId, name: &str) -> Result<ChannelId, ()> {
+        let category = match &self.settings_category {
+            Some(name) => Some(self.obtain_category(guild, name).await?),
+            None => None,
+        };
+
+        let bot_id = match self.bot_id().await {
+            Ok(id) => id,
+            Err(e) => {
+                tracing::error!("Loading Bot-ID: {:?}", e);
+                return Err(());
+            }
+        };
+        let permissions = settings_channel_permissions(guild, bot_id, self.hide_settings_channel);
+
         let create_channel_result = guild
             .create_channel(self.http.as_ref(), |c| {
-                c.name(SETTINGS_CHANNEL_NAME)
+                c.name(name)
                     .kind(ChannelType::Text)
                     .topic("A simple Storage Channel for the Settings of the Bot")
+                    .permissions(permissions);
+                if let Some(category) = category {
+                    c.category(category);
+                }
+                c
             })
             .await;
 
@@ -103,28 +474,97 @@ impl DiscordStorage {
             return Some(id);
         }
 
-        if let Ok(id) = self.create_settings_channel(guild).await {
+        if let Ok(id) = self
+            .create_settings_channel(guild, SETTINGS_CHANNEL_NAME)
+            .await
+        {
             return Some(id);
         }
 
         None
     }
 
+    /// All the Channels in which the Roles of the Guild are stored, starting with the main
+    /// Settings-Channel followed by the additional Channels
+    async fn role_channels(&self, guild: GuildId, main: ChannelId) -> Vec<ChannelId> {
+        let channels = match guild.channels(self.http.as_ref()).await {
+            Ok(c) => c,
+            Err(e) => {
+                tracing::error!("Loading Guild Channels: {:?}", e);
+                return vec![main];
+            }
+        };
+
+        let mut result = vec![main];
+        result.extend(
+            find_shard_channels(&channels)
+                .into_iter()
+                .map(|(_, id)| id)
+                .filter(|id| *id != main),
+        );
+        result
+    }
+
+    /// Obtains the Settings-Channel in which the Role should be stored, the additional Channel is
+    /// created if it does not exist yet
+    async fn obtain_role_channel(
+        &self,
+        guild: GuildId,
+        main: ChannelId,
+        role_name: &str,
+    ) -> Result<ChannelId, DiscordError> {
+        let index = shard_for(role_name, self.role_channels);
+        if index == 0 {
+            return Ok(main);
+        }
+
+        let channels = guild
+            .channels(self.http.as_ref())
+            .await
+            .map_err(DiscordError::SerenityError)?;
+        if let Some((_, id)) = find_shard_channels(&channels)
+            .into_iter()
+            .find(|(i, _)| *i == index)
+        {
+            return Ok(id);
+        }
+
+        self.create_settings_channel(guild, &shard_channel_name(index))
+            .await
+            .map_err(|_| DiscordError::ObtainSettingsChannel)
+    }
+
+    /// Iterates over the Messages of the Bot in the Settings-Channel. The first Page is loaded
+    /// up front, so that a deleted Channel results in an Error instead of an empty Channel
     async fn settings_message_iter(
         &'_ self,
         channel_id: ChannelId,
         bot_id: UserId,
-    ) -> impl serenity::futures::Stream<Item = Message> + '_ {
-        let raw_msg_iter = channel_id.messages_iter(self.http.as_ref()).boxed();
-
-        raw_msg_iter
-            .filter_map(|raw_message| {
-                ready(match raw_message {
-                    Ok(m) => Some(m),
-                    Err(_) => None,
-                })
+    ) -> Result<impl serenity::futures::Stream<Item = Message> + '_, DiscordError> {
+        let mut raw_msg_iter = channel_id.messages_iter(self.http.as_ref()).boxed();
+        let first = match raw_msg_iter.next().await {
+            Some(Ok(msg)) => Some(msg),
+            Some(Err(e)) => return Err(DiscordError::SerenityError(e)),
+            None => None,
+        };
+        let raw_msg_iter = serenity::futures::stream::iter(first.map(Ok)).chain(raw_msg_iter);
+
+        let limit = self.scan_limit.unwrap_or(usize::MAX);
+        Ok(raw_msg_iter
+            .take(limit)
+            .enumerate()
+            .map(move |(index, raw_message)| {
+                if index + 1 == limit {
+                    tracing::warn!(
+                        "Reached the Limit of {} scanned Messages in the Settings-Channel {:?}, Settings stored in older Messages are ignored",
+                        limit,
+                        channel_id
+                    );
+                }
+                raw_message
             })
-            .filter(move |m| ready(m.author.id == bot_id))
+            .filter_map(|raw_message| ready(raw_message.ok()))
+            .filter(move |m| ready(m.author.id == bot_id)))
     }
 
     async fn find_role_message(
@@ -132,136 +572,1098 @@ impl DiscordStorage {
         channel_id: ChannelId,
         bot_id: UserId,
         role_name: &str,
-    ) -> Option<MessageId> {
-        let message_iter = self.settings_message_iter(channel_id, bot_id).await;
+    ) -> Result<Option<MessageId>, DiscordError> {
+        let message_iter = self.settings_message_iter(channel_id, bot_id).await?;
 
         let mut result_iter = message_iter
-            .map(|msg| {
-                let parsed = serde_json::from_str::<WereWolfRoleConfig>(&msg.content);
-                (msg, parsed)
-            })
-            .filter(|(_, p_res)| ready(p_res.is_ok()))
-            .map(|(msg, tmp)| (msg, tmp.unwrap()))
+            .filter_map(|msg| ready(self.parse_role_message(&msg.content).map(|(c, _)| (msg, c))))
             .filter(|(_, config)| ready(config.name() == role_name));
 
-        result_iter.next().await.map(|(c, _)| c.id)
+        Ok(result_iter.next().await.map(|(c, _)| c.id))
     }
 
-    async fn load_roles(&self, guild: GuildId) -> Result<Vec<WereWolfRoleConfig>, DiscordError> {
-        let channel_id = match self.obtain_settings_channel(guild).await {
-            Some(c) => c,
-            None => {
-                return Err(DiscordError::ObtainSettingsChannel);
-            }
-        };
+    async fn find_settings_message(
+        &self,
+        channel_id: ChannelId,
+        bot_id: UserId,
+    ) -> Result<Option<(MessageId, GuildSettings)>, DiscordError> {
+        let message_iter = self.settings_message_iter(channel_id, bot_id).await?;
 
-        let current_user = match self.http.get_current_user().await {
-            Ok(u) => u,
-            Err(e) => return Err(DiscordError::SerenityError(e)),
-        };
+        let mut result_iter = message_iter.filter_map(|msg| {
+            ready(
+                serde_json::from_str::<SettingsRecord>(&msg.content)
+                    .ok()
+                    .map(|record| (msg.id, record.guild_settings)),
+            )
+        });
+
+        Ok(result_iter.next().await)
+    }
 
-        let message_iter = self
-            .settings_message_iter(channel_id, current_user.id)
+    /// Parses the Role stored in a Message of the Bot, together with whether the Message has
+    /// the current Marker
+    fn parse_role_message(&self, content: &str) -> Option<(WereWolfRoleConfig, bool)> {
+        match records::parse_role(&self.role_marker, content) {
+            Some(config) => Some((config, true)),
+            None => records::parse_unmarked_role(content).map(|config| (config, false)),
+        }
+    }
+
+    /// Loads all the Roles that are stored in their own Message. Messages without the current
+    /// Marker were stored before the Marker was introduced or with a different Marker, they are
+    /// loaded as well and marked again
+    async fn load_role_messages(
+        &self,
+        channel_id: ChannelId,
+        bot_id: UserId,
+    ) -> Result<Vec<(MessageId, WereWolfRoleConfig)>, DiscordError> {
+        let messages: Vec<Message> = self
+            .settings_message_iter(channel_id, bot_id)
+            .await?
+            .collect()
             .await;
 
-        let role_config_iter = message_iter
-            .map(|msg| serde_json::from_str::<WereWolfRoleConfig>(&msg.content))
-            .filter_map(|p_res| async move {
-                match p_res {
-                    Ok(v) => Some(v),
-                    Err(_) => None,
+        let mut roles = Vec::new();
+        let mut unmarked = Vec::new();
+        for msg in messages.iter() {
+            let (config, marked) = match self.parse_role_message(&msg.content) {
+                Some(r) => r,
+                None => continue,
+            };
+
+            if !marked {
+                unmarked.push((msg.id, config.clone()));
+            }
+            roles.push((msg.id, config));
+        }
+        if unmarked.is_empty() {
+            return Ok(roles);
+        }
+
+        tracing::info!(
+            "Marking {} Role-Messages in {:?}",
+            unmarked.len(),
+            channel_id
+        );
+        for (msg_id, config) in unmarked.iter() {
+            let serialized = match serde_json::to_string(config) {
+                Ok(s) => s,
+                Err(e) => {
+                    tracing::error!("Serializing Role: {:?}", e);
+                    continue;
                 }
-            });
+            };
+            let content = records::mark_role(&self.role_marker, &serialized);
+
+            if let Err(e) = channel_id
+                .edit_message(self.http.as_ref(), *msg_id, |m| m.content(content))
+                .await
+            {
+                tracing::error!("Marking Role-Message: {:?}", e);
+            }
+        }
 
-        Ok(role_config_iter.collect().await)
+        Ok(roles)
     }
 
-    async fn set_role(&self, guild: GuildId, role: WereWolfRoleConfig) -> Result<(), DiscordError> {
-        let channel_id = match self.obtain_settings_channel(guild).await {
-            Some(id) => id,
-            None => {
-                return Err(DiscordError::ObtainSettingsChannel);
-            }
-        };
+    /// Deletes the outdated Messages of Roles that have been stored again in a newer Message
+    async fn compact(&self, channel_id: ChannelId, outdated: &[MessageId]) {
+        if outdated.is_empty() {
+            return;
+        }
 
-        let serialized = match serde_json::to_string(&role) {
-            Ok(s) => s,
-            Err(e) => {
-                return Err(DiscordError::Serde(e));
+        tracing::info!(
+            "Deleting {} outdated Role-Messages in {:?}",
+            outdated.len(),
+            channel_id
+        );
+        for msg_id in outdated {
+            if let Err(e) = channel_id.delete_message(self.http.as_ref(), *msg_id).await {
+                tracing::error!("Deleting outdated Role-Message: {:?}", e);
             }
-        };
+        }
+    }
 
-        if let Err(e) = channel_id
-            .send_message(self.http.as_ref(), |m| m.content(serialized))
+    /// Loads the pinned Records of the combined Storage-Format, together with the Messages they
+    /// are stored in
+    async fn load_records(
+        &self,
+        channel_id: ChannelId,
+        bot_id: UserId,
+    ) -> Result<Vec<(MessageId, RolesRecord)>, DiscordError> {
+        let pins = channel_id
+            .pins(self.http.as_ref())
             .await
-        {
-            return Err(DiscordError::SerenityError(e));
+            .map_err(DiscordError::SerenityError)?;
+
+        // The Pins are returned newest first, but the Records should be in the Order in which
+        // they were written
+        Ok(pins
+            .into_iter()
+            .rev()
+            .filter(|msg| msg.author.id == bot_id)
+            .filter_map(|msg| RolesRecord::parse(&msg.content).map(|record| (msg.id, record)))
+            .collect())
+    }
+
+    /// Stores the Roles in the combined Storage-Format, reusing the existing Record-Messages
+    async fn write_records(
+        &self,
+        channel_id: ChannelId,
+        bot_id: UserId,
+        roles: &[WereWolfRoleConfig],
+    ) -> Result<(), DiscordError> {
+        let packed =
+            records::pack_roles(roles, records::MAX_MESSAGE_LENGTH).map_err(DiscordError::Serde)?;
+        let existing = self.load_records(channel_id, bot_id).await?;
+
+        for (index, content) in packed.iter().enumerate() {
+            match existing.get(index) {
+                Some((msg_id, _)) => {
+                    channel_id
+                        .edit_message(self.http.as_ref(), *msg_id, |m| m.content(content))
+                        .await
+                        .map_err(DiscordError::SerenityError)?;
+                }
+                None => {
+                    let msg = channel_id
+                        .send_message(self.http.as_ref(), |m| m.content(content))
+                        .await
+                        .map_err(DiscordError::SerenityError)?;
+                    msg.pin(self.http.as_ref())
+                        .await
+                        .map_err(DiscordError::SerenityError)?;
+                }
+            };
+        }
+
+        // Remove the Records that are no longer needed
+        for (msg_id, _) in existing.iter().skip(packed.len()) {
+            channel_id
+                .delete_message(self.http.as_ref(), *msg_id)
+                .await
+                .map_err(DiscordError::SerenityError)?;
         }
 
         Ok(())
     }
 
-    async fn remove_role(&self, guild: GuildId, role_name: &str) -> Result<(), DiscordError> {
-        let channel_id = match self.obtain_settings_channel(guild).await {
-            Some(id) => id,
-            None => {
-                return Err(DiscordError::ObtainSettingsChannel);
+    /// Loads the Roles stored in the combined Storage-Format. If there are no Records yet, the
+    /// Roles stored in their own Messages are migrated into Records
+    async fn load_combined(
+        &self,
+        channel_id: ChannelId,
+        bot_id: UserId,
+    ) -> Result<Vec<WereWolfRoleConfig>, DiscordError> {
+        let records = self.load_records(channel_id, bot_id).await?;
+        if !records.is_empty() {
+            return Ok(records
+                .into_iter()
+                .flat_map(|(_, record)| record.roles)
+                .collect());
+        }
+
+        let (role_messages, outdated) =
+            dedup_role_messages(self.load_role_messages(channel_id, bot_id).await?);
+        if role_messages.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        tracing::info!(
+            "Migrating {} Roles in {:?} into the combined Storage-Format",
+            role_messages.len(),
+            channel_id
+        );
+
+        let roles: Vec<WereWolfRoleConfig> = role_messages
+            .iter()
+            .map(|(_, config)| config.clone())
+            .collect();
+        self.write_records(channel_id, bot_id, &roles).await?;
+
+        for (msg_id, _) in role_messages {
+            if let Err(e) = channel_id.delete_message(self.http.as_ref(), msg_id).await {
+                tracing::error!("Deleting migrated Role-Message: {:?}", e);
             }
-        };
+        }
+        self.compact(channel_id, &outdated).await;
 
-        let current_user = match self.http.get_current_user().await {
-            Ok(u) => u,
-            Err(e) => return Err(DiscordError::SerenityError(e)),
-        };
+        Ok(roles)
+    }
 
-        let role_msg_id = match self
-            .find_role_message(channel_id, current_user.id, role_name)
-            .await
-        {
-            Some(id) => id,
-            None => {
-                return Err(DiscordError::FindingRole);
+    /// Obtains the Settings-Channel for the Guild, forgetting all the cached Messages whenever
+    /// the Channel has to be obtained again after it was deleted
+    fn settings_channel_source<'s>(
+        &'s self,
+        guild: GuildId,
+    ) -> impl FnMut() -> Pin<Box<dyn Future<Output = Option<ChannelId>> + Send + 's>> + 's {
+        let mut attempts = 0;
+        move || {
+            if attempts > 0 {
+                self.role_messages.clear(guild);
             }
-        };
+            attempts += 1;
 
-        match channel_id
-            .delete_message(self.http.as_ref(), role_msg_id)
-            .await
-        {
-            Ok(_) => Ok(()),
-            Err(e) => Err(DiscordError::SerenityError(e)),
+            Box::pin(self.obtain_settings_channel(guild))
         }
     }
-}
 
-#[async_trait]
-impl StorageBackend for DiscordStorage {
-    async fn load_roles(
-        &self,
-        guild: GuildId,
-    ) -> Result<Vec<WereWolfRoleConfig>, Box<dyn Error + Send>> {
-        self.load_roles(guild)
-            .await
-            .map_err(|e| Box::new(e) as Box<dyn Error + Send>)
+    async fn load_roles(&self, guild: GuildId) -> Result<Vec<WereWolfRoleConfig>, DiscordError> {
+        retry_unknown_channel(self.settings_channel_source(guild), |channel_id| {
+            self.load_roles_from(guild, channel_id)
+        })
+        .await
     }
 
-    async fn set_role(
+    async fn load_roles_from(
         &self,
         guild: GuildId,
-        role: WereWolfRoleConfig,
-    ) -> Result<(), Box<dyn Error + Send>> {
-        self.set_role(guild, role)
-            .await
-            .map_err(|e| Box::new(e) as Box<dyn Error + Send>)
+        channel_id: ChannelId,
+    ) -> Result<Vec<WereWolfRoleConfig>, DiscordError> {
+        let bot_id = self.bot_id().await?;
+
+        if self.format == StorageFormat::Combined {
+            return self.load_combined(channel_id, bot_id).await;
+        }
+
+        let mut messages = Vec::new();
+        let mut message_channels = HashMap::new();
+        for role_channel in self.role_channels(guild, channel_id).await {
+            for (msg_id, config) in self.load_role_messages(role_channel, bot_id).await? {
+                message_channels.insert(msg_id, role_channel);
+                messages.push((msg_id, config));
+            }
+        }
+        // The IDs of the Messages are ordered by Time across all the Channels, so the Messages
+        // are ordered newest first again
+        messages.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        let (roles, outdated) = dedup_role_messages(messages);
+        for (msg_id, config) in roles.iter() {
+            self.role_messages
+                .insert(guild, config.name(), message_channels[msg_id], *msg_id);
+        }
+
+        let mut outdated_by_channel: HashMap<ChannelId, Vec<MessageId>> = HashMap::new();
+        for msg_id in outdated {
+            outdated_by_channel
+                .entry(message_channels[&msg_id])
+                .or_default()
+                .push(msg_id);
+        }
+        for (outdated_channel, outdated) in outdated_by_channel {
+            self.compact(outdated_channel, &outdated).await;
+        }
+
+        Ok(roles.into_iter().map(|(_, config)| config).collect())
     }
 
-    async fn remove_role(
+    async fn set_role(&self, guild: GuildId, role: WereWolfRoleConfig) -> Result<(), DiscordError> {
+        retry_unknown_channel(self.settings_channel_source(guild), |channel_id| {
+            self.set_role_in(guild, channel_id, role.clone())
+        })
+        .await
+    }
+
+    async fn set_role_in(
         &self,
         guild: GuildId,
-        role_name: &str,
-    ) -> Result<(), Box<dyn Error + Send>> {
-        self.remove_role(guild, role_name)
+        channel_id: ChannelId,
+        role: WereWolfRoleConfig,
+    ) -> Result<(), DiscordError> {
+        if self.format == StorageFormat::Combined {
+            let bot_id = self.bot_id().await?;
+
+            let mut roles = self.load_combined(channel_id, bot_id).await?;
+            match roles.iter_mut().find(|r| r.name() == role.name()) {
+                Some(existing) => *existing = role,
+                None => roles.push(role),
+            };
+
+            return self.write_records(channel_id, bot_id, &roles).await;
+        }
+
+        let serialized = match serde_json::to_string(&role) {
+            Ok(s) => s,
+            Err(e) => {
+                return Err(DiscordError::Serde(e));
+            }
+        };
+
+        let role_channel = self
+            .obtain_role_channel(guild, channel_id, role.name())
+            .await?;
+
+        let content = records::mark_role(&self.role_marker, &serialized);
+        let msg = match role_channel
+            .send_message(self.http.as_ref(), |m| m.content(content))
+            .await
+        {
+            Ok(m) => m,
+            Err(e) => return Err(DiscordError::SerenityError(e)),
+        };
+        self.role_messages
+            .insert(guild, role.name(), role_channel, msg.id);
+
+        Ok(())
+    }
+
+    async fn remove_role(&self, guild: GuildId, role_name: &str) -> Result<(), DiscordError> {
+        retry_unknown_channel(self.settings_channel_source(guild), |channel_id| {
+            self.remove_role_from(guild, channel_id, role_name)
+        })
+        .await
+    }
+
+    async fn remove_role_from(
+        &self,
+        guild: GuildId,
+        channel_id: ChannelId,
+        role_name: &str,
+    ) -> Result<(), DiscordError> {
+        if self.format == StorageFormat::Combined {
+            let bot_id = self.bot_id().await?;
+
+            let mut roles = self.load_combined(channel_id, bot_id).await?;
+            let previous_count = roles.len();
+            roles.retain(|r| r.name() != role_name);
+            if roles.len() == previous_count {
+                return Err(DiscordError::FindingRole);
+            }
+
+            return self.write_records(channel_id, bot_id, &roles).await;
+        }
+
+        let (role_channel, role_msg_id) = match self.role_messages.get(guild, role_name) {
+            Some(ids) => ids,
+            None => {
+                let bot_id = self.bot_id().await?;
+
+                let mut found = None;
+                for role_channel in self.role_channels(guild, channel_id).await {
+                    if let Some(id) = self
+                        .find_role_message(role_channel, bot_id, role_name)
+                        .await?
+                    {
+                        found = Some((role_channel, id));
+                        break;
+                    }
+                }
+
+                match found {
+                    Some(ids) => ids,
+                    None => {
+                        return Err(DiscordError::FindingRole);
+                    }
+                }
+            }
+        };
+
+        // The cached ID is removed either way, so a stale ID is only ever tried once
+        self.role_messages.remove(guild, role_name);
+
+        match role_channel
+            .delete_message(self.http.as_ref(), role_msg_id)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(DiscordError::SerenityError(e)),
+        }
+    }
+
+    async fn load_settings(&self, guild: GuildId) -> Result<GuildSettings, DiscordError> {
+        let channel_id = match self.obtain_settings_channel(guild).await {
+            Some(c) => c,
+            None => {
+                return Err(DiscordError::ObtainSettingsChannel);
+            }
+        };
+
+        let bot_id = self.bot_id().await?;
+
+        Ok(self
+            .find_settings_message(channel_id, bot_id)
+            .await?
+            .map(|(_, settings)| settings)
+            .unwrap_or_default())
+    }
+
+    async fn save_settings(
+        &self,
+        guild: GuildId,
+        settings: GuildSettings,
+    ) -> Result<(), DiscordError> {
+        let channel_id = match self.obtain_settings_channel(guild).await {
+            Some(id) => id,
+            None => {
+                return Err(DiscordError::ObtainSettingsChannel);
+            }
+        };
+
+        let bot_id = self.bot_id().await?;
+
+        let serialized = match serde_json::to_string(&SettingsRecord {
+            guild_settings: settings,
+        }) {
+            Ok(s) => s,
+            Err(e) => {
+                return Err(DiscordError::Serde(e));
+            }
+        };
+
+        if serialized.chars().count() > records::MAX_MESSAGE_LENGTH {
+            return Err(DiscordError::SettingsTooLarge);
+        }
+
+        let result = match self.find_settings_message(channel_id, bot_id).await? {
+            Some((msg_id, _)) => channel_id
+                .edit_message(self.http.as_ref(), msg_id, |m| m.content(serialized))
+                .await
+                .map(|_| ()),
+            None => channel_id
+                .send_message(self.http.as_ref(), |m| m.content(serialized))
+                .await
+                .map(|_| ()),
+        };
+
+        result.map_err(DiscordError::SerenityError)
+    }
+}
+
+/// Whether or not the Message contains a Backup of the Roles
+fn is_backup(msg: &Message) -> bool {
+    BackupRecord::parse(&msg.content).is_some()
+        || msg
+            .attachments
+            .iter()
+            .any(|a| a.filename == BACKUP_FILE_NAME)
+}
+
+impl DiscordStorage {
+    async fn store_backup(
+        &self,
+        guild: GuildId,
+        roles: &[WereWolfRoleConfig],
+    ) -> Result<(), DiscordError> {
+        let channel_id = match self.obtain_settings_channel(guild).await {
+            Some(id) => id,
+            None => {
+                return Err(DiscordError::ObtainSettingsChannel);
+            }
+        };
+
+        let backup = BackupRecord {
+            roles_backup: roles.to_vec(),
+        };
+        let encoded = backup.encode().map_err(DiscordError::Serde)?;
+
+        // Backups that don't fit into a single Message are attached as a File instead
+        let msg = if encoded.len() <= records::MAX_MESSAGE_LENGTH {
+            channel_id
+                .send_message(self.http.as_ref(), |m| m.content(encoded))
+                .await
+        } else {
+            let raw = serde_json::to_vec(&backup).map_err(DiscordError::Serde)?;
+            channel_id
+                .send_message(self.http.as_ref(), |m| {
+                    m.content("Roles-Backup")
+                        .add_file((raw.as_slice(), BACKUP_FILE_NAME))
+                })
+                .await
+        }
+        .map_err(DiscordError::SerenityError)?;
+
+        msg.pin(self.http.as_ref())
+            .await
+            .map_err(DiscordError::SerenityError)?;
+
+        // Only the most recent Backup stays pinned, so the Backups don't use up all the Pins
+        let bot_id = self.bot_id().await?;
+        let pins = channel_id
+            .pins(self.http.as_ref())
+            .await
+            .map_err(DiscordError::SerenityError)?;
+        for old in pins
+            .into_iter()
+            .filter(|m| m.id != msg.id && m.author.id == bot_id && is_backup(m))
+        {
+            if let Err(e) = old.unpin(self.http.as_ref()).await {
+                tracing::error!("Unpinning old Backup: {:?}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn load_backup(
+        &self,
+        guild: GuildId,
+    ) -> Result<Option<Vec<WereWolfRoleConfig>>, DiscordError> {
+        let channel_id = match self.obtain_settings_channel(guild).await {
+            Some(id) => id,
+            None => {
+                return Err(DiscordError::ObtainSettingsChannel);
+            }
+        };
+
+        let bot_id = self.bot_id().await?;
+
+        let pins = channel_id
+            .pins(self.http.as_ref())
+            .await
+            .map_err(DiscordError::SerenityError)?;
+
+        // The Pins are returned newest first, so the first Backup is the most recent one
+        for msg in pins.into_iter().filter(|m| m.author.id == bot_id) {
+            if let Some(backup) = BackupRecord::parse(&msg.content) {
+                return Ok(Some(backup.roles_backup));
+            }
+
+            let attachment = match msg
+                .attachments
+                .iter()
+                .find(|a| a.filename == BACKUP_FILE_NAME)
+            {
+                Some(a) => a,
+                None => continue,
+            };
+            let raw = attachment
+                .download()
+                .await
+                .map_err(DiscordError::SerenityError)?;
+            let backup: BackupRecord = serde_json::from_slice(&raw).map_err(DiscordError::Serde)?;
+
+            return Ok(Some(backup.roles_backup));
+        }
+
+        Ok(None)
+    }
+}
+
+impl DiscordStorage {
+    async fn store_round(&self, guild: GuildId, round: &RoundRecord) -> Result<(), DiscordError> {
+        let channel_id = match self.obtain_settings_channel(guild).await {
+            Some(id) => id,
+            None => {
+                return Err(DiscordError::ObtainSettingsChannel);
+            }
+        };
+
+        let encoded = HistoryRecord {
+            round_history: round.clone(),
+        }
+        .encode()
+        .map_err(DiscordError::Serde)?;
+
+        channel_id
+            .send_message(self.http.as_ref(), |m| m.content(encoded))
+            .await
+            .map_err(DiscordError::SerenityError)?;
+
+        self.prune_rounds(channel_id).await;
+
+        Ok(())
+    }
+
+    /// Deletes the oldest Rounds, once there are more than [`MAX_STORED_ROUNDS`] stored
+    async fn prune_rounds(&self, channel_id: ChannelId) {
+        let bot_id = match self.bot_id().await {
+            Ok(id) => id,
+            Err(e) => {
+                tracing::error!("Loading the ID of the Bot: {:?}", e);
+                return;
+            }
+        };
+
+        let message_iter = match self.settings_message_iter(channel_id, bot_id).await {
+            Ok(m) => m,
+            Err(e) => {
+                tracing::error!("Loading the stored Rounds: {:?}", e);
+                return;
+            }
+        };
+
+        // The Messages are returned newest first, so the oldest Rounds are at the End
+        let outdated: Vec<MessageId> = message_iter
+            .filter(|msg| ready(HistoryRecord::parse(&msg.content).is_some()))
+            .skip(MAX_STORED_ROUNDS)
+            .map(|msg| msg.id)
+            .collect()
+            .await;
+        if outdated.is_empty() {
+            return;
+        }
+
+        tracing::info!(
+            "Deleting {} outdated Rounds in {:?}",
+            outdated.len(),
+            channel_id
+        );
+        for msg_id in outdated {
+            if let Err(e) = channel_id.delete_message(self.http.as_ref(), msg_id).await {
+                tracing::error!("Deleting outdated Round: {:?}", e);
+            }
+        }
+    }
+
+    async fn load_rounds(&self, guild: GuildId) -> Result<Vec<RoundRecord>, DiscordError> {
+        let channel_id = match self.obtain_settings_channel(guild).await {
+            Some(id) => id,
+            None => {
+                return Err(DiscordError::ObtainSettingsChannel);
+            }
+        };
+
+        let bot_id = self.bot_id().await?;
+
+        // The Messages are returned newest first, which is also the Order of the History
+        let message_iter = self.settings_message_iter(channel_id, bot_id).await?;
+        let rounds = message_iter.filter_map(|msg| {
+            ready(HistoryRecord::parse(&msg.content).map(|record| record.round_history))
+        });
+
+        Ok(rounds.collect().await)
+    }
+}
+
+impl DiscordStorage {
+    /// Loads all the stored States of Rounds whose Role-Counts are still being entered, together
+    /// with the Message they are stored in
+    async fn load_counts_messages(
+        &self,
+        channel_id: ChannelId,
+        bot_id: UserId,
+    ) -> Result<Vec<(MessageId, PendingCounts)>, DiscordError> {
+        let message_iter = self.settings_message_iter(channel_id, bot_id).await?;
+
+        Ok(message_iter
+            .filter_map(|msg| {
+                ready(
+                    CountsRecord::parse(&msg.content).map(|record| (msg.id, record.pending_counts)),
+                )
+            })
+            .collect()
+            .await)
+    }
+
+    async fn save_pending_counts(
+        &self,
+        guild: GuildId,
+        counts: &PendingCounts,
+    ) -> Result<(), DiscordError> {
+        let channel_id = match self.obtain_settings_channel(guild).await {
+            Some(id) => id,
+            None => {
+                return Err(DiscordError::ObtainSettingsChannel);
+            }
+        };
+
+        let bot_id = self.bot_id().await?;
+
+        let encoded = CountsRecord {
+            pending_counts: counts.clone(),
+        }
+        .encode()
+        .map_err(DiscordError::Serde)?;
+
+        let existing = self
+            .load_counts_messages(channel_id, bot_id)
+            .await?
+            .into_iter()
+            .find(|(_, stored)| stored.message == counts.message);
+        let result = match existing {
+            Some((msg_id, _)) => channel_id
+                .edit_message(self.http.as_ref(), msg_id, |m| m.content(encoded))
+                .await
+                .map(|_| ()),
+            None => channel_id
+                .send_message(self.http.as_ref(), |m| m.content(encoded))
+                .await
+                .map(|_| ()),
+        };
+
+        result.map_err(DiscordError::SerenityError)
+    }
+
+    async fn clear_pending_counts(
+        &self,
+        guild: GuildId,
+        round: MessageId,
+    ) -> Result<(), DiscordError> {
+        let channel_id = match self.obtain_settings_channel(guild).await {
+            Some(id) => id,
+            None => {
+                return Err(DiscordError::ObtainSettingsChannel);
+            }
+        };
+
+        let bot_id = self.bot_id().await?;
+
+        for (msg_id, stored) in self.load_counts_messages(channel_id, bot_id).await? {
+            if stored.message != round {
+                continue;
+            }
+
+            channel_id
+                .delete_message(self.http.as_ref(), msg_id)
+                .await
+                .map_err(DiscordError::SerenityError)?;
+        }
+
+        Ok(())
+    }
+
+    async fn load_pending_counts(
+        &self,
+        guild: GuildId,
+    ) -> Result<Vec<PendingCounts>, DiscordError> {
+        // This is checked for every Guild on Startup, which should not create any Channels
+        let channel_id = match self.get_settings_channel(guild).await {
+            Ok(id) => id,
+            Err(_) => return Ok(Vec::new()),
+        };
+
+        let bot_id = self.bot_id().await?;
+
+        Ok(self
+            .load_counts_messages(channel_id, bot_id)
+            .await?
+            .into_iter()
+            .map(|(_, counts)| counts)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl StorageBackend for DiscordStorage {
+    async fn load_roles(
+        &self,
+        guild: GuildId,
+    ) -> Result<Vec<WereWolfRoleConfig>, Box<dyn Error + Send>> {
+        self.load_roles(guild)
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn Error + Send>)
+    }
+
+    async fn set_role(
+        &self,
+        guild: GuildId,
+        role: WereWolfRoleConfig,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        self.set_role(guild, role)
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn Error + Send>)
+    }
+
+    async fn remove_role(
+        &self,
+        guild: GuildId,
+        role_name: &str,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        self.remove_role(guild, role_name)
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn Error + Send>)
+    }
+
+    async fn store_backup(
+        &self,
+        guild: GuildId,
+        roles: &[WereWolfRoleConfig],
+    ) -> Result<(), Box<dyn Error + Send>> {
+        self.store_backup(guild, roles)
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn Error + Send>)
+    }
+
+    async fn load_backup(
+        &self,
+        guild: GuildId,
+    ) -> Result<Option<Vec<WereWolfRoleConfig>>, Box<dyn Error + Send>> {
+        self.load_backup(guild)
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn Error + Send>)
+    }
+
+    async fn store_round(
+        &self,
+        guild: GuildId,
+        round: &RoundRecord,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        self.store_round(guild, round)
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn Error + Send>)
+    }
+
+    async fn load_rounds(&self, guild: GuildId) -> Result<Vec<RoundRecord>, Box<dyn Error + Send>> {
+        self.load_rounds(guild)
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn Error + Send>)
+    }
+
+    async fn load_settings(&self, guild: GuildId) -> Result<GuildSettings, Box<dyn Error + Send>> {
+        self.load_settings(guild)
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn Error + Send>)
+    }
+
+    async fn save_settings(
+        &self,
+        guild: GuildId,
+        settings: GuildSettings,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        self.save_settings(guild, settings)
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn Error + Send>)
+    }
+
+    async fn save_pending_counts(
+        &self,
+        guild: GuildId,
+        counts: &PendingCounts,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        self.save_pending_counts(guild, counts)
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn Error + Send>)
+    }
+
+    async fn clear_pending_counts(
+        &self,
+        guild: GuildId,
+        round: MessageId,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        self.clear_pending_counts(guild, round)
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn Error + Send>)
+    }
+
+    async fn load_pending_counts(
+        &self,
+        guild: GuildId,
+    ) -> Result<Vec<PendingCounts>, Box<dyn Error + Send>> {
+        self.load_pending_counts(guild)
             .await
             .map_err(|e| Box::new(e) as Box<dyn Error + Send>)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn role_messages() {
+        let messages = RoleMessages::default();
+
+        messages.insert(GuildId(13), "Seer", ChannelId(7), MessageId(1));
+        messages.insert(GuildId(13), "Werewolf", ChannelId(8), MessageId(2));
+        messages.insert(GuildId(14), "Seer", ChannelId(7), MessageId(3));
+
+        assert_eq!(
+            Some((ChannelId(7), MessageId(1))),
+            messages.get(GuildId(13), "Seer")
+        );
+        assert_eq!(
+            Some((ChannelId(7), MessageId(3))),
+            messages.get(GuildId(14), "Seer")
+        );
+        assert_eq!(None, messages.get(GuildId(14), "Werewolf"));
+
+        messages.remove(GuildId(13), "Seer");
+        assert_eq!(None, messages.get(GuildId(13), "Seer"));
+        assert_eq!(
+            Some((ChannelId(8), MessageId(2))),
+            messages.get(GuildId(13), "Werewolf")
+        );
+    }
+
+    #[test]
+    fn duplicate_role_messages() {
+        let seer = |emoji: &str| WereWolfRoleConfig::new("Seer", emoji, false, false, Vec::new());
+        let wolf = WereWolfRoleConfig::new("Werewolf", "🐺", true, false, Vec::new());
+
+        // The Messages are loaded newest first
+        let messages = vec![
+            (MessageId(5), seer("🔮")),
+            (MessageId(4), wolf.clone()),
+            (MessageId(3), seer("👁")),
+            (MessageId(1), seer("👀")),
+        ];
+
+        let (kept, outdated) = dedup_role_messages(messages);
+
+        assert_eq!(vec![(MessageId(5), seer("🔮")), (MessageId(4), wolf)], kept);
+        assert_eq!(vec![MessageId(3), MessageId(1)], outdated);
+    }
+
+    fn text_channel(id: u64, name: &str, kind: u8) -> (ChannelId, GuildChannel) {
+        let channel = serde_json::from_value(serde_json::json!({
+            "id": id.to_string(),
+            "guild_id": "1",
+            "type": kind,
+            "name": name,
+            "permission_overwrites": [],
+            "position": 0,
+        }))
+        .unwrap();
+
+        (ChannelId(id), channel)
+    }
+
+    #[test]
+    fn shard_names() {
+        assert_eq!("W-Settings", shard_channel_name(0));
+        assert_eq!("W-Settings-2", shard_channel_name(2));
+
+        assert_eq!(Some(2), shard_index("W-Settings-2"));
+        assert_eq!(Some(13), shard_index("w-settings-13"));
+        assert_eq!(None, shard_index("W-Settings"));
+        assert_eq!(None, shard_index("W-Settings-0"));
+        assert_eq!(None, shard_index("W-Settings-old"));
+        assert_eq!(None, shard_index("W-Other-1"));
+    }
+
+    #[test]
+    fn discover_shard_channels() {
+        let channels: HashMap<ChannelId, GuildChannel> = vec![
+            text_channel(1, "W-Settings", 0),
+            text_channel(2, "w-settings-2", 0),
+            text_channel(3, "W-Settings-1", 0),
+            text_channel(4, "general", 0),
+            // Categories are never used as Settings-Channels
+            text_channel(5, "W-Settings-3", 4),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            vec![(1, ChannelId(3)), (2, ChannelId(2))],
+            find_shard_channels(&channels)
+        );
+    }
+
+    #[test]
+    fn no_shard_channels() {
+        let channels: HashMap<ChannelId, GuildChannel> =
+            vec![text_channel(1, "W-Settings", 0)].into_iter().collect();
+
+        assert!(find_shard_channels(&channels).is_empty());
+    }
+
+    #[test]
+    fn roles_spread_across_channels() {
+        let names: Vec<String> = (0..100).map(|i| format!("Role-{}", i)).collect();
+
+        // A single Channel stores everything
+        assert!(names.iter().all(|n| shard_for(n, 1) == 0));
+        assert!(names.iter().all(|n| shard_for(n, 0) == 0));
+
+        let shards: Vec<usize> = names.iter().map(|n| shard_for(n, 3)).collect();
+        assert!(shards.iter().all(|s| *s < 3));
+        for index in 0..3 {
+            assert!(shards.contains(&index));
+        }
+
+        // The same Role always ends up in the same Channel
+        assert_eq!(shard_for("Seer", 3), shard_for("Seer", 3));
+    }
+
+    #[tokio::test]
+    async fn bot_id_fetched_once() {
+        let cell = OnceCell::new();
+        let fetches = AtomicUsize::new(0);
+
+        for _ in 0..3 {
+            let result = cached_id(&cell, || async {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                Ok(UserId(13))
+            })
+            .await;
+
+            assert_eq!(UserId(13), result.unwrap());
+        }
+
+        assert_eq!(1, fetches.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn visible_settings_channel() {
+        assert!(settings_channel_permissions(GuildId(1), UserId(2), false).is_empty());
+    }
+
+    #[test]
+    fn hidden_settings_channel() {
+        let permissions = settings_channel_permissions(GuildId(1), UserId(2), true);
+
+        let everyone = permissions
+            .iter()
+            .find(|p| {
+                p.kind == PermissionOverwriteType::Role(util::roles::everyone_role(GuildId(1)))
+            })
+            .unwrap();
+        assert!(everyone.deny.read_messages());
+
+        let bot = permissions
+            .iter()
+            .find(|p| p.kind == PermissionOverwriteType::Member(UserId(2)))
+            .unwrap();
+        assert!(bot.allow.read_messages());
+        assert!(bot.allow.send_messages());
+    }
+
+    #[tokio::test]
+    async fn unknown_channel_retried() {
+        let obtained = AtomicUsize::new(0);
+        let attempts = AtomicUsize::new(0);
+
+        let result = retry_unknown_channel(
+            || async {
+                let count = obtained.fetch_add(1, Ordering::SeqCst) as u64;
+                Some(ChannelId(10 + count))
+            },
+            |channel_id| {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if attempt == 0 {
+                        Err(DiscordError::UnknownChannel)
+                    } else {
+                        Ok(channel_id)
+                    }
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(ChannelId(11), result.unwrap());
+        assert_eq!(2, obtained.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn other_errors_not_retried() {
+        let attempts = AtomicUsize::new(0);
+
+        let result: Result<(), _> = retry_unknown_channel(
+            || async { Some(ChannelId(10)) },
+            |_| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async { Err(DiscordError::FindingRole) }
+            },
+        )
+        .await;
+
+        assert!(matches!(result, Err(DiscordError::FindingRole)));
+        assert_eq!(1, attempts.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn bot_id_retried_after_error() {
+        let cell = OnceCell::new();
+
+        let result = cached_id(&cell, || async { Err(DiscordError::FindingRole) }).await;
+        assert!(result.is_err());
+
+        let result = cached_id(&cell, || async { Ok(UserId(13)) }).await;
+        assert_eq!(UserId(13), result.unwrap());
+    }
+}
@@ -0,0 +1,436 @@
+use std::{
+    fmt::Display,
+    io::{Read, Write},
+    str::FromStr,
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    roles::WereWolfRoleConfig,
+    rounds::{counts::PendingCounts, history::RoundRecord},
+};
+
+/// The maximum Length of a single Discord-Message
+pub const MAX_MESSAGE_LENGTH: usize = 2000;
+
+/// The Prefix marking a Message whose Content is gzip-compressed and base64-encoded
+const COMPRESSED_PREFIX: &str = "gz:";
+
+/// Compresses the given Content and marks it as compressed
+pub fn compress(content: &str) -> String {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(content.as_bytes())
+        .expect("Writing into a Vec should never fail");
+    let compressed = encoder
+        .finish()
+        .expect("Writing into a Vec should never fail");
+
+    format!("{}{}", COMPRESSED_PREFIX, base64::encode(compressed))
+}
+
+/// Decompresses the given Content if it is marked as compressed, otherwise the Content is
+/// returned as is
+pub fn decompress(content: &str) -> Option<String> {
+    let encoded = match content.strip_prefix(COMPRESSED_PREFIX) {
+        Some(e) => e,
+        None => return Some(content.to_string()),
+    };
+
+    let compressed = base64::decode(encoded).ok()?;
+
+    let mut result = String::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_string(&mut result)
+        .ok()?;
+    Some(result)
+}
+
+/// The default Marker in front of every Message that stores a single Role
+pub const DEFAULT_ROLE_MARKER: &str = "🐺";
+
+/// Puts the Marker in front of the serialized Role, so the Message can be told apart from other
+/// Messages that happen to contain valid JSON
+pub fn mark_role(marker: &str, serialized: &str) -> String {
+    format!("{}{}", marker, serialized)
+}
+
+/// Attempts to parse a Role from the Content of a Message, only Messages starting with the Marker
+/// are considered. An empty Marker accepts every Message that contains a valid Role
+pub fn parse_role(marker: &str, content: &str) -> Option<WereWolfRoleConfig> {
+    let raw = content.strip_prefix(marker)?;
+    serde_json::from_str(raw).ok()
+}
+
+/// Attempts to parse a Role that is stored without the current Marker, because it was either
+/// stored before the Marker was introduced or with a previously configured Marker
+pub fn parse_unmarked_role(content: &str) -> Option<WereWolfRoleConfig> {
+    let start = content.find('{')?;
+    serde_json::from_str(&content[start..]).ok()
+}
+
+/// How the Roles are stored in the Settings-Channel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StorageFormat {
+    /// Every Role is stored in its own Message
+    PerRole,
+    /// All the Roles are stored together in as few pinned Messages as possible
+    Combined,
+}
+
+impl Display for StorageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PerRole => write!(f, "per-role"),
+            Self::Combined => write!(f, "combined"),
+        }
+    }
+}
+
+impl FromStr for StorageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "per-role" => Ok(Self::PerRole),
+            "combined" => Ok(Self::Combined),
+            other => Err(format!("Unknown Storage-Format '{}'", other)),
+        }
+    }
+}
+
+/// A Message storing multiple Roles at once, the Roles are wrapped in their own Key so that the
+/// Message can't be mistaken for a single Role
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RolesRecord {
+    pub roles: Vec<WereWolfRoleConfig>,
+}
+
+impl RolesRecord {
+    /// Attempts to parse a Record from the Content of a Message, which may be compressed
+    pub fn parse(content: &str) -> Option<Self> {
+        serde_json::from_str(&decompress(content)?).ok()
+    }
+
+    /// Serializes and compresses the Record into the Content of a Message
+    fn encode(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self).map(|raw| compress(&raw))
+    }
+}
+
+/// A Backup of all the Roles of a Guild
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct BackupRecord {
+    pub roles_backup: Vec<WereWolfRoleConfig>,
+}
+
+impl BackupRecord {
+    /// Attempts to parse a Backup from the Content of a Message, which may be compressed
+    pub fn parse(content: &str) -> Option<Self> {
+        serde_json::from_str(&decompress(content)?).ok()
+    }
+
+    /// Serializes and compresses the Backup into the Content of a Message
+    pub fn encode(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self).map(|raw| compress(&raw))
+    }
+}
+
+/// A finished Round in the History of a Guild
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    pub round_history: RoundRecord,
+}
+
+impl HistoryRecord {
+    /// Attempts to parse a History-Entry from the Content of a Message, which may be compressed
+    pub fn parse(content: &str) -> Option<Self> {
+        serde_json::from_str(&decompress(content)?).ok()
+    }
+
+    /// Serializes and compresses the History-Entry into the Content of a Message
+    pub fn encode(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self).map(|raw| compress(&raw))
+    }
+}
+
+/// The State of a Round whose Role-Counts are still being entered
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct CountsRecord {
+    pub pending_counts: PendingCounts,
+}
+
+impl CountsRecord {
+    /// Attempts to parse the State from the Content of a Message, which may be compressed
+    pub fn parse(content: &str) -> Option<Self> {
+        serde_json::from_str(&decompress(content)?).ok()
+    }
+
+    /// Serializes and compresses the State into the Content of a Message
+    pub fn encode(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self).map(|raw| compress(&raw))
+    }
+}
+
+/// Splits the Roles into as few compressed Records as possible, where every Record fits into a
+/// Message with the given maximum Length
+pub fn pack_roles(
+    roles: &[WereWolfRoleConfig],
+    max_length: usize,
+) -> Result<Vec<String>, serde_json::Error> {
+    let mut result = Vec::new();
+    let mut current: Vec<WereWolfRoleConfig> = Vec::new();
+    let mut current_serialized: Option<String> = None;
+
+    for role in roles {
+        current.push(role.clone());
+        let serialized = RolesRecord {
+            roles: current.clone(),
+        }
+        .encode()?;
+
+        if serialized.len() > max_length && current.len() > 1 {
+            // The Record without the new Role still fit, so it is finished and the Role starts
+            // the next Record
+            current.pop();
+            result.extend(current_serialized.take());
+
+            current = vec![role.clone()];
+            current_serialized = Some(
+                RolesRecord {
+                    roles: current.clone(),
+                }
+                .encode()?,
+            );
+        } else {
+            current_serialized = Some(serialized);
+        }
+    }
+    result.extend(current_serialized);
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serenity::model::id::{ChannelId, GuildId, MessageId, UserId};
+
+    use crate::rounds::{counts::PendingRole, options::RoundOptions};
+
+    use super::*;
+
+    fn roles(count: usize) -> Vec<WereWolfRoleConfig> {
+        (0..count)
+            .map(|i| WereWolfRoleConfig::new(format!("Role-{}", i), "", false, false, Vec::new()))
+            .collect()
+    }
+
+    #[test]
+    fn pack_empty() {
+        assert_eq!(
+            Vec::<String>::new(),
+            pack_roles(&[], MAX_MESSAGE_LENGTH).unwrap()
+        );
+    }
+
+    #[test]
+    fn pack_single_record() {
+        let roles = roles(3);
+
+        let packed = pack_roles(&roles, MAX_MESSAGE_LENGTH).unwrap();
+
+        assert_eq!(1, packed.len());
+        assert_eq!(Some(RolesRecord { roles }), RolesRecord::parse(&packed[0]));
+    }
+
+    #[test]
+    fn pack_multiple_records() {
+        let roles = roles(100);
+
+        let packed = pack_roles(&roles, 300).unwrap();
+
+        assert!(packed.len() > 1);
+        assert!(packed.iter().all(|p| p.len() <= 300));
+
+        let unpacked: Vec<WereWolfRoleConfig> = packed
+            .iter()
+            .flat_map(|p| RolesRecord::parse(p).unwrap().roles)
+            .collect();
+        assert_eq!(roles, unpacked);
+    }
+
+    #[test]
+    fn history_round_trip() {
+        let record = HistoryRecord {
+            round_history: RoundRecord {
+                started_at: 10,
+                ended_at: 20,
+                players: Vec::new(),
+                winner: Some("Village".to_string()),
+                roles: BTreeMap::new(),
+                label: Some("Final".to_string()),
+            },
+        };
+
+        let encoded = record.encode().unwrap();
+
+        assert_eq!(Some(record), HistoryRecord::parse(&encoded));
+        assert_eq!(None, RolesRecord::parse(&encoded));
+    }
+
+    #[test]
+    fn counts_round_trip() {
+        let wolf = WereWolfRoleConfig::new("Werewolf", "🐺", true, false, Vec::new());
+        let mut options = RoundOptions::default();
+        options.pinned.insert(UserId(11), "Seer".to_string());
+
+        let record = CountsRecord {
+            pending_counts: PendingCounts {
+                guild: GuildId(1),
+                channel: ChannelId(2),
+                message: MessageId(3),
+                mods: vec![UserId(4)].into_iter().collect(),
+                bot_user: UserId(5),
+                options,
+                players: vec![UserId(10), UserId(11), UserId(12)],
+                counted: vec![(
+                    WereWolfRoleConfig::new("Seer", "🔮", false, false, Vec::new()),
+                    1,
+                )],
+                pending: vec![PendingRole {
+                    role: wolf,
+                    channel: ChannelId(6),
+                    message: MessageId(7),
+                }],
+                counted_roles: 1,
+                via_dm: true,
+            },
+        };
+
+        let encoded = record.encode().unwrap();
+
+        assert_eq!(Some(record), CountsRecord::parse(&encoded));
+        assert_eq!(None, HistoryRecord::parse(&encoded));
+        assert_eq!(None, RolesRecord::parse(&encoded));
+    }
+
+    #[test]
+    fn compress_round_trip() {
+        let roles = roles(500);
+        let raw = serde_json::to_string(&RolesRecord { roles }).unwrap();
+
+        let compressed = compress(&raw);
+
+        assert!(compressed.starts_with(COMPRESSED_PREFIX));
+        assert!(compressed.len() < raw.len());
+        assert_eq!(Some(raw), decompress(&compressed));
+    }
+
+    #[test]
+    fn parse_plaintext_record() {
+        let roles = roles(2);
+        let raw = serde_json::to_string(&RolesRecord {
+            roles: roles.clone(),
+        })
+        .unwrap();
+
+        assert_eq!(Some(RolesRecord { roles }), RolesRecord::parse(&raw));
+    }
+
+    #[test]
+    fn decompress_invalid() {
+        assert_eq!(None, decompress("gz:not base64!"));
+    }
+
+    #[test]
+    fn large_set_fits() {
+        let roles = roles(300);
+
+        let packed = pack_roles(&roles, MAX_MESSAGE_LENGTH).unwrap();
+
+        assert!(packed.len() < 5);
+        let unpacked: Vec<WereWolfRoleConfig> = packed
+            .iter()
+            .flat_map(|p| RolesRecord::parse(p).unwrap().roles)
+            .collect();
+        assert_eq!(roles, unpacked);
+    }
+
+    #[test]
+    fn backup_round_trip() {
+        let backup = BackupRecord {
+            roles_backup: roles(20),
+        };
+
+        let encoded = backup.encode().unwrap();
+
+        assert_eq!(Some(backup), BackupRecord::parse(&encoded));
+        assert_eq!(None, RolesRecord::parse(&encoded));
+    }
+
+    #[test]
+    fn role_is_not_record() {
+        let role = serde_json::to_string(&roles(1)[0]).unwrap();
+
+        assert_eq!(None, RolesRecord::parse(&role));
+    }
+
+    #[test]
+    fn marked_role() {
+        let role = roles(1).remove(0);
+        let serialized = serde_json::to_string(&role).unwrap();
+
+        let marked = mark_role(DEFAULT_ROLE_MARKER, &serialized);
+
+        assert_eq!(Some(role.clone()), parse_role(DEFAULT_ROLE_MARKER, &marked));
+        assert_eq!(None, parse_role("⚙", &marked));
+        // Without a Marker every valid Role is accepted
+        assert_eq!(Some(role), parse_role("", &serialized));
+    }
+
+    #[test]
+    fn unmarked_role() {
+        let role = roles(1).remove(0);
+        let serialized = serde_json::to_string(&role).unwrap();
+
+        assert_eq!(Some(role.clone()), parse_unmarked_role(&serialized));
+        assert_eq!(
+            Some(role),
+            parse_unmarked_role(&mark_role("⚙", &serialized))
+        );
+        assert_eq!(None, parse_unmarked_role("Some other Message"));
+    }
+
+    #[test]
+    fn decoy_role_message() {
+        let decoy = serde_json::to_string(&roles(1)[0]).unwrap();
+
+        // Valid JSON without the Marker, like one posted by a User
+        assert_eq!(None, parse_role(DEFAULT_ROLE_MARKER, &decoy));
+        // The Marker without a valid Role
+        assert_eq!(
+            None,
+            parse_role(DEFAULT_ROLE_MARKER, &mark_role(DEFAULT_ROLE_MARKER, "{}"))
+        );
+        // Records are never mistaken for a Role
+        let record = serde_json::to_string(&RolesRecord { roles: roles(1) }).unwrap();
+        assert_eq!(
+            None,
+            parse_role(
+                DEFAULT_ROLE_MARKER,
+                &mark_role(DEFAULT_ROLE_MARKER, &record)
+            )
+        );
+    }
+
+    #[test]
+    fn parse_format() {
+        assert_eq!(Ok(StorageFormat::PerRole), "per-role".parse());
+        assert_eq!(Ok(StorageFormat::Combined), "combined".parse());
+        assert!("other".parse::<StorageFormat>().is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use std::{error::Error, fmt::Display};
+
+use serenity::model::id::GuildId;
+
+use super::StorageBackend;
+
+/// The Outcome of migrating the Roles of a Guild from one Backend to another
+#[derive(Debug, Default, PartialEq)]
+pub struct MigrationReport {
+    /// The Number of Roles that were written to the Target
+    pub migrated: usize,
+    /// The Number of Roles that already existed in the Target with the same Configuration
+    pub unchanged: usize,
+    /// The Names of the Roles that already existed in the Target with a different Configuration,
+    /// these are kept as they are in the Target
+    pub conflicts: Vec<String>,
+    /// The Names of the Roles that could not be written to the Target
+    pub failed: Vec<String>,
+}
+
+impl Display for MigrationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Migrated {} Roles, {} were already up to date",
+            self.migrated, self.unchanged
+        )?;
+        if !self.conflicts.is_empty() {
+            write!(
+                f,
+                "\nKept the existing Version of: {}",
+                self.conflicts.join(", ")
+            )?;
+        }
+        if !self.failed.is_empty() {
+            write!(f, "\nCould not migrate: {}", self.failed.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Copies all the Roles of the Guild from the Source- into the Target-Backend. This only ever
+/// writes to the Target, Roles that already exist there with a different Configuration are
+/// reported as Conflicts instead of being overwritten
+pub async fn migrate_roles<S, T>(
+    source: &S,
+    target: &T,
+    guild: GuildId,
+) -> Result<MigrationReport, Box<dyn Error + Send>>
+where
+    S: StorageBackend + ?Sized,
+    T: StorageBackend + ?Sized,
+{
+    let roles = source.load_roles(guild).await?;
+    let existing = target.load_roles(guild).await?;
+
+    let mut report = MigrationReport::default();
+    for role in roles {
+        match existing.iter().find(|r| r.name() == role.name()) {
+            Some(present) if *present == role => {
+                report.unchanged += 1;
+                continue;
+            }
+            Some(_) => {
+                report.conflicts.push(role.name().to_string());
+                continue;
+            }
+            None => {}
+        };
+
+        let name = role.name().to_string();
+        match target.set_role(guild, role).await {
+            Ok(_) => report.migrated += 1,
+            Err(e) => {
+                tracing::error!("Migrating Role '{}': {:?}", name, e);
+                report.failed.push(name);
+            }
+        };
+    }
+
+    Ok(report)
+}
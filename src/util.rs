@@ -1,3 +1,10 @@
+pub mod concurrency;
+pub mod confirm;
+pub mod dry_run;
 pub mod mods;
 pub mod msgs;
+pub mod pacing;
+pub mod permissions;
+pub mod ratelimit;
 pub mod roles;
+pub mod time;
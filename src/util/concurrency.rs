@@ -0,0 +1,69 @@
+use std::future::Future;
+
+use serenity::futures::{stream, StreamExt};
+
+/// Runs the Operation for all the Items, while at most `limit` of them are running at the same
+/// time. The Results are returned in the same Order as the Items, independent of the Order in
+/// which the Operations finished
+pub async fn bounded<I, F, Fut>(items: I, limit: usize, operation: F) -> Vec<Fut::Output>
+where
+    I: IntoIterator,
+    F: FnMut(I::Item) -> Fut,
+    Fut: Future,
+{
+    stream::iter(items)
+        .map(operation)
+        .buffered(limit.max(1))
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use super::*;
+
+    /// Stands in for the Http-Client and keeps track of how many Calls are running at once
+    #[derive(Default)]
+    struct MockHttp {
+        active: AtomicUsize,
+        max_active: AtomicUsize,
+    }
+
+    impl MockHttp {
+        async fn create_channel(&self, id: u64) -> u64 {
+            let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_active.fetch_max(active, Ordering::SeqCst);
+
+            // Later Calls finish sooner, to check that the Order of the Results is kept
+            tokio::time::sleep(Duration::from_millis(20 - id)).await;
+
+            self.active.fetch_sub(1, Ordering::SeqCst);
+            id
+        }
+    }
+
+    #[tokio::test]
+    async fn limits_concurrent_calls() {
+        let http = MockHttp::default();
+
+        let result = bounded(0..10, 3, |id| http.create_channel(id)).await;
+
+        assert_eq!((0..10).collect::<Vec<_>>(), result);
+        assert_eq!(3, http.max_active.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn zero_limit_runs_sequentially() {
+        let http = MockHttp::default();
+
+        let result = bounded(0..4, 0, |id| http.create_channel(id)).await;
+
+        assert_eq!(vec![0, 1, 2, 3], result);
+        assert_eq!(1, http.max_active.load(Ordering::SeqCst));
+    }
+}
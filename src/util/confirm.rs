@@ -0,0 +1,434 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Mutex,
+    time::Duration,
+};
+
+use lazy_static::lazy_static;
+use serenity::{
+    http::Http,
+    model::{
+        channel::{Message, Reaction, ReactionType},
+        id::{ChannelId, MessageId, UserId},
+        interactions::{
+            ButtonStyle, Interaction, InteractionData, InteractionResponseType, InteractionType,
+        },
+    },
+};
+
+use super::msgs::ReplyTarget;
+use crate::Reactions;
+use tokio::sync::oneshot;
+
+/// The Custom-ID of the Button that confirms the Action
+const CONFIRM_ID: &str = "confirm";
+/// The Custom-ID of the Button that cancels the Action
+const CANCEL_ID: &str = "cancel";
+
+lazy_static! {
+    static ref PENDING: PendingConfirmations = PendingConfirmations::new();
+}
+
+/// How a single Reaction answers a [`Confirmation`]
+#[derive(Debug, PartialEq)]
+pub enum Answer {
+    /// One of the Users that can confirm reacted with the confirming Reaction
+    Confirmed,
+    /// Someone that can't confirm reacted with the confirming Reaction
+    NotAllowed,
+    /// The Reaction is not the confirming one, so it may still mean something else
+    OtherReaction,
+}
+
+/// The Reaction with which an Action is confirmed together with the Users that may confirm it.
+///
+/// This is used for the awaited Confirmations as well as for the Confirmations of the Rounds,
+/// which receive their Reactions through their State-Machines instead
+#[derive(Debug, Clone)]
+pub struct Confirmation {
+    reaction: Reactions,
+    users: BTreeSet<UserId>,
+}
+
+impl Confirmation {
+    /// A Confirmation that any of the given Users can confirm by reacting with the Reaction
+    pub fn new<I>(reaction: Reactions, users: I) -> Self
+    where
+        I: IntoIterator<Item = UserId>,
+    {
+        Self {
+            reaction,
+            users: users.into_iter().collect(),
+        }
+    }
+
+    /// Whether or not the User can answer the Confirmation
+    pub fn allows(&self, user: UserId) -> bool {
+        self.users.contains(&user)
+    }
+
+    /// Checks how the Reaction of the User answers the Confirmation
+    pub fn answer(&self, user: UserId, emoji: &ReactionType) -> Answer {
+        if self.reaction != emoji {
+            Answer::OtherReaction
+        } else if !self.allows(user) {
+            Answer::NotAllowed
+        } else {
+            Answer::Confirmed
+        }
+    }
+}
+
+/// The Outcome of a Reaction or a Button-Press on a Confirmation-Message
+#[derive(Debug, PartialEq)]
+enum Resolution {
+    /// The Message does not belong to a pending Confirmation
+    NotPending,
+    /// Someone other than the User that has to confirm pressed a Button or reacted
+    WrongUser,
+    /// The User reacted with something other than the confirming Reaction
+    OtherReaction,
+    Resolved,
+}
+
+/// A Message that is waiting for its User to react with the confirming Reaction or to press one
+/// of its Buttons
+#[derive(Debug)]
+struct PendingConfirmation {
+    confirmation: Confirmation,
+    answer: oneshot::Sender<bool>,
+}
+
+/// Keeps track of the Messages that are still waiting for their User to react with the
+/// confirming Reaction or to press one of their Buttons
+#[derive(Debug)]
+struct PendingConfirmations {
+    waiting: Mutex<HashMap<MessageId, PendingConfirmation>>,
+}
+
+impl PendingConfirmations {
+    fn new() -> Self {
+        Self {
+            waiting: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Registers the Message as a Confirmation that is answered once the given User reacts with
+    /// the given Reaction
+    fn register(
+        &self,
+        message: MessageId,
+        user: UserId,
+        reaction: Reactions,
+    ) -> oneshot::Receiver<bool> {
+        let (tx, rx) = oneshot::channel();
+        self.waiting.lock().unwrap().insert(
+            message,
+            PendingConfirmation {
+                confirmation: Confirmation::new(reaction, Some(user)),
+                answer: tx,
+            },
+        );
+        rx
+    }
+
+    /// Confirms the Message, if the Reaction is the confirming one and comes from the right User
+    fn resolve(&self, message: MessageId, user: UserId, emoji: &ReactionType) -> Resolution {
+        let mut waiting = self.waiting.lock().unwrap();
+
+        let answer = match waiting.get(&message) {
+            Some(pending) => pending.confirmation.answer(user, emoji),
+            None => return Resolution::NotPending,
+        };
+
+        match answer {
+            Answer::Confirmed => Self::answer(&mut waiting, message, true),
+            Answer::NotAllowed => Resolution::WrongUser,
+            Answer::OtherReaction => Resolution::OtherReaction,
+        }
+    }
+
+    /// Answers the Confirmation of the Message with the pressed Button, if the User is allowed to
+    /// do so
+    fn press(&self, message: MessageId, user: UserId, confirmed: bool) -> Resolution {
+        let mut waiting = self.waiting.lock().unwrap();
+
+        match waiting.get(&message) {
+            None => return Resolution::NotPending,
+            Some(pending) if !pending.confirmation.allows(user) => return Resolution::WrongUser,
+            Some(_) => {}
+        };
+
+        Self::answer(&mut waiting, message, confirmed)
+    }
+
+    fn answer(
+        waiting: &mut HashMap<MessageId, PendingConfirmation>,
+        message: MessageId,
+        confirmed: bool,
+    ) -> Resolution {
+        let pending = waiting.remove(&message).unwrap();
+        // The Receiver is only gone if the Confirmation already timed out
+        let _ = pending.answer.send(confirmed);
+        Resolution::Resolved
+    }
+
+    fn remove(&self, message: MessageId) {
+        self.waiting.lock().unwrap().remove(&message);
+    }
+}
+
+/// Waits for the Answer to a Confirmation, which counts as cancelled once the Timeout ran out
+async fn wait_for_answer<F>(answer: oneshot::Receiver<bool>, timeout: Duration, remove: F) -> bool
+where
+    F: FnOnce(),
+{
+    match tokio::time::timeout(timeout, answer).await {
+        Ok(Ok(c)) => c,
+        _ => {
+            remove();
+            false
+        }
+    }
+}
+
+/// Waits for the User to confirm an Action by reacting to the Message with the given Reaction,
+/// which the Bot adds to the Message first.
+///
+/// Returns `true` only if the User reacted before the Timeout ran out, Reactions of other Users
+/// and other Reactions are ignored
+pub async fn await_confirmation(
+    http: &Http,
+    message: &Message,
+    user: UserId,
+    reaction: Reactions,
+    timeout: Duration,
+) -> Result<bool, serenity::Error> {
+    // Registered before reacting, so a fast User can't react before the Confirmation is pending
+    let answer = PENDING.register(message.id, user, reaction.clone());
+    if let Err(e) = message.react(http, reaction).await {
+        PENDING.remove(message.id);
+        return Err(e);
+    }
+
+    Ok(wait_for_answer(answer, timeout, || PENDING.remove(message.id)).await)
+}
+
+/// Asks the User to confirm an Action by posting a Message with a Confirm- and a Cancel-Button,
+/// which they can also confirm by reacting to it with [`Reactions::Confirm`].
+///
+/// Returns `true` only if the User confirmed before the Timeout ran out, the Buttons are replaced
+/// by the Outcome afterwards either way
+pub async fn confirm(
+    http: &Http,
+    channel_id: ChannelId,
+    user: UserId,
+    content: &str,
+    timeout: Duration,
+) -> Result<bool, serenity::Error> {
+    let content = format!(
+        "{}\nPress Confirm or react with {} to confirm",
+        content,
+        Reactions::Confirm
+    );
+    let msg = channel_id
+        .send_message(http, |m| {
+            m.content(&content).components(|c| {
+                c.create_action_row(|row| {
+                    row.create_button(|b| {
+                        b.style(ButtonStyle::Danger)
+                            .label("Confirm")
+                            .custom_id(CONFIRM_ID)
+                    })
+                    .create_button(|b| {
+                        b.style(ButtonStyle::Secondary)
+                            .label("Cancel")
+                            .custom_id(CANCEL_ID)
+                    })
+                })
+            })
+        })
+        .await?;
+
+    // The Buttons answer the same pending Confirmation as the Reaction
+    let confirmed = await_confirmation(http, &msg, user, Reactions::Confirm, timeout).await?;
+
+    let outcome = if confirmed { "Confirmed" } else { "Cancelled" };
+    if let Err(e) = channel_id
+        .edit_message(http, msg.id, |m| {
+            m.content(format!("{}\n*{}*", content, outcome))
+                .components(|c| c)
+        })
+        .await
+    {
+        tracing::error!("Adding Outcome to Confirmation: {:?}", e);
+    }
+
+    Ok(confirmed)
+}
+
+/// Handles a Reaction on a Message, that may be waiting for its Confirmation. Returns whether
+/// the Message belongs to a pending Confirmation, in which case the Reaction needs no further
+/// handling
+pub fn handle_reaction(reaction: &Reaction) -> bool {
+    let user = match reaction.user_id {
+        Some(u) => u,
+        None => return false,
+    };
+
+    PENDING.resolve(reaction.message_id, user, &reaction.emoji) != Resolution::NotPending
+}
+
+/// Handles the Button-Presses on Confirmation-Messages, all other Interactions are ignored
+pub async fn handle_interaction(http: &Http, interaction: Interaction) {
+    if interaction.kind != InteractionType::MessageComponent {
+        return;
+    }
+
+    let confirmed = match &interaction.data {
+        Some(InteractionData::MessageComponent(c)) if c.custom_id == CONFIRM_ID => true,
+        Some(InteractionData::MessageComponent(c)) if c.custom_id == CANCEL_ID => false,
+        _ => return,
+    };
+    let message = match &interaction.message {
+        Some(m) => m.id(),
+        None => return,
+    };
+    let user = match (&interaction.member, &interaction.user) {
+        (Some(member), _) => member.user.id,
+        (None, Some(user)) => user.id,
+        (None, None) => return,
+    };
+
+    let reply = ReplyTarget::Interaction(&interaction);
+    match PENDING.press(message, user, confirmed) {
+        Resolution::NotPending => {
+            reply
+                .send_error(http, "This Confirmation is no longer active")
+                .await;
+        }
+        Resolution::WrongUser => {
+            reply
+                .send_error(http, "Only the User that ran the Command can answer this")
+                .await;
+        }
+        // Buttons are always one of the two answering Buttons
+        Resolution::OtherReaction => {}
+        Resolution::Resolved => {
+            if let Err(e) = interaction
+                .create_interaction_response(http, |r| {
+                    r.kind(InteractionResponseType::DeferredUpdateMessage)
+                })
+                .await
+            {
+                tracing::error!("Responding to Button-Press: {:?}", e);
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confirmation_answer() {
+        let confirmation = Confirmation::new(Reactions::Confirm, vec![UserId(1), UserId(2)]);
+
+        assert_eq!(
+            Answer::Confirmed,
+            confirmation.answer(UserId(2), &Reactions::Confirm.into())
+        );
+        assert_eq!(
+            Answer::NotAllowed,
+            confirmation.answer(UserId(3), &Reactions::Confirm.into())
+        );
+        // Other Reactions are left to the Caller, no matter who reacted
+        assert_eq!(
+            Answer::OtherReaction,
+            confirmation.answer(UserId(1), &Reactions::Entry.into())
+        );
+        assert_eq!(
+            Answer::OtherReaction,
+            confirmation.answer(UserId(3), &Reactions::Entry.into())
+        );
+    }
+
+    #[tokio::test]
+    async fn reaction_confirmed() {
+        let pending = PendingConfirmations::new();
+        let answer = pending.register(MessageId(1), UserId(2), Reactions::Confirm);
+
+        assert_eq!(
+            Resolution::Resolved,
+            pending.resolve(MessageId(1), UserId(2), &Reactions::Confirm.into())
+        );
+        assert!(wait_for_answer(answer, Duration::from_secs(1), || unreachable!()).await);
+
+        assert_eq!(
+            Resolution::NotPending,
+            pending.resolve(MessageId(1), UserId(2), &Reactions::Confirm.into())
+        );
+    }
+
+    #[tokio::test]
+    async fn reaction_other() {
+        let pending = PendingConfirmations::new();
+        let mut answer = pending.register(MessageId(1), UserId(2), Reactions::Confirm);
+
+        assert_eq!(
+            Resolution::OtherReaction,
+            pending.resolve(MessageId(1), UserId(2), &Reactions::Stop.into())
+        );
+        assert_eq!(
+            Resolution::WrongUser,
+            pending.resolve(MessageId(1), UserId(3), &Reactions::Confirm.into())
+        );
+        // Neither of them answered the Confirmation
+        assert!(answer.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn button_pressed() {
+        let pending = PendingConfirmations::new();
+        let answer = pending.register(MessageId(1), UserId(2), Reactions::Confirm);
+
+        assert_eq!(
+            Resolution::WrongUser,
+            pending.press(MessageId(1), UserId(3), true)
+        );
+        assert_eq!(
+            Resolution::Resolved,
+            pending.press(MessageId(1), UserId(2), false)
+        );
+        assert!(!answer.await.unwrap());
+
+        // The Confirmation was already answered, so neither a Button nor a Reaction count anymore
+        assert_eq!(
+            Resolution::NotPending,
+            pending.press(MessageId(1), UserId(2), true)
+        );
+        assert_eq!(
+            Resolution::NotPending,
+            pending.resolve(MessageId(1), UserId(2), &Reactions::Confirm.into())
+        );
+    }
+
+    #[tokio::test]
+    async fn reaction_timed_out() {
+        let pending = PendingConfirmations::new();
+        let answer = pending.register(MessageId(1), UserId(2), Reactions::Confirm);
+
+        let confirmed = wait_for_answer(answer, Duration::from_millis(10), || {
+            pending.remove(MessageId(1))
+        })
+        .await;
+        assert!(!confirmed);
+
+        // A late Reaction no longer confirms anything
+        assert_eq!(
+            Resolution::NotPending,
+            pending.resolve(MessageId(1), UserId(2), &Reactions::Confirm.into())
+        );
+    }
+}
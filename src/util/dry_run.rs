@@ -0,0 +1,98 @@
+//! The Dry-Run Mode of the Bot, in which mutating Discord-Operations are only logged instead of
+//! being executed. This allows for testing the Command-Flows against a real Guild without any
+//! lasting Side-Effects.
+//!
+//! Operations that are stubbed in the Dry-Run Mode:
+//! * Creating Channels, Categories and Roles for a Round, which get Placeholder-IDs instead
+//! * Moving, editing and deleting Channels
+//! * Creating and deleting Permission-Overwrites on Channels
+//! * Removing Roles from Members
+//! * Editing and deleting the Messages used by the Setup-Flows
+//! * Removing Reactions of Users
+//!
+//! Operations that are still executed:
+//! * Reading anything from Discord, like Reactions, Members, Channels or Messages
+//! * Sending new Messages and adding Reactions to them, as the Flows rely on these to work at all
+//! * Everything related to the Settings-Channel, because it stores the State of the Bot itself
+//! * Confirmation-Messages, as they only belong to the Bot
+//!
+//! The `self-test` Command skips its Checks that would create Channels or Roles.
+//!
+//! Because the Placeholder-Channels don't exist, sending Messages to them will fail, which means
+//! that Rounds only fully start if all the needed Channels already exist on the Guild.
+
+use std::{
+    future::Future,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+use serenity::model::id::{ChannelId, RoleId};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static NEXT_PLACEHOLDER: AtomicU64 = AtomicU64::new(u64::MAX);
+
+/// Enables or disables the Dry-Run Mode for the entire Bot
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// Whether or not the Bot is currently running in the Dry-Run Mode
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// Returns a new unique ID that is used in place of a Channel that was not actually created
+pub fn placeholder_channel() -> ChannelId {
+    ChannelId(NEXT_PLACEHOLDER.fetch_sub(1, Ordering::SeqCst))
+}
+
+/// Returns a new unique ID that is used in place of a Role that was not actually created
+pub fn placeholder_role() -> RoleId {
+    RoleId(NEXT_PLACEHOLDER.fetch_sub(1, Ordering::SeqCst))
+}
+
+/// Performs the given mutating Operation, unless the Dry-Run Mode is enabled in which case the
+/// Operation is only logged and the `skipped` Value is returned instead.
+///
+/// The `operation` should describe what would have happened, for the Logs
+pub async fn mutate<F, T, E>(operation: &str, skipped: T, action: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    mutate_with(is_enabled(), operation, skipped, action).await
+}
+
+async fn mutate_with<F, T, E>(enabled: bool, operation: &str, skipped: T, action: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    if enabled {
+        tracing::info!("[Dry-Run] Skipped: {}", operation);
+        return Ok(skipped);
+    }
+
+    action.await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn disabled_runs_action() {
+        let result: Result<u64, ()> = mutate_with(false, "Test", 0, async { Ok(13) }).await;
+        assert_eq!(Ok(13), result);
+    }
+
+    #[tokio::test]
+    async fn enabled_skips_action() {
+        let result: Result<u64, ()> =
+            mutate_with(true, "Test", 0, async { unreachable!("Should not be run") }).await;
+        assert_eq!(Ok(0), result);
+    }
+
+    #[test]
+    fn unique_placeholders() {
+        assert_ne!(placeholder_channel(), placeholder_channel());
+    }
+}
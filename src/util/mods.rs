@@ -1,29 +1,190 @@
-use std::collections::BTreeSet;
+use std::{collections::BTreeSet, fmt::Display};
 
 use serenity::{
     client::Context,
+    futures::StreamExt,
     http::CacheHttp,
-    model::id::{GuildId, UserId},
+    model::id::{GuildId, RoleId, UserId},
 };
 
-use super::roles;
+use super::{msgs::ReplyTarget, roles};
+use crate::{get_storage, storage::StorageBackend};
 
 #[derive(Debug)]
 pub enum LoadModsError {
     FindModRole(roles::FindRoleError),
 }
 
+impl Display for LoadModsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FindModRole(e) => write!(f, "Finding Mod-Role: {}", e),
+        }
+    }
+}
+
+/// The Mods of a Guild together with the Names of the Roles that make a User a Mod
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuildMods {
+    pub users: BTreeSet<UserId>,
+    pub role_names: Vec<String>,
+}
+
+impl GuildMods {
+    /// Whether or not the User is one of the Mods
+    pub fn contains(&self, user: &UserId) -> bool {
+        self.users.contains(user)
+    }
+
+    /// The Names of the Mod-Roles formatted for displaying them in a Message, like
+    /// `'Game Master' or 'Admin'`
+    pub fn role_list(&self) -> String {
+        self.role_names
+            .iter()
+            .map(|n| format!("'{}'", n))
+            .collect::<Vec<_>>()
+            .join(" or ")
+    }
+}
+
+/// Collects all the Members that have at least one of the Mod-Roles
+fn collect_mods<I>(members: I, mod_roles: &[RoleId]) -> BTreeSet<UserId>
+where
+    I: IntoIterator<Item = (UserId, Vec<RoleId>)>,
+{
+    members
+        .into_iter()
+        .filter(|(_, roles)| roles.iter().any(|r| mod_roles.contains(r)))
+        .map(|(id, _)| id)
+        .collect()
+}
+
+/// Loads all the Users that have at least one of the Roles with the given Names, Roles that
+/// don't exist on the Guild are ignored as long as at least one of them exists
 pub async fn load_mods(
     ctx: &Context,
     guild_id: GuildId,
-    role_name: &str,
+    role_names: &[String],
 ) -> Result<BTreeSet<UserId>, LoadModsError> {
-    let mod_role = match roles::find_role(role_name, guild_id, ctx.http()).await {
-        Ok(r) => r,
-        Err(e) => return Err(LoadModsError::FindModRole(e)),
+    let guild_roles = guild_id
+        .roles(ctx.http())
+        .await
+        .map_err(|e| LoadModsError::FindModRole(e.into()))?;
+
+    let mod_roles: Vec<RoleId> = guild_roles
+        .iter()
+        .filter(|(_, role)| {
+            role_names
+                .iter()
+                .any(|name| role.name.eq_ignore_ascii_case(name))
+        })
+        .map(|(id, _)| *id)
+        .collect();
+    if mod_roles.is_empty() {
+        return Err(LoadModsError::FindModRole(roles::FindRoleError::NotFound));
+    }
+
+    let mut members = Vec::new();
+    let mut member_iter = guild_id.members_iter(ctx.http()).boxed();
+    while let Some(member_res) = member_iter.next().await {
+        match member_res {
+            Ok(m) => members.push((m.user.id, m.roles)),
+            Err(e) => {
+                tracing::error!("Loading Guild Member: {:?}", e);
+            }
+        };
+    }
+
+    Ok(collect_mods(members, &mod_roles))
+}
+
+/// Loads the Mods of the Guild based on the Mod-Roles configured in its Settings
+pub async fn load_guild_mods(ctx: &Context, guild_id: GuildId) -> Result<GuildMods, LoadModsError> {
+    let role_names = {
+        let data = ctx.data.read().await;
+        let storage = get_storage(&data);
+
+        match storage.load_settings(guild_id).await {
+            Ok(s) => s.mod_roles,
+            Err(e) => {
+                tracing::error!("Loading Settings: {:?}", e);
+                crate::settings::GuildSettings::default().mod_roles
+            }
+        }
     };
 
-    let mods = roles::role_users(mod_role, guild_id, ctx.http()).await;
+    let users = load_mods(ctx, guild_id, &role_names).await?;
+
+    Ok(GuildMods { users, role_names })
+}
+
+/// Loads the Mods of the Guild and checks that the User is one of them. If that is not the case,
+/// or the Mods could not be loaded, the User is told so using the given Reply-Target
+pub async fn require_mod(
+    ctx: &Context,
+    guild_id: GuildId,
+    user: UserId,
+    reply: ReplyTarget<'_>,
+) -> Option<GuildMods> {
+    let server_mods = match load_guild_mods(ctx, guild_id).await {
+        Ok(m) => m,
+        Err(e) => {
+            tracing::error!("Loading Mods: {:?}", e);
+
+            reply
+                .send_error(ctx.http(), "Could not load Mods for the Server")
+                .await;
+
+            return None;
+        }
+    };
+
+    if !server_mods.contains(&user) {
+        tracing::error!("Non Mod User executed the Command");
+
+        reply
+            .send_error(
+                ctx.http(),
+                &format!(
+                    "Only Users with the {}-Role can use this Command",
+                    server_mods.role_list()
+                ),
+            )
+            .await;
+
+        return None;
+    }
+
+    Some(server_mods)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_mod_roles() {
+        let members = vec![
+            (UserId(1), vec![RoleId(10)]),
+            (UserId(2), vec![RoleId(20), RoleId(30)]),
+            (UserId(3), vec![RoleId(30)]),
+            (UserId(4), vec![RoleId(10), RoleId(20)]),
+            (UserId(5), vec![]),
+        ];
+
+        let result = collect_mods(members, &[RoleId(10), RoleId(20)]);
+
+        let expected: BTreeSet<_> = vec![UserId(1), UserId(2), UserId(4)].into_iter().collect();
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn role_list() {
+        let mods = GuildMods {
+            users: BTreeSet::new(),
+            role_names: vec!["Game Master".to_string(), "Admin".to_string()],
+        };
 
-    Ok(mods)
+        assert_eq!("'Game Master' or 'Admin'", mods.role_list());
+    }
 }
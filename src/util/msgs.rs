@@ -1,4 +1,14 @@
-use serenity::{http::Http, model::id::ChannelId};
+use std::{sync::Arc, time::Duration};
+
+use serenity::{
+    http::Http,
+    model::{
+        id::ChannelId,
+        interactions::{
+            Interaction, InteractionApplicationCommandCallbackDataFlags, InteractionResponseType,
+        },
+    },
+};
 
 /// This will send a message with the given Content in the given Channel and if an error
 /// occures output it via tracing on the error level
@@ -7,3 +17,64 @@ pub async fn send_content(channel_id: ChannelId, http: &Http, content: &str) {
         tracing::error!("Sending Message: {:?}", e);
     }
 }
+
+/// Sends a Message with the given Content, which is automatically deleted again after the given
+/// Duration. The Deletion happens in the Background so this does not block the Caller
+pub async fn send_transient(
+    channel_id: ChannelId,
+    http: Arc<Http>,
+    content: &str,
+    lifetime: Duration,
+) {
+    let msg = match channel_id.send_message(&http, |m| m.content(content)).await {
+        Ok(m) => m,
+        Err(e) => {
+            tracing::error!("Sending Message: {:?}", e);
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        tokio::time::sleep(lifetime).await;
+
+        if let Err(e) = msg.delete(&http).await {
+            tracing::error!("Deleting transient Message: {:?}", e);
+        }
+    });
+}
+
+/// Responds to the Interaction with a Message that is only visible to the User that triggered it
+pub async fn reply_ephemeral(interaction: &Interaction, http: &Http, content: &str) {
+    if let Err(e) = interaction
+        .create_interaction_response(http, |r| {
+            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|d| {
+                    d.content(content)
+                        .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
+                })
+        })
+        .await
+    {
+        tracing::error!("Sending ephemeral Reply: {:?}", e);
+    }
+}
+
+/// Where the Response to an Action of a User is sent to
+#[derive(Debug, Clone, Copy)]
+pub enum ReplyTarget<'i> {
+    /// The Action was a Prefix-Command, so the Response is posted in its Channel
+    Channel(ChannelId),
+    /// The Action was an Interaction, so Errors are only shown to the User that triggered it
+    Interaction(&'i Interaction),
+}
+
+impl ReplyTarget<'_> {
+    /// Lets the User know that their Action was rejected, like when they lack the Permissions or
+    /// gave invalid Input
+    pub async fn send_error(&self, http: &Http, content: &str) {
+        match self {
+            Self::Channel(channel_id) => send_content(*channel_id, http, content).await,
+            Self::Interaction(interaction) => reply_ephemeral(interaction, http, content).await,
+        }
+    }
+}
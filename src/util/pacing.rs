@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+/// The Source of Time used to wait between Operations
+#[async_trait]
+pub trait Clock: Sync {
+    async fn sleep(&self, duration: Duration);
+}
+
+/// The actual Clock, based on the Timers of tokio
+#[derive(Debug, Default)]
+pub struct TokioClock;
+
+#[async_trait]
+impl Clock for TokioClock {
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+/// Spaces out a Series of Discord-Operations by waiting the configured Delay between every two
+/// of them, to stay below the Rate-Limits of Discord when a lot of Channels are changed at once
+#[derive(Debug)]
+pub struct Pacer<C = TokioClock> {
+    delay: Duration,
+    clock: C,
+    /// Whether an Operation has already been started, the first one never waits
+    started: bool,
+}
+
+impl Pacer {
+    pub fn new(delay: Duration) -> Self {
+        Self::with_clock(delay, TokioClock)
+    }
+
+    /// A Pacer for Channel- and Permission-Operations, using the configured Delay
+    pub fn channel_operations() -> Self {
+        Self::new(crate::CONFIG.channel_operation_delay())
+    }
+}
+
+impl<C> Pacer<C>
+where
+    C: Clock,
+{
+    pub fn with_clock(delay: Duration, clock: C) -> Self {
+        Self {
+            delay,
+            clock,
+            started: false,
+        }
+    }
+
+    /// Waits until the next Operation can be started
+    pub async fn pace(&mut self) {
+        if self.started && !self.delay.is_zero() {
+            self.clock.sleep(self.delay).await;
+        }
+        self.started = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    /// Only records how long it was asked to sleep, without actually waiting
+    #[derive(Default, Clone)]
+    struct MockClock {
+        sleeps: Arc<Mutex<Vec<Duration>>>,
+    }
+
+    #[async_trait]
+    impl Clock for MockClock {
+        async fn sleep(&self, duration: Duration) {
+            self.sleeps.lock().unwrap().push(duration);
+        }
+    }
+
+    #[tokio::test]
+    async fn waits_between_operations() {
+        let clock = MockClock::default();
+        let mut pacer = Pacer::with_clock(Duration::from_millis(50), clock.clone());
+
+        for _ in 0..3 {
+            pacer.pace().await;
+        }
+
+        // The first Operation starts right away
+        assert_eq!(
+            vec![Duration::from_millis(50), Duration::from_millis(50)],
+            *clock.sleeps.lock().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn no_delay() {
+        let clock = MockClock::default();
+        let mut pacer = Pacer::with_clock(Duration::ZERO, clock.clone());
+
+        for _ in 0..3 {
+            pacer.pace().await;
+        }
+
+        assert!(clock.sleeps.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+use std::fmt::Display;
+
+use serenity::{
+    http::Http,
+    model::{
+        id::{GuildId, UserId},
+        Permissions,
+    },
+};
+
+/// The Permissions the Bot needs on a Guild to set up and clean up a Round, together with a
+/// readable Name for each of them
+pub const REQUIRED_PERMISSIONS: [(Permissions, &str); 3] = [
+    (Permissions::MANAGE_CHANNELS, "Manage Channels"),
+    (Permissions::MANAGE_ROLES, "Manage Roles"),
+    (Permissions::MANAGE_MESSAGES, "Manage Messages"),
+];
+
+/// The Permissions the Bot uses for the Messages of its Flows, which are granted to most Bots by
+/// default and are therefore not checked upfront
+pub const MESSAGE_PERMISSIONS: [(Permissions, &str); 4] = [
+    (Permissions::READ_MESSAGES, "View Channels"),
+    (Permissions::SEND_MESSAGES, "Send Messages"),
+    (Permissions::ADD_REACTIONS, "Add Reactions"),
+    (Permissions::READ_MESSAGE_HISTORY, "Read Message History"),
+];
+
+#[derive(Debug)]
+pub enum CheckPermissionsError {
+    /// The Permissions of the User could not be loaded
+    Loading(serenity::Error),
+    /// The User is missing the Permissions with the given Names
+    Missing(Vec<&'static str>),
+}
+
+impl Display for CheckPermissionsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Loading(e) => write!(f, "Loading the Permissions of the Bot ({})", e),
+            Self::Missing(names) => write!(
+                f,
+                "I'm missing the following Permissions: {}",
+                names.join(", ")
+            ),
+        }
+    }
+}
+
+/// Returns the Names of all the required Permissions that are not contained in the given
+/// Permissions
+pub fn missing_permissions(permissions: Permissions) -> Vec<&'static str> {
+    REQUIRED_PERMISSIONS
+        .iter()
+        .filter(|(perm, _)| !permissions.contains(*perm))
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+/// Whether each of the required and Message-Permissions is contained in the given Permissions,
+/// together with its Name
+pub fn checklist(permissions: Permissions) -> Vec<(&'static str, bool)> {
+    REQUIRED_PERMISSIONS
+        .iter()
+        .chain(MESSAGE_PERMISSIONS.iter())
+        .map(|(perm, name)| (*name, permissions.contains(*perm)))
+        .collect()
+}
+
+/// Loads the effective Permissions of the given User on the Guild
+pub async fn guild_permissions(
+    guild: GuildId,
+    user: UserId,
+    http: &Http,
+) -> Result<Permissions, serenity::Error> {
+    let partial_guild = guild.to_partial_guild(http).await?;
+
+    partial_guild.member_permissions(http, user).await
+}
+
+/// Checks that the given User has all the [`REQUIRED_PERMISSIONS`] on the Guild
+pub async fn check_permissions(
+    guild: GuildId,
+    user: UserId,
+    http: &Http,
+) -> Result<(), CheckPermissionsError> {
+    let permissions = guild_permissions(guild, user, http)
+        .await
+        .map_err(CheckPermissionsError::Loading)?;
+
+    let missing = missing_permissions(permissions);
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(CheckPermissionsError::Missing(missing))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_none() {
+        let permissions =
+            Permissions::MANAGE_CHANNELS | Permissions::MANAGE_ROLES | Permissions::MANAGE_MESSAGES;
+
+        assert_eq!(Vec::<&str>::new(), missing_permissions(permissions));
+        assert_eq!(Vec::<&str>::new(), missing_permissions(Permissions::all()));
+    }
+
+    #[test]
+    fn missing_some() {
+        let permissions = Permissions::MANAGE_ROLES | Permissions::SEND_MESSAGES;
+
+        assert_eq!(
+            vec!["Manage Channels", "Manage Messages"],
+            missing_permissions(permissions)
+        );
+    }
+
+    #[test]
+    fn checklist_entries() {
+        let permissions = Permissions::MANAGE_CHANNELS | Permissions::SEND_MESSAGES;
+
+        assert_eq!(
+            vec![
+                ("Manage Channels", true),
+                ("Manage Roles", false),
+                ("Manage Messages", false),
+                ("View Channels", false),
+                ("Send Messages", true),
+                ("Add Reactions", false),
+                ("Read Message History", false),
+            ],
+            checklist(permissions)
+        );
+        assert!(checklist(Permissions::all())
+            .iter()
+            .all(|(_, granted)| *granted));
+    }
+
+    #[test]
+    fn missing_display() {
+        let error = CheckPermissionsError::Missing(vec!["Manage Channels", "Manage Roles"]);
+
+        assert_eq!(
+            "I'm missing the following Permissions: Manage Channels, Manage Roles",
+            error.to_string()
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// A simple Rate-Limiter that allows at most one Action per Key in the configured Interval
+#[derive(Debug)]
+pub struct RateLimiter<K> {
+    interval: Duration,
+    last: Mutex<HashMap<K, Instant>>,
+}
+
+impl<K> RateLimiter<K>
+where
+    K: Hash + Eq,
+{
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Checks if an Action for the given Key is currently allowed and if so, records it
+    pub fn try_acquire(&self, key: K) -> bool {
+        self.try_acquire_at(key, Instant::now())
+    }
+
+    fn try_acquire_at(&self, key: K, now: Instant) -> bool {
+        let mut last = self.last.lock().unwrap();
+
+        // Forget about all the old Entries to avoid growing forever
+        let interval = self.interval;
+        last.retain(|_, previous| now.saturating_duration_since(*previous) < interval);
+
+        if last.contains_key(&key) {
+            return false;
+        }
+
+        last.insert(key, now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_same_key() {
+        let limiter = RateLimiter::new(Duration::from_secs(10));
+        let now = Instant::now();
+
+        assert!(limiter.try_acquire_at(1, now));
+        assert!(!limiter.try_acquire_at(1, now + Duration::from_secs(5)));
+        assert!(limiter.try_acquire_at(1, now + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn independent_keys() {
+        let limiter = RateLimiter::new(Duration::from_secs(10));
+        let now = Instant::now();
+
+        assert!(limiter.try_acquire_at(1, now));
+        assert!(limiter.try_acquire_at(2, now));
+        assert!(!limiter.try_acquire_at(2, now));
+    }
+}
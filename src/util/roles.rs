@@ -1,9 +1,8 @@
-use std::collections::BTreeSet;
+use std::fmt::Display;
 
 use serenity::{
-    futures::StreamExt,
     http::Http,
-    model::id::{GuildId, RoleId, UserId},
+    model::id::{GuildId, RoleId},
 };
 
 #[derive(Debug)]
@@ -12,6 +11,15 @@ pub enum FindRoleError {
     NotFound,
 }
 
+impl Display for FindRoleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SerenityError(e) => write!(f, "Serenity ({})", e),
+            Self::NotFound => write!(f, "NotFound"),
+        }
+    }
+}
+
 impl From<serenity::Error> for FindRoleError {
     fn from(e: serenity::Error) -> Self {
         Self::SerenityError(e)
@@ -29,38 +37,17 @@ pub async fn find_role(name: &str, guild: GuildId, http: &Http) -> Result<RoleId
         .map(|(id, _)| *id)
 }
 
-/// Loads all Users that belong to a given Role
-pub async fn role_users(role: RoleId, guild: GuildId, http: &Http) -> BTreeSet<UserId> {
-    let mut member_iter = guild.members_iter(http).boxed();
-
-    let mut result = BTreeSet::new();
-
-    while let Some(member_res) = member_iter.next().await {
-        let member = match member_res {
-            Ok(m) => m,
-            Err(e) => {
-                tracing::error!("Loading Guild Member: {:?}", e);
-                continue;
-            }
-        };
-
-        for m_role in member.roles {
-            if m_role == role {
-                result.insert(member.user.id);
-                break;
-            }
-        }
-    }
-
-    result
+/// The @everyone Role of the Guild, which always has the same ID as the Guild itself
+pub fn everyone_role(guild: GuildId) -> RoleId {
+    RoleId(guild.0)
 }
 
-pub async fn get_everyone_role(guild: GuildId, http: &Http) -> Result<RoleId, FindRoleError> {
-    let roles = guild.roles(http).await?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(*roles
-        .iter()
-        .min_by(|(_, x), (_, y)| x.position.cmp(&y.position))
-        .expect("There is always at least the @everyone Role")
-        .0)
+    #[test]
+    fn everyone_role_is_guild() {
+        assert_eq!(RoleId(13), everyone_role(GuildId(13)));
+    }
 }
@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+/// Formats the given Duration in a short human readable Form, like "1d 2h 3m 4s"
+pub fn format_duration(duration: Duration) -> String {
+    let total = duration.as_secs();
+
+    let days = total / (24 * 60 * 60);
+    let hours = (total / (60 * 60)) % 24;
+    let minutes = (total / 60) % 60;
+    let seconds = total % 60;
+
+    if days > 0 {
+        format!("{}d {}h {}m {}s", days, hours, minutes, seconds)
+    } else if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_seconds() {
+        assert_eq!("13s", format_duration(Duration::from_secs(13)));
+    }
+
+    #[test]
+    fn format_minutes() {
+        assert_eq!("2m 5s", format_duration(Duration::from_secs(125)));
+    }
+
+    #[test]
+    fn format_days() {
+        assert_eq!(
+            "1d 2h 3m 4s",
+            format_duration(Duration::from_secs(24 * 3600 + 2 * 3600 + 3 * 60 + 4))
+        );
+    }
+}
@@ -93,6 +93,13 @@ where
             StateResult::Second(second_res) => second_res.clone(),
         }
     }
+
+    fn state_name(&self) -> Option<&'static str> {
+        match &self.result {
+            StateResult::Empty => self.first.state_name(),
+            StateResult::First(_) | StateResult::Second(_) => self.second.state_name(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -13,5 +13,8 @@ pub use chained::Chained;
 mod withstate;
 pub use withstate::WithState;
 
+mod named;
+pub use named::Named;
+
 mod withlazystate;
 pub use withlazystate::WithLazyState;
@@ -0,0 +1,81 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use async_trait::async_trait;
+
+use crate::{AsyncTransition, TransitionResult};
+
+/// Gives a Transition a Name, which is reported as its State-Name while it is active
+pub struct Named<T, A, C, N, E> {
+    inner: T,
+    name: &'static str,
+
+    _marker: PhantomData<(A, C, N, E)>,
+}
+
+impl<T, A, C, N, E> Named<T, A, C, N, E>
+where
+    T: AsyncTransition<A, C, N, E>,
+{
+    /// Wraps the given Transition using the Name
+    pub fn new(inner: T, name: &'static str) -> Self {
+        Self {
+            inner,
+            name,
+
+            _marker: PhantomData {},
+        }
+    }
+}
+
+#[async_trait]
+impl<T, A, C, N, E> AsyncTransition<A, C, N, E> for Named<T, A, C, N, E>
+where
+    Self: Send,
+    T: AsyncTransition<A, C, N, E> + Send,
+    A: Send + 'static,
+    C: Send + 'static,
+{
+    async fn transition(&mut self, context: C, arguments: A) -> Arc<TransitionResult<N, E>> {
+        self.inner.transition(context, arguments).await
+    }
+
+    fn state_name(&self) -> Option<&'static str> {
+        self.inner.state_name().or(Some(self.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Next;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn chained_names() {
+        let mut sm = Next::new(|_: (), number: usize| async move {
+            TransitionResult::<usize, ()>::Done(number * 2)
+        })
+        .named("First")
+        .chain(
+            Next::new(|_: (), number: usize| async move { TransitionResult::Done(number + 4) })
+                .named("Second"),
+        );
+
+        assert_eq!(Some("First"), sm.state_name());
+
+        sm.transition((), 13).await;
+        assert_eq!(Some("Second"), sm.state_name());
+
+        sm.transition((), 13).await;
+        assert_eq!(Some("Second"), sm.state_name());
+    }
+
+    #[test]
+    fn unnamed() {
+        let sm = Next::new(|_: (), number: usize| async move {
+            TransitionResult::<usize, ()>::Done(number * 2)
+        });
+
+        assert_eq!(None, sm.state_name());
+    }
+}
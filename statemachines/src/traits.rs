@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 
-use crate::Chained;
+use crate::{Chained, Named};
 
 /// The Result of an attempted Transition
 #[derive(Debug)]
@@ -23,6 +23,12 @@ pub trait AsyncTransition<A, C, N, E> {
     /// State while transitioning
     async fn transition(&mut self, context: C, arguments: A) -> Arc<TransitionResult<N, E>>;
 
+    /// The Name of the currently active State, if it has been given one using
+    /// [`named`](AsyncTransition::named)
+    fn state_name(&self) -> Option<&'static str> {
+        None
+    }
+
     /// This is a simple way to chain two Transitions together by simply appending
     /// the other Transition to the current one
     fn chain<T, O>(self, other: T) -> Chained<Self, T, A, N, O, E, C>
@@ -32,4 +38,13 @@ pub trait AsyncTransition<A, C, N, E> {
     {
         Chained::new(self, other)
     }
+
+    /// Gives the Transition a Name, which is then reported by
+    /// [`state_name`](AsyncTransition::state_name) while it is active
+    fn named(self, name: &'static str) -> Named<Self, A, C, N, E>
+    where
+        Self: Sized,
+    {
+        Named::new(self, name)
+    }
 }